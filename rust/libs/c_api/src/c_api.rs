@@ -1,10 +1,17 @@
 use crate::c_api_diag_reporter::*;
+use crate::web_demo_location::*;
+use cpp_vs_rust_fe::cancellation::*;
+use cpp_vs_rust_fe::diag_reporter::*;
+use cpp_vs_rust_fe::diagnostic::*;
+use cpp_vs_rust_fe::document::*;
+use cpp_vs_rust_fe::limiting_diag_reporter::*;
 use cpp_vs_rust_fe::linter::*;
+use cpp_vs_rust_fe::sorting_diag_reporter::*;
 use cpp_vs_rust_i18n::translation::*;
 use cpp_vs_rust_i18n::translation_table_generated::*;
 use cpp_vs_rust_util::c_string::*;
-use cpp_vs_rust_util::padded_string::*;
 use cpp_vs_rust_util::qljs_assert;
+use cpp_vs_rust_util::utf_8::*;
 
 #[allow(non_camel_case_types)]
 pub type c_size_t = usize;
@@ -37,21 +44,155 @@ pub const QLJS_LANGUAGE_OPTIONS_TYPESCRIPT_BIT: QLJSLanguageOptions = 1 << 1;
 // If unset, parse JavaScript or TypeScript.
 pub const QLJS_LANGUAGE_OPTIONS_CONFIG_JSON_BIT: QLJSLanguageOptions = 1 << 2;
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 #[repr(C)]
 pub enum QLJSSeverity {
     Error = 1,
     Warning = 2,
 }
 
+// What kind of problem a diagnostic reports.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(C)]
+pub enum QLJSDiagnosticCategory {
+    Syntax = 1,
+    Correctness = 2,
+    Style = 3,
+    Compatibility = 4,
+}
+
+impl QLJSDiagnosticCategory {
+    // Returns None if value is not a QLJSDiagnosticCategory.
+    pub fn from_c_int(value: std::ffi::c_int) -> Option<QLJSDiagnosticCategory> {
+        match value {
+            1 => Some(QLJSDiagnosticCategory::Syntax),
+            2 => Some(QLJSDiagnosticCategory::Correctness),
+            3 => Some(QLJSDiagnosticCategory::Style),
+            4 => Some(QLJSDiagnosticCategory::Compatibility),
+            _ => None,
+        }
+    }
+
+    pub fn from_category(category: DiagnosticCategory) -> QLJSDiagnosticCategory {
+        match category {
+            DiagnosticCategory::Syntax => QLJSDiagnosticCategory::Syntax,
+            DiagnosticCategory::Correctness => QLJSDiagnosticCategory::Correctness,
+            DiagnosticCategory::Style => QLJSDiagnosticCategory::Style,
+            DiagnosticCategory::Compatibility => QLJSDiagnosticCategory::Compatibility,
+        }
+    }
+
+    pub fn to_category(self) -> DiagnosticCategory {
+        match self {
+            QLJSDiagnosticCategory::Syntax => DiagnosticCategory::Syntax,
+            QLJSDiagnosticCategory::Correctness => DiagnosticCategory::Correctness,
+            QLJSDiagnosticCategory::Style => DiagnosticCategory::Style,
+            QLJSDiagnosticCategory::Compatibility => DiagnosticCategory::Compatibility,
+        }
+    }
+}
+
+// How qljs_web_demo_lint reports diagnostics in a QLJSDiagnosticCategory.
+//
+// To change a category's severity, call qljs_web_demo_set_category_severity.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(C)]
+pub enum QLJSCategorySeverity {
+    // Report each diagnostic with its usual severity.
+    Default = 0,
+    Error = 1,
+    Warning = 2,
+    // Do not report diagnostics in the category.
+    Off = 3,
+}
+
+impl QLJSCategorySeverity {
+    // Returns None if value is not a QLJSCategorySeverity.
+    pub fn from_c_int(value: std::ffi::c_int) -> Option<QLJSCategorySeverity> {
+        match value {
+            0 => Some(QLJSCategorySeverity::Default),
+            1 => Some(QLJSCategorySeverity::Error),
+            2 => Some(QLJSCategorySeverity::Warning),
+            3 => Some(QLJSCategorySeverity::Off),
+            _ => None,
+        }
+    }
+}
+
+// A bit set (i.e. flags) which tells editors how to render the code a
+// diagnostic refers to. Corresponds to LSP's DiagnosticTag.
+pub type QLJSDiagnosticTags = std::ffi::c_int;
+
+// If set, the diagnosed code is unused or unreachable. Editors may gray it out.
+pub const QLJS_DIAGNOSTIC_TAG_UNNECESSARY_BIT: QLJSDiagnosticTags = 1 << 0;
+
+// If set, the diagnosed code uses a deprecated feature. Editors may strike it
+// through.
+pub const QLJS_DIAGNOSTIC_TAG_DEPRECATED_BIT: QLJSDiagnosticTags = 1 << 1;
+
+// The outcome of the most recent call to qljs_web_demo_lint(document).
+//
+// To get a document's lint status, call qljs_web_demo_get_lint_status.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(C)]
+pub enum QLJSLintStatus {
+    // qljs_web_demo_lint has not been called for the document.
+    NotLinted = 0,
+    // qljs_web_demo_lint returned every diagnostic.
+    Completed = 1,
+    // qljs_web_demo_cancel stopped qljs_web_demo_lint early.
+    // qljs_web_demo_lint returned no diagnostics.
+    Cancelled = 2,
+}
+
+// How offsets (such as QLJSWebDemoDiagnostic::begin_offset) count characters.
+// Corresponds to LSP's PositionEncodingKind.
+//
+// To change a document's position encoding, call
+// qljs_web_demo_set_position_encoding.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(C)]
+pub enum QLJSPositionEncoding {
+    // Count bytes.
+    UTF8 = 1,
+    // Count UTF-16 code units, like JavaScript strings.
+    UTF16 = 2,
+    // Count code points.
+    UTF32 = 3,
+}
+
+impl QLJSPositionEncoding {
+    // Returns None if value is not a QLJSPositionEncoding.
+    pub fn from_c_int(value: std::ffi::c_int) -> Option<QLJSPositionEncoding> {
+        match value {
+            1 => Some(QLJSPositionEncoding::UTF8),
+            2 => Some(QLJSPositionEncoding::UTF16),
+            3 => Some(QLJSPositionEncoding::UTF32),
+            _ => None,
+        }
+    }
+
+    pub fn to_position_encoding(self) -> PositionEncoding {
+        match self {
+            QLJSPositionEncoding::UTF8 => PositionEncoding::UTF8,
+            QLJSPositionEncoding::UTF16 => PositionEncoding::UTF16,
+            QLJSPositionEncoding::UTF32 => PositionEncoding::UTF32,
+        }
+    }
+}
+
 // A QLJSWebDemoDocument is a text document.
 //
 // A QLJSWebDemoDocument contains the following state:
 //
-// * Text, changed using qljs_web_demo_set_text
+// * Text, changed using qljs_web_demo_set_text or qljs_web_demo_replace_text
 // * Language options, changed using qljs_web_demo_set_language_options
 // * Configuration document, changed using qljs_web_demo_set_config
 // * Locale, changed using qljs_web_demo_set_locale
-// * Output diagnostics, changed using qljs_web_demo_lint
+// * Category severities, changed using qljs_web_demo_set_category_severity
+// * Diagnostic limits, changed using qljs_web_demo_set_diagnostic_limits
+// * Position encoding, changed using qljs_web_demo_set_position_encoding
+// * Output diagnostics and lint status, changed using qljs_web_demo_lint
 //
 // QLJSWebDemoDocument objects are allocated dynamically. To create a
 // QLJSWebDemoDocument, call qljs_web_demo_create_document. When you are
@@ -70,21 +211,32 @@ pub enum QLJSSeverity {
 // on thread 2, then these calls must be synchronized by you.
 //
 // A mutex is sufficient synchronization.
+//
+// Exception: qljs_web_demo_cancel(A) can be called from any thread without
+// synchronization, including while qljs_web_demo_lint(A) is running on another
+// thread.
 pub struct QLJSWebDemoDocument {
-    text: PaddedString,
+    document: Document<WebDemoLocator</* HACK(strager) */ 'static>>,
     diag_reporter: CAPIDiagReporter</* HACK(strager) */ 'static>,
     linter_options: LinterOptions,
+    diagnostic_limits: DiagnosticLimits,
     is_config_json: bool,
     config_document: *mut QLJSWebDemoDocument,
     need_update_config: bool,
+    // Shared with qljs_web_demo_cancel, which might be running on another
+    // thread.
+    cancellation: CancellationToken,
+    lint_status: QLJSLintStatus,
 }
 
+#[derive(Clone, Copy)]
 #[repr(C)]
 pub struct QLJSWebDemoDiagnostic {
     pub message: *const u8,
     pub code: [std::ffi::c_char; 6], // null-terminated
     pub severity: QLJSSeverity,
-    // Offsets count UTF-16 code units.
+    // Offsets count characters according to the document's position encoding
+    // (UTF-16 code units by default).
     pub begin_offset: std::ffi::c_int,
     pub end_offset: std::ffi::c_int,
 }
@@ -101,6 +253,81 @@ impl Default for QLJSWebDemoDiagnostic {
     }
 }
 
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct QLJSWebDemoDiagnosticNote {
+    pub message: *const u8,
+    // Offsets count characters according to the document's position encoding
+    // (UTF-16 code units by default).
+    pub begin_offset: std::ffi::c_int,
+    pub end_offset: std::ffi::c_int,
+}
+
+impl Default for QLJSWebDemoDiagnosticNote {
+    fn default() -> Self {
+        QLJSWebDemoDiagnosticNote {
+            message: std::ptr::null(),
+            begin_offset: 0,
+            end_offset: 0,
+        }
+    }
+}
+
+// A QLJSWebDemoDiagnostic with line and column numbers, notes, category, and
+// tags. Returned by qljs_web_demo_lint_ex.
+//
+// Lines and columns are 0-based, like LSP positions. Columns count characters
+// according to the document's position encoding (UTF-16 code units by
+// default). Lines are terminated by CR, LF, CRLF, LS (U+2028), or PS (U+2029).
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct QLJSWebDemoDiagnosticEx {
+    pub diagnostic: QLJSWebDemoDiagnostic,
+    pub begin_line: std::ffi::c_int,
+    pub begin_column: std::ffi::c_int,
+    pub end_line: std::ffi::c_int,
+    pub end_column: std::ffi::c_int,
+    // Secondary locations which give context for this diagnostic. In LSP terms,
+    // these are the diagnostic's relatedInformation.
+    //
+    // notes points to an array terminated by an item where
+    // QLJSWebDemoDiagnosticNoteEx::note.message is null.
+    //
+    // notes is null only for the item terminating the array returned by
+    // qljs_web_demo_lint_ex.
+    pub notes: *const QLJSWebDemoDiagnosticNoteEx,
+    pub category: QLJSDiagnosticCategory,
+    // A bitwise-or of zero or more QLJS_DIAGNOSTIC_TAG_*_BIT values.
+    pub tags: QLJSDiagnosticTags,
+}
+
+impl Default for QLJSWebDemoDiagnosticEx {
+    fn default() -> Self {
+        QLJSWebDemoDiagnosticEx {
+            diagnostic: QLJSWebDemoDiagnostic::default(),
+            begin_line: 0,
+            begin_column: 0,
+            end_line: 0,
+            end_column: 0,
+            notes: std::ptr::null(),
+            category: QLJSDiagnosticCategory::Correctness,
+            tags: 0,
+        }
+    }
+}
+
+// A QLJSWebDemoDiagnosticNote with line and column numbers. See
+// QLJSWebDemoDiagnosticEx.
+#[derive(Clone, Copy, Default)]
+#[repr(C)]
+pub struct QLJSWebDemoDiagnosticNoteEx {
+    pub note: QLJSWebDemoDiagnosticNote,
+    pub begin_line: std::ffi::c_int,
+    pub begin_column: std::ffi::c_int,
+    pub end_line: std::ffi::c_int,
+    pub end_column: std::ffi::c_int,
+}
+
 // Create a new document.
 //
 // The new document ('d') has the following state:
@@ -110,7 +337,15 @@ impl Default for QLJSWebDemoDiagnostic {
 // * No configuration document, as if by qljs_web_demo_set_config(d, NULL)
 // * A default locale, as if by qljs_web_demo_set_locale(d, default_locale)
 //   * TODO(strager): What is default_locale?
+// * Default category severities, as if by
+//   qljs_web_demo_set_category_severity(d, category, QLJSCategorySeverity::Default)
+//   for each category
+// * Default diagnostic limits (at most 1000 diagnostics, and at most 100
+//   diagnostics per error code)
+// * UTF-16 offsets, as if by
+//   qljs_web_demo_set_position_encoding(d, QLJSPositionEncoding::UTF16)
 // * Unspecified output diagnostics
+// * QLJSLintStatus::NotLinted lint status
 //
 // Thread safety: Thread-safe. Not async-signal-safe.
 //
@@ -118,12 +353,18 @@ impl Default for QLJSWebDemoDiagnostic {
 #[no_mangle]
 pub unsafe extern "C" fn qljs_web_demo_create_document() -> *mut QLJSWebDemoDocument {
     let p: Box<QLJSWebDemoDocument> = Box::new(QLJSWebDemoDocument {
-        text: PaddedString::new(),
+        document: Document::new(),
         diag_reporter: CAPIDiagReporter::new(),
-        linter_options: LinterOptions::default(),
+        linter_options: LinterOptions {
+            bail_out_on_binary: true,
+            ..LinterOptions::default()
+        },
+        diagnostic_limits: DiagnosticLimits::default(),
         is_config_json: false,
         config_document: std::ptr::null_mut(),
         need_update_config: true,
+        cancellation: CancellationToken::new(),
+        lint_status: QLJSLintStatus::NotLinted,
     });
     Box::leak(p) as *mut _
 }
@@ -168,12 +409,52 @@ pub unsafe extern "C" fn qljs_web_demo_set_text(
     text_utf_8: *const std::ffi::c_void,
     text_byte_count: c_size_t,
 ) {
-    (*p).text = PaddedString::from_slice(std::slice::from_raw_parts(
+    (*p).document.set_text(std::slice::from_raw_parts(
         text_utf_8 as *const u8,
         text_byte_count,
     ));
 }
 
+// Replace part of the document's text, leaving the rest unchanged.
+//
+// begin_offset and end_offset count characters (according to the document's
+// position encoding) from the beginning of the document's text, like
+// QLJSWebDemoDiagnostic::begin_offset and QLJSWebDemoDiagnostic::end_offset.
+// The text in [begin_offset, end_offset) is replaced with replacement_utf_8. If
+// begin_offset == end_offset, replacement_utf_8 is inserted.
+//
+// An offset past the end of the document's text is treated as the end of the
+// text. With the UTF-16 position encoding, an offset in the middle of a
+// surrogate pair is treated as the beginning of the surrogate pair. If
+// begin_offset > end_offset, the offsets are swapped.
+//
+// qljs_web_demo_replace_text makes an internal copy of the given array.
+//
+// Thread safety: See NOTE[QLJSWebDemoDocument threads].
+//
+// Precondition: qljs_web_demo_create_document() returned document, and
+//               qljs_web_demo_destroy_document(document) has not been called.
+// Precondition: replacement_utf_8 points to an array of at least
+//               replacement_byte_count bytes.
+// Precondition: replacement_utf_8 is not null, even if replacement_byte_count
+//               is 0.
+#[no_mangle]
+pub unsafe extern "C" fn qljs_web_demo_replace_text(
+    p: *mut QLJSWebDemoDocument,
+    begin_offset: WebDemoSourceOffset,
+    end_offset: WebDemoSourceOffset,
+    replacement_utf_8: *const std::ffi::c_void,
+    replacement_byte_count: c_size_t,
+) {
+    (*p).document.replace_text(
+        WebDemoSourceRange {
+            begin: begin_offset,
+            end: end_offset,
+        },
+        std::slice::from_raw_parts(replacement_utf_8 as *const u8, replacement_byte_count),
+    );
+}
+
 // When running qljs_web_demo_lint(js_document), treat config_document's text as
 // if it was js_document's associated quick-lint-js.config file.
 //
@@ -245,9 +526,106 @@ pub unsafe extern "C" fn qljs_web_demo_set_locale(
     (*p).diag_reporter.set_translator(t);
 }
 
+// Change how qljs_web_demo_lint(document) reports diagnostics in the given
+// category.
+//
+// If severity is QLJSCategorySeverity::Off, then qljs_web_demo_lint omits
+// diagnostics in the category. If severity is QLJSCategorySeverity::Error or
+// QLJSCategorySeverity::Warning, then qljs_web_demo_lint reports diagnostics in
+// the category with that severity.
+//
+// category is a QLJSDiagnosticCategory and severity is a QLJSCategorySeverity.
+// If either is not a known value, qljs_web_demo_set_category_severity does
+// nothing.
+//
+// Thread safety: See NOTE[QLJSWebDemoDocument threads].
+//
+// Precondition: qljs_web_demo_create_document() returned document, and
+//               qljs_web_demo_destroy_document(document) has not been called.
+#[no_mangle]
+pub unsafe extern "C" fn qljs_web_demo_set_category_severity(
+    p: *mut QLJSWebDemoDocument,
+    category: std::ffi::c_int,
+    severity: std::ffi::c_int,
+) {
+    let Some(category) = QLJSDiagnosticCategory::from_c_int(category) else {
+        return;
+    };
+    let Some(severity) = QLJSCategorySeverity::from_c_int(severity) else {
+        return;
+    };
+    let severity: CategorySeverity = match severity {
+        QLJSCategorySeverity::Default => CategorySeverity::Default,
+        QLJSCategorySeverity::Error => CategorySeverity::Error,
+        QLJSCategorySeverity::Warning => CategorySeverity::Warning,
+        QLJSCategorySeverity::Off => CategorySeverity::Off,
+    };
+    (*p).diag_reporter
+        .set_category_severity(category.to_category(), severity);
+}
+
+// Limit how many diagnostics qljs_web_demo_lint(document) reports.
+//
+// qljs_web_demo_lint reports at most max_total diagnostics, and at most
+// max_per_code diagnostics with the same error code. If either limit is 0, that
+// limit is disabled. Diagnostics in categories turned off with
+// qljs_web_demo_set_category_severity do not count towards the limits.
+//
+// If a limit is hit, qljs_web_demo_lint omits further diagnostics and instead
+// reports a single E0300 diagnostic at the end of the document, after all
+// other diagnostics. E0300's message says how many diagnostics were omitted.
+//
+// Thread safety: See NOTE[QLJSWebDemoDocument threads].
+//
+// Precondition: qljs_web_demo_create_document() returned document, and
+//               qljs_web_demo_destroy_document(document) has not been called.
+#[no_mangle]
+pub unsafe extern "C" fn qljs_web_demo_set_diagnostic_limits(
+    p: *mut QLJSWebDemoDocument,
+    max_total: c_size_t,
+    max_per_code: c_size_t,
+) {
+    let to_limit = |limit: c_size_t| -> Option<usize> {
+        if limit == 0 {
+            None
+        } else {
+            Some(limit)
+        }
+    };
+    (*p).diagnostic_limits = DiagnosticLimits {
+        max_total: to_limit(max_total),
+        max_per_code: to_limit(max_per_code),
+    };
+}
+
+// Change how offsets count characters, both in qljs_web_demo_replace_text's
+// arguments and in diagnostics returned by qljs_web_demo_lint(document).
+//
+// Diagnostics returned by previous qljs_web_demo_lint calls are unaffected.
+//
+// encoding is a QLJSPositionEncoding. If it is not a known value,
+// qljs_web_demo_set_position_encoding does nothing.
+//
+// Thread safety: See NOTE[QLJSWebDemoDocument threads].
+//
+// Precondition: qljs_web_demo_create_document() returned document, and
+//               qljs_web_demo_destroy_document(document) has not been called.
+#[no_mangle]
+pub unsafe extern "C" fn qljs_web_demo_set_position_encoding(
+    p: *mut QLJSWebDemoDocument,
+    encoding: std::ffi::c_int,
+) {
+    let Some(encoding) = QLJSPositionEncoding::from_c_int(encoding) else {
+        return;
+    };
+    let encoding: PositionEncoding = encoding.to_position_encoding();
+    (*p).document.locator_mut().set_position_encoding(encoding);
+    (*p).diag_reporter.set_position_encoding(encoding);
+}
+
 // Parse and lint document's text [1], according to its language options [2] and
 // config [3], and return a list of diagnostics according to document's
-// locale [4].
+// locale [4] and limits [5].
 //
 // The returned pointer refers to an array of qljs_web_demo_diagnostic objects.
 // The array is terminated by an item where:
@@ -262,6 +640,12 @@ pub unsafe extern "C" fn qljs_web_demo_set_locale(
 // [2] qljs_web_demo_set_language_options
 // [3] qljs_web_demo_set_config
 // [4] qljs_web_demo_set_locale
+// [5] qljs_web_demo_set_diagnostic_limits
+//
+// If qljs_web_demo_cancel(document) is called while qljs_web_demo_lint is
+// running, qljs_web_demo_lint stops early and returns an empty list. Call
+// qljs_web_demo_get_lint_status to tell this apart from a document without
+// diagnostics.
 //
 // Thread safety: See NOTE[QLJSWebDemoDocument threads].
 //
@@ -275,12 +659,108 @@ pub unsafe extern "C" fn qljs_web_demo_set_locale(
 pub unsafe extern "C" fn qljs_web_demo_lint(
     p: *mut QLJSWebDemoDocument,
 ) -> *const QLJSWebDemoDiagnostic {
+    lint_document(p, false);
+    (*p).diag_reporter.get_diagnostics()
+}
+
+// Lint document into its diag_reporter. See qljs_web_demo_lint.
+//
+// If compute_line_numbers is false, the reported diagnostics have no line or
+// column numbers.
+unsafe fn lint_document(p: *mut QLJSWebDemoDocument, compute_line_numbers: bool) {
+    // Forget cancellations which happened before this lint started.
+    (*p).cancellation.reset();
     (*p).diag_reporter.reset();
-    (*p).diag_reporter.set_input((*p).text.view());
+    (*p).diag_reporter
+        .set_input((*p).document.string(), compute_line_numbers);
+    (*p).lint_status = QLJSLintStatus::Completed;
     if !(*p).is_config_json {
-        parse_and_lint((*p).text.view(), &(*p).diag_reporter, (*p).linter_options);
+        let mut sorter: SortingDiagReporter = SortingDiagReporter::new();
+        let linter_options: LinterOptions = LinterOptions {
+            cancellation: Some((*p).cancellation.clone()),
+            ..(*p).linter_options.clone()
+        };
+        let status: LintStatus = parse_and_lint((*p).document.string(), &sorter, linter_options);
+        match status {
+            LintStatus::Completed => {
+                // Limit after sorting so that duplicates (which the sorter
+                // drops) and diagnostics in Off categories do not count
+                // towards the limits.
+                let mut limiter: LimitingDiagReporter =
+                    LimitingDiagReporter::new(&(*p).diag_reporter, (*p).diagnostic_limits);
+                limiter.set_category_filter((*p).diag_reporter.category_filter().clone());
+                sorter.move_into(&limiter);
+                // Report the summary after the sorted diagnostics so that it
+                // comes last.
+                if let Some(summary) = limiter.summary((*p).document.string()) {
+                    report(&(*p).diag_reporter, summary);
+                }
+            }
+            LintStatus::Cancelled => (*p).lint_status = QLJSLintStatus::Cancelled,
+        }
     }
-    (*p).diag_reporter.get_diagnostics()
+}
+
+// Like qljs_web_demo_lint, but each returned diagnostic also has line and
+// column numbers, notes, a category, and tags. See QLJSWebDemoDiagnosticEx for
+// details.
+//
+// The returned array is terminated by an item where
+// qljs_web_demo_diagnostic_ex::diagnostic.message is null.
+//
+// Each diagnostic's notes (qljs_web_demo_diagnostic_ex::notes) are valid for
+// as long as the returned pointer is valid.
+//
+// The returned pointer is valid until either the next call to
+// qljs_web_demo_lint(document), the next call to
+// qljs_web_demo_lint_ex(document), or a call to
+// qljs_web_demo_destroy_document(document), whichever comes first.
+//
+// qljs_web_demo_cancel(document) and qljs_web_demo_get_lint_status(document)
+// work the same as with qljs_web_demo_lint.
+//
+// Thread safety: See NOTE[QLJSWebDemoDocument threads].
+//
+// Precondition: See qljs_web_demo_lint.
+// Postcondition: The returned value is not null.
+#[no_mangle]
+pub unsafe extern "C" fn qljs_web_demo_lint_ex(
+    p: *mut QLJSWebDemoDocument,
+) -> *const QLJSWebDemoDiagnosticEx {
+    lint_document(p, true);
+    (*p).diag_reporter.get_diagnostics_ex()
+}
+
+// Ask a running qljs_web_demo_lint(document) to stop early.
+//
+// If qljs_web_demo_lint(document) is not running, qljs_web_demo_cancel has no
+// effect. In particular, it does not cancel the next qljs_web_demo_lint call.
+//
+// Thread safety: Thread-safe. Not async-signal-safe. See
+// NOTE[QLJSWebDemoDocument threads].
+//
+// Precondition: qljs_web_demo_create_document() returned document, and
+//               qljs_web_demo_destroy_document(document) has not been called.
+#[no_mangle]
+pub unsafe extern "C" fn qljs_web_demo_cancel(p: *const QLJSWebDemoDocument) {
+    // NOTE(port): Only touch the cancellation field. Other fields might be in
+    // use by qljs_web_demo_lint on another thread.
+    let cancellation: &CancellationToken = &*std::ptr::addr_of!((*p).cancellation);
+    cancellation.cancel();
+}
+
+// Returns whether the most recent qljs_web_demo_lint(document) call finished
+// or was cancelled.
+//
+// Thread safety: See NOTE[QLJSWebDemoDocument threads].
+//
+// Precondition: qljs_web_demo_create_document() returned document, and
+//               qljs_web_demo_destroy_document(document) has not been called.
+#[no_mangle]
+pub unsafe extern "C" fn qljs_web_demo_get_lint_status(
+    p: *const QLJSWebDemoDocument,
+) -> QLJSLintStatus {
+    (*p).lint_status
 }
 
 // Returns a null-terminated array of null-terminated strings.
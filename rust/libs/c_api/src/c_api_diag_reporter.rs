@@ -6,6 +6,7 @@ use cpp_vs_rust_fe::diag_reporter::*;
 use cpp_vs_rust_fe::diagnostic::*;
 use cpp_vs_rust_fe::diagnostic_formatter::*;
 use cpp_vs_rust_fe::diagnostic_types::*;
+use cpp_vs_rust_fe::lsp_location::*;
 use cpp_vs_rust_fe::source_code_span::*;
 use cpp_vs_rust_i18n::translation::*;
 use cpp_vs_rust_port::maybe_uninit::*;
@@ -13,17 +14,27 @@ use cpp_vs_rust_util::narrow_cast::*;
 use cpp_vs_rust_util::padded_string::*;
 use cpp_vs_rust_util::qljs_assert;
 use cpp_vs_rust_util::qljs_const_assert;
+use cpp_vs_rust_util::utf_8::*;
 
 // NOTE(port): The C++ code had a generic Diagnostic parameter. KISS by inlining it to
 // QLJSWebDemoDiagnostic.
 // NOTE(port): The C++ code had a generic Locator parameter. KISS by inlining it to
-// WebDemoLocator.
+// CAPILocator.
 pub struct CAPIDiagReporter<'code> {
     translator: Translator,
-    diagnostics: std::cell::UnsafeCell<Vec<QLJSWebDemoDiagnostic>>,
+    category_filter: DiagnosticCategoryFilter,
+    // NOTE(port): diagnostics does not include a terminator item.
+    // get_diagnostics and get_diagnostics_ex copy diagnostics into
+    // returned_diagnostics or returned_diagnostics_ex.
+    diagnostics: std::cell::UnsafeCell<Vec<QLJSWebDemoDiagnosticEx>>,
+    returned_diagnostics: Vec<QLJSWebDemoDiagnostic>,
+    returned_diagnostics_ex: Vec<QLJSWebDemoDiagnosticEx>,
     _input: *const u8,
     input_phantom: std::marker::PhantomData<&'code [u8]>,
-    locator: Option<WebDemoLocator<'code>>,
+    locator: Option<CAPILocator<'code>>,
+    // If false, diagnostics have zero line and column numbers.
+    compute_line_numbers: bool,
+    position_encoding: PositionEncoding,
     string_allocator: MonotonicAllocator,
 }
 
@@ -31,20 +42,33 @@ impl<'code> CAPIDiagReporter<'code> {
     pub fn new() -> Self {
         CAPIDiagReporter {
             translator: Translator::new_using_messages_from_source_code(),
+            category_filter: DiagnosticCategoryFilter::new(),
             diagnostics: std::cell::UnsafeCell::new(vec![]),
+            returned_diagnostics: vec![],
+            returned_diagnostics_ex: vec![],
             _input: std::ptr::null(),
             input_phantom: std::marker::PhantomData,
             locator: None,
+            compute_line_numbers: false,
+            position_encoding: PositionEncoding::UTF16,
             string_allocator: MonotonicAllocator::new("c_api_diag_reporter::string_allocator_"),
         }
     }
 
-    pub fn set_input(&mut self, input: PaddedStringView<'code>) {
+    // If compute_line_numbers is false, diagnostics reported for this input
+    // have zero line and column numbers.
+    pub fn set_input(&mut self, input: PaddedStringView<'code>, compute_line_numbers: bool) {
         self._input = input.c_str();
-        self.locator = Some(WebDemoLocator::new(input));
+        self.locator = Some(CAPILocator::new(input, self.position_encoding));
+        self.compute_line_numbers = compute_line_numbers;
     }
 
-    // Does not reset translator.
+    // Takes effect at the next call to set_input.
+    pub fn set_position_encoding(&mut self, encoding: PositionEncoding) {
+        self.position_encoding = encoding;
+    }
+
+    // Does not reset translator or category severities.
     pub fn reset(&mut self) {
         self.diagnostics.get_mut().clear();
         // TODO(strager): Release allocated string memory.
@@ -54,13 +78,43 @@ impl<'code> CAPIDiagReporter<'code> {
         self.translator = t;
     }
 
+    pub fn set_category_severity(
+        &mut self,
+        category: DiagnosticCategory,
+        severity: CategorySeverity,
+    ) {
+        self.category_filter.set(category, severity);
+    }
+
+    pub fn category_filter(&self) -> &DiagnosticCategoryFilter {
+        &self.category_filter
+    }
+
+    // The returned pointer is valid until the next call to get_diagnostics,
+    // get_diagnostics_ex, or reset.
     pub fn get_diagnostics(&mut self) -> *const QLJSWebDemoDiagnostic {
+        self.returned_diagnostics.clear();
+        self.returned_diagnostics.extend(
+            self.diagnostics
+                .get_mut()
+                .iter()
+                .map(|diag: &QLJSWebDemoDiagnosticEx| diag.diagnostic),
+        );
         // Null-terminate the returned diagnostics.
-        self.diagnostics
-            .get_mut()
+        self.returned_diagnostics
             .push(QLJSWebDemoDiagnostic::default());
+        self.returned_diagnostics.as_ptr()
+    }
 
-        self.diagnostics.get_mut().as_ptr()
+    // Like get_diagnostics, but include line and column numbers.
+    pub fn get_diagnostics_ex(&mut self) -> *const QLJSWebDemoDiagnosticEx {
+        self.returned_diagnostics_ex.clear();
+        self.returned_diagnostics_ex
+            .extend_from_slice(self.diagnostics.get_mut());
+        // Null-terminate the returned diagnostics.
+        self.returned_diagnostics_ex
+            .push(QLJSWebDemoDiagnosticEx::default());
+        self.returned_diagnostics_ex.as_ptr()
     }
 
     fn allocate_c_string<'this>(&'this self, string: &[u8]) -> &'this [u8] {
@@ -71,18 +125,38 @@ impl<'code> CAPIDiagReporter<'code> {
         result[string.len()].write(b'\0');
         unsafe { slice_assume_init_ref(result) }
     }
+
+    // Returns a copy of notes followed by a terminator note.
+    fn allocate_notes<'this>(
+        &'this self,
+        notes: &[QLJSWebDemoDiagnosticNoteEx],
+    ) -> &'this [QLJSWebDemoDiagnosticNoteEx] {
+        let result: &'this mut [std::mem::MaybeUninit<QLJSWebDemoDiagnosticNoteEx>] = self
+            .string_allocator
+            .allocate_uninitialized_array::<QLJSWebDemoDiagnosticNoteEx>(notes.len() + 1);
+        write_slice(&mut result[0..notes.len()], notes);
+        result[notes.len()].write(QLJSWebDemoDiagnosticNoteEx::default());
+        unsafe { slice_assume_init_ref(result) }
+    }
 }
 
 impl<'code> DiagReporter for CAPIDiagReporter<'code> {
     fn report_impl(&self, type_: DiagType, diag: *const u8) {
+        let info: &DiagnosticInfo = get_diagnostic_info(type_);
+        let Some(severity) = self.category_filter.severity(info) else {
+            return;
+        };
         let mut formatter = CAPIDiagFormatter::new(self);
-        formatter.format(get_diagnostic_info(type_), diag);
+        formatter.format_with_severity(info, severity, diag);
+        formatter.finish();
     }
 }
 
 struct CAPIDiagFormatter<'code, 'reporter> {
     reporter: &'reporter CAPIDiagReporter<'code>,
     current_message: Vec<u8>,
+    diagnostic: QLJSWebDemoDiagnosticEx,
+    notes: Vec<QLJSWebDemoDiagnosticNoteEx>,
 }
 
 impl<'code, 'reporter> CAPIDiagFormatter<'code, 'reporter> {
@@ -90,6 +164,18 @@ impl<'code, 'reporter> CAPIDiagFormatter<'code, 'reporter> {
         CAPIDiagFormatter {
             reporter: reporter,
             current_message: vec![],
+            diagnostic: QLJSWebDemoDiagnosticEx::default(),
+            notes: vec![],
+        }
+    }
+
+    // Add the formatted diagnostic and its notes to the reporter.
+    fn finish(self) {
+        let mut diag: QLJSWebDemoDiagnosticEx = self.diagnostic;
+        qljs_assert!(!diag.diagnostic.message.is_null());
+        diag.notes = self.reporter.allocate_notes(&self.notes).as_ptr();
+        unsafe {
+            (*self.reporter.diagnostics.get()).push(diag);
         }
     }
 }
@@ -98,21 +184,13 @@ impl<'code, 'reporter> DiagnosticFormatter for CAPIDiagFormatter<'code, 'reporte
     fn write_before_message(
         &mut self,
         _code: &str,
-        sev: DiagnosticSeverity,
+        _sev: DiagnosticSeverity,
         _origin: SourceCodeSpan<'_>,
     ) {
-        if sev == DiagnosticSeverity::Note {
-            // Don't write notes. Only write the main message.
-            return;
-        }
         qljs_assert!(self.current_message.is_empty());
     }
 
-    fn write_message_part(&mut self, _code: &str, sev: DiagnosticSeverity, message: &[u8]) {
-        if sev == DiagnosticSeverity::Note {
-            // Don't write notes. Only write the main message.
-            return;
-        }
+    fn write_message_part(&mut self, _code: &str, _sev: DiagnosticSeverity, message: &[u8]) {
         self.current_message.extend_from_slice(message);
     }
 
@@ -122,16 +200,52 @@ impl<'code, 'reporter> DiagnosticFormatter for CAPIDiagFormatter<'code, 'reporte
         sev: DiagnosticSeverity,
         origin: SourceCodeSpan<'_>,
     ) {
+        let locator: &CAPILocator = self.reporter.locator.as_ref().unwrap();
+        let lines: LSPRange = locator.lines.range(origin);
+        let r: WebDemoSourceRange = WebDemoSourceRange {
+            begin: locator.offset(lines.start),
+            end: locator.offset(lines.end),
+        };
+        let (begin_line, begin_column, end_line, end_column): (i32, i32, i32, i32) =
+            if self.reporter.compute_line_numbers {
+                (
+                    narrow_cast::<i32, _>(lines.start.line),
+                    narrow_cast::<i32, _>(lines.start.character),
+                    narrow_cast::<i32, _>(lines.end.line),
+                    narrow_cast::<i32, _>(lines.end.character),
+                )
+            } else {
+                (0, 0, 0, 0)
+            };
+        let message: *const u8 = self
+            .reporter
+            .allocate_c_string(self.current_message.as_slice())
+            .as_ptr();
+        self.current_message.clear();
+
         let diag_severity: QLJSSeverity = match sev {
             DiagnosticSeverity::Note => {
-                // Don't write notes. Only write the main message.
+                self.notes.push(QLJSWebDemoDiagnosticNoteEx {
+                    note: QLJSWebDemoDiagnosticNote {
+                        message: message,
+                        begin_offset: narrow_cast::<i32, _>(r.begin),
+                        end_offset: narrow_cast::<i32, _>(r.end),
+                    },
+                    begin_line: begin_line,
+                    begin_column: begin_column,
+                    end_line: end_line,
+                    end_column: end_column,
+                });
                 return;
             }
             DiagnosticSeverity::Error => QLJSSeverity::Error,
             DiagnosticSeverity::Warning => QLJSSeverity::Warning,
         };
-        let mut diag: QLJSWebDemoDiagnostic = QLJSWebDemoDiagnostic::default();
-        let r: WebDemoSourceRange = self.reporter.locator.as_ref().unwrap().range(origin);
+        self.diagnostic.begin_line = begin_line;
+        self.diagnostic.begin_column = begin_column;
+        self.diagnostic.end_line = end_line;
+        self.diagnostic.end_column = end_column;
+        let diag: &mut QLJSWebDemoDiagnostic = &mut self.diagnostic.diagnostic;
         diag.begin_offset = narrow_cast::<i32, _>(r.begin);
         diag.end_offset = narrow_cast::<i32, _>(r.end);
 
@@ -141,18 +255,64 @@ impl<'code, 'reporter> DiagnosticFormatter for CAPIDiagFormatter<'code, 'reporte
         });
         diag.code[code.len()] = b'\0' as std::ffi::c_char;
 
-        diag.message = self
-            .reporter
-            .allocate_c_string(self.current_message.as_slice())
-            .as_ptr();
+        diag.message = message;
         diag.severity = diag_severity;
-
-        unsafe {
-            (*self.reporter.diagnostics.get()).push(diag);
-        }
     }
 
     fn translator(&self) -> Translator {
         self.reporter.translator.clone()
     }
+
+    fn write_category_and_tags(&mut self, category: DiagnosticCategory, tags: DiagnosticTags) {
+        self.diagnostic.category = QLJSDiagnosticCategory::from_category(category);
+        let mut c_tags: QLJSDiagnosticTags = 0;
+        if tags.contains(DiagnosticTag::Unnecessary) {
+            c_tags |= QLJS_DIAGNOSTIC_TAG_UNNECESSARY_BIT;
+        }
+        if tags.contains(DiagnosticTag::Deprecated) {
+            c_tags |= QLJS_DIAGNOSTIC_TAG_DEPRECATED_BIT;
+        }
+        self.diagnostic.tags = c_tags;
+    }
+}
+
+// Converts pointers into the input to offsets from the beginning of the input
+// (like WebDemoLocator) and to line and column numbers.
+//
+// Offsets are computed from LSPLocator's line table, so locating a pointer
+// does not need to scan the input from its beginning.
+struct CAPILocator<'code> {
+    lines: LSPLocator<'code>,
+    // character_offset_of_lines[i] is the number of characters (according to
+    // the position encoding) before line i.
+    character_offset_of_lines: Vec<usize>,
+}
+
+impl<'code> CAPILocator<'code> {
+    fn new(input: PaddedStringView<'code>, encoding: PositionEncoding) -> CAPILocator<'code> {
+        let lines: LSPLocator<'code> = LSPLocator::new_with_encoding(input, encoding);
+        let offset_of_lines: &[LSPOffset] = lines.offset_of_lines();
+        let mut character_offset_of_lines: Vec<usize> = Vec::with_capacity(offset_of_lines.len());
+        let mut character_offset: usize = 0;
+        for (line_index, line_offset) in offset_of_lines.iter().enumerate() {
+            character_offset_of_lines.push(character_offset);
+            if let Some(next_line_offset) = offset_of_lines.get(line_index + 1) {
+                character_offset += count_characters_in_utf_8(
+                    input.substr(narrow_cast::<PaddedStringSizeType, _>(*line_offset)),
+                    next_line_offset - line_offset,
+                    encoding,
+                );
+            }
+        }
+        CAPILocator {
+            lines: lines,
+            character_offset_of_lines: character_offset_of_lines,
+        }
+    }
+
+    fn offset(&self, position: LSPPosition) -> WebDemoSourceOffset {
+        narrow_cast::<WebDemoSourceOffset, _>(
+            self.character_offset_of_lines[position.line as usize] + position.character as usize,
+        )
+    }
 }
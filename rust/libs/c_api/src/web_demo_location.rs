@@ -11,13 +11,55 @@ pub struct WebDemoSourceRange {
     pub end: WebDemoSourceOffset,
 }
 
+impl SourceRangeLike for WebDemoSourceRange {
+    type PositionType = WebDemoSourceOffset;
+
+    fn new(begin: WebDemoSourceOffset, end: WebDemoSourceOffset) -> WebDemoSourceRange {
+        WebDemoSourceRange {
+            begin: begin,
+            end: end,
+        }
+    }
+
+    fn begin(&self) -> WebDemoSourceOffset {
+        self.begin
+    }
+
+    fn end(&self) -> WebDemoSourceOffset {
+        self.end
+    }
+}
+
+// Converts between pointers into the input and offsets from the beginning of
+// the input.
+//
+// By default, offsets count UTF-16 code units, like JavaScript strings.
 pub struct WebDemoLocator<'code> {
     input: PaddedStringView<'code>,
+    encoding: PositionEncoding,
 }
 
 impl<'code> WebDemoLocator<'code> {
     pub fn new(input: PaddedStringView<'code>) -> WebDemoLocator<'code> {
-        WebDemoLocator { input: input }
+        WebDemoLocator::new_with_encoding(input, PositionEncoding::UTF16)
+    }
+
+    pub fn new_with_encoding(
+        input: PaddedStringView<'code>,
+        encoding: PositionEncoding,
+    ) -> WebDemoLocator<'code> {
+        WebDemoLocator {
+            input: input,
+            encoding: encoding,
+        }
+    }
+
+    pub fn position_encoding(&self) -> PositionEncoding {
+        self.encoding
+    }
+
+    pub fn set_position_encoding(&mut self, encoding: PositionEncoding) {
+        self.encoding = encoding;
     }
 
     pub fn range(&self, span: SourceCodeSpan<'_>) -> WebDemoSourceRange {
@@ -28,15 +70,27 @@ impl<'code> WebDemoLocator<'code> {
     }
 
     pub fn position(&self, c: *const u8) -> WebDemoSourceOffset {
-        let byte_offset: i32 = narrow_cast::<i32, _>(unsafe { c.offset_from(self.input.c_str()) });
-        narrow_cast::<WebDemoSourceOffset, _>(count_lsp_characters_in_utf_8(
+        let byte_offset: usize =
+            narrow_cast::<usize, _>(unsafe { c.offset_from(self.input.c_str()) });
+        narrow_cast::<WebDemoSourceOffset, _>(count_characters_in_utf_8(
             self.input,
             byte_offset,
+            self.encoding,
         ))
     }
+
+    pub fn pointer_at_position(&self, position: WebDemoSourceOffset) -> *const u8 {
+        let byte_offset: PaddedStringSizeType = advance_characters_in_utf_8(
+            self.input,
+            narrow_cast::<usize, _>(position),
+            self.encoding,
+        );
+        unsafe { self.input.c_str().offset(byte_offset as isize) }
+    }
 }
 
 impl<'code> LocatorLike<'code> for WebDemoLocator<'code> {
+    type PositionType = WebDemoSourceOffset;
     type RangeType = WebDemoSourceRange;
 
     fn new(s: PaddedStringView<'code>) -> Self {
@@ -46,4 +100,22 @@ impl<'code> LocatorLike<'code> for WebDemoLocator<'code> {
     fn range(&self, s: SourceCodeSpan<'_>) -> Self::RangeType {
         self.range(s)
     }
+
+    fn pointer_at_position(&self, position: WebDemoSourceOffset) -> *const u8 {
+        self.pointer_at_position(position)
+    }
+
+    fn set_input(&mut self, new_input: PaddedStringView<'code>) {
+        self.input = new_input;
+    }
+
+    fn replace_text(
+        &mut self,
+        _range: WebDemoSourceRange,
+        _replacement_text: &[u8],
+        new_input: PaddedStringView<'code>,
+    ) {
+        // WebDemoLocator caches nothing about the input.
+        self.input = new_input;
+    }
 }
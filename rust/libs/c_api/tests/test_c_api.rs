@@ -133,6 +133,78 @@ fn setting_locale_changes_messages_forever() {
     }
 }
 
+#[test]
+fn category_severity_changes_or_hides_diagnostics() {
+    unsafe {
+        let p: *mut QLJSWebDemoDocument = qljs_web_demo_create_document();
+
+        let document_text: &[u8] = b"'unfinished";
+        qljs_web_demo_set_text(
+            p,
+            document_text.as_ptr() as *const std::ffi::c_void,
+            document_text.len(),
+        );
+        let diagnostics: *const QLJSWebDemoDiagnosticEx = qljs_web_demo_lint_ex(p);
+        assert_eq!(
+            (*diagnostics.add(0)).category,
+            QLJSDiagnosticCategory::Syntax
+        );
+        assert_eq!((*diagnostics.add(0)).tags, 0);
+
+        let diagnostics: *const QLJSWebDemoDiagnostic = qljs_web_demo_lint(p);
+        assert!(matches!(
+            (*diagnostics.add(0)).severity,
+            QLJSSeverity::Error
+        ));
+
+        qljs_web_demo_set_category_severity(
+            p,
+            QLJSDiagnosticCategory::Syntax as std::ffi::c_int,
+            QLJSCategorySeverity::Warning as std::ffi::c_int,
+        );
+        let diagnostics: *const QLJSWebDemoDiagnostic = qljs_web_demo_lint(p);
+        assert!(matches!(
+            (*diagnostics.add(0)).severity,
+            QLJSSeverity::Warning
+        ));
+
+        qljs_web_demo_set_category_severity(
+            p,
+            QLJSDiagnosticCategory::Syntax as std::ffi::c_int,
+            QLJSCategorySeverity::Off as std::ffi::c_int,
+        );
+        let diagnostics: *const QLJSWebDemoDiagnostic = qljs_web_demo_lint(p);
+        assert_eq!((*diagnostics.add(0)).message, std::ptr::null());
+
+        qljs_web_demo_set_category_severity(
+            p,
+            QLJSDiagnosticCategory::Syntax as std::ffi::c_int,
+            QLJSCategorySeverity::Default as std::ffi::c_int,
+        );
+        let diagnostics: *const QLJSWebDemoDiagnostic = qljs_web_demo_lint(p);
+        assert!(matches!(
+            (*diagnostics.add(0)).severity,
+            QLJSSeverity::Error
+        ));
+
+        // Unknown values are ignored.
+        qljs_web_demo_set_category_severity(p, 999, QLJSCategorySeverity::Off as std::ffi::c_int);
+        qljs_web_demo_set_category_severity(
+            p,
+            QLJSDiagnosticCategory::Syntax as std::ffi::c_int,
+            999,
+        );
+        qljs_web_demo_set_category_severity(p, -1, -1);
+        let diagnostics: *const QLJSWebDemoDiagnostic = qljs_web_demo_lint(p);
+        assert!(matches!(
+            (*diagnostics.add(0)).severity,
+            QLJSSeverity::Error
+        ));
+
+        qljs_web_demo_destroy_document(p);
+    }
+}
+
 #[test]
 fn locale_list() {
     unsafe {
@@ -157,3 +229,499 @@ fn locale_list() {
         assert_eq!(locale_strings, expected_locale_strings);
     }
 }
+
+#[test]
+fn lint_reports_diagnostics_in_source_order() {
+    unsafe {
+        let p: *mut QLJSWebDemoDocument = qljs_web_demo_create_document();
+
+        let document_text: &[u8] = b"0o1e3 1.5n /*";
+        qljs_web_demo_set_text(
+            p,
+            document_text.as_ptr() as *const std::ffi::c_void,
+            document_text.len(),
+        );
+        let diagnostics: *const QLJSWebDemoDiagnostic = qljs_web_demo_lint(p);
+        let mut begin_offsets: Vec<usize> = vec![];
+        let mut i: usize = 0;
+        while !(*diagnostics.add(i)).message.is_null() {
+            begin_offsets.push((*diagnostics.add(i)).begin_offset as usize);
+            i += 1;
+        }
+        assert!(begin_offsets.len() >= 2);
+        let mut sorted_begin_offsets: Vec<usize> = begin_offsets.clone();
+        sorted_begin_offsets.sort();
+        assert_eq!(begin_offsets, sorted_begin_offsets);
+
+        qljs_web_demo_destroy_document(p);
+    }
+}
+
+#[test]
+fn diagnostic_limit_summary_is_reported_last() {
+    unsafe {
+        let p: *mut QLJSWebDemoDocument = qljs_web_demo_create_document();
+        qljs_web_demo_set_diagnostic_limits(p, /*max_total=*/ 0, /*max_per_code=*/ 1);
+
+        let document_text: &[u8] = b"@ @ 'x";
+        qljs_web_demo_set_text(
+            p,
+            document_text.as_ptr() as *const std::ffi::c_void,
+            document_text.len(),
+        );
+        let diagnostics: *const QLJSWebDemoDiagnostic = qljs_web_demo_lint(p);
+        let codes: Vec<&str> = (0..3)
+            .map(|i: usize| read_utf8_c_string_from_c_slice(&(*diagnostics.add(i)).code))
+            .collect();
+        assert_eq!(codes, ["E0042", "E0040", "E0300"]);
+        assert!((*diagnostics.add(3)).message.is_null());
+
+        qljs_web_demo_destroy_document(p);
+    }
+}
+
+#[test]
+fn lint_stops_reporting_after_diagnostic_limit() {
+    unsafe {
+        let p: *mut QLJSWebDemoDocument = qljs_web_demo_create_document();
+        qljs_web_demo_set_diagnostic_limits(p, /*max_total=*/ 2, /*max_per_code=*/ 0);
+
+        let document_text: &[u8] = b"@ @ @ @ @";
+        qljs_web_demo_set_text(
+            p,
+            document_text.as_ptr() as *const std::ffi::c_void,
+            document_text.len(),
+        );
+        let diagnostics: *const QLJSWebDemoDiagnostic = qljs_web_demo_lint(p);
+        assert_eq!(
+            read_utf8_c_string_from_c_slice(&(*diagnostics.add(0)).code),
+            "E0042"
+        );
+        assert_eq!((*diagnostics.add(0)).begin_offset, 0);
+        assert_eq!(
+            read_utf8_c_string_from_c_slice(&(*diagnostics.add(1)).code),
+            "E0042"
+        );
+        assert_eq!((*diagnostics.add(1)).begin_offset, 2);
+        assert_eq!(
+            read_utf8_c_string_from_c_slice(&(*diagnostics.add(2)).code),
+            "E0300"
+        );
+        assert_eq!(
+            read_utf8_c_string((*diagnostics.add(2)).message),
+            "too many errors; 3 more were not reported"
+        );
+        // The summary is reported last, at the end of the document.
+        assert_eq!((*diagnostics.add(2)).begin_offset, 9);
+        assert_eq!((*diagnostics.add(2)).end_offset, 9);
+        assert!((*diagnostics.add(3)).message.is_null());
+
+        // Disabling the limits reports everything.
+        qljs_web_demo_set_diagnostic_limits(p, /*max_total=*/ 0, /*max_per_code=*/ 0);
+        let diagnostics: *const QLJSWebDemoDiagnostic = qljs_web_demo_lint(p);
+        for i in 0..5 {
+            assert_eq!(
+                read_utf8_c_string_from_c_slice(&(*diagnostics.add(i)).code),
+                "E0042"
+            );
+        }
+        assert!((*diagnostics.add(5)).message.is_null());
+
+        qljs_web_demo_destroy_document(p);
+    }
+}
+
+#[test]
+fn diagnostics_in_off_categories_do_not_count_towards_limits() {
+    unsafe {
+        let p: *mut QLJSWebDemoDocument = qljs_web_demo_create_document();
+        qljs_web_demo_set_category_severity(
+            p,
+            QLJSDiagnosticCategory::Correctness as std::ffi::c_int,
+            QLJSCategorySeverity::Off as std::ffi::c_int,
+        );
+        qljs_web_demo_set_diagnostic_limits(p, /*max_total=*/ 3, /*max_per_code=*/ 0);
+
+        // E0212 (integer rounded) is a Correctness diagnostic. E0042
+        // (unexpected '@') is a Syntax diagnostic.
+        let document_text: &[u8] = &b"9007199254740993; @ ".repeat(3);
+        qljs_web_demo_set_text(
+            p,
+            document_text.as_ptr() as *const std::ffi::c_void,
+            document_text.len(),
+        );
+        let diagnostics: *const QLJSWebDemoDiagnostic = qljs_web_demo_lint(p);
+        for i in 0..3 {
+            assert_eq!(
+                read_utf8_c_string_from_c_slice(&(*diagnostics.add(i)).code),
+                "E0042"
+            );
+        }
+        assert!((*diagnostics.add(3)).message.is_null());
+
+        // E0300 is a Syntax diagnostic, so the summary is still reported.
+        qljs_web_demo_set_diagnostic_limits(p, /*max_total=*/ 2, /*max_per_code=*/ 0);
+        let diagnostics: *const QLJSWebDemoDiagnostic = qljs_web_demo_lint(p);
+        let codes: Vec<&str> = (0..3)
+            .map(|i: usize| read_utf8_c_string_from_c_slice(&(*diagnostics.add(i)).code))
+            .collect();
+        assert_eq!(codes, ["E0042", "E0042", "E0300"]);
+        assert!((*diagnostics.add(3)).message.is_null());
+
+        qljs_web_demo_destroy_document(p);
+    }
+}
+
+#[test]
+fn lint_binary_file_reports_single_diagnostic() {
+    unsafe {
+        let p: *mut QLJSWebDemoDocument = qljs_web_demo_create_document();
+
+        let document_text: Vec<u8> = (0..4096).map(|i: u32| (i % 7) as u8).collect();
+        qljs_web_demo_set_text(
+            p,
+            document_text.as_ptr() as *const std::ffi::c_void,
+            document_text.len(),
+        );
+        let diagnostics: *const QLJSWebDemoDiagnostic = qljs_web_demo_lint(p);
+        assert_eq!(
+            read_utf8_c_string_from_c_slice(&(*diagnostics.add(0)).code),
+            "E0301"
+        );
+        assert!((*diagnostics.add(1)).message.is_null());
+
+        qljs_web_demo_destroy_document(p);
+    }
+}
+
+#[test]
+fn new_document_has_not_been_linted() {
+    unsafe {
+        let p: *mut QLJSWebDemoDocument = qljs_web_demo_create_document();
+        assert_eq!(qljs_web_demo_get_lint_status(p), QLJSLintStatus::NotLinted);
+        qljs_web_demo_destroy_document(p);
+    }
+}
+
+#[test]
+fn cancel_before_lint_does_not_cancel_lint() {
+    unsafe {
+        let p: *mut QLJSWebDemoDocument = qljs_web_demo_create_document();
+
+        let document_text: &[u8] = b"'unfinished";
+        qljs_web_demo_set_text(
+            p,
+            document_text.as_ptr() as *const std::ffi::c_void,
+            document_text.len(),
+        );
+        qljs_web_demo_cancel(p);
+        let diagnostics: *const QLJSWebDemoDiagnostic = qljs_web_demo_lint(p);
+        assert!(!(*diagnostics.add(0)).message.is_null());
+        assert_eq!(qljs_web_demo_get_lint_status(p), QLJSLintStatus::Completed);
+
+        qljs_web_demo_destroy_document(p);
+    }
+}
+
+#[test]
+fn cancel_from_another_thread_stops_lint() {
+    unsafe {
+        let p: *mut QLJSWebDemoDocument = qljs_web_demo_create_document();
+
+        let document_text: Vec<u8> = b"@ x ".repeat(1_000_000);
+        qljs_web_demo_set_text(
+            p,
+            document_text.as_ptr() as *const std::ffi::c_void,
+            document_text.len(),
+        );
+
+        let lint_done = std::sync::atomic::AtomicBool::new(false);
+        let document_address: usize = p as usize;
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                let p: *mut QLJSWebDemoDocument = document_address as *mut QLJSWebDemoDocument;
+                let diagnostics: *const QLJSWebDemoDiagnostic = qljs_web_demo_lint(p);
+                assert!((*diagnostics.add(0)).message.is_null());
+                lint_done.store(true, std::sync::atomic::Ordering::Release);
+            });
+            // qljs_web_demo_lint forgets earlier cancellations, so keep
+            // cancelling until it finishes.
+            while !lint_done.load(std::sync::atomic::Ordering::Acquire) {
+                qljs_web_demo_cancel(document_address as *const QLJSWebDemoDocument);
+                std::thread::yield_now();
+            }
+        });
+        assert_eq!(qljs_web_demo_get_lint_status(p), QLJSLintStatus::Cancelled);
+
+        qljs_web_demo_destroy_document(p);
+    }
+}
+
+#[test]
+fn lint_after_replace_text_sees_edit() {
+    unsafe {
+        let p: *mut QLJSWebDemoDocument = qljs_web_demo_create_document();
+
+        let document_text: &[u8] = b"let x = 'unfinished;";
+        qljs_web_demo_set_text(
+            p,
+            document_text.as_ptr() as *const std::ffi::c_void,
+            document_text.len(),
+        );
+        let diagnostics: *const QLJSWebDemoDiagnostic = qljs_web_demo_lint(p);
+        assert_eq!(
+            read_utf8_c_string_from_c_slice(&(*diagnostics.add(0)).code),
+            "E0040"
+        );
+
+        // Close the string: "let x = 'unfinished';"
+        let replacement: &[u8] = b"'";
+        qljs_web_demo_replace_text(
+            p,
+            19,
+            19,
+            replacement.as_ptr() as *const std::ffi::c_void,
+            replacement.len(),
+        );
+        let diagnostics: *const QLJSWebDemoDiagnostic = qljs_web_demo_lint(p);
+        assert!((*diagnostics.add(0)).message.is_null());
+
+        // Replace "x = 'unfinished'" with "y = @": "let y = @;"
+        let replacement: &[u8] = b"y = @";
+        qljs_web_demo_replace_text(
+            p,
+            4,
+            20,
+            replacement.as_ptr() as *const std::ffi::c_void,
+            replacement.len(),
+        );
+        let diagnostics: *const QLJSWebDemoDiagnostic = qljs_web_demo_lint(p);
+        assert_eq!(
+            read_utf8_c_string_from_c_slice(&(*diagnostics.add(0)).code),
+            "E0042"
+        );
+        assert_eq!((*diagnostics.add(0)).begin_offset, 8);
+        assert_eq!((*diagnostics.add(0)).end_offset, 9);
+        assert!((*diagnostics.add(1)).message.is_null());
+
+        qljs_web_demo_destroy_document(p);
+    }
+}
+
+#[test]
+fn replace_text_offsets_count_utf_16_code_units() {
+    unsafe {
+        let p: *mut QLJSWebDemoDocument = qljs_web_demo_create_document();
+
+        // U+1F430 is two UTF-16 code units.
+        let document_text: &[u8] = "'\u{1f430}' 'x'".as_bytes();
+        qljs_web_demo_set_text(
+            p,
+            document_text.as_ptr() as *const std::ffi::c_void,
+            document_text.len(),
+        );
+        // Delete the closing quote after "x".
+        let replacement: &[u8] = b"";
+        qljs_web_demo_replace_text(
+            p,
+            7,
+            8,
+            replacement.as_ptr() as *const std::ffi::c_void,
+            replacement.len(),
+        );
+        let diagnostics: *const QLJSWebDemoDiagnostic = qljs_web_demo_lint(p);
+        assert_eq!(
+            read_utf8_c_string_from_c_slice(&(*diagnostics.add(0)).code),
+            "E0040"
+        );
+        assert_eq!((*diagnostics.add(0)).begin_offset, 5);
+        assert_eq!((*diagnostics.add(0)).end_offset, 7);
+        assert!((*diagnostics.add(1)).message.is_null());
+
+        qljs_web_demo_destroy_document(p);
+    }
+}
+
+#[test]
+fn replace_text_with_reversed_offsets_swaps_them() {
+    unsafe {
+        let p: *mut QLJSWebDemoDocument = qljs_web_demo_create_document();
+
+        let document_text: &[u8] = b"'a' 'x'";
+        qljs_web_demo_set_text(
+            p,
+            document_text.as_ptr() as *const std::ffi::c_void,
+            document_text.len(),
+        );
+        // Delete the closing quote after "x".
+        let replacement: &[u8] = b"";
+        qljs_web_demo_replace_text(
+            p,
+            7,
+            6,
+            replacement.as_ptr() as *const std::ffi::c_void,
+            replacement.len(),
+        );
+        let diagnostics: *const QLJSWebDemoDiagnostic = qljs_web_demo_lint(p);
+        assert_eq!(
+            read_utf8_c_string_from_c_slice(&(*diagnostics.add(0)).code),
+            "E0040"
+        );
+        assert_eq!((*diagnostics.add(0)).begin_offset, 4);
+        assert_eq!((*diagnostics.add(0)).end_offset, 6);
+        assert!((*diagnostics.add(1)).message.is_null());
+
+        qljs_web_demo_destroy_document(p);
+    }
+}
+
+#[test]
+fn position_encoding_changes_diagnostic_offsets() {
+    for (encoding, expected_begin_offset) in [
+        (QLJSPositionEncoding::UTF8, 7),
+        (QLJSPositionEncoding::UTF16, 5),
+        (QLJSPositionEncoding::UTF32, 4),
+    ] {
+        unsafe {
+            let p: *mut QLJSWebDemoDocument = qljs_web_demo_create_document();
+            qljs_web_demo_set_position_encoding(p, encoding as std::ffi::c_int);
+
+            // U+1F430 is four UTF-8 code units, two UTF-16 code units, and one
+            // UTF-32 code unit.
+            let document_text: &[u8] = "'\u{1f430}' 'x".as_bytes();
+            qljs_web_demo_set_text(
+                p,
+                document_text.as_ptr() as *const std::ffi::c_void,
+                document_text.len(),
+            );
+            let diagnostics: *const QLJSWebDemoDiagnostic = qljs_web_demo_lint(p);
+            assert_eq!(
+                read_utf8_c_string_from_c_slice(&(*diagnostics.add(0)).code),
+                "E0040"
+            );
+            assert_eq!((*diagnostics.add(0)).begin_offset, expected_begin_offset);
+            assert_eq!((*diagnostics.add(0)).end_offset, expected_begin_offset + 2);
+            assert!((*diagnostics.add(1)).message.is_null());
+
+            qljs_web_demo_destroy_document(p);
+        }
+    }
+}
+
+#[test]
+fn unknown_position_encoding_is_ignored() {
+    unsafe {
+        let p: *mut QLJSWebDemoDocument = qljs_web_demo_create_document();
+        qljs_web_demo_set_position_encoding(p, QLJSPositionEncoding::UTF8 as std::ffi::c_int);
+        qljs_web_demo_set_position_encoding(p, 0);
+        qljs_web_demo_set_position_encoding(p, 4);
+        qljs_web_demo_set_position_encoding(p, -1);
+
+        // U+1F430 is four UTF-8 code units.
+        let document_text: &[u8] = "'\u{1f430}' 'x".as_bytes();
+        qljs_web_demo_set_text(
+            p,
+            document_text.as_ptr() as *const std::ffi::c_void,
+            document_text.len(),
+        );
+        let diagnostics: *const QLJSWebDemoDiagnostic = qljs_web_demo_lint(p);
+        assert_eq!(
+            read_utf8_c_string_from_c_slice(&(*diagnostics.add(0)).code),
+            "E0040"
+        );
+        assert_eq!((*diagnostics.add(0)).begin_offset, 7);
+        assert_eq!((*diagnostics.add(0)).end_offset, 9);
+        assert!((*diagnostics.add(1)).message.is_null());
+
+        qljs_web_demo_destroy_document(p);
+    }
+}
+
+#[test]
+fn replace_text_offsets_follow_position_encoding() {
+    unsafe {
+        let p: *mut QLJSWebDemoDocument = qljs_web_demo_create_document();
+        qljs_web_demo_set_position_encoding(p, QLJSPositionEncoding::UTF8 as std::ffi::c_int);
+
+        // U+1F430 is four UTF-8 code units.
+        let document_text: &[u8] = "'\u{1f430}' 'x'".as_bytes();
+        qljs_web_demo_set_text(
+            p,
+            document_text.as_ptr() as *const std::ffi::c_void,
+            document_text.len(),
+        );
+        // Delete the closing quote after "x".
+        let replacement: &[u8] = b"";
+        qljs_web_demo_replace_text(
+            p,
+            9,
+            10,
+            replacement.as_ptr() as *const std::ffi::c_void,
+            replacement.len(),
+        );
+        let diagnostics: *const QLJSWebDemoDiagnostic = qljs_web_demo_lint(p);
+        assert_eq!(
+            read_utf8_c_string_from_c_slice(&(*diagnostics.add(0)).code),
+            "E0040"
+        );
+        assert_eq!((*diagnostics.add(0)).begin_offset, 7);
+        assert_eq!((*diagnostics.add(0)).end_offset, 9);
+        assert!((*diagnostics.add(1)).message.is_null());
+
+        qljs_web_demo_destroy_document(p);
+    }
+}
+
+#[test]
+fn lint_ex_reports_lines_and_columns() {
+    unsafe {
+        let p: *mut QLJSWebDemoDocument = qljs_web_demo_create_document();
+
+        // U+00E9 is one UTF-16 code unit.
+        let document_text: &[u8] = "let x;\r\n\n'\u{00e9}' + 'x".as_bytes();
+        qljs_web_demo_set_text(
+            p,
+            document_text.as_ptr() as *const std::ffi::c_void,
+            document_text.len(),
+        );
+        let diagnostics: *const QLJSWebDemoDiagnosticEx = qljs_web_demo_lint_ex(p);
+        let diag: &QLJSWebDemoDiagnosticEx = &*diagnostics.add(0);
+        assert_eq!(
+            read_utf8_c_string_from_c_slice(&diag.diagnostic.code),
+            "E0040"
+        );
+        assert_eq!(diag.diagnostic.begin_offset, 15);
+        assert_eq!(diag.diagnostic.end_offset, 17);
+        assert_eq!(diag.begin_line, 2);
+        assert_eq!(diag.begin_column, 6);
+        assert_eq!(diag.end_line, 2);
+        assert_eq!(diag.end_column, 8);
+        assert!((*diagnostics.add(1)).diagnostic.message.is_null());
+        assert!((*diagnostics.add(1)).notes.is_null());
+
+        qljs_web_demo_destroy_document(p);
+    }
+}
+
+#[test]
+fn lint_ex_columns_follow_position_encoding() {
+    unsafe {
+        let p: *mut QLJSWebDemoDocument = qljs_web_demo_create_document();
+        qljs_web_demo_set_position_encoding(p, QLJSPositionEncoding::UTF8 as std::ffi::c_int);
+
+        // U+00E9 is two UTF-8 code units.
+        let document_text: &[u8] = "\n'\u{00e9}' + 'x".as_bytes();
+        qljs_web_demo_set_text(
+            p,
+            document_text.as_ptr() as *const std::ffi::c_void,
+            document_text.len(),
+        );
+        let diagnostics: *const QLJSWebDemoDiagnosticEx = qljs_web_demo_lint_ex(p);
+        let diag: &QLJSWebDemoDiagnosticEx = &*diagnostics.add(0);
+        assert_eq!(diag.begin_line, 1);
+        assert_eq!(diag.begin_column, 7);
+        assert_eq!(diag.end_column, 9);
+
+        qljs_web_demo_destroy_document(p);
+    }
+}
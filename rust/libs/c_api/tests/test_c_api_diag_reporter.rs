@@ -0,0 +1,273 @@
+use cpp_vs_rust_c_api::c_api::*;
+use cpp_vs_rust_c_api::c_api_diag_reporter::*;
+use cpp_vs_rust_c_api::web_demo_location::*;
+use cpp_vs_rust_fe::diag_reporter::*;
+use cpp_vs_rust_fe::diagnostic::*;
+use cpp_vs_rust_fe::diagnostic_types::*;
+use cpp_vs_rust_fe::source_code_span::*;
+use cpp_vs_rust_util::c_string::*;
+use cpp_vs_rust_util::padded_string::*;
+use cpp_vs_rust_util::utf_8::*;
+
+#[test]
+fn diagnostic_without_notes_has_empty_notes_array() {
+    let input = PaddedString::from_slice(b"'hello");
+    let mut reporter = CAPIDiagReporter::new();
+    reporter.set_input(input.view(), /*compute_line_numbers=*/ false);
+    report(
+        &reporter,
+        DiagUnclosedStringLiteral {
+            string_literal: SourceCodeSpan::from_slice(&input.as_slice()[0..6]),
+        },
+    );
+    let diagnostics: *const QLJSWebDemoDiagnosticEx = reporter.get_diagnostics_ex();
+    unsafe {
+        let notes: *const QLJSWebDemoDiagnosticNoteEx = (*diagnostics.add(0)).notes;
+        assert_ne!(notes, std::ptr::null());
+        assert_eq!((*notes.add(0)).note.message, std::ptr::null());
+
+        // The terminator diagnostic has no notes array.
+        assert_eq!((*diagnostics.add(1)).diagnostic.message, std::ptr::null());
+        assert_eq!((*diagnostics.add(1)).notes, std::ptr::null());
+    }
+}
+
+#[test]
+fn notes_are_attached_to_their_diagnostic() {
+    let input = PaddedString::from_slice("aaa\u{2603}bbb".as_bytes());
+    let mut reporter = CAPIDiagReporter::new();
+    reporter.set_input(input.view(), /*compute_line_numbers=*/ false);
+    report(
+        &reporter,
+        DiagMultipleMessageTest {
+            a: SourceCodeSpan::from_slice(&input.as_slice()[0..3]),
+            b: SourceCodeSpan::from_slice(&input.as_slice()[6..8]),
+        },
+    );
+    let diagnostics: *const QLJSWebDemoDiagnosticEx = reporter.get_diagnostics_ex();
+    unsafe {
+        let diag: &QLJSWebDemoDiagnostic = &(*diagnostics.add(0)).diagnostic;
+        assert_eq!(
+            read_utf8_c_string(diag.message),
+            "test for multiple messages"
+        );
+        assert_eq!(read_utf8_c_string_from_c_slice(&diag.code), "E6969");
+        assert_eq!(diag.begin_offset, 0);
+        assert_eq!(diag.end_offset, 3);
+
+        let notes: *const QLJSWebDemoDiagnosticNoteEx = (*diagnostics.add(0)).notes;
+        assert_eq!(
+            read_utf8_c_string((*notes.add(0)).note.message),
+            "second message here"
+        );
+        // U+2603 is one UTF-16 code unit.
+        assert_eq!((*notes.add(0)).note.begin_offset, 4);
+        assert_eq!((*notes.add(0)).note.end_offset, 6);
+        assert_eq!((*notes.add(1)).note.message, std::ptr::null());
+
+        assert_eq!((*diagnostics.add(1)).diagnostic.message, std::ptr::null());
+    }
+}
+
+#[test]
+fn ex_diagnostics_have_lines_and_columns_for_notes() {
+    let input = PaddedString::from_slice(b"aaa\nb\n  ccc");
+    let mut reporter = CAPIDiagReporter::new();
+    reporter.set_input(input.view(), /*compute_line_numbers=*/ true);
+    report(
+        &reporter,
+        DiagMultipleMessageTest {
+            a: SourceCodeSpan::from_slice(&input.as_slice()[0..3]),
+            b: SourceCodeSpan::from_slice(&input.as_slice()[8..11]),
+        },
+    );
+    let diagnostics: *const QLJSWebDemoDiagnosticEx = reporter.get_diagnostics_ex();
+    unsafe {
+        let diag: &QLJSWebDemoDiagnosticEx = &*diagnostics.add(0);
+        assert_eq!(
+            read_utf8_c_string_from_c_slice(&diag.diagnostic.code),
+            "E6969"
+        );
+        assert_eq!((diag.begin_line, diag.begin_column), (0, 0));
+        assert_eq!((diag.end_line, diag.end_column), (0, 3));
+
+        let notes: *const QLJSWebDemoDiagnosticNoteEx = diag.notes;
+        assert_eq!(
+            read_utf8_c_string((*notes.add(0)).note.message),
+            "second message here"
+        );
+        assert_eq!((*notes.add(0)).note.begin_offset, 8);
+        assert_eq!((*notes.add(0)).note.end_offset, 11);
+        assert_eq!(
+            ((*notes.add(0)).begin_line, (*notes.add(0)).begin_column),
+            (2, 2)
+        );
+        assert_eq!(
+            ((*notes.add(0)).end_line, (*notes.add(0)).end_column),
+            (2, 5)
+        );
+        assert_eq!((*notes.add(1)).note.message, std::ptr::null());
+
+        assert_eq!((*diagnostics.add(1)).diagnostic.message, std::ptr::null());
+    }
+}
+
+#[test]
+fn ex_diagnostics_have_no_lines_without_computing_line_numbers() {
+    let input = PaddedString::from_slice(b"aaa\nbbb");
+    let mut reporter = CAPIDiagReporter::new();
+    reporter.set_input(input.view(), /*compute_line_numbers=*/ false);
+    report(
+        &reporter,
+        DiagUnclosedStringLiteral {
+            string_literal: SourceCodeSpan::from_slice(&input.as_slice()[4..7]),
+        },
+    );
+    let diagnostics: *const QLJSWebDemoDiagnosticEx = reporter.get_diagnostics_ex();
+    unsafe {
+        let diag: &QLJSWebDemoDiagnosticEx = &*diagnostics.add(0);
+        assert_eq!(diag.diagnostic.begin_offset, 4);
+        assert_eq!(diag.diagnostic.end_offset, 7);
+        assert_eq!((diag.begin_line, diag.begin_column), (0, 0));
+        assert_eq!((diag.end_line, diag.end_column), (0, 0));
+    }
+}
+
+#[test]
+fn offsets_on_later_lines_match_web_demo_locator() {
+    let input =
+        PaddedString::from_slice("\u{1f430}\r\n\u{00e9}\u{2028}aaa\nb\u{2603}bb".as_bytes());
+    for encoding in [
+        PositionEncoding::UTF8,
+        PositionEncoding::UTF16,
+        PositionEncoding::UTF32,
+    ] {
+        let mut reporter = CAPIDiagReporter::new();
+        reporter.set_position_encoding(encoding);
+        reporter.set_input(input.view(), /*compute_line_numbers=*/ false);
+        let locator: WebDemoLocator = WebDemoLocator::new_with_encoding(input.view(), encoding);
+        let spans: Vec<SourceCodeSpan> = [(0, 4), (6, 8), (11, 14), (15, 19), (19, 21)]
+            .iter()
+            .map(|(begin, end): &(usize, usize)| {
+                SourceCodeSpan::from_slice(&input.as_slice()[*begin..*end])
+            })
+            .collect();
+        for span in &spans {
+            report(
+                &reporter,
+                DiagUnclosedStringLiteral {
+                    string_literal: *span,
+                },
+            );
+        }
+        let diagnostics: *const QLJSWebDemoDiagnostic = reporter.get_diagnostics();
+        for (i, span) in spans.iter().enumerate() {
+            let expected: WebDemoSourceRange = locator.range(*span);
+            let diag: &QLJSWebDemoDiagnostic = unsafe { &*diagnostics.add(i) };
+            assert_eq!(
+                (diag.begin_offset, diag.end_offset),
+                (expected.begin as i32, expected.end as i32),
+                "span {i} with {encoding:?}",
+            );
+        }
+    }
+}
+
+#[test]
+fn missing_optional_note_is_omitted() {
+    let input = PaddedString::from_slice(b"aaa bbb");
+    let mut reporter = CAPIDiagReporter::new();
+    reporter.set_input(input.view(), /*compute_line_numbers=*/ false);
+    report(
+        &reporter,
+        DiagOptionalNoteTest {
+            a: SourceCodeSpan::from_slice(&input.as_slice()[0..3]),
+            b: None,
+        },
+    );
+    report(
+        &reporter,
+        DiagOptionalNoteTest {
+            a: SourceCodeSpan::from_slice(&input.as_slice()[0..3]),
+            b: Some(SourceCodeSpan::from_slice(&input.as_slice()[4..7])),
+        },
+    );
+    let diagnostics: *const QLJSWebDemoDiagnosticEx = reporter.get_diagnostics_ex();
+    unsafe {
+        let diag: &QLJSWebDemoDiagnosticEx = &*diagnostics.add(0);
+        assert_eq!(
+            read_utf8_c_string(diag.diagnostic.message),
+            "test for optional notes"
+        );
+        assert_eq!((*diag.notes.add(0)).note.message, std::ptr::null());
+
+        let diag: &QLJSWebDemoDiagnosticEx = &*diagnostics.add(1);
+        assert_eq!(
+            read_utf8_c_string(diag.diagnostic.message),
+            "test for optional notes"
+        );
+        let notes: *const QLJSWebDemoDiagnosticNoteEx = diag.notes;
+        assert_eq!(
+            read_utf8_c_string((*notes.add(0)).note.message),
+            "optional note here"
+        );
+        assert_eq!((*notes.add(0)).note.begin_offset, 4);
+        assert_eq!((*notes.add(0)).note.end_offset, 7);
+        assert_eq!((*notes.add(1)).note.message, std::ptr::null());
+
+        assert_eq!((*diagnostics.add(2)).diagnostic.message, std::ptr::null());
+    }
+}
+
+#[test]
+fn diagnostic_has_category_and_tags() {
+    let input = PaddedString::from_slice(b"aaa");
+    let mut reporter = CAPIDiagReporter::new();
+    reporter.set_input(input.view(), /*compute_line_numbers=*/ false);
+    report(
+        &reporter,
+        DiagOptionalNoteTest {
+            a: SourceCodeSpan::from_slice(&input.as_slice()[0..3]),
+            b: None,
+        },
+    );
+    let diagnostics: *const QLJSWebDemoDiagnosticEx = reporter.get_diagnostics_ex();
+    unsafe {
+        let diag: &QLJSWebDemoDiagnosticEx = &*diagnostics.add(0);
+        assert_eq!(diag.category, QLJSDiagnosticCategory::Style);
+        assert_eq!(
+            diag.tags,
+            QLJS_DIAGNOSTIC_TAG_UNNECESSARY_BIT | QLJS_DIAGNOSTIC_TAG_DEPRECATED_BIT
+        );
+    }
+}
+
+#[test]
+fn disabled_category_is_not_reported() {
+    let input = PaddedString::from_slice(b"aaa");
+    let mut reporter = CAPIDiagReporter::new();
+    reporter.set_input(input.view(), /*compute_line_numbers=*/ false);
+    reporter.set_category_severity(DiagnosticCategory::Style, CategorySeverity::Off);
+    report(
+        &reporter,
+        DiagOptionalNoteTest {
+            a: SourceCodeSpan::from_slice(&input.as_slice()[0..3]),
+            b: None,
+        },
+    );
+    report(
+        &reporter,
+        DiagMultipleMessageTest {
+            a: SourceCodeSpan::from_slice(&input.as_slice()[0..1]),
+            b: SourceCodeSpan::from_slice(&input.as_slice()[1..2]),
+        },
+    );
+    let diagnostics: *const QLJSWebDemoDiagnostic = reporter.get_diagnostics();
+    unsafe {
+        assert_eq!(
+            read_utf8_c_string_from_c_slice(&(*diagnostics.add(0)).code),
+            "E6969"
+        );
+        assert_eq!((*diagnostics.add(1)).message, std::ptr::null());
+    }
+}
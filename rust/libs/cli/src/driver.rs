@@ -0,0 +1,453 @@
+use crate::file_discovery::*;
+use crate::options::*;
+use crate::parallel::*;
+use cpp_vs_rust_fe::configuration::*;
+use cpp_vs_rust_fe::configuration_loader::*;
+use cpp_vs_rust_fe::diag_reporter::*;
+use cpp_vs_rust_fe::diagnostic::*;
+use cpp_vs_rust_fe::diagnostic_types::*;
+use cpp_vs_rust_fe::json_diag_reporter::*;
+use cpp_vs_rust_fe::lex::*;
+use cpp_vs_rust_fe::linter::*;
+use cpp_vs_rust_fe::sarif_diag_reporter::*;
+use cpp_vs_rust_fe::text_diag_reporter::*;
+use cpp_vs_rust_i18n::translation::*;
+use cpp_vs_rust_util::padded_string::*;
+
+// No diagnostics at least as severe as --exit-fail-on were reported.
+pub const EXIT_CODE_SUCCESS: i32 = 0;
+// A diagnostic at least as severe as --exit-fail-on was reported.
+pub const EXIT_CODE_DIAGNOSTICS: i32 = 1;
+// The command line was invalid, or a file could not be read.
+pub const EXIT_CODE_ERROR: i32 = 2;
+
+const USAGE: &str = "\
+Usage: quick-lint-js [OPTIONS]... [FILE | DIRECTORY]... [--stdin]
+
+Directories are searched recursively for JavaScript and TypeScript files,
+skipping files matched by .gitignore and .quick-lint-js-ignore files.
+
+Each file is linted using the quick-lint-js.config file in its directory or
+in the nearest parent directory which has one.
+
+Options:
+  --stdin                    Lint standard input
+  --language=LANGUAGE        Lint every file as javascript, jsx, typescript,
+                             or tsx (default: guess from the file extension)
+  --output-format=FORMAT     gnu-like (default), json, or sarif
+  --locale=LOCALE            Translate messages into LOCALE
+                             (default: from the environment)
+  --watch                    Keep running, re-linting files when they change
+                             (Linux only)
+  --exit-fail-on=SEVERITY    Exit with status 1 if a diagnostic is at least as
+                             severe as SEVERITY: error (default), warning, or
+                             never
+  -h, --help                 Print this help message
+  -v, --version              Print the version of quick-lint-js
+
+Exit status:
+  0  no failing diagnostics
+  1  a diagnostic was at least as severe as --exit-fail-on
+  2  invalid command line, or a file could not be read
+";
+
+// Runs the quick-lint-js command-line program with the given arguments (not
+// including the program name).
+//
+// Returns the process exit code.
+pub fn run_cli(
+    args: &[String],
+    stdin: &mut dyn std::io::Read,
+    stdout: &mut dyn std::io::Write,
+    stderr: &mut dyn std::io::Write,
+) -> i32 {
+    let options: Options = match parse_options(args) {
+        Ok(options) => options,
+        Err(error) => {
+            let _ = writeln!(stderr, "error: {error}");
+            let _ = writeln!(stderr, "Try 'quick-lint-js --help' for more information.");
+            return EXIT_CODE_ERROR;
+        }
+    };
+    if options.help {
+        let _ = stdout.write_all(USAGE.as_bytes());
+        return EXIT_CODE_SUCCESS;
+    }
+    if options.version {
+        let _ = writeln!(
+            stdout,
+            "quick-lint-js version {}",
+            env!("CARGO_PKG_VERSION")
+        );
+        return EXIT_CODE_SUCCESS;
+    }
+    if options.files_to_lint.is_empty() {
+        let _ = writeln!(stderr, "error: expected file name");
+        return EXIT_CODE_ERROR;
+    }
+
+    let translator: Translator = translator_for_options(&options);
+    if options.watch {
+        #[cfg(target_os = "linux")]
+        return crate::watch::run_watch(&options, translator, stdout, stderr);
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = writeln!(stderr, "error: --watch is only supported on Linux");
+            return EXIT_CODE_ERROR;
+        }
+    }
+    let mut failed: bool = false;
+    let mut had_read_error: bool = false;
+    let files: Vec<FileToLint> = expand_directories(
+        &options,
+        &mut |path: &std::path::Path, error: std::io::Error| {
+            let _ = writeln!(stderr, "error: failed to read {}: {error}", path.display());
+            had_read_error = true;
+        },
+    );
+    let stdin_data: std::io::Result<Vec<u8>> =
+        if files.iter().any(|file: &FileToLint| file.is_stdin) {
+            let mut data: Vec<u8> = vec![];
+            stdin.read_to_end(&mut data).map(|_| data)
+        } else {
+            Ok(vec![])
+        };
+
+    // Standard input is linted as if it was a file in the current directory.
+    let mut config_loader: ConfigurationLoader = ConfigurationLoader::new();
+    let mut config_files: Vec<Option<std::sync::Arc<ConfigFile>>> = vec![];
+    for file in &files {
+        let mut on_error = |path: &std::path::Path, error: std::io::Error| {
+            let _ = writeln!(stderr, "error: failed to read {}: {error}", path.display());
+            had_read_error = true;
+        };
+        config_files.push(if file.is_stdin {
+            config_loader.load_for_directory(std::path::Path::new("."), &mut on_error)
+        } else {
+            config_loader.load_for_file(std::path::Path::new(&file.path), &mut on_error)
+        });
+    }
+    let configurations: Vec<Option<&Configuration>> = config_files
+        .iter()
+        .map(|config_file: &Option<std::sync::Arc<ConfigFile>>| {
+            config_file.as_deref().map(ConfigFile::configuration)
+        })
+        .collect();
+
+    let mut output_writer: OutputWriter = OutputWriter::new(options.output_format);
+    let mut write_error: Option<std::io::Error> = None;
+    // Report each broken config file once, before any linted file.
+    let mut reported_config_paths: std::collections::HashSet<&std::path::Path> =
+        std::collections::HashSet::new();
+    for config_file in config_files.iter().flatten() {
+        if !config_file.has_errors() || !reported_config_paths.insert(config_file.path()) {
+            continue;
+        }
+        let linted: LintedFile =
+            lint_config_file(config_file, options.output_format, translator.clone());
+        failed |= linted.fails(options.exit_fail_on);
+        if write_error.is_none() {
+            write_error = output_writer.add_file(&linted, stdout).err();
+        }
+    }
+    lint_files_in_parallel(
+        &files,
+        &configurations,
+        &stdin_data,
+        options.output_format,
+        &translator,
+        default_job_count(),
+        &mut |file: &FileToLint, outcome: FileOutcome| {
+            let linted: LintedFile = match outcome {
+                FileOutcome::Linted(linted) => linted,
+                FileOutcome::ReadError(message) => {
+                    let _ = writeln!(
+                        stderr,
+                        "error: failed to read {}: {message}",
+                        file.display_path()
+                    );
+                    had_read_error = true;
+                    return;
+                }
+            };
+            failed |= linted.fails(options.exit_fail_on);
+            if write_error.is_none() {
+                write_error = output_writer.add_file(&linted, stdout).err();
+            }
+        },
+    );
+    let write_result: std::io::Result<()> = match write_error {
+        Some(error) => Err(error),
+        None => output_writer.finish(stdout),
+    };
+    if let Err(error) = write_result {
+        let _ = writeln!(stderr, "error: failed to write output: {error}");
+        return EXIT_CODE_ERROR;
+    }
+
+    if had_read_error {
+        EXIT_CODE_ERROR
+    } else if failed {
+        EXIT_CODE_DIAGNOSTICS
+    } else {
+        EXIT_CODE_SUCCESS
+    }
+}
+
+// The result of linting one file.
+pub struct LintedFile {
+    // For OutputFormat::GNULike, the text to print. For OutputFormat::JSON and
+    // OutputFormat::Sarif, comma-separated JSON objects.
+    pub output: Vec<u8>,
+    pub error_count: usize,
+    pub warning_count: usize,
+}
+
+impl LintedFile {
+    // Returns true if this file's diagnostics should cause a failure exit
+    // status.
+    pub fn fails(&self, exit_fail_on: ExitFailOn) -> bool {
+        match exit_fail_on {
+            ExitFailOn::Error => self.error_count > 0,
+            ExitFailOn::Warning => self.error_count > 0 || self.warning_count > 0,
+            ExitFailOn::Never => false,
+        }
+    }
+}
+
+// Writes the output of lint_file for several files.
+pub struct OutputWriter {
+    output_format: OutputFormat,
+    // Comma-separated JSON objects for the json and sarif formats.
+    json_objects: Vec<u8>,
+}
+
+impl OutputWriter {
+    pub fn new(output_format: OutputFormat) -> OutputWriter {
+        OutputWriter {
+            output_format: output_format,
+            json_objects: vec![],
+        }
+    }
+
+    // Write (for OutputFormat::GNULike) or buffer (for OutputFormat::JSON and
+    // OutputFormat::Sarif) the output for one file.
+    pub fn add_file(
+        &mut self,
+        linted: &LintedFile,
+        out: &mut dyn std::io::Write,
+    ) -> std::io::Result<()> {
+        match self.output_format {
+            OutputFormat::GNULike => out.write_all(&linted.output),
+            OutputFormat::JSON | OutputFormat::Sarif => {
+                if !linted.output.is_empty() {
+                    if !self.json_objects.is_empty() {
+                        self.json_objects.push(b',');
+                    }
+                    self.json_objects.extend_from_slice(&linted.output);
+                }
+                Ok(())
+            }
+        }
+    }
+
+    // Write buffered output (such as the JSON array containing every file's
+    // diagnostics), then flush out.
+    pub fn finish(&mut self, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+        let json_objects: Vec<u8> = std::mem::take(&mut self.json_objects);
+        match self.output_format {
+            OutputFormat::GNULike => {}
+            OutputFormat::JSON => {
+                out.write_all(b"[")?;
+                out.write_all(&json_objects)?;
+                out.write_all(b"]\n")?;
+            }
+            OutputFormat::Sarif => out.write_all(&sarif_log_with_results(&json_objects))?,
+        }
+        out.flush()
+    }
+}
+
+// configuration is from the quick-lint-js.config file for the linted file, if
+// any.
+pub fn lint_file(
+    input: PaddedStringView<'_>,
+    display_path: &str,
+    language: InputFileLanguage,
+    configuration: Option<&Configuration>,
+    output_format: OutputFormat,
+    translator: Translator,
+    allocator: &LexerAllocator,
+) -> LintedFile {
+    let mut linter_options: LinterOptions = language.linter_options();
+    if let Some(configuration) = configuration {
+        configuration.apply_to(&mut linter_options);
+    }
+    format_diagnostics(
+        input,
+        display_path,
+        output_format,
+        translator,
+        &|reporter: &dyn DiagReporter| {
+            parse_and_lint_with_allocator(input, reporter, linter_options.clone(), allocator);
+        },
+    )
+}
+
+// Format the problems in a quick-lint-js.config file, such as JSON syntax
+// errors, as if the config file was linted.
+pub fn lint_config_file(
+    config_file: &ConfigFile,
+    output_format: OutputFormat,
+    translator: Translator,
+) -> LintedFile {
+    format_diagnostics(
+        config_file.content(),
+        &config_file.path().display().to_string(),
+        output_format,
+        translator,
+        &|reporter: &dyn DiagReporter| config_file.report_errors(reporter),
+    )
+}
+
+// Calls report_all, formatting the diagnostics it reports about input.
+fn format_diagnostics(
+    input: PaddedStringView<'_>,
+    display_path: &str,
+    output_format: OutputFormat,
+    translator: Translator,
+    report_all: &dyn Fn(&dyn DiagReporter),
+) -> LintedFile {
+    match output_format {
+        OutputFormat::GNULike => {
+            let mut reporter: TextDiagReporter = TextDiagReporter::new(translator);
+            reporter.set_source(input, display_path);
+            let counter: SeverityCountingDiagReporter =
+                SeverityCountingDiagReporter::new(&reporter);
+            report_all(&counter);
+            let (error_count, warning_count): (usize, usize) = counter.counts();
+            LintedFile {
+                output: reporter.take_output(),
+                error_count: error_count,
+                warning_count: warning_count,
+            }
+        }
+        OutputFormat::JSON => {
+            let mut reporter: JSONDiagReporter = JSONDiagReporter::new(translator);
+            reporter.set_source(input, display_path);
+            let counter: SeverityCountingDiagReporter =
+                SeverityCountingDiagReporter::new(&reporter);
+            report_all(&counter);
+            let (error_count, warning_count): (usize, usize) = counter.counts();
+            LintedFile {
+                output: reporter.take_diagnostics(),
+                error_count: error_count,
+                warning_count: warning_count,
+            }
+        }
+        OutputFormat::Sarif => {
+            let mut reporter: SarifDiagReporter = SarifDiagReporter::new(translator);
+            reporter.set_source(input, display_path);
+            let counter: SeverityCountingDiagReporter =
+                SeverityCountingDiagReporter::new(&reporter);
+            report_all(&counter);
+            let (error_count, warning_count): (usize, usize) = counter.counts();
+            LintedFile {
+                output: reporter.take_results(),
+                error_count: error_count,
+                warning_count: warning_count,
+            }
+        }
+    }
+}
+
+pub fn translator_for_options(options: &Options) -> Translator {
+    let mut translator: Translator = Translator::new_using_messages_from_source_code();
+    match &options.locale {
+        // If the locale is unknown, use untranslated messages.
+        Some(locale) => {
+            let _ = translator.use_messages_from_locale(locale);
+        }
+        None => {
+            let locales: Vec<String> = get_user_locale_preferences();
+            let locales: Vec<&str> = locales.iter().map(String::as_str).collect();
+            let _ = translator.use_messages_from_locales(&locales);
+        }
+    }
+    translator
+}
+
+// Replace each directory in options.files_to_lint with the files to lint in
+// that directory (see find_files_in_directory).
+pub fn expand_directories(
+    options: &Options,
+    on_error: &mut dyn FnMut(&std::path::Path, std::io::Error),
+) -> Vec<FileToLint> {
+    let mut files: Vec<FileToLint> = vec![];
+    for file in &options.files_to_lint {
+        let directory: &std::path::Path = std::path::Path::new(&file.path);
+        if file.is_stdin || !directory.is_dir() {
+            files.push(file.clone());
+            continue;
+        }
+        for path in find_files_in_directory(directory, on_error) {
+            let path: String = match path.into_os_string().into_string() {
+                Ok(path) => path,
+                Err(path) => {
+                    on_error(
+                        std::path::Path::new(&path),
+                        std::io::Error::new(
+                            std::io::ErrorKind::InvalidData,
+                            "path is not valid UTF-8",
+                        ),
+                    );
+                    continue;
+                }
+            };
+            files.push(FileToLint {
+                language: options
+                    .language
+                    .unwrap_or_else(|| InputFileLanguage::from_path(&path)),
+                path: path,
+                is_stdin: false,
+            });
+        }
+    }
+    files
+}
+
+// Forwards diagnostics to another DiagReporter, counting them by severity.
+struct SeverityCountingDiagReporter<'reporter> {
+    reporter: &'reporter dyn DiagReporter,
+    error_count: std::cell::Cell<usize>,
+    warning_count: std::cell::Cell<usize>,
+}
+
+impl<'reporter> SeverityCountingDiagReporter<'reporter> {
+    fn new(reporter: &'reporter dyn DiagReporter) -> Self {
+        SeverityCountingDiagReporter {
+            reporter: reporter,
+            error_count: std::cell::Cell::new(0),
+            warning_count: std::cell::Cell::new(0),
+        }
+    }
+
+    // Returns (error_count, warning_count).
+    fn counts(&self) -> (usize, usize) {
+        (self.error_count.get(), self.warning_count.get())
+    }
+}
+
+impl<'reporter> DiagReporter for SeverityCountingDiagReporter<'reporter> {
+    fn report_impl(&self, type_: DiagType, diag: *const u8) {
+        let count: Option<&std::cell::Cell<usize>> = match get_diagnostic_info(type_).severity() {
+            DiagnosticSeverity::Error => Some(&self.error_count),
+            DiagnosticSeverity::Warning => Some(&self.warning_count),
+            DiagnosticSeverity::Note => None,
+        };
+        if let Some(count) = count {
+            count.set(count.get() + 1);
+        }
+        self.reporter.report_impl(type_, diag);
+    }
+}
@@ -0,0 +1,108 @@
+use crate::ignore_file::*;
+
+// Extensions of files found by find_files_in_directory.
+const LINTABLE_EXTENSIONS: [&str; 8] = ["cjs", "cts", "js", "jsx", "mjs", "mts", "ts", "tsx"];
+
+// Returns true if find_files_in_directory should lint the file at path.
+pub fn is_lintable_path(path: &std::path::Path) -> bool {
+    path.extension()
+        .and_then(|extension: &std::ffi::OsStr| extension.to_str())
+        .is_some_and(|extension: &str| LINTABLE_EXTENSIONS.contains(&extension))
+}
+
+// The result of list_directory_recursively.
+pub struct DirectoryListing {
+    // JavaScript and TypeScript files to lint.
+    pub files: Vec<std::path::PathBuf>,
+    // The directory given to list_directory_recursively and its subdirectories
+    // which were searched (i.e. not ignored).
+    pub directories: Vec<std::path::PathBuf>,
+}
+
+// Returns the paths of JavaScript and TypeScript files in directory and its
+// subdirectories, skipping files and directories excluded by .gitignore or
+// .quick-lint-js-ignore files in directory or its subdirectories. .git
+// directories and symbolic links to directories are also skipped.
+//
+// The paths are sorted by component, so the result is deterministic: a
+// directory's files and subdirectories are visited in byte order of their
+// names.
+//
+// Errors (such as unreadable directories) are passed to on_error. The walk
+// continues after an error.
+pub fn find_files_in_directory(
+    directory: &std::path::Path,
+    on_error: &mut dyn FnMut(&std::path::Path, std::io::Error),
+) -> Vec<std::path::PathBuf> {
+    list_directory_recursively(directory, on_error).files
+}
+
+// Like find_files_in_directory, but also returns the searched directories.
+pub fn list_directory_recursively(
+    directory: &std::path::Path,
+    on_error: &mut dyn FnMut(&std::path::Path, std::io::Error),
+) -> DirectoryListing {
+    list_directory_recursively_with_ignore_rules(directory, &mut IgnoreRules::new(), on_error)
+}
+
+// Like list_directory_recursively, but also skip files and directories ignored
+// by ignore_rules (for example, rules from directory's parents).
+pub fn list_directory_recursively_with_ignore_rules(
+    directory: &std::path::Path,
+    ignore_rules: &mut IgnoreRules,
+    on_error: &mut dyn FnMut(&std::path::Path, std::io::Error),
+) -> DirectoryListing {
+    let mut listing: DirectoryListing = DirectoryListing {
+        files: vec![],
+        directories: vec![],
+    };
+    find_files_recursive(directory, ignore_rules, &mut listing, on_error);
+    listing
+}
+
+fn find_files_recursive(
+    directory: &std::path::Path,
+    ignore_rules: &mut IgnoreRules,
+    listing: &mut DirectoryListing,
+    on_error: &mut dyn FnMut(&std::path::Path, std::io::Error),
+) {
+    let mut entries: Vec<(std::ffi::OsString, std::fs::FileType)> = vec![];
+    let read_result: std::io::Result<()> = std::fs::read_dir(directory).and_then(
+        |read_dir: std::fs::ReadDir| -> std::io::Result<()> {
+            for entry in read_dir {
+                let entry: std::fs::DirEntry = entry?;
+                entries.push((entry.file_name(), entry.file_type()?));
+            }
+            Ok(())
+        },
+    );
+    if let Err(error) = read_result {
+        on_error(directory, error);
+        return;
+    }
+    entries.sort_by(|(a, _), (b, _)| a.as_encoded_bytes().cmp(b.as_encoded_bytes()));
+    listing.directories.push(directory.to_path_buf());
+
+    ignore_rules.push_directory(directory, on_error);
+    for (name, file_type) in entries {
+        if name == ".git" {
+            continue;
+        }
+        let path: std::path::PathBuf = directory.join(&name);
+        let is_file: bool = if file_type.is_symlink() {
+            // Lint symbolic links to files. Don't follow symbolic links to
+            // directories; they might form cycles.
+            std::fs::metadata(&path).is_ok_and(|metadata: std::fs::Metadata| metadata.is_file())
+        } else {
+            file_type.is_file()
+        };
+        if file_type.is_dir() {
+            if !ignore_rules.is_ignored(&path, true) {
+                find_files_recursive(&path, ignore_rules, listing, on_error);
+            }
+        } else if is_file && is_lintable_path(&path) && !ignore_rules.is_ignored(&path, false) {
+            listing.files.push(path);
+        }
+    }
+    ignore_rules.pop_directory();
+}
@@ -0,0 +1,198 @@
+use cpp_vs_rust_util::glob::*;
+
+// Names of files, in priority order (lowest first), whose patterns exclude
+// files and directories from linting.
+pub const IGNORE_FILE_NAMES: [&str; 2] = [".gitignore", ".quick-lint-js-ignore"];
+
+// One pattern line of a .gitignore or .quick-lint-js-ignore file.
+//
+// https://git-scm.com/docs/gitignore#_pattern_format
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IgnorePattern {
+    glob: Vec<u8>,
+    // If true, a match un-ignores the path ('!pattern').
+    negated: bool,
+    // If true, only directories match ('pattern/').
+    directory_only: bool,
+    // If true, the glob is matched against the whole path relative to the
+    // ignore file's directory. If false, the glob is matched against the last
+    // path component only.
+    anchored: bool,
+}
+
+impl IgnorePattern {
+    // relative_path is '/'-separated and relative to the directory containing
+    // the ignore file.
+    pub fn matches(&self, relative_path: &[u8], is_directory: bool) -> bool {
+        if self.directory_only && !is_directory {
+            return false;
+        }
+        if self.anchored {
+            glob_matches(&self.glob, relative_path)
+        } else {
+            let name_begin: usize = relative_path
+                .iter()
+                .rposition(|c: &u8| *c == b'/')
+                .map_or(0, |slash_index: usize| slash_index + 1);
+            glob_matches(&self.glob, &relative_path[name_begin..])
+        }
+    }
+
+    pub fn is_negated(&self) -> bool {
+        self.negated
+    }
+}
+
+pub fn parse_ignore_file(content: &[u8]) -> Vec<IgnorePattern> {
+    content
+        .split(|c: &u8| *c == b'\n')
+        .filter_map(parse_ignore_line)
+        .collect()
+}
+
+fn parse_ignore_line(line: &[u8]) -> Option<IgnorePattern> {
+    let mut line: &[u8] = line.strip_suffix(b"\r").unwrap_or(line);
+    // Trailing spaces are ignored unless escaped with a backslash.
+    while let Some(trimmed) = line.strip_suffix(b" ") {
+        if trimmed.ends_with(b"\\") {
+            break;
+        }
+        line = trimmed;
+    }
+    if line.is_empty() || line.starts_with(b"#") {
+        return None;
+    }
+
+    let negated: bool = line.starts_with(b"!");
+    if negated {
+        line = &line[1..];
+    }
+    let directory_only: bool = line.ends_with(b"/");
+    if directory_only {
+        line = &line[..line.len() - 1];
+    }
+    // A '/' at the beginning or in the middle anchors the pattern.
+    let anchored: bool = line.contains(&b'/');
+    if let Some(unanchored) = line.strip_prefix(b"/") {
+        line = unanchored;
+    }
+    if line.is_empty() {
+        return None;
+    }
+    Some(IgnorePattern {
+        glob: line.to_vec(),
+        negated: negated,
+        directory_only: directory_only,
+        anchored: anchored,
+    })
+}
+
+// The ignore patterns which apply to one directory: patterns from its ignore
+// files and from its ancestors' ignore files.
+//
+// Patterns from deeper ignore files take priority over patterns from shallower
+// ignore files. Within one directory, later patterns take priority over
+// earlier patterns.
+pub struct IgnoreRules {
+    directories: Vec<IgnoreDirectory>,
+}
+
+struct IgnoreDirectory {
+    path: std::path::PathBuf,
+    patterns: Vec<IgnorePattern>,
+}
+
+impl IgnoreRules {
+    pub fn new() -> IgnoreRules {
+        IgnoreRules {
+            directories: vec![],
+        }
+    }
+
+    // Read the ignore files in directory, if any. directory must be inside the
+    // directory given to the previous call to push_directory, if any.
+    //
+    // Each call must be matched by a call to pop_directory, even if reading an
+    // ignore file failed.
+    pub fn push_directory(
+        &mut self,
+        directory: &std::path::Path,
+        on_error: &mut dyn FnMut(&std::path::Path, std::io::Error),
+    ) {
+        let mut patterns: Vec<IgnorePattern> = vec![];
+        for file_name in IGNORE_FILE_NAMES {
+            let ignore_file_path: std::path::PathBuf = directory.join(file_name);
+            match std::fs::read(&ignore_file_path) {
+                Ok(content) => patterns.extend(parse_ignore_file(&content)),
+                Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+                Err(error) => on_error(&ignore_file_path, error),
+            }
+        }
+        self.directories.push(IgnoreDirectory {
+            path: directory.to_path_buf(),
+            patterns: patterns,
+        });
+    }
+
+    // Push root and every directory between root and path (not including path
+    // itself), so is_ignored(path) considers every ignore file in root which
+    // applies to path. path must be inside root.
+    pub fn push_directories_down_to(
+        &mut self,
+        root: &std::path::Path,
+        path: &std::path::Path,
+        on_error: &mut dyn FnMut(&std::path::Path, std::io::Error),
+    ) {
+        let mut directory: std::path::PathBuf = root.to_path_buf();
+        self.push_directory(&directory, on_error);
+        if let Some(relative_parent) = path
+            .strip_prefix(root)
+            .ok()
+            .and_then(|relative_path: &std::path::Path| relative_path.parent())
+        {
+            for component in relative_parent.components() {
+                directory.push(component);
+                self.push_directory(&directory, on_error);
+            }
+        }
+    }
+
+    pub fn pop_directory(&mut self) {
+        self.directories.pop();
+    }
+
+    // Returns true if the file or directory at path should not be linted.
+    // path must be inside the directory given to the most recent call to
+    // push_directory.
+    pub fn is_ignored(&self, path: &std::path::Path, is_directory: bool) -> bool {
+        for directory in self.directories.iter().rev() {
+            let Ok(relative_path) = path.strip_prefix(&directory.path) else {
+                continue;
+            };
+            let relative_path: Vec<u8> = slash_separated_path(relative_path);
+            for pattern in directory.patterns.iter().rev() {
+                if pattern.matches(&relative_path, is_directory) {
+                    return !pattern.is_negated();
+                }
+            }
+        }
+        false
+    }
+}
+
+impl Default for IgnoreRules {
+    fn default() -> Self {
+        IgnoreRules::new()
+    }
+}
+
+fn slash_separated_path(path: &std::path::Path) -> Vec<u8> {
+    let mut out: Vec<u8> = vec![];
+    for (i, component) in path.components().enumerate() {
+        if i != 0 {
+            out.push(b'/');
+        }
+        out.extend_from_slice(component.as_os_str().as_encoded_bytes());
+    }
+    out
+}
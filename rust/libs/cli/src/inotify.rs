@@ -0,0 +1,142 @@
+// A thin wrapper around Linux's inotify API.
+//
+// https://man7.org/linux/man-pages/man7/inotify.7.html
+
+use std::os::unix::ffi::OsStrExt;
+
+pub type WatchDescriptor = libc::c_int;
+
+pub struct InotifyEvent {
+    pub watch_descriptor: WatchDescriptor,
+    // IN_* flags.
+    pub mask: u32,
+    // The name of the file or directory inside the watched directory. Empty if
+    // the event is about the watched file or directory itself.
+    pub name: std::ffi::OsString,
+}
+
+impl InotifyEvent {
+    pub fn is_directory(&self) -> bool {
+        self.mask & libc::IN_ISDIR != 0
+    }
+}
+
+// An inotify instance. Watches are removed when the Inotify is dropped.
+pub struct Inotify {
+    fd: libc::c_int,
+}
+
+impl Inotify {
+    pub fn new() -> std::io::Result<Inotify> {
+        let fd: libc::c_int = unsafe { libc::inotify_init1(libc::IN_NONBLOCK | libc::IN_CLOEXEC) };
+        if fd == -1 {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(Inotify { fd: fd })
+    }
+
+    // Start watching path for the events in mask (IN_* flags).
+    //
+    // If path is already watched, its existing watch descriptor is returned and
+    // its mask is replaced.
+    pub fn add_watch(&self, path: &std::path::Path, mask: u32) -> std::io::Result<WatchDescriptor> {
+        let c_path: std::ffi::CString = std::ffi::CString::new(path.as_os_str().as_bytes())
+            .map_err(|_| {
+                std::io::Error::new(std::io::ErrorKind::InvalidInput, "path contains NUL")
+            })?;
+        let watch_descriptor: WatchDescriptor =
+            unsafe { libc::inotify_add_watch(self.fd, c_path.as_ptr(), mask) };
+        if watch_descriptor == -1 {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(watch_descriptor)
+    }
+
+    // Wait up to timeout (or forever if timeout is None) for events, then
+    // return every event which is ready.
+    //
+    // Returns no events if the timeout expired or if waiting was interrupted
+    // by a signal.
+    pub fn read_events(
+        &self,
+        timeout: Option<std::time::Duration>,
+    ) -> std::io::Result<Vec<InotifyEvent>> {
+        let timeout_ms: libc::c_int = match timeout {
+            Some(timeout) => libc::c_int::try_from(timeout.as_millis()).unwrap_or(libc::c_int::MAX),
+            None => -1,
+        };
+        let mut poll_fd: libc::pollfd = libc::pollfd {
+            fd: self.fd,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let rc: libc::c_int = unsafe { libc::poll(&mut poll_fd, 1, timeout_ms) };
+        if rc == -1 {
+            let error: std::io::Error = std::io::Error::last_os_error();
+            if error.kind() == std::io::ErrorKind::Interrupted {
+                return Ok(vec![]);
+            }
+            return Err(error);
+        }
+
+        let mut events: Vec<InotifyEvent> = vec![];
+        // Aligned for inotify_event.
+        let mut buffer: Vec<libc::inotify_event> = Vec::with_capacity(
+            READ_BUFFER_SIZE / std::mem::size_of::<libc::inotify_event>(),
+        );
+        let buffer_size: usize = buffer.capacity() * std::mem::size_of::<libc::inotify_event>();
+        loop {
+            let read_size: isize = unsafe {
+                libc::read(
+                    self.fd,
+                    buffer.as_mut_ptr() as *mut libc::c_void,
+                    buffer_size,
+                )
+            };
+            if read_size == -1 {
+                let error: std::io::Error = std::io::Error::last_os_error();
+                match error.kind() {
+                    std::io::ErrorKind::WouldBlock | std::io::ErrorKind::Interrupted => break,
+                    _ => return Err(error),
+                }
+            }
+            let data: &[u8] = unsafe {
+                std::slice::from_raw_parts(buffer.as_ptr() as *const u8, read_size as usize)
+            };
+            parse_events(data, &mut events);
+        }
+        Ok(events)
+    }
+}
+
+impl Drop for Inotify {
+    fn drop(&mut self) {
+        unsafe {
+            libc::close(self.fd);
+        }
+    }
+}
+
+// Big enough for several events with names of maximum length (NAME_MAX).
+const READ_BUFFER_SIZE: usize = 64 * 1024;
+
+fn parse_events(mut data: &[u8], events: &mut Vec<InotifyEvent>) {
+    const HEADER_SIZE: usize = std::mem::size_of::<libc::inotify_event>();
+    while data.len() >= HEADER_SIZE {
+        let header: libc::inotify_event =
+            unsafe { std::ptr::read_unaligned(data.as_ptr() as *const libc::inotify_event) };
+        let name_end: usize = std::cmp::min(HEADER_SIZE + header.len as usize, data.len());
+        // The name is padded with null bytes.
+        let name: &[u8] = &data[HEADER_SIZE..name_end];
+        let name: &[u8] = match name.iter().position(|c: &u8| *c == 0) {
+            Some(null_index) => &name[..null_index],
+            None => name,
+        };
+        events.push(InotifyEvent {
+            watch_descriptor: header.wd,
+            mask: header.mask,
+            name: std::ffi::OsStr::from_bytes(name).to_os_string(),
+        });
+        data = &data[name_end..];
+    }
+}
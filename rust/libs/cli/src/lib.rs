@@ -0,0 +1,9 @@
+pub mod driver;
+pub mod file_discovery;
+pub mod ignore_file;
+#[cfg(target_os = "linux")]
+pub mod inotify;
+pub mod options;
+pub mod parallel;
+#[cfg(target_os = "linux")]
+pub mod watch;
@@ -0,0 +1,15 @@
+use cpp_vs_rust_cli::driver::*;
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let stdin: std::io::Stdin = std::io::stdin();
+    let stdout: std::io::Stdout = std::io::stdout();
+    let stderr: std::io::Stderr = std::io::stderr();
+    let exit_code: i32 = run_cli(
+        &args,
+        &mut stdin.lock(),
+        &mut stdout.lock(),
+        &mut stderr.lock(),
+    );
+    std::process::exit(exit_code);
+}
@@ -0,0 +1,252 @@
+use cpp_vs_rust_fe::global_declared_variable_set::*;
+use cpp_vs_rust_fe::linter::*;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum InputFileLanguage {
+    JavaScript,
+    JavaScriptJSX,
+    TypeScript,
+    TypeScriptJSX,
+}
+
+impl InputFileLanguage {
+    // Parses the value of the --language option.
+    pub fn from_name(name: &str) -> Option<InputFileLanguage> {
+        match name {
+            "javascript" => Some(InputFileLanguage::JavaScript),
+            "jsx" => Some(InputFileLanguage::JavaScriptJSX),
+            "typescript" => Some(InputFileLanguage::TypeScript),
+            "tsx" => Some(InputFileLanguage::TypeScriptJSX),
+            _ => None,
+        }
+    }
+
+    // Guesses the language of a file from its extension. Files with unknown
+    // extensions (and standard input) are JavaScript with JSX.
+    pub fn from_path(path: &str) -> InputFileLanguage {
+        if path.ends_with(".tsx") {
+            InputFileLanguage::TypeScriptJSX
+        } else if path.ends_with(".ts") || path.ends_with(".mts") || path.ends_with(".cts") {
+            InputFileLanguage::TypeScript
+        } else {
+            InputFileLanguage::JavaScriptJSX
+        }
+    }
+
+    pub fn linter_options(self) -> LinterOptions {
+        let (jsx, typescript): (bool, bool) = match self {
+            InputFileLanguage::JavaScript => (false, false),
+            InputFileLanguage::JavaScriptJSX => (true, false),
+            InputFileLanguage::TypeScript => (false, true),
+            InputFileLanguage::TypeScriptJSX => (true, true),
+        };
+        LinterOptions {
+            jsx: jsx,
+            typescript: typescript,
+            print_parser_visits: false,
+            bail_out_on_binary: true,
+            cancellation: None,
+            globals: GlobalDeclaredVariableSet::new(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OutputFormat {
+    // path:line:column: severity: message [code]
+    GNULike,
+    JSON,
+    Sarif,
+}
+
+impl OutputFormat {
+    // Parses the value of the --output-format option.
+    pub fn from_name(name: &str) -> Option<OutputFormat> {
+        match name {
+            "gnu-like" => Some(OutputFormat::GNULike),
+            "json" => Some(OutputFormat::JSON),
+            "sarif" => Some(OutputFormat::Sarif),
+            _ => None,
+        }
+    }
+}
+
+// The least severe diagnostic which makes the program exit with a failure
+// status.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ExitFailOn {
+    Error,
+    Warning,
+    // Diagnostics never cause a failure status.
+    Never,
+}
+
+impl ExitFailOn {
+    // Parses the value of the --exit-fail-on option.
+    pub fn from_name(name: &str) -> Option<ExitFailOn> {
+        match name {
+            "error" => Some(ExitFailOn::Error),
+            "warning" => Some(ExitFailOn::Warning),
+            "never" => Some(ExitFailOn::Never),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FileToLint {
+    // Unused if is_stdin is true.
+    pub path: String,
+    pub is_stdin: bool,
+    pub language: InputFileLanguage,
+}
+
+impl FileToLint {
+    // The name used for this file in diagnostics.
+    pub fn display_path(&self) -> &str {
+        if self.is_stdin {
+            "<stdin>"
+        } else {
+            &self.path
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Options {
+    pub help: bool,
+    pub version: bool,
+    pub files_to_lint: Vec<FileToLint>,
+    // The --language option, if given. Also stored in each FileToLint.
+    pub language: Option<InputFileLanguage>,
+    pub output_format: OutputFormat,
+    // If None, use the locale from the environment.
+    pub locale: Option<String>,
+    pub exit_fail_on: ExitFailOn,
+    // If true, keep running and re-lint files when they change.
+    pub watch: bool,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum OptionsError {
+    UnrecognizedOption(String),
+    MissingValue(String),
+    InvalidValue { option: String, value: String },
+    MultipleStdin,
+}
+
+impl std::fmt::Display for OptionsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OptionsError::UnrecognizedOption(option) => {
+                write!(f, "unrecognized option: {option}")
+            }
+            OptionsError::MissingValue(option) => write!(f, "missing value for option: {option}"),
+            OptionsError::InvalidValue { option, value } => {
+                write!(f, "invalid value for option {option}: {value}")
+            }
+            OptionsError::MultipleStdin => write!(f, "multiple --stdin options given"),
+        }
+    }
+}
+
+// Options which take a value, either as --option=value or --option value.
+const OPTIONS_WITH_VALUES: [&str; 4] = [
+    "--exit-fail-on",
+    "--language",
+    "--locale",
+    "--output-format",
+];
+
+pub fn parse_options(args: &[String]) -> Result<Options, OptionsError> {
+    let mut options: Options = Options {
+        help: false,
+        version: false,
+        files_to_lint: vec![],
+        language: None,
+        output_format: OutputFormat::GNULike,
+        locale: None,
+        exit_fail_on: ExitFailOn::Error,
+        watch: false,
+    };
+    let mut files: Vec<(String, bool)> = vec![];
+
+    let mut args_iter = args.iter();
+    let mut only_files: bool = false;
+    while let Some(arg) = args_iter.next() {
+        if only_files || !arg.starts_with('-') {
+            files.push((arg.clone(), false));
+            continue;
+        }
+        if arg == "--" {
+            only_files = true;
+            continue;
+        }
+
+        let (name, inline_value): (&str, Option<&str>) = match arg.split_once('=') {
+            Some((name, value)) => (name, Some(value)),
+            None => (arg.as_str(), None),
+        };
+        if OPTIONS_WITH_VALUES.contains(&name) {
+            let value: &str = match inline_value {
+                Some(value) => value,
+                None => match args_iter.next() {
+                    Some(value) => value,
+                    None => return Err(OptionsError::MissingValue(name.to_string())),
+                },
+            };
+            let invalid_value = || OptionsError::InvalidValue {
+                option: name.to_string(),
+                value: value.to_string(),
+            };
+            match name {
+                "--exit-fail-on" => {
+                    options.exit_fail_on =
+                        ExitFailOn::from_name(value).ok_or_else(invalid_value)?;
+                }
+                "--language" => {
+                    options.language =
+                        Some(InputFileLanguage::from_name(value).ok_or_else(invalid_value)?);
+                }
+                "--locale" => options.locale = Some(value.to_string()),
+                "--output-format" => {
+                    options.output_format =
+                        OutputFormat::from_name(value).ok_or_else(invalid_value)?;
+                }
+                _ => unreachable!(),
+            }
+            continue;
+        }
+
+        match (name, inline_value) {
+            ("-h" | "--help", None) => options.help = true,
+            ("-v" | "--version", None) => options.version = true,
+            ("--watch", None) => options.watch = true,
+            ("--stdin", None) => {
+                if files.iter().any(|(_, is_stdin)| *is_stdin) {
+                    return Err(OptionsError::MultipleStdin);
+                }
+                files.push((String::new(), true));
+            }
+            _ => return Err(OptionsError::UnrecognizedOption(arg.clone())),
+        }
+    }
+
+    options.files_to_lint = files
+        .into_iter()
+        .map(|(path, is_stdin)| {
+            // --language applies to every file, even files listed before it.
+            let file_language: InputFileLanguage = match options.language {
+                Some(language) => language,
+                None if is_stdin => InputFileLanguage::JavaScriptJSX,
+                None => InputFileLanguage::from_path(&path),
+            };
+            FileToLint {
+                path: path,
+                is_stdin: is_stdin,
+                language: file_language,
+            }
+        })
+        .collect();
+    Ok(options)
+}
@@ -0,0 +1,112 @@
+use crate::driver::*;
+use crate::options::*;
+use cpp_vs_rust_fe::configuration::*;
+use cpp_vs_rust_fe::lex::*;
+use cpp_vs_rust_i18n::translation::*;
+use cpp_vs_rust_util::padded_string::*;
+
+// What happened when linting one file.
+pub enum FileOutcome {
+    Linted(LintedFile),
+    // The file could not be read. Contains the error message.
+    ReadError(String),
+}
+
+// Returns the number of worker threads to use if the user didn't ask for a
+// specific number.
+pub fn default_job_count() -> usize {
+    std::thread::available_parallelism().map_or(1, std::num::NonZeroUsize::get)
+}
+
+// Lints files on up to job_count worker threads. Each worker thread has its own
+// LexerAllocator.
+//
+// on_outcome is called on the calling thread once for each file, in the order
+// of files (not in the order in which linting finished).
+//
+// stdin_data is the content of standard input, used for files with is_stdin.
+// configurations[i] is the configuration for files[i] (see lint_file).
+pub fn lint_files_in_parallel(
+    files: &[FileToLint],
+    configurations: &[Option<&Configuration>],
+    stdin_data: &std::io::Result<Vec<u8>>,
+    output_format: OutputFormat,
+    translator: &Translator,
+    job_count: usize,
+    on_outcome: &mut dyn FnMut(&FileToLint, FileOutcome),
+) {
+    let next_file_index: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+    let (sender, receiver) = std::sync::mpsc::channel::<(usize, FileOutcome)>();
+    std::thread::scope(|scope| {
+        for _ in 0..std::cmp::max(1, std::cmp::min(job_count, files.len())) {
+            let sender: std::sync::mpsc::Sender<(usize, FileOutcome)> = sender.clone();
+            let next_file_index: &std::sync::atomic::AtomicUsize = &next_file_index;
+            let translator: Translator = translator.clone();
+            scope.spawn(move || {
+                let allocator: LexerAllocator = LexerAllocator::new();
+                loop {
+                    let file_index: usize =
+                        next_file_index.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                    let Some(file) = files.get(file_index) else {
+                        break;
+                    };
+                    let outcome: FileOutcome = lint_one_file(
+                        file,
+                        configurations[file_index],
+                        stdin_data,
+                        output_format,
+                        &translator,
+                        &allocator,
+                    );
+                    if sender.send((file_index, outcome)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        // Let the receiver loop end when every worker is done.
+        drop(sender);
+
+        // Outcomes which arrived before the outcomes of earlier files.
+        let mut pending_outcomes: std::collections::BTreeMap<usize, FileOutcome> =
+            std::collections::BTreeMap::new();
+        let mut next_index_to_report: usize = 0;
+        for (file_index, outcome) in receiver {
+            pending_outcomes.insert(file_index, outcome);
+            while let Some(outcome) = pending_outcomes.remove(&next_index_to_report) {
+                on_outcome(&files[next_index_to_report], outcome);
+                next_index_to_report += 1;
+            }
+        }
+    });
+}
+
+fn lint_one_file(
+    file: &FileToLint,
+    configuration: Option<&Configuration>,
+    stdin_data: &std::io::Result<Vec<u8>>,
+    output_format: OutputFormat,
+    translator: &Translator,
+    allocator: &LexerAllocator,
+) -> FileOutcome {
+    let source: PaddedString = if file.is_stdin {
+        match stdin_data {
+            Ok(data) => PaddedString::from_slice(data),
+            Err(error) => return FileOutcome::ReadError(error.to_string()),
+        }
+    } else {
+        match std::fs::read(&file.path) {
+            Ok(data) => PaddedString::from_slice(&data),
+            Err(error) => return FileOutcome::ReadError(error.to_string()),
+        }
+    };
+    FileOutcome::Linted(lint_file(
+        source.view(),
+        file.display_path(),
+        file.language,
+        configuration,
+        output_format,
+        translator.clone(),
+        allocator,
+    ))
+}
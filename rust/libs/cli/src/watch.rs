@@ -0,0 +1,569 @@
+use crate::driver::*;
+use crate::file_discovery::*;
+use crate::ignore_file::*;
+use crate::inotify::*;
+use crate::options::*;
+use cpp_vs_rust_fe::cli_location::*;
+use cpp_vs_rust_fe::configuration_loader::*;
+use cpp_vs_rust_fe::document::*;
+use cpp_vs_rust_fe::lex::*;
+use cpp_vs_rust_i18n::translation::*;
+
+// After a change, wait until nothing changes for this long before re-linting.
+// Editors often save a file in several steps (such as truncating then writing,
+// or writing a temporary file then renaming it).
+pub const DEBOUNCE_DURATION: std::time::Duration = std::time::Duration::from_millis(100);
+
+// Re-lint at most this long after the first change, even if files keep
+// changing, so that a busy directory (such as one a build writes to) does not
+// delay linting forever.
+pub const MAX_DEBOUNCE_DURATION: std::time::Duration = std::time::Duration::from_secs(1);
+
+// Events for a config file which might change its content.
+const CONFIG_CHANGE_MASK: u32 =
+    libc::IN_CLOSE_WRITE | libc::IN_DELETE | libc::IN_MOVED_FROM | libc::IN_MOVED_TO;
+
+const WATCH_MASK: u32 = libc::IN_CLOSE_WRITE
+    | libc::IN_CREATE
+    | libc::IN_DELETE
+    | libc::IN_MODIFY
+    | libc::IN_MOVED_FROM
+    | libc::IN_MOVED_TO;
+
+// Runs --watch mode: lint every file, then re-lint files whenever they change.
+//
+// Returns the process exit code if watching fails. Otherwise, never returns.
+pub fn run_watch(
+    options: &Options,
+    translator: Translator,
+    stdout: &mut dyn std::io::Write,
+    stderr: &mut dyn std::io::Write,
+) -> i32 {
+    if options
+        .files_to_lint
+        .iter()
+        .any(|file: &FileToLint| file.is_stdin)
+    {
+        let _ = writeln!(stderr, "error: --watch cannot be used with --stdin");
+        return EXIT_CODE_ERROR;
+    }
+    let mut session: WatchSession = match WatchSession::new(
+        options,
+        translator,
+        &mut |path: &std::path::Path, error: std::io::Error| {
+            report_watch_error(stderr, path, error)
+        },
+    ) {
+        Ok(session) => session,
+        Err(error) => {
+            let _ = writeln!(stderr, "error: failed to watch files: {error}");
+            return EXIT_CODE_ERROR;
+        }
+    };
+
+    let linted_files: Vec<LintedFile> =
+        session.lint_all_files(&mut |path: &std::path::Path, error: std::io::Error| {
+            report_watch_error(stderr, path, error)
+        });
+    if let Err(error) = write_linted_files(options.output_format, &linted_files, stdout) {
+        let _ = writeln!(stderr, "error: failed to write output: {error}");
+        return EXIT_CODE_ERROR;
+    }
+    loop {
+        let linted_files: Vec<LintedFile> = match session.lint_changed_files(
+            None,
+            &mut |path: &std::path::Path, error: std::io::Error| {
+                report_watch_error(stderr, path, error)
+            },
+        ) {
+            Ok(linted_files) => linted_files,
+            Err(error) => {
+                let _ = writeln!(stderr, "error: failed to watch files: {error}");
+                return EXIT_CODE_ERROR;
+            }
+        };
+        // Events which changed nothing (such as saving a file without
+        // modifying it) produce no output.
+        if linted_files.is_empty() {
+            continue;
+        }
+        if let Err(error) = write_linted_files(options.output_format, &linted_files, stdout) {
+            let _ = writeln!(stderr, "error: failed to write output: {error}");
+            return EXIT_CODE_ERROR;
+        }
+    }
+}
+
+fn report_watch_error(
+    stderr: &mut dyn std::io::Write,
+    path: &std::path::Path,
+    error: std::io::Error,
+) {
+    let _ = writeln!(stderr, "error: failed to read {}: {error}", path.display());
+}
+
+fn write_linted_files(
+    output_format: OutputFormat,
+    linted_files: &[LintedFile],
+    out: &mut dyn std::io::Write,
+) -> std::io::Result<()> {
+    let mut output_writer: OutputWriter = OutputWriter::new(output_format);
+    for linted in linted_files {
+        output_writer.add_file(linted, out)?;
+    }
+    output_writer.finish(out)
+}
+
+struct WatchedDirectory {
+    path: std::path::PathBuf,
+    // If Some, this directory is inside the given directory from the command
+    // line, and new files in this directory should be linted. If None, this
+    // directory is watched only for files given on the command line.
+    root: Option<std::path::PathBuf>,
+}
+
+struct WatchedFile {
+    document: Document<CLILocator</* HACK(strager) */ 'static>>,
+    display_path: String,
+    language: InputFileLanguage,
+    // If true, the file was given on the command line, so keep watching it
+    // even if it is deleted.
+    explicit: bool,
+    // The config file used the last time this file was linted.
+    config_file: Option<std::sync::Arc<ConfigFile>>,
+}
+
+// Watches files and directories using inotify, keeping the content of each
+// watched file in memory.
+//
+// Files created inside directories given on the command line are linted too,
+// unless they are excluded by ignore files.
+//
+// Each file is linted with its quick-lint-js.config file, which is re-read
+// when it changes. Problems in a config file are reported before the first
+// file which uses that version of the config file. When a config file in a
+// watched directory is written, moved, or deleted, the files which use it are
+// re-linted. Changes to ignore files do not cause files to be re-linted.
+pub struct WatchSession {
+    inotify: Inotify,
+    output_format: OutputFormat,
+    translator: Translator,
+    // The --language option, if given.
+    language: Option<InputFileLanguage>,
+    allocator: LexerAllocator,
+    config_loader: ConfigurationLoader,
+    // The config files whose problems were most recently reported, keyed by
+    // ConfigFile::path.
+    reported_config_files:
+        std::collections::HashMap<std::path::PathBuf, std::sync::Arc<ConfigFile>>,
+    directories: std::collections::BTreeMap<WatchDescriptor, WatchedDirectory>,
+    files: std::collections::BTreeMap<std::path::PathBuf, WatchedFile>,
+    // Keys of files, in the order they should be linted by lint_all_files.
+    initial_paths: Vec<std::path::PathBuf>,
+}
+
+impl WatchSession {
+    // Start watching the files and directories in options.files_to_lint.
+    //
+    // Problems with individual files and directories are passed to on_error.
+    // Returns an error if inotify could not be initialized.
+    pub fn new(
+        options: &Options,
+        translator: Translator,
+        on_error: &mut dyn FnMut(&std::path::Path, std::io::Error),
+    ) -> std::io::Result<WatchSession> {
+        let mut session: WatchSession = WatchSession {
+            inotify: Inotify::new()?,
+            output_format: options.output_format,
+            translator: translator,
+            language: options.language,
+            allocator: LexerAllocator::new(),
+            config_loader: ConfigurationLoader::new(),
+            reported_config_files: std::collections::HashMap::new(),
+            directories: std::collections::BTreeMap::new(),
+            files: std::collections::BTreeMap::new(),
+            initial_paths: vec![],
+        };
+        for file in &options.files_to_lint {
+            let path: &std::path::Path = std::path::Path::new(&file.path);
+            if path.is_dir() {
+                let listing: DirectoryListing = list_directory_recursively(path, on_error);
+                session.watch_listing(&listing, path, on_error);
+                continue;
+            }
+            let (Some(parent), Some(file_name)) = (path.parent(), path.file_name()) else {
+                on_error(
+                    path,
+                    std::io::Error::new(std::io::ErrorKind::InvalidInput, "not a file"),
+                );
+                continue;
+            };
+            let parent: &std::path::Path = if parent.as_os_str().is_empty() {
+                std::path::Path::new(".")
+            } else {
+                parent
+            };
+            // Key the file by the path which inotify events will produce.
+            let watched_parent: std::path::PathBuf =
+                session.watch_directory(parent, None, on_error);
+            session.add_initial_file(
+                watched_parent.join(file_name),
+                file.path.clone(),
+                file.language,
+                true,
+            );
+        }
+        Ok(session)
+    }
+
+    // Read and lint every watched file, in command-line order. Files inside
+    // directories are in path order (see find_files_in_directory).
+    pub fn lint_all_files(
+        &mut self,
+        on_error: &mut dyn FnMut(&std::path::Path, std::io::Error),
+    ) -> Vec<LintedFile> {
+        let mut linted_files: Vec<LintedFile> = vec![];
+        for path in std::mem::take(&mut self.initial_paths) {
+            let content: Vec<u8> = match std::fs::read(&path) {
+                Ok(content) => content,
+                Err(error) => {
+                    on_error(&path, error);
+                    continue;
+                }
+            };
+            let file: &mut WatchedFile = self.files.get_mut(&path).unwrap();
+            file.document.set_text(&content);
+            self.lint_watched_file(&path, &mut linted_files, on_error);
+        }
+        linted_files
+    }
+
+    // Wait up to timeout (or forever if timeout is None) for a file to change.
+    // Then, wait for changes to settle (see DEBOUNCE_DURATION and
+    // MAX_DEBOUNCE_DURATION), and re-lint the changed files in path order.
+    //
+    // Files whose content and config file did not change are not re-linted.
+    // Returns no files if nothing changed before the timeout.
+    pub fn lint_changed_files(
+        &mut self,
+        timeout: Option<std::time::Duration>,
+        on_error: &mut dyn FnMut(&std::path::Path, std::io::Error),
+    ) -> std::io::Result<Vec<LintedFile>> {
+        let mut events: Vec<InotifyEvent> = self.inotify.read_events(timeout)?;
+        if events.is_empty() {
+            return Ok(vec![]);
+        }
+        let debounce_deadline: std::time::Instant =
+            std::time::Instant::now() + MAX_DEBOUNCE_DURATION;
+        loop {
+            let time_until_deadline: std::time::Duration =
+                debounce_deadline.saturating_duration_since(std::time::Instant::now());
+            if time_until_deadline.is_zero() {
+                break;
+            }
+            let more_events: Vec<InotifyEvent> = self
+                .inotify
+                .read_events(Some(std::cmp::min(DEBOUNCE_DURATION, time_until_deadline)))?;
+            if more_events.is_empty() {
+                break;
+            }
+            events.extend(more_events);
+        }
+
+        let mut changed_paths: std::collections::BTreeSet<std::path::PathBuf> =
+            std::collections::BTreeSet::new();
+        let mut new_directories: Vec<(std::path::PathBuf, std::path::PathBuf)> = vec![];
+        for event in events {
+            if event.mask & libc::IN_Q_OVERFLOW != 0 {
+                // Some events were lost. Check every file.
+                changed_paths.extend(self.files.keys().cloned());
+                continue;
+            }
+            if event.mask & libc::IN_IGNORED != 0 {
+                // The directory was deleted, so its watch was removed.
+                self.directories.remove(&event.watch_descriptor);
+                continue;
+            }
+            let Some(directory) = self.directories.get(&event.watch_descriptor) else {
+                continue;
+            };
+            if event.name.is_empty() {
+                continue;
+            }
+            let path: std::path::PathBuf = directory.path.join(&event.name);
+            if !event.is_directory() {
+                if event.name == CONFIG_FILE_NAME && event.mask & CONFIG_CHANGE_MASK != 0 {
+                    // Files in this directory and its subdirectories might use
+                    // the changed config file. Check them.
+                    changed_paths.extend(
+                        self.files
+                            .keys()
+                            .filter(|file_path: &&std::path::PathBuf| {
+                                file_path.starts_with(&directory.path)
+                            })
+                            .cloned(),
+                    );
+                }
+                changed_paths.insert(path);
+                continue;
+            }
+            if event.mask & (libc::IN_CREATE | libc::IN_MOVED_TO) != 0 {
+                if let Some(root) = &directory.root {
+                    new_directories.push((path.clone(), root.clone()));
+                }
+            }
+            // Files in a directory which was moved away don't get their own
+            // events. Check them.
+            changed_paths.extend(
+                self.files
+                    .keys()
+                    .filter(|file_path: &&std::path::PathBuf| file_path.starts_with(&path))
+                    .cloned(),
+            );
+        }
+
+        for (directory, root) in new_directories {
+            let mut ignore_rules: IgnoreRules = IgnoreRules::new();
+            ignore_rules.push_directories_down_to(&root, &directory, on_error);
+            if ignore_rules.is_ignored(&directory, true) {
+                continue;
+            }
+            let listing: DirectoryListing = list_directory_recursively_with_ignore_rules(
+                &directory,
+                &mut ignore_rules,
+                on_error,
+            );
+            changed_paths.extend(listing.files.iter().cloned());
+            for subdirectory in &listing.directories {
+                self.watch_directory(subdirectory, Some(&root), on_error);
+            }
+        }
+
+        let mut linted_files: Vec<LintedFile> = vec![];
+        for path in changed_paths {
+            self.relint_path(&path, &mut linted_files, on_error);
+        }
+        Ok(linted_files)
+    }
+
+    fn watch_listing(
+        &mut self,
+        listing: &DirectoryListing,
+        root: &std::path::Path,
+        on_error: &mut dyn FnMut(&std::path::Path, std::io::Error),
+    ) {
+        for directory in &listing.directories {
+            self.watch_directory(directory, Some(root), on_error);
+        }
+        for path in &listing.files {
+            let Some(display_path) = path.to_str() else {
+                continue;
+            };
+            let language: InputFileLanguage = self
+                .language
+                .unwrap_or_else(|| InputFileLanguage::from_path(display_path));
+            self.add_initial_file(path.clone(), display_path.to_string(), language, false);
+        }
+    }
+
+    // Returns the path used for events in directory. This might differ from
+    // directory if directory was already watched under a different name.
+    fn watch_directory(
+        &mut self,
+        directory: &std::path::Path,
+        root: Option<&std::path::Path>,
+        on_error: &mut dyn FnMut(&std::path::Path, std::io::Error),
+    ) -> std::path::PathBuf {
+        let watch_descriptor: WatchDescriptor = match self.inotify.add_watch(directory, WATCH_MASK)
+        {
+            Ok(watch_descriptor) => watch_descriptor,
+            Err(error) => {
+                on_error(directory, error);
+                return directory.to_path_buf();
+            }
+        };
+        let watched: &mut WatchedDirectory = self
+            .directories
+            .entry(watch_descriptor)
+            .or_insert_with(|| WatchedDirectory {
+                path: directory.to_path_buf(),
+                root: None,
+            });
+        if watched.root.is_none() {
+            watched.root = root.map(std::path::Path::to_path_buf);
+        }
+        watched.path.clone()
+    }
+
+    // Like add_file, but also lint the file in lint_all_files.
+    fn add_initial_file(
+        &mut self,
+        path: std::path::PathBuf,
+        display_path: String,
+        language: InputFileLanguage,
+        explicit: bool,
+    ) {
+        if let Some(file) = self.files.get_mut(&path) {
+            // The same file was given twice (such as 'dir dir/a.js').
+            file.explicit |= explicit;
+            return;
+        }
+        self.initial_paths.push(path.clone());
+        self.add_file(path, display_path, language, explicit);
+    }
+
+    fn add_file(
+        &mut self,
+        path: std::path::PathBuf,
+        display_path: String,
+        language: InputFileLanguage,
+        explicit: bool,
+    ) {
+        self.files.insert(
+            path,
+            WatchedFile {
+                document: Document::new(),
+                display_path: display_path,
+                language: language,
+                explicit: explicit,
+                config_file: None,
+            },
+        );
+    }
+
+    // Returns true if path is a file created inside a directory from the
+    // command line, and it should be linted.
+    fn should_lint_new_file(
+        &self,
+        path: &std::path::Path,
+        on_error: &mut dyn FnMut(&std::path::Path, std::io::Error),
+    ) -> bool {
+        if !is_lintable_path(path) || path.to_str().is_none() {
+            return false;
+        }
+        let Some(parent) = path.parent() else {
+            return false;
+        };
+        let Some(root) = self
+            .directories
+            .values()
+            .find(|directory: &&WatchedDirectory| directory.path == parent)
+            .and_then(|directory: &WatchedDirectory| directory.root.as_ref())
+        else {
+            return false;
+        };
+        let mut ignore_rules: IgnoreRules = IgnoreRules::new();
+        ignore_rules.push_directories_down_to(root, path, on_error);
+        !ignore_rules.is_ignored(path, false)
+    }
+
+    // If path changed, re-lint it, adding the results to linted_files.
+    fn relint_path(
+        &mut self,
+        path: &std::path::Path,
+        linted_files: &mut Vec<LintedFile>,
+        on_error: &mut dyn FnMut(&std::path::Path, std::io::Error),
+    ) {
+        let is_watched: bool = self.files.contains_key(path);
+        if !is_watched && !self.should_lint_new_file(path, on_error) {
+            return;
+        }
+        let content: Vec<u8> = match std::fs::read(path) {
+            Ok(content) => content,
+            Err(error) => {
+                if error.kind() != std::io::ErrorKind::NotFound {
+                    on_error(path, error);
+                } else if self
+                    .files
+                    .get(path)
+                    .is_some_and(|file: &WatchedFile| !file.explicit)
+                {
+                    self.files.remove(path);
+                }
+                return;
+            }
+        };
+        if !is_watched {
+            let display_path: &str = path.to_str().unwrap();
+            let language: InputFileLanguage = self
+                .language
+                .unwrap_or_else(|| InputFileLanguage::from_path(display_path));
+            self.add_file(
+                path.to_path_buf(),
+                display_path.to_string(),
+                language,
+                false,
+            );
+        }
+        let content_changed: bool = self.files[path].document.string().slice() != &content[..];
+        if is_watched && !content_changed && !self.config_file_changed(path, on_error) {
+            return;
+        }
+        if content_changed {
+            let file: &mut WatchedFile = self.files.get_mut(path).unwrap();
+            file.document.set_text(&content);
+        }
+        self.lint_watched_file(path, linted_files, on_error);
+    }
+
+    // Returns true if the watched file at path would be linted with a
+    // different config file than last time, such as because its config file
+    // was modified or deleted.
+    fn config_file_changed(
+        &mut self,
+        path: &std::path::Path,
+        on_error: &mut dyn FnMut(&std::path::Path, std::io::Error),
+    ) -> bool {
+        let config_file: Option<std::sync::Arc<ConfigFile>> =
+            self.config_loader.load_for_file(path, on_error);
+        match (&config_file, &self.files[path].config_file) {
+            (Some(new_config_file), Some(old_config_file)) => {
+                !std::sync::Arc::ptr_eq(new_config_file, old_config_file)
+            }
+            (None, None) => false,
+            _ => true,
+        }
+    }
+
+    // Lint the file at path, adding the results to linted_files. If its config
+    // file has new problems, add them to linted_files first.
+    fn lint_watched_file(
+        &mut self,
+        path: &std::path::Path,
+        linted_files: &mut Vec<LintedFile>,
+        on_error: &mut dyn FnMut(&std::path::Path, std::io::Error),
+    ) {
+        let config_file: Option<std::sync::Arc<ConfigFile>> =
+            self.config_loader.load_for_file(path, on_error);
+        if let Some(config_file) = &config_file {
+            let is_reported: bool = self
+                .reported_config_files
+                .get(config_file.path())
+                .is_some_and(|reported: &std::sync::Arc<ConfigFile>| {
+                    std::sync::Arc::ptr_eq(reported, config_file)
+                });
+            if !is_reported {
+                self.reported_config_files
+                    .insert(config_file.path().to_path_buf(), config_file.clone());
+                if config_file.has_errors() {
+                    linted_files.push(lint_config_file(
+                        config_file,
+                        self.output_format,
+                        self.translator.clone(),
+                    ));
+                }
+            }
+        }
+        let file: &mut WatchedFile = self.files.get_mut(path).unwrap();
+        linted_files.push(lint_file(
+            file.document.string(),
+            &file.display_path,
+            file.language,
+            config_file.as_deref().map(ConfigFile::configuration),
+            self.output_format,
+            self.translator.clone(),
+            &self.allocator,
+        ));
+        file.config_file = config_file;
+    }
+}
@@ -0,0 +1,285 @@
+use cpp_vs_rust_cli::driver::*;
+use cpp_vs_rust_test::temp_directory::*;
+use cpp_vs_rust_util::json::*;
+
+struct CLIResult {
+    exit_code: i32,
+    stdout: String,
+    stderr: String,
+}
+
+fn run(args: &[&str], stdin: &[u8]) -> CLIResult {
+    let args: Vec<String> = args.iter().map(|arg: &&str| arg.to_string()).collect();
+    let mut stdin: &[u8] = stdin;
+    let mut stdout: Vec<u8> = vec![];
+    let mut stderr: Vec<u8> = vec![];
+    let exit_code: i32 = run_cli(&args, &mut stdin, &mut stdout, &mut stderr);
+    CLIResult {
+        exit_code: exit_code,
+        stdout: String::from_utf8(stdout).unwrap(),
+        stderr: String::from_utf8(stderr).unwrap(),
+    }
+}
+
+// NOTE(port): E0212 (integer rounded) is a warning. E0042 (unexpected '@') is
+// an error.
+const WARNING_SOURCE: &[u8] = b"let x = 9007199254740993;";
+const ERROR_SOURCE: &[u8] = b"let x;\n@";
+
+#[test]
+fn clean_stdin_succeeds_with_no_output() {
+    let result: CLIResult = run(&["--stdin"], b"let x = 42;");
+    assert_eq!(result.exit_code, EXIT_CODE_SUCCESS);
+    assert_eq!(result.stdout, "");
+    assert_eq!(result.stderr, "");
+}
+
+#[test]
+fn error_in_stdin_fails_with_gnu_like_output() {
+    let result: CLIResult = run(&["--stdin"], ERROR_SOURCE);
+    assert_eq!(result.exit_code, EXIT_CODE_DIAGNOSTICS);
+    assert!(
+        result.stdout.starts_with("<stdin>:2:1: error: "),
+        "{}",
+        result.stdout
+    );
+    assert!(
+        result.stdout.contains("[E0042]\n@\n^\n"),
+        "{}",
+        result.stdout
+    );
+}
+
+#[test]
+fn binary_stdin_reports_single_diagnostic() {
+    let result: CLIResult = run(&["--stdin"], &b"\x00\x01\x02\x03 @".repeat(8));
+    assert_eq!(result.stdout.matches("[E0").count(), 1, "{}", result.stdout);
+    assert!(result.stdout.contains("[E0301]"), "{}", result.stdout);
+}
+
+#[test]
+fn exit_fail_on_decides_which_severities_fail() {
+    assert_eq!(
+        run(&["--stdin"], WARNING_SOURCE).exit_code,
+        EXIT_CODE_SUCCESS
+    );
+    assert_eq!(
+        run(&["--stdin", "--exit-fail-on=warning"], WARNING_SOURCE).exit_code,
+        EXIT_CODE_DIAGNOSTICS
+    );
+    assert_eq!(
+        run(&["--stdin", "--exit-fail-on=warning"], ERROR_SOURCE).exit_code,
+        EXIT_CODE_DIAGNOSTICS
+    );
+    assert_eq!(
+        run(&["--stdin", "--exit-fail-on=never"], ERROR_SOURCE).exit_code,
+        EXIT_CODE_SUCCESS
+    );
+}
+
+#[test]
+fn json_output_is_an_array_of_diagnostics() {
+    let result: CLIResult = run(&["--stdin", "--output-format=json"], ERROR_SOURCE);
+    assert_eq!(result.exit_code, EXIT_CODE_DIAGNOSTICS);
+    let diagnostics: JSONValue = parse_json(result.stdout.as_bytes()).unwrap();
+    let diagnostics: &[JSONValue] = diagnostics.as_array().unwrap();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+        diagnostics[0].get("file").and_then(JSONValue::as_str),
+        Some("<stdin>")
+    );
+    assert_eq!(
+        diagnostics[0].get("code").and_then(JSONValue::as_str),
+        Some("E0042")
+    );
+
+    let result: CLIResult = run(&["--stdin", "--output-format=json"], b"");
+    assert_eq!(result.stdout, "[]\n");
+}
+
+#[test]
+fn sarif_output_combines_files() {
+    let dir: TempDirectory = TempDirectory::new("sarif_output_combines_files");
+    let one: String = dir.write_file("one.js", b"@");
+    let two: String = dir.write_file("two.js", b"#");
+    let result: CLIResult = run(&["--output-format=sarif", &one, &two], b"");
+    assert_eq!(result.exit_code, EXIT_CODE_DIAGNOSTICS);
+    let log: JSONValue = parse_json(result.stdout.as_bytes()).unwrap();
+    let results: &[JSONValue] = log
+        .get("runs")
+        .and_then(JSONValue::as_array)
+        .and_then(|runs: &[JSONValue]| runs[0].get("results"))
+        .and_then(JSONValue::as_array)
+        .unwrap();
+    let rule_ids: Vec<&str> = results
+        .iter()
+        .map(|result: &JSONValue| result.get("ruleId").and_then(JSONValue::as_str).unwrap())
+        .collect();
+    assert_eq!(rule_ids, vec!["E0042", "E0052"]);
+}
+
+#[test]
+fn files_are_linted_in_command_line_order() {
+    let dir: TempDirectory = TempDirectory::new("files_are_linted_in_command_line_order");
+    let b: String = dir.write_file("b.js", b"@");
+    let a: String = dir.write_file("a.ts", b"@");
+    let result: CLIResult = run(&[&b, &a], b"");
+    let b_index: usize = result.stdout.find("b.js:1:1:").unwrap();
+    let a_index: usize = result.stdout.find("a.ts:1:1:").unwrap();
+    assert!(b_index < a_index, "{}", result.stdout);
+}
+
+#[test]
+fn unreadable_file_is_reported_and_other_files_are_linted() {
+    let dir: TempDirectory =
+        TempDirectory::new("unreadable_file_is_reported_and_other_files_are_linted");
+    let missing: String = dir.path().join("missing.js").to_str().unwrap().to_string();
+    let result: CLIResult = run(&[&missing, "--stdin"], ERROR_SOURCE);
+    assert_eq!(result.exit_code, EXIT_CODE_ERROR);
+    assert!(
+        result
+            .stderr
+            .starts_with(&format!("error: failed to read {missing}: ")),
+        "{}",
+        result.stderr
+    );
+    assert!(result.stdout.contains("<stdin>:2:1: error: "));
+}
+
+#[test]
+fn broken_config_file_is_reported_once() {
+    let dir: TempDirectory = TempDirectory::new("broken_config_file_is_reported_once");
+    let config: String = dir.write_file("quick-lint-js.config", b"{\"globals\": 42}");
+    let a: String = dir.write_file("src/a.js", b"let x = 1;");
+    let b: String = dir.write_file("src/b.js", b"let y = 2;");
+    let result: CLIResult = run(&[&a, &b], b"");
+    assert_eq!(result.exit_code, EXIT_CODE_DIAGNOSTICS);
+    let config_path: std::path::PathBuf = std::fs::canonicalize(config).unwrap();
+    assert!(
+        result.stdout.starts_with(&format!(
+            "{}:1:13: error: 'globals' must be an object [E0168]\n",
+            config_path.display()
+        )),
+        "{}",
+        result.stdout
+    );
+    assert_eq!(
+        result.stdout.matches("[E0168]").count(),
+        1,
+        "{}",
+        result.stdout
+    );
+}
+
+#[test]
+fn config_file_errors_use_output_format() {
+    let dir: TempDirectory = TempDirectory::new("config_file_errors_use_output_format");
+    let config: String = dir.write_file("quick-lint-js.config", b"{");
+    let a: String = dir.write_file("a.js", b"@");
+    let result: CLIResult = run(&["--output-format=json", &a], b"");
+    let diagnostics: JSONValue = parse_json(result.stdout.as_bytes()).unwrap();
+    let files_and_codes: Vec<(&str, &str)> = diagnostics
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|diagnostic: &JSONValue| {
+            (
+                diagnostic.get("file").and_then(JSONValue::as_str).unwrap(),
+                diagnostic.get("code").and_then(JSONValue::as_str).unwrap(),
+            )
+        })
+        .collect();
+    let config_path: std::path::PathBuf = std::fs::canonicalize(config).unwrap();
+    assert_eq!(
+        files_and_codes,
+        vec![
+            (config_path.to_str().unwrap(), "E0164"),
+            (a.as_str(), "E0042")
+        ]
+    );
+}
+
+#[test]
+fn messages_are_translated_with_locale_option() {
+    let result: CLIResult = run(&["--stdin", "--locale=en_US@snarky"], b"'");
+    assert!(
+        result.stdout.contains("error: \"unclosed string literal"),
+        "{}",
+        result.stdout
+    );
+}
+
+#[test]
+fn usage_errors() {
+    let result: CLIResult = run(&["--bogus", "x.js"], b"");
+    assert_eq!(result.exit_code, EXIT_CODE_ERROR);
+    assert!(result
+        .stderr
+        .starts_with("error: unrecognized option: --bogus\n"));
+    assert_eq!(result.stdout, "");
+
+    let result: CLIResult = run(&[], b"");
+    assert_eq!(result.exit_code, EXIT_CODE_ERROR);
+    assert_eq!(result.stderr, "error: expected file name\n");
+}
+
+#[test]
+fn help_and_version_print_to_stdout() {
+    let result: CLIResult = run(&["--help"], b"");
+    assert_eq!(result.exit_code, EXIT_CODE_SUCCESS);
+    assert!(result.stdout.starts_with("Usage: quick-lint-js "));
+
+    let result: CLIResult = run(&["--version"], b"");
+    assert_eq!(result.exit_code, EXIT_CODE_SUCCESS);
+    assert!(result.stdout.starts_with("quick-lint-js version "));
+}
+
+#[test]
+fn directories_are_searched_recursively() {
+    let dir: TempDirectory = TempDirectory::new("driver_directories_are_searched_recursively");
+    dir.write_file(".gitignore", b"ignored/\n");
+    dir.write_file("ignored/bad.js", b"@");
+    dir.write_file("src/bad.ts", b"@");
+    dir.write_file("src/good.js", b"let x;");
+    let result: CLIResult = run(&[dir.path().to_str().unwrap()], b"");
+    assert_eq!(result.exit_code, EXIT_CODE_DIAGNOSTICS);
+    assert_eq!(
+        result.stdout.matches("error:").count(),
+        1,
+        "{}",
+        result.stdout
+    );
+    assert!(
+        result.stdout.contains("bad.ts:1:1: error: "),
+        "{}",
+        result.stdout
+    );
+}
+
+#[test]
+fn results_are_in_deterministic_path_order() {
+    let dir: TempDirectory = TempDirectory::new("driver_results_are_in_deterministic_path_order");
+    const FILE_COUNT: usize = 200;
+    for i in 0..FILE_COUNT {
+        // Vary file sizes so files finish linting out of order.
+        let source: String = format!("{}@", "x ".repeat((FILE_COUNT - i) * 50));
+        dir.write_file(&format!("dir{}/file{i:03}.js", i % 3), source.as_bytes());
+    }
+    let mut expected_paths: Vec<String> = (0..FILE_COUNT)
+        .map(|i: usize| format!("dir{}/file{i:03}.js", i % 3))
+        .collect();
+    expected_paths.sort();
+
+    for output_format in ["gnu-like", "json"] {
+        let format_arg: String = format!("--output-format={output_format}");
+        let result: CLIResult = run(&[&format_arg, dir.path().to_str().unwrap()], b"");
+        let mut positions: Vec<usize> = vec![];
+        for path in &expected_paths {
+            positions.push(result.stdout.find(path.as_str()).unwrap());
+        }
+        assert!(
+            positions.windows(2).all(|pair: &[usize]| pair[0] < pair[1]),
+            "{output_format}"
+        );
+    }
+}
@@ -0,0 +1,77 @@
+use cpp_vs_rust_cli::file_discovery::*;
+use cpp_vs_rust_test::temp_directory::*;
+
+// Returns paths relative to dir, '/'-separated.
+fn find_files(dir: &TempDirectory) -> Vec<String> {
+    let files: Vec<std::path::PathBuf> =
+        find_files_in_directory(dir.path(), &mut |path: &std::path::Path, error| {
+            panic!("{}: {error}", path.display());
+        });
+    files
+        .iter()
+        .map(|path: &std::path::PathBuf| {
+            path.strip_prefix(dir.path())
+                .unwrap()
+                .to_str()
+                .unwrap()
+                .replace('\\', "/")
+        })
+        .collect()
+}
+
+#[test]
+fn finds_javascript_and_typescript_files_recursively_in_sorted_order() {
+    let dir: TempDirectory = TempDirectory::new("file_discovery_finds_files_in_sorted_order");
+    dir.write_file("b.ts", b"");
+    dir.write_file("a.js", b"");
+    dir.write_file("c/z.tsx", b"");
+    dir.write_file("c/d/y.mjs", b"");
+    dir.write_file("README.md", b"");
+    dir.write_file("data.json", b"");
+    dir.create_directory("empty");
+    assert_eq!(
+        find_files(&dir),
+        vec!["a.js", "b.ts", "c/d/y.mjs", "c/z.tsx"]
+    );
+}
+
+#[test]
+fn skips_ignored_files_and_directories() {
+    let dir: TempDirectory = TempDirectory::new("file_discovery_skips_ignored");
+    dir.write_file(".gitignore", b"node_modules/\n/dist\n*.min.js\n");
+    dir.write_file(".quick-lint-js-ignore", b"fixtures/\n");
+    dir.write_file("node_modules/lib/index.js", b"");
+    dir.write_file("dist/app.js", b"");
+    dir.write_file("src/dist/app.js", b"");
+    dir.write_file("src/app.min.js", b"");
+    dir.write_file("src/app.js", b"");
+    dir.write_file("test/fixtures/bad.js", b"");
+    dir.write_file("test/.gitignore", b"*.snap.js\n");
+    dir.write_file("test/x.snap.js", b"");
+    dir.write_file("test/x.js", b"");
+    dir.write_file("x.snap.js", b"");
+    assert_eq!(
+        find_files(&dir),
+        vec!["src/app.js", "src/dist/app.js", "test/x.js", "x.snap.js"]
+    );
+}
+
+#[test]
+fn skips_git_directory() {
+    let dir: TempDirectory = TempDirectory::new("file_discovery_skips_git_directory");
+    dir.write_file(".git/hooks/pre-commit.js", b"");
+    dir.write_file("index.js", b"");
+    assert_eq!(find_files(&dir), vec!["index.js"]);
+}
+
+#[test]
+fn lintable_extensions() {
+    for path in [
+        "a.js", "a.cjs", "a.mjs", "a.jsx", "a.ts", "a.cts", "a.mts", "a.tsx",
+    ] {
+        assert!(is_lintable_path(std::path::Path::new(path)), "{path}");
+    }
+    for path in ["a.json", "a.md", "js", "a.js.map", "Makefile"] {
+        assert!(!is_lintable_path(std::path::Path::new(path)), "{path}");
+    }
+}
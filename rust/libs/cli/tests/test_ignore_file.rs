@@ -0,0 +1,89 @@
+use cpp_vs_rust_cli::ignore_file::*;
+use cpp_vs_rust_test::temp_directory::*;
+
+fn is_ignored(ignore_file: &str, relative_path: &str, is_directory: bool) -> bool {
+    let mut ignored: bool = false;
+    for pattern in parse_ignore_file(ignore_file.as_bytes()) {
+        if pattern.matches(relative_path.as_bytes(), is_directory) {
+            ignored = !pattern.is_negated();
+        }
+    }
+    ignored
+}
+
+#[test]
+fn comments_and_blank_lines_are_not_patterns() {
+    assert_eq!(
+        parse_ignore_file(b"# comment\n\n   \r\n\\#not-comment\n").len(),
+        1
+    );
+    assert!(is_ignored("\\#not-comment", "#not-comment", false));
+}
+
+#[test]
+fn pattern_without_slash_matches_name_at_any_depth() {
+    assert!(is_ignored("*.min.js", "app.min.js", false));
+    assert!(is_ignored("*.min.js", "dist/app.min.js", false));
+    assert!(is_ignored("node_modules", "a/b/node_modules", true));
+    assert!(!is_ignored("*.min.js", "app.js", false));
+}
+
+#[test]
+fn pattern_with_slash_is_relative_to_ignore_file() {
+    assert!(is_ignored("/build", "build", true));
+    assert!(!is_ignored("/build", "src/build", true));
+    assert!(is_ignored("src/*.js", "src/a.js", false));
+    assert!(!is_ignored("src/*.js", "lib/src/a.js", false));
+    assert!(is_ignored("**/gen/*.js", "a/b/gen/x.js", false));
+}
+
+#[test]
+fn trailing_slash_matches_only_directories() {
+    assert!(is_ignored("out/", "out", true));
+    assert!(!is_ignored("out/", "out", false));
+    assert!(is_ignored("out/", "sub/out", true));
+}
+
+#[test]
+fn later_negated_pattern_unignores() {
+    let ignore_file: &str = "*.js\n!keep.js\n";
+    assert!(is_ignored(ignore_file, "drop.js", false));
+    assert!(!is_ignored(ignore_file, "keep.js", false));
+    assert!(is_ignored("!keep.js\n*.js\n", "keep.js", false));
+}
+
+#[test]
+fn trailing_whitespace_and_crlf_are_trimmed() {
+    assert!(is_ignored("a.js  \r\n", "a.js", false));
+    assert!(is_ignored("a\\ ", "a ", false));
+}
+
+#[test]
+fn deeper_ignore_files_take_priority() {
+    let dir: TempDirectory = TempDirectory::new("ignore_file_deeper_ignore_files_take_priority");
+    dir.write_file(".gitignore", b"*.js\n");
+    dir.write_file("sub/.gitignore", b"!keep.js\n");
+    let mut rules: IgnoreRules = IgnoreRules::new();
+    let mut on_error = |path: &std::path::Path, error: std::io::Error| {
+        panic!("{}: {error}", path.display());
+    };
+    rules.push_directory(dir.path(), &mut on_error);
+    assert!(rules.is_ignored(&dir.path().join("keep.js"), false));
+    rules.push_directory(&dir.path().join("sub"), &mut on_error);
+    assert!(rules.is_ignored(&dir.path().join("sub/drop.js"), false));
+    assert!(!rules.is_ignored(&dir.path().join("sub/keep.js"), false));
+    rules.pop_directory();
+    rules.pop_directory();
+}
+
+#[test]
+fn quick_lint_js_ignore_takes_priority_over_gitignore() {
+    let dir: TempDirectory =
+        TempDirectory::new("ignore_file_quick_lint_js_ignore_takes_priority_over_gitignore");
+    dir.write_file(".gitignore", b"generated/\n");
+    dir.write_file(".quick-lint-js-ignore", b"!generated/\nvendor/\n");
+    let mut rules: IgnoreRules = IgnoreRules::new();
+    rules.push_directory(dir.path(), &mut |_, _| panic!());
+    assert!(!rules.is_ignored(&dir.path().join("generated"), true));
+    assert!(rules.is_ignored(&dir.path().join("vendor"), true));
+}
@@ -0,0 +1,172 @@
+use cpp_vs_rust_cli::options::*;
+
+fn parse(args: &[&str]) -> Result<Options, OptionsError> {
+    let args: Vec<String> = args.iter().map(|arg: &&str| arg.to_string()).collect();
+    parse_options(&args)
+}
+
+#[test]
+fn defaults() {
+    let options: Options = parse(&["hello.js"]).unwrap();
+    assert!(!options.help);
+    assert!(!options.version);
+    assert_eq!(options.output_format, OutputFormat::GNULike);
+    assert_eq!(options.locale, None);
+    assert_eq!(options.exit_fail_on, ExitFailOn::Error);
+    assert_eq!(
+        options.files_to_lint,
+        vec![FileToLint {
+            path: "hello.js".to_string(),
+            is_stdin: false,
+            language: InputFileLanguage::JavaScriptJSX,
+        }]
+    );
+}
+
+#[test]
+fn language_is_guessed_from_extension() {
+    let options: Options = parse(&["a.js", "b.ts", "c.tsx", "d.mjs", "--stdin"]).unwrap();
+    let languages: Vec<InputFileLanguage> = options
+        .files_to_lint
+        .iter()
+        .map(|file: &FileToLint| file.language)
+        .collect();
+    assert_eq!(
+        languages,
+        vec![
+            InputFileLanguage::JavaScriptJSX,
+            InputFileLanguage::TypeScript,
+            InputFileLanguage::TypeScriptJSX,
+            InputFileLanguage::JavaScriptJSX,
+            InputFileLanguage::JavaScriptJSX,
+        ]
+    );
+}
+
+#[test]
+fn language_option_applies_to_every_file() {
+    for (name, language) in [
+        ("javascript", InputFileLanguage::JavaScript),
+        ("jsx", InputFileLanguage::JavaScriptJSX),
+        ("typescript", InputFileLanguage::TypeScript),
+        ("tsx", InputFileLanguage::TypeScriptJSX),
+    ] {
+        let language_arg: String = format!("--language={name}");
+        let options: Options = parse(&["a.ts", "--stdin", &language_arg, "b.js"]).unwrap();
+        assert!(options
+            .files_to_lint
+            .iter()
+            .all(|file: &FileToLint| file.language == language));
+    }
+}
+
+#[test]
+fn options_accept_separate_values() {
+    let options: Options = parse(&[
+        "--output-format",
+        "sarif",
+        "--locale",
+        "de",
+        "--exit-fail-on",
+        "warning",
+        "--language",
+        "typescript",
+        "x.js",
+    ])
+    .unwrap();
+    assert_eq!(options.output_format, OutputFormat::Sarif);
+    assert_eq!(options.locale.as_deref(), Some("de"));
+    assert_eq!(options.exit_fail_on, ExitFailOn::Warning);
+    assert_eq!(
+        options.files_to_lint[0].language,
+        InputFileLanguage::TypeScript
+    );
+}
+
+#[test]
+fn output_formats_and_exit_fail_on() {
+    assert_eq!(
+        parse(&["--output-format=json"]).unwrap().output_format,
+        OutputFormat::JSON
+    );
+    assert_eq!(
+        parse(&["--output-format=gnu-like"]).unwrap().output_format,
+        OutputFormat::GNULike
+    );
+    assert_eq!(
+        parse(&["--exit-fail-on=never"]).unwrap().exit_fail_on,
+        ExitFailOn::Never
+    );
+}
+
+#[test]
+fn help_and_version() {
+    assert!(parse(&["--help"]).unwrap().help);
+    assert!(parse(&["-h"]).unwrap().help);
+    assert!(parse(&["--version"]).unwrap().version);
+    assert!(parse(&["-v"]).unwrap().version);
+}
+
+#[test]
+fn watch() {
+    assert!(!parse(&["a.js"]).unwrap().watch);
+    let options: Options = parse(&["--watch", "a.js"]).unwrap();
+    assert!(options.watch);
+    assert_eq!(options.files_to_lint.len(), 1);
+}
+
+#[test]
+fn arguments_after_double_dash_are_files() {
+    let options: Options = parse(&["--", "--stdin", "-x.js"]).unwrap();
+    let paths: Vec<&str> = options
+        .files_to_lint
+        .iter()
+        .map(|file: &FileToLint| file.path.as_str())
+        .collect();
+    assert_eq!(paths, vec!["--stdin", "-x.js"]);
+    assert!(options
+        .files_to_lint
+        .iter()
+        .all(|file: &FileToLint| !file.is_stdin));
+}
+
+#[test]
+fn errors() {
+    assert_eq!(
+        parse(&["--bogus"]),
+        Err(OptionsError::UnrecognizedOption("--bogus".to_string()))
+    );
+    assert_eq!(
+        parse(&["--stdin=yes"]),
+        Err(OptionsError::UnrecognizedOption("--stdin=yes".to_string()))
+    );
+    assert_eq!(
+        parse(&["x.js", "--language"]),
+        Err(OptionsError::MissingValue("--language".to_string()))
+    );
+    assert_eq!(
+        parse(&["--output-format=xml"]),
+        Err(OptionsError::InvalidValue {
+            option: "--output-format".to_string(),
+            value: "xml".to_string(),
+        })
+    );
+    assert_eq!(
+        parse(&["--language=coffeescript"]),
+        Err(OptionsError::InvalidValue {
+            option: "--language".to_string(),
+            value: "coffeescript".to_string(),
+        })
+    );
+    assert_eq!(
+        parse(&["--exit-fail-on=note"]),
+        Err(OptionsError::InvalidValue {
+            option: "--exit-fail-on".to_string(),
+            value: "note".to_string(),
+        })
+    );
+    assert_eq!(
+        parse(&["--stdin", "--stdin"]),
+        Err(OptionsError::MultipleStdin)
+    );
+}
@@ -0,0 +1,249 @@
+#![cfg(target_os = "linux")]
+
+use cpp_vs_rust_cli::driver::*;
+use cpp_vs_rust_cli::options::*;
+use cpp_vs_rust_cli::watch::*;
+use cpp_vs_rust_test::temp_directory::*;
+
+// NOTE(port): E0042 (unexpected '@') is an error. Every file in these tests
+// has diagnostics so the output says which files were linted.
+const ERROR_SOURCE: &[u8] = b"let x;\n@";
+
+const EVENT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+const NO_EVENT_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(300);
+
+fn new_session(args: &[&str]) -> WatchSession {
+    let args: Vec<String> = args.iter().map(|arg: &&str| arg.to_string()).collect();
+    let options: Options = parse_options(&args).unwrap();
+    WatchSession::new(
+        &options,
+        translator_for_options(&options),
+        &mut fail_on_error,
+    )
+    .unwrap()
+}
+
+fn fail_on_error(path: &std::path::Path, error: std::io::Error) {
+    panic!("{}: {error}", path.display());
+}
+
+fn outputs(linted_files: &[LintedFile]) -> Vec<String> {
+    linted_files
+        .iter()
+        .map(|linted: &LintedFile| String::from_utf8(linted.output.clone()).unwrap())
+        .collect()
+}
+
+#[test]
+fn lint_all_files_lints_in_command_line_order() {
+    let temp_dir: TempDirectory = TempDirectory::new("watch-initial");
+    let b: String = temp_dir.write_file("b.js", ERROR_SOURCE);
+    let dir: String = temp_dir.create_directory("dir");
+    temp_dir.write_file("dir/a.js", ERROR_SOURCE);
+    temp_dir.write_file("dir/notes.txt", b"");
+
+    let mut session: WatchSession = new_session(&[&b, &dir]);
+    let outputs: Vec<String> = outputs(&session.lint_all_files(&mut fail_on_error));
+    assert_eq!(outputs.len(), 2);
+    assert!(outputs[0].starts_with(&format!("{b}:2:1: ")), "{outputs:?}");
+    assert!(
+        outputs[1].starts_with(&format!("{dir}/a.js:2:1: ")),
+        "{outputs:?}"
+    );
+}
+
+#[test]
+fn changing_a_file_relints_only_that_file() {
+    let temp_dir: TempDirectory = TempDirectory::new("watch-change");
+    let a: String = temp_dir.write_file("a.js", ERROR_SOURCE);
+    let b: String = temp_dir.write_file("b.js", ERROR_SOURCE);
+    let mut session: WatchSession = new_session(&[&a, &b]);
+    session.lint_all_files(&mut fail_on_error);
+
+    std::fs::write(&b, b"let y;\n\n@").unwrap();
+    let outputs: Vec<String> = outputs(
+        &session
+            .lint_changed_files(Some(EVENT_TIMEOUT), &mut fail_on_error)
+            .unwrap(),
+    );
+    assert_eq!(outputs.len(), 1, "{outputs:?}");
+    assert!(outputs[0].starts_with(&format!("{b}:3:1: ")), "{outputs:?}");
+}
+
+#[test]
+fn saving_without_changes_does_not_relint() {
+    let temp_dir: TempDirectory = TempDirectory::new("watch-same-content");
+    let a: String = temp_dir.write_file("a.js", ERROR_SOURCE);
+    let mut session: WatchSession = new_session(&[&a]);
+    session.lint_all_files(&mut fail_on_error);
+
+    std::fs::write(&a, ERROR_SOURCE).unwrap();
+    let linted_files: Vec<LintedFile> = session
+        .lint_changed_files(Some(EVENT_TIMEOUT), &mut fail_on_error)
+        .unwrap();
+    assert!(linted_files.is_empty());
+}
+
+#[test]
+fn no_changes_times_out() {
+    let temp_dir: TempDirectory = TempDirectory::new("watch-timeout");
+    let a: String = temp_dir.write_file("a.js", ERROR_SOURCE);
+    let mut session: WatchSession = new_session(&[&a]);
+    session.lint_all_files(&mut fail_on_error);
+
+    let linted_files: Vec<LintedFile> = session
+        .lint_changed_files(Some(NO_EVENT_TIMEOUT), &mut fail_on_error)
+        .unwrap();
+    assert!(linted_files.is_empty());
+}
+
+#[test]
+fn constant_changes_relint_after_max_debounce_duration() {
+    let temp_dir: TempDirectory = TempDirectory::new("watch-max-debounce");
+    let a: String = temp_dir.write_file("a.js", ERROR_SOURCE);
+    let mut session: WatchSession = new_session(&[&a]);
+    session.lint_all_files(&mut fail_on_error);
+
+    // Keep changing the file more often than DEBOUNCE_DURATION.
+    let stop: std::sync::Arc<std::sync::atomic::AtomicBool> =
+        std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let writer: std::thread::JoinHandle<()> = {
+        let stop: std::sync::Arc<std::sync::atomic::AtomicBool> = stop.clone();
+        let a: String = a.clone();
+        std::thread::spawn(move || {
+            let mut i: usize = 0;
+            while !stop.load(std::sync::atomic::Ordering::Relaxed) {
+                std::fs::write(&a, format!("let x{i};\n@")).unwrap();
+                i += 1;
+                std::thread::sleep(DEBOUNCE_DURATION / 4);
+            }
+        })
+    };
+    let start: std::time::Instant = std::time::Instant::now();
+    let linted_files: Vec<LintedFile> = session
+        .lint_changed_files(Some(EVENT_TIMEOUT), &mut fail_on_error)
+        .unwrap();
+    let elapsed: std::time::Duration = start.elapsed();
+    stop.store(true, std::sync::atomic::Ordering::Relaxed);
+    writer.join().unwrap();
+
+    assert_eq!(linted_files.len(), 1);
+    assert!(
+        elapsed < MAX_DEBOUNCE_DURATION + std::time::Duration::from_secs(1),
+        "{elapsed:?}"
+    );
+}
+
+#[test]
+fn new_files_in_watched_directories_are_linted() {
+    let temp_dir: TempDirectory = TempDirectory::new("watch-new-files");
+    let dir: String = temp_dir.create_directory("dir");
+    temp_dir.write_file("dir/.gitignore", b"ignored.js\n");
+    let mut session: WatchSession = new_session(&[&dir]);
+    session.lint_all_files(&mut fail_on_error);
+
+    temp_dir.write_file("dir/new.js", ERROR_SOURCE);
+    temp_dir.write_file("dir/ignored.js", ERROR_SOURCE);
+    temp_dir.write_file("dir/new.txt", ERROR_SOURCE);
+    temp_dir.create_directory("dir/sub");
+    temp_dir.write_file("dir/sub/nested.ts", ERROR_SOURCE);
+    let outputs: Vec<String> = outputs(
+        &session
+            .lint_changed_files(Some(EVENT_TIMEOUT), &mut fail_on_error)
+            .unwrap(),
+    );
+    assert_eq!(outputs.len(), 2, "{outputs:?}");
+    assert!(
+        outputs[0].starts_with(&format!("{dir}/new.js:2:1: ")),
+        "{outputs:?}"
+    );
+    assert!(
+        outputs[1].starts_with(&format!("{dir}/sub/nested.ts:2:1: ")),
+        "{outputs:?}"
+    );
+}
+
+#[test]
+fn files_in_new_directories_are_watched() {
+    let temp_dir: TempDirectory = TempDirectory::new("watch-new-directory");
+    let dir: String = temp_dir.create_directory("dir");
+    let mut session: WatchSession = new_session(&[&dir]);
+    session.lint_all_files(&mut fail_on_error);
+    temp_dir.create_directory("dir/sub");
+    let nested: String = temp_dir.write_file("dir/sub/nested.js", ERROR_SOURCE);
+    session
+        .lint_changed_files(Some(EVENT_TIMEOUT), &mut fail_on_error)
+        .unwrap();
+
+    std::fs::write(&nested, b"let y;\n\n@").unwrap();
+    let outputs: Vec<String> = outputs(
+        &session
+            .lint_changed_files(Some(EVENT_TIMEOUT), &mut fail_on_error)
+            .unwrap(),
+    );
+    assert_eq!(outputs.len(), 1, "{outputs:?}");
+    assert!(
+        outputs[0].starts_with(&format!("{nested}:3:1: ")),
+        "{outputs:?}"
+    );
+}
+
+#[test]
+fn changing_a_config_file_relints_files_which_use_it() {
+    let temp_dir: TempDirectory = TempDirectory::new("watch-config-change");
+    let dir: String = temp_dir.create_directory("dir");
+    temp_dir.write_file("dir/a.js", ERROR_SOURCE);
+    temp_dir.write_file("dir/sub/b.js", ERROR_SOURCE);
+    let other: String = temp_dir.write_file("other/c.js", ERROR_SOURCE);
+    let mut session: WatchSession = new_session(&[&dir, &other]);
+    session.lint_all_files(&mut fail_on_error);
+
+    // NOTE(port): Config problems are the only observable effect of a config
+    // file on output.
+    let config: String = temp_dir.write_file("dir/quick-lint-js.config", b"{\"globals\": 42}");
+    let broken_outputs: Vec<String> = outputs(
+        &session
+            .lint_changed_files(Some(EVENT_TIMEOUT), &mut fail_on_error)
+            .unwrap(),
+    );
+    assert_eq!(broken_outputs.len(), 3, "{broken_outputs:?}");
+    assert!(broken_outputs[0].contains("[E0168]"), "{broken_outputs:?}");
+    assert!(
+        broken_outputs[1].starts_with(&format!("{dir}/a.js:2:1: ")),
+        "{broken_outputs:?}"
+    );
+    assert!(
+        broken_outputs[2].starts_with(&format!("{dir}/sub/b.js:2:1: ")),
+        "{broken_outputs:?}"
+    );
+
+    std::fs::remove_file(&config).unwrap();
+    let outputs: Vec<String> = outputs(
+        &session
+            .lint_changed_files(Some(EVENT_TIMEOUT), &mut fail_on_error)
+            .unwrap(),
+    );
+    assert_eq!(outputs.len(), 2, "{outputs:?}");
+    assert!(
+        outputs[0].starts_with(&format!("{dir}/a.js:2:1: ")),
+        "{outputs:?}"
+    );
+    assert!(
+        outputs[1].starts_with(&format!("{dir}/sub/b.js:2:1: ")),
+        "{outputs:?}"
+    );
+}
+
+#[test]
+fn watch_rejects_stdin() {
+    let args: Vec<String> = vec!["--watch".to_string(), "--stdin".to_string()];
+    let mut stdin: &[u8] = b"";
+    let mut stdout: Vec<u8> = vec![];
+    let mut stderr: Vec<u8> = vec![];
+    let exit_code: i32 = run_cli(&args, &mut stdin, &mut stdout, &mut stderr);
+    assert_eq!(exit_code, EXIT_CODE_ERROR);
+    assert_eq!(
+        String::from_utf8(stderr).unwrap(),
+        "error: --watch cannot be used with --stdin\n"
+    );
+}
@@ -11,12 +11,23 @@ use cpp_vs_rust_util::qljs_slow_assert;
 //   calling push).
 // * Items are never copied or moved when adding or removing different items. Pointer stability.
 //   * TODO(port-later): Is pointer stability important in the Rust port?
-pub struct LinkedVector<'alloc, T> {
+//
+// A LinkedVector can be sent to another thread if its items can and its allocator can be used from
+// several threads (i.e. A is Sync). Use LinkedVector::new_in to pick a concrete allocator type.
+pub struct LinkedVector<'alloc, T, A: ?Sized + Allocator + 'alloc = dyn Allocator + 'alloc> {
     head: *mut ChunkHeader<T>,
     tail: *mut ChunkHeader<T>,
-    allocator: &'alloc dyn Allocator,
+    allocator: &'alloc A,
 }
 
+// SAFETY: The chunks reachable from head and tail are owned by this LinkedVector alone, so sending
+// it sends its T items (hence T: Send). Chunks are allocated and freed through &'alloc A from
+// whichever thread owns the LinkedVector, so A must be usable from several threads (A: Sync).
+unsafe impl<'alloc, T: Send, A: ?Sized + Allocator + Sync> Send for LinkedVector<'alloc, T, A> {}
+// SAFETY: &LinkedVector only reads items (back, for_each), handing out &T (hence T: Sync), and
+// never touches the allocator; items are added and removed only through &mut self.
+unsafe impl<'alloc, T: Sync, A: ?Sized + Allocator + Sync> Sync for LinkedVector<'alloc, T, A> {}
+
 fn items_per_chunk<T>() -> usize {
     std::cmp::max(
         1,
@@ -25,9 +36,15 @@ fn items_per_chunk<T>() -> usize {
 }
 
 impl<'alloc, T> LinkedVector<'alloc, T> {
+    pub fn new(allocator: &'alloc dyn Allocator) -> LinkedVector<'alloc, T> {
+        LinkedVector::new_in(allocator)
+    }
+}
+
+impl<'alloc, T, A: ?Sized + Allocator> LinkedVector<'alloc, T, A> {
     const ALIGNMENT: usize = std::mem::align_of::<T>();
 
-    pub fn new(allocator: &'alloc dyn Allocator) -> LinkedVector<'alloc, T> {
+    pub fn new_in(allocator: &'alloc A) -> LinkedVector<'alloc, T, A> {
         LinkedVector {
             head: std::ptr::null_mut(),
             tail: std::ptr::null_mut(),
@@ -182,7 +199,7 @@ impl<'alloc, T> LinkedVector<'alloc, T> {
     }
 }
 
-impl<'alloc, T> Drop for LinkedVector<'alloc, T> {
+impl<'alloc, T, A: ?Sized + Allocator> Drop for LinkedVector<'alloc, T, A> {
     fn drop(&mut self) {
         self.clear();
     }
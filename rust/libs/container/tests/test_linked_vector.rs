@@ -117,6 +117,24 @@ fn push_full_chunk_plus_one_then_pop_most() {
     assert_eq!(*v.back(), *expected_items.last().unwrap());
 }
 
+#[test]
+fn vector_with_sync_allocator_can_be_sent_to_another_thread() {
+    let mut v = LinkedVector::<i32, _>::new_in(global_allocator());
+    let mut expected_items: Vec<i32> = vec![];
+    for i in 0..(narrow_cast::<i32, usize>(v.items_per_chunk()) + 1) {
+        v.push(i);
+        expected_items.push(i);
+    }
+    let items: Vec<i32> = std::thread::spawn(move || {
+        let mut result: Vec<i32> = vec![];
+        v.for_each(|x: &i32| result.push(*x));
+        result
+    })
+    .join()
+    .unwrap();
+    assert_eq!(items, expected_items);
+}
+
 fn to_vec<T: Clone>(v: &LinkedVector<T>) -> Vec<T> {
     let mut result: Vec<T> = vec![];
     v.for_each(|x| {
@@ -5,26 +5,52 @@ use cpp_vs_rust_port::allocator::*;
 use cpp_vs_rust_port::maybe_uninit::*;
 use cpp_vs_rust_util::qljs_const_assert;
 
-pub struct BufferingDiagReporter<'alloc, 'code> {
-    diagnostics: std::cell::UnsafeCell<LinkedVector<'alloc, StoredDiag<'code>>>,
+// Stores diagnostics so they can be reported later with copy_into or
+// move_into.
+//
+// A BufferingDiagReporter is Send if its allocator is Sync. This lets a worker
+// thread collect diagnostics for a file, then hand the BufferingDiagReporter to
+// another thread which merges it into its own reporter with move_into:
+//
+//   let mut reporter = BufferingDiagReporter::new_in(global_allocator());
+//   parse_and_lint(code, &reporter, options);
+//   // Send reporter to the main thread, then:
+//   reporter.move_into(&main_reporter);
+pub struct BufferingDiagReporter<
+    'alloc,
+    'code,
+    A: ?Sized + Allocator + 'alloc = dyn Allocator + 'alloc,
+> {
+    diagnostics: std::cell::UnsafeCell<LinkedVector<'alloc, StoredDiag<'code>, A>>,
 }
 
 impl<'alloc, 'code> BufferingDiagReporter<'alloc, 'code> {
     pub fn new(allocator: &'alloc dyn Allocator) -> BufferingDiagReporter<'alloc, 'code> {
+        BufferingDiagReporter::new_in(allocator)
+    }
+}
+
+impl<'alloc, 'code, A: ?Sized + Allocator> BufferingDiagReporter<'alloc, 'code, A> {
+    pub fn new_in(allocator: &'alloc A) -> BufferingDiagReporter<'alloc, 'code, A> {
         BufferingDiagReporter {
-            diagnostics: std::cell::UnsafeCell::new(LinkedVector::new(allocator)),
+            diagnostics: std::cell::UnsafeCell::new(LinkedVector::new_in(allocator)),
         }
     }
 
     pub fn copy_into(&self, other: &dyn DiagReporter) {
         unsafe { &mut *self.diagnostics.get() }.for_each(|diag: &StoredDiag| {
             // TODO(strager): Make report_impl accept a const pointer to reduce casting.
-            other.report_impl(diag.type_, &diag.diag.data as *const _ as *mut u8);
+            other.report_impl(diag.type_, diag.data() as *mut u8);
         });
     }
 
+    // Report the buffered diagnostics to other, then forget them.
+    //
+    // other can be another BufferingDiagReporter, merging this reporter's
+    // diagnostics into it.
     pub fn move_into(&mut self, other: &dyn DiagReporter) {
         self.copy_into(other);
+        self.clear();
     }
 
     pub fn is_empty(&self) -> bool {
@@ -36,29 +62,43 @@ impl<'alloc, 'code> BufferingDiagReporter<'alloc, 'code> {
     }
 }
 
-impl<'alloc, 'code> DiagReporter for BufferingDiagReporter<'alloc, 'code> {
+impl<'alloc, 'code, A: ?Sized + Allocator> DiagReporter
+    for BufferingDiagReporter<'alloc, 'code, A>
+{
     fn report_impl(&self, type_: DiagType, diag: *const u8) {
+        unsafe { &mut *self.diagnostics.get() }.push(unsafe { StoredDiag::new(type_, diag) });
+    }
+}
+
+// A copy of a diagnostic struct of any type.
+pub(crate) struct StoredDiag<'code> {
+    pub(crate) type_: DiagType,
+    diag: StoredDiagData<'code>,
+}
+
+impl<'code> StoredDiag<'code> {
+    // Precondition: diag points to a diagnostic struct of type type_.
+    pub(crate) unsafe fn new(type_: DiagType, diag: *const u8) -> StoredDiag<'code> {
         let mut diag_data = [std::mem::MaybeUninit::uninit(); MAX_SIZE_OF_DIAGNOSTIC_TYPE];
-        let diag_byte_size: usize = unsafe { *DIAG_SIZES.get_unchecked(type_ as usize) as usize };
-        unsafe {
-            write_slice(
-                &mut diag_data[0..diag_byte_size],
-                std::slice::from_raw_parts(diag, diag_byte_size),
-            );
-        }
-        unsafe { &mut *self.diagnostics.get() }.push(StoredDiag {
+        let diag_byte_size: usize = *DIAG_SIZES.get_unchecked(type_ as usize) as usize;
+        write_slice(
+            &mut diag_data[0..diag_byte_size],
+            std::slice::from_raw_parts(diag, diag_byte_size),
+        );
+        StoredDiag {
             type_: type_,
             diag: StoredDiagData {
                 data: diag_data,
                 phantom: std::marker::PhantomData,
             },
-        });
+        }
     }
-}
 
-struct StoredDiag<'code> {
-    type_: DiagType,
-    diag: StoredDiagData<'code>,
+    // Returns a pointer to the diagnostic struct, suitable for
+    // DiagReporter::report_impl.
+    pub(crate) fn data(&self) -> *const u8 {
+        &self.diag.data as *const _ as *const u8
+    }
 }
 
 #[repr(align(8))]
@@ -0,0 +1,81 @@
+// A CancellationToken lets one thread ask work on another thread to stop
+// early.
+//
+// Clones of a CancellationToken share state: cancelling one clone cancels all
+// of them.
+#[derive(Clone, Debug)]
+pub struct CancellationToken {
+    state: std::sync::Arc<CancellationTokenState>,
+}
+
+#[derive(Debug)]
+struct CancellationTokenState {
+    cancelled: std::sync::atomic::AtomicBool,
+    deadline: Option<std::time::Instant>,
+}
+
+impl CancellationToken {
+    // Create a token which is cancelled only if cancel is called.
+    pub fn new() -> CancellationToken {
+        CancellationToken::with_optional_deadline(None)
+    }
+
+    // Create a token which is cancelled when cancel is called or when deadline
+    // passes, whichever comes first.
+    pub fn with_deadline(deadline: std::time::Instant) -> CancellationToken {
+        CancellationToken::with_optional_deadline(Some(deadline))
+    }
+
+    // Create a token which is cancelled when cancel is called or when budget
+    // has elapsed (starting now), whichever comes first.
+    pub fn with_time_budget(budget: std::time::Duration) -> CancellationToken {
+        CancellationToken::with_deadline(std::time::Instant::now() + budget)
+    }
+
+    fn with_optional_deadline(deadline: Option<std::time::Instant>) -> CancellationToken {
+        CancellationToken {
+            state: std::sync::Arc::new(CancellationTokenState {
+                cancelled: std::sync::atomic::AtomicBool::new(false),
+                deadline: deadline,
+            }),
+        }
+    }
+
+    // Thread safety: Thread-safe. Not async-signal-safe.
+    pub fn cancel(&self) {
+        self.state
+            .cancelled
+            .store(true, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    // Undo cancel. Has no effect on the deadline.
+    pub fn reset(&self) {
+        self.state
+            .cancelled
+            .store(false, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    // Returns true if cancel was called or if the deadline has passed.
+    //
+    // is_cancelled reads the clock if the token has a deadline, so callers in
+    // hot loops should call it only every so often.
+    pub fn is_cancelled(&self) -> bool {
+        if self
+            .state
+            .cancelled
+            .load(std::sync::atomic::Ordering::Relaxed)
+        {
+            return true;
+        }
+        match self.state.deadline {
+            Some(deadline) => std::time::Instant::now() >= deadline,
+            None => false,
+        }
+    }
+}
+
+impl Default for CancellationToken {
+    fn default() -> CancellationToken {
+        CancellationToken::new()
+    }
+}
@@ -0,0 +1,174 @@
+use crate::document::*;
+use crate::source_code_span::*;
+use cpp_vs_rust_util::narrow_cast::*;
+use cpp_vs_rust_util::padded_string::*;
+use cpp_vs_rust_util::utf_8::*;
+
+pub type CLILineNumber = i32;
+pub type CLIOffset = usize;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct CLISourcePosition {
+    // 1-based.
+    pub line_number: CLILineNumber,
+    // 1-based. Counts UTF-8 characters (i.e. code points).
+    pub column_number: i32,
+    // 0-based. Counts bytes.
+    pub offset: CLIOffset,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct CLISourceRange {
+    pub begin: CLISourcePosition,
+    pub end: CLISourcePosition,
+}
+
+impl SourceRangeLike for CLISourceRange {
+    type PositionType = CLISourcePosition;
+
+    fn new(begin: CLISourcePosition, end: CLISourcePosition) -> CLISourceRange {
+        CLISourceRange {
+            begin: begin,
+            end: end,
+        }
+    }
+
+    fn begin(&self) -> CLISourcePosition {
+        self.begin
+    }
+
+    fn end(&self) -> CLISourcePosition {
+        self.end
+    }
+}
+
+// Converts pointers into the input to line and column numbers suitable for
+// command-line output (e.g. "file.js:12:34").
+//
+// Lines are terminated by CR, LF, or CRLF. LS and PS do not terminate lines.
+pub struct CLILocator<'code> {
+    input: PaddedStringView<'code>,
+    // offset_of_lines[i] is the byte offset of the beginning of line i+1.
+    offset_of_lines: Vec<CLIOffset>,
+}
+
+impl<'code> CLILocator<'code> {
+    pub fn new(input: PaddedStringView<'code>) -> CLILocator<'code> {
+        let mut locator = CLILocator {
+            input: input,
+            offset_of_lines: vec![],
+        };
+        locator.cache_offsets_of_lines();
+        locator
+    }
+
+    pub fn range(&self, span: SourceCodeSpan<'_>) -> CLISourceRange {
+        let begin: CLISourcePosition = self.position(span.begin_ptr());
+        let end: CLISourcePosition = self.position(span.end_ptr());
+        CLISourceRange {
+            begin: begin,
+            end: end,
+        }
+    }
+
+    pub fn position(&self, c: *const u8) -> CLISourcePosition {
+        let offset: CLIOffset = self.offset(c);
+        let line_number: CLILineNumber = self.find_line_at_offset(offset);
+        self.position_in_line(line_number, offset)
+    }
+
+    // Returns the text of the given line, excluding the line terminator.
+    //
+    // line_number is 1-based.
+    pub fn line_text(&self, line_number: CLILineNumber) -> &'code [u8] {
+        let line_index: usize = narrow_cast::<usize, _>(line_number - 1);
+        let begin: CLIOffset = self.offset_of_lines[line_index];
+        let end: CLIOffset = match self.offset_of_lines.get(line_index + 1) {
+            Some(next_line_offset) => *next_line_offset,
+            None => narrow_cast::<usize, _>(self.input.len()),
+        };
+        let line: &'code [u8] = &self.input.slice()[begin..end];
+        line.strip_suffix(b"\r\n")
+            .or_else(|| line.strip_suffix(b"\n"))
+            .or_else(|| line.strip_suffix(b"\r"))
+            .unwrap_or(line)
+    }
+
+    fn cache_offsets_of_lines(&mut self) {
+        let input: &[u8] = self.input.slice();
+        self.offset_of_lines.push(0);
+        let mut i: usize = 0;
+        while i < input.len() {
+            match input[i] {
+                b'\r' if input.get(i + 1) == Some(&b'\n') => {
+                    i += 2;
+                    self.offset_of_lines.push(i);
+                }
+                b'\n' | b'\r' => {
+                    i += 1;
+                    self.offset_of_lines.push(i);
+                }
+                _ => {
+                    i += 1;
+                }
+            }
+        }
+    }
+
+    fn find_line_at_offset(&self, offset: CLIOffset) -> CLILineNumber {
+        let following_line_index: usize = self
+            .offset_of_lines
+            .partition_point(|line_offset: &CLIOffset| *line_offset <= offset);
+        narrow_cast::<CLILineNumber, _>(following_line_index)
+    }
+
+    fn offset(&self, c: *const u8) -> CLIOffset {
+        narrow_cast::<CLIOffset, _>(unsafe { c.offset_from(self.input.c_str()) })
+    }
+
+    fn position_in_line(&self, line_number: CLILineNumber, offset: CLIOffset) -> CLISourcePosition {
+        let beginning_of_line_offset: CLIOffset =
+            self.offset_of_lines[narrow_cast::<usize, _>(line_number - 1)];
+        let column_number: i32 = narrow_cast::<i32, _>(count_utf_8_characters(
+            self.input.substr(narrow_cast::<PaddedStringSizeType, _>(
+                beginning_of_line_offset,
+            )),
+            offset - beginning_of_line_offset,
+        )) + 1;
+        CLISourcePosition {
+            line_number: line_number,
+            column_number: column_number,
+            offset: offset,
+        }
+    }
+}
+
+impl<'code> LocatorLike<'code> for CLILocator<'code> {
+    type PositionType = CLISourcePosition;
+    type RangeType = CLISourceRange;
+
+    fn new(s: PaddedStringView<'code>) -> Self {
+        CLILocator::new(s)
+    }
+
+    fn range(&self, s: SourceCodeSpan<'_>) -> Self::RangeType {
+        self.range(s)
+    }
+
+    fn pointer_at_position(&self, position: CLISourcePosition) -> *const u8 {
+        let offset: CLIOffset = std::cmp::min(
+            position.offset,
+            narrow_cast::<CLIOffset, _>(self.input.len()),
+        );
+        unsafe { self.input.c_str().add(offset) }
+    }
+
+    fn replace_text(
+        &mut self,
+        _range: CLISourceRange,
+        _replacement_text: &[u8],
+        new_input: PaddedStringView<'code>,
+    ) {
+        *self = CLILocator::new(new_input);
+    }
+}
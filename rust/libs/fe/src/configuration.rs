@@ -0,0 +1,210 @@
+use crate::diag_reporter::*;
+use crate::diagnostic_types::*;
+use crate::global_declared_variable_set::*;
+use crate::linter::*;
+use crate::source_code_span::*;
+use cpp_vs_rust_util::json::*;
+use cpp_vs_rust_util::padded_string::*;
+
+// Which predefined groups of globals (such as "browser" or "node.js") are
+// enabled.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum GlobalGroups {
+    All,
+    // Only the named groups, in the order they were written.
+    Only(Vec<String>),
+}
+
+// The settings from a quick-lint-js.config file.
+//
+// https://quick-lint-js.com/config/
+//
+// NOTE(port): The globals in each global group aren't ported, so
+// global_groups is recorded but does not add globals.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Configuration {
+    globals: GlobalDeclaredVariableSet,
+    global_groups: GlobalGroups,
+}
+
+impl Configuration {
+    // The configuration used if there is no quick-lint-js.config file.
+    pub fn new() -> Configuration {
+        Configuration {
+            globals: GlobalDeclaredVariableSet::new(),
+            global_groups: GlobalGroups::All,
+        }
+    }
+
+    // Parses the content of a quick-lint-js.config file:
+    //
+    //   {
+    //     "global-groups": ["browser", "node.js"],
+    //     "globals": {
+    //       "gsap": true,
+    //       "$": {"writable": false, "shadowable": false},
+    //       "window": false
+    //     }
+    //   }
+    //
+    // Problems are reported to reporter with spans inside json. Invalid
+    // settings are ignored, but valid settings still apply. If json is not
+    // valid JSON, the default configuration is returned.
+    pub fn load_from_json<'code>(
+        json: PaddedStringView<'code>,
+        reporter: &dyn DiagReporter,
+    ) -> Configuration {
+        let mut config: Configuration = Configuration::new();
+        let input: &'code [u8] = json.slice();
+        let (root, spans) = match parse_json_with_spans(input) {
+            Ok(parsed) => parsed,
+            Err(error) => {
+                let end: usize = std::cmp::min(error.offset + 1, input.len());
+                report(
+                    reporter,
+                    DiagConfigJSONSyntaxError {
+                        where_: SourceCodeSpan::from_slice(&input[error.offset..end]),
+                    },
+                );
+                return config;
+            }
+        };
+        let span_at = |path: &[JSONPathComponent]| -> SourceCodeSpan<'code> {
+            let span: JSONSpan = spans.get(path).unwrap();
+            SourceCodeSpan::from_slice(&input[span.begin..span.end])
+        };
+
+        if let Some(global_groups) = root.get("global-groups") {
+            let path: Vec<JSONPathComponent> =
+                vec![JSONPathComponent::Key("global-groups".to_string())];
+            match global_groups {
+                JSONValue::Bool(true) => config.global_groups = GlobalGroups::All,
+                JSONValue::Bool(false) => config.global_groups = GlobalGroups::Only(vec![]),
+                JSONValue::Array(items) => {
+                    let mut groups: Vec<String> = vec![];
+                    for (i, item) in items.iter().enumerate() {
+                        match item {
+                            JSONValue::String(group) => groups.push(group.clone()),
+                            _ => {
+                                let mut item_path: Vec<JSONPathComponent> = path.clone();
+                                item_path.push(JSONPathComponent::Index(i));
+                                report(
+                                    reporter,
+                                    DiagConfigGlobalGroupsGroupTypeMismatch {
+                                        group: span_at(&item_path),
+                                    },
+                                );
+                            }
+                        }
+                    }
+                    config.global_groups = GlobalGroups::Only(groups);
+                }
+                _ => report(
+                    reporter,
+                    DiagConfigGlobalGroupsTypeMismatch {
+                        where_: span_at(&path),
+                    },
+                ),
+            }
+        }
+
+        if let Some(globals) = root.get("globals") {
+            let path: Vec<JSONPathComponent> = vec![JSONPathComponent::Key("globals".to_string())];
+            match globals.as_object() {
+                Some(members) => {
+                    for (name, descriptor) in members {
+                        let mut descriptor_path: Vec<JSONPathComponent> = path.clone();
+                        descriptor_path.push(JSONPathComponent::Key(name.clone()));
+                        config.load_global(name, descriptor, &descriptor_path, &span_at, reporter);
+                    }
+                }
+                None => report(
+                    reporter,
+                    DiagConfigGlobalsTypeMismatch {
+                        where_: span_at(&path),
+                    },
+                ),
+            }
+        }
+
+        config
+    }
+
+    fn load_global<'code>(
+        &mut self,
+        name: &str,
+        descriptor: &JSONValue,
+        descriptor_path: &[JSONPathComponent],
+        span_at: &dyn Fn(&[JSONPathComponent]) -> SourceCodeSpan<'code>,
+        reporter: &dyn DiagReporter,
+    ) {
+        let mut variable: GlobalDeclaredVariable = GlobalDeclaredVariable {
+            name: name.to_string(),
+            is_writable: true,
+            is_shadowable: true,
+        };
+        match descriptor {
+            JSONValue::Bool(true) => {}
+            JSONValue::Bool(false) => {
+                self.globals.remove_variable(name);
+                return;
+            }
+            JSONValue::Object(_) => {
+                let property_span = |property: &str| -> SourceCodeSpan<'code> {
+                    let mut property_path: Vec<JSONPathComponent> = descriptor_path.to_vec();
+                    property_path.push(JSONPathComponent::Key(property.to_string()));
+                    span_at(&property_path)
+                };
+                match descriptor.get("shadowable") {
+                    None => {}
+                    Some(JSONValue::Bool(shadowable)) => variable.is_shadowable = *shadowable,
+                    Some(_) => report(
+                        reporter,
+                        DiagConfigGlobalsDescriptorShadowableTypeMismatch {
+                            value: property_span("shadowable"),
+                        },
+                    ),
+                }
+                match descriptor.get("writable") {
+                    None => {}
+                    Some(JSONValue::Bool(writable)) => variable.is_writable = *writable,
+                    Some(_) => report(
+                        reporter,
+                        DiagConfigGlobalsDescriptorWritableTypeMismatch {
+                            value: property_span("writable"),
+                        },
+                    ),
+                }
+            }
+            _ => {
+                report(
+                    reporter,
+                    DiagConfigGlobalsDescriptorTypeMismatch {
+                        descriptor: span_at(descriptor_path),
+                    },
+                );
+                return;
+            }
+        }
+        self.globals.add_variable(variable);
+    }
+
+    pub fn globals(&self) -> &GlobalDeclaredVariableSet {
+        &self.globals
+    }
+
+    pub fn global_groups(&self) -> &GlobalGroups {
+        &self.global_groups
+    }
+
+    // Make linter_options lint code as configured.
+    pub fn apply_to(&self, linter_options: &mut LinterOptions) {
+        linter_options.globals = self.globals.clone();
+    }
+}
+
+impl Default for Configuration {
+    fn default() -> Self {
+        Configuration::new()
+    }
+}
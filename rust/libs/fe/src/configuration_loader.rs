@@ -0,0 +1,209 @@
+use crate::configuration::*;
+use crate::diag_reporter::*;
+use crate::diagnostic_types::*;
+use cpp_vs_rust_util::padded_string::*;
+
+pub const CONFIG_FILE_NAME: &str = "quick-lint-js.config";
+
+// A quick-lint-js.config file, read and parsed.
+pub struct ConfigFile {
+    path: std::path::PathBuf,
+    content: PaddedString,
+    configuration: Configuration,
+    has_errors: bool,
+}
+
+impl ConfigFile {
+    // Parse a config file which was read from path.
+    pub fn new(path: std::path::PathBuf, content: &[u8]) -> ConfigFile {
+        let content: PaddedString = PaddedString::from_slice(content);
+        let error_detector: ErrorDetectingDiagReporter = ErrorDetectingDiagReporter {
+            has_errors: std::cell::Cell::new(false),
+        };
+        let configuration: Configuration =
+            Configuration::load_from_json(content.view(), &error_detector);
+        ConfigFile {
+            path: path,
+            content: content,
+            configuration: configuration,
+            has_errors: error_detector.has_errors.get(),
+        }
+    }
+
+    // The canonical path of the config file.
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+
+    pub fn content(&self) -> PaddedStringView<'_> {
+        self.content.view()
+    }
+
+    pub fn configuration(&self) -> &Configuration {
+        &self.configuration
+    }
+
+    // Returns true if the config file has problems, such as JSON syntax errors.
+    // See report_errors.
+    pub fn has_errors(&self) -> bool {
+        self.has_errors
+    }
+
+    // Report the config file's problems to reporter. Spans are inside
+    // self.content().
+    pub fn report_errors(&self, reporter: &dyn DiagReporter) {
+        let _ = Configuration::load_from_json(self.content(), reporter);
+    }
+}
+
+struct ErrorDetectingDiagReporter {
+    has_errors: std::cell::Cell<bool>,
+}
+
+impl DiagReporter for ErrorDetectingDiagReporter {
+    fn report_impl(&self, _type: DiagType, _diag: *const u8) {
+        self.has_errors.set(true);
+    }
+}
+
+// Finds and parses the quick-lint-js.config file for each linted file.
+//
+// Parsed config files are cached. A cached config file is re-read if its
+// modification time or size changed.
+pub struct ConfigurationLoader {
+    // Keyed by ConfigFile::path.
+    cache: std::collections::HashMap<std::path::PathBuf, CachedConfigFile>,
+}
+
+struct CachedConfigFile {
+    modified: std::time::SystemTime,
+    len: u64,
+    file: std::sync::Arc<ConfigFile>,
+}
+
+impl ConfigurationLoader {
+    pub fn new() -> ConfigurationLoader {
+        ConfigurationLoader {
+            cache: std::collections::HashMap::new(),
+        }
+    }
+
+    // Returns the config file for the JavaScript or TypeScript file at
+    // file_path: quick-lint-js.config in file_path's directory, or else in the
+    // nearest ancestor directory which has one. file_path itself does not need
+    // to exist.
+    //
+    // Returns None if there is no config file, or if it could not be read.
+    // Read errors are passed to on_error.
+    pub fn load_for_file(
+        &mut self,
+        file_path: &std::path::Path,
+        on_error: &mut dyn FnMut(&std::path::Path, std::io::Error),
+    ) -> Option<std::sync::Arc<ConfigFile>> {
+        let directory: &std::path::Path = match file_path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => std::path::Path::new("."),
+        };
+        self.load_for_directory(directory, on_error)
+    }
+
+    // Like load_for_file, but for a file in directory. Useful for files which
+    // aren't on disk, such as standard input (which is in the current
+    // directory).
+    pub fn load_for_directory(
+        &mut self,
+        directory: &std::path::Path,
+        on_error: &mut dyn FnMut(&std::path::Path, std::io::Error),
+    ) -> Option<std::sync::Arc<ConfigFile>> {
+        let directory: std::path::PathBuf = match std::fs::canonicalize(directory) {
+            Ok(directory) => directory,
+            Err(error) => {
+                on_error(directory, error);
+                return None;
+            }
+        };
+        for ancestor in directory.ancestors() {
+            let config_path: std::path::PathBuf = ancestor.join(CONFIG_FILE_NAME);
+            match std::fs::metadata(&config_path) {
+                Ok(metadata) if metadata.is_file() => {
+                    return self.load_config_file_with_metadata(config_path, &metadata, on_error);
+                }
+                // For example, a directory named quick-lint-js.config.
+                Ok(_) => {}
+                Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+                Err(error) => {
+                    on_error(&config_path, error);
+                    return None;
+                }
+            }
+        }
+        None
+    }
+
+    // Read and parse the config file at config_path, or return the cached
+    // ConfigFile if the file has not changed since it was last read.
+    pub fn load_config_file(
+        &mut self,
+        config_path: &std::path::Path,
+        on_error: &mut dyn FnMut(&std::path::Path, std::io::Error),
+    ) -> Option<std::sync::Arc<ConfigFile>> {
+        let config_path: std::path::PathBuf = match std::fs::canonicalize(config_path) {
+            Ok(config_path) => config_path,
+            Err(error) => {
+                on_error(config_path, error);
+                return None;
+            }
+        };
+        match std::fs::metadata(&config_path) {
+            Ok(metadata) => self.load_config_file_with_metadata(config_path, &metadata, on_error),
+            Err(error) => {
+                on_error(&config_path, error);
+                None
+            }
+        }
+    }
+
+    fn load_config_file_with_metadata(
+        &mut self,
+        config_path: std::path::PathBuf,
+        metadata: &std::fs::Metadata,
+        on_error: &mut dyn FnMut(&std::path::Path, std::io::Error),
+    ) -> Option<std::sync::Arc<ConfigFile>> {
+        let modified: std::time::SystemTime = match metadata.modified() {
+            Ok(modified) => modified,
+            Err(error) => {
+                on_error(&config_path, error);
+                return None;
+            }
+        };
+        if let Some(cached) = self.cache.get(&config_path) {
+            if cached.modified == modified && cached.len == metadata.len() {
+                return Some(cached.file.clone());
+            }
+        }
+        let content: Vec<u8> = match std::fs::read(&config_path) {
+            Ok(content) => content,
+            Err(error) => {
+                on_error(&config_path, error);
+                return None;
+            }
+        };
+        let file: std::sync::Arc<ConfigFile> =
+            std::sync::Arc::new(ConfigFile::new(config_path.clone(), &content));
+        self.cache.insert(
+            config_path,
+            CachedConfigFile {
+                modified: modified,
+                len: metadata.len(),
+                file: file.clone(),
+            },
+        );
+        Some(file)
+    }
+}
+
+impl Default for ConfigurationLoader {
+    fn default() -> Self {
+        ConfigurationLoader::new()
+    }
+}
@@ -0,0 +1,434 @@
+use crate::diag_reporter::*;
+use crate::diagnostic::*;
+use crate::diagnostic_types::*;
+use crate::identifier::*;
+use crate::language::*;
+use crate::source_code_span::*;
+use crate::token::*;
+use cpp_vs_rust_container::linked_bump_allocator::*;
+use cpp_vs_rust_container::monotonic_allocator::*;
+use cpp_vs_rust_port::maybe_uninit::*;
+use cpp_vs_rust_util::narrow_cast::*;
+use cpp_vs_rust_util::padded_string::*;
+use cpp_vs_rust_util::qljs_assert;
+use cpp_vs_rust_util::qljs_const_assert;
+
+// Diagnostics refer to source code with pointers, so they can't be stored or
+// sent to another process as-is. The serialized form instead refers to source
+// code with byte offsets, so it can be deserialized against a copy of the same
+// source code.
+//
+// Serialized diagnostics are a format version byte followed by concatenated
+// records. All integers are little-endian:
+//
+// stream:
+//   u8  version  // DIAG_SERIALIZATION_FORMAT_VERSION
+//   diagnostic * (until the end of the data)
+//
+// diagnostic:
+//   u16 code           // e.g. 40 for E0040
+//   u8  message_count  // every message, including omitted notes
+//   message * message_count
+//
+// message:
+//   u8  message_index  // 0 for the main message, 1 and up for notes
+//   u8  arg_count
+//   arg * arg_count
+//
+// arg:
+//   u8  type  // DiagnosticArgType
+//   Char8: u8
+//   CodePoint: u32
+//   EnumKind: u8 discriminant
+//   Identifier: span, then the normalized name as a string
+//   Integer: u64
+//   OptionalSourceCodeSpan: u8 0 if None, or u8 1 followed by span
+//   SourceCodeSpan: span
+//   StatementKind: u8 discriminant
+//   String8View: string
+//   TokenType: u8 discriminant
+//   VariableKind: u8 discriminant
+//
+// span:   u32 begin_offset, u32 end_offset  // byte offsets into the source
+// string: u32 length, then the bytes
+//
+// The diagnostic's fields are rebuilt from its messages' arguments, so every
+// field must be an argument of some message. qljs_diagnostic_types! checks this.
+//
+// The message index and argument types let the deserializer detect data written
+// by a program with different diagnostic definitions.
+
+// Change this whenever the serialized form changes, including when an enum's
+// discriminants change.
+pub const DIAG_SERIALIZATION_FORMAT_VERSION: u8 = 1;
+
+// Serializes each reported diagnostic.
+pub struct SerializingDiagReporter<'code> {
+    input: PaddedStringView<'code>,
+    output: std::cell::RefCell<Vec<u8>>,
+}
+
+impl<'code> SerializingDiagReporter<'code> {
+    // Diagnostics reported to the new SerializingDiagReporter must refer to
+    // input.
+    //
+    // Spans are serialized as u32 offsets, so input must be shorter than
+    // 4 GiB. Reporting a diagnostic for a longer input fails an assertion.
+    pub fn new(input: PaddedStringView<'code>) -> SerializingDiagReporter<'code> {
+        SerializingDiagReporter {
+            input: input,
+            output: std::cell::RefCell::new(vec![DIAG_SERIALIZATION_FORMAT_VERSION]),
+        }
+    }
+
+    // Returns everything serialized since the last call to take_output,
+    // preceded by the format version.
+    pub fn take_output(&mut self) -> Vec<u8> {
+        std::mem::replace(
+            self.output.get_mut(),
+            vec![DIAG_SERIALIZATION_FORMAT_VERSION],
+        )
+    }
+}
+
+impl<'code> DiagReporter for SerializingDiagReporter<'code> {
+    fn report_impl(&self, type_: DiagType, diag: *const u8) {
+        let out: &mut Vec<u8> = &mut self.output.borrow_mut();
+        let info: &DiagnosticInfo = get_diagnostic_info(type_);
+        out.extend_from_slice(&info.code().to_le_bytes());
+        out.push(info.messages().len() as u8);
+        for (message_index, message) in info.messages().iter().enumerate() {
+            out.push(message_index as u8);
+            out.push(message.args.len() as u8);
+            for arg in message.args {
+                out.push(arg.type_() as u8);
+                unsafe {
+                    self.serialize_arg(arg, diag.add(arg.offset()), out);
+                }
+            }
+        }
+    }
+}
+
+impl<'code> SerializingDiagReporter<'code> {
+    unsafe fn serialize_arg(
+        &self,
+        arg: &DiagnosticMessageArgInfo,
+        arg_data: *const u8,
+        out: &mut Vec<u8>,
+    ) {
+        match arg.type_() {
+            DiagnosticArgType::Char8 => out.push(*arg_data),
+            DiagnosticArgType::CodePoint => {
+                out.extend_from_slice(&(*(arg_data as *const char) as u32).to_le_bytes())
+            }
+            DiagnosticArgType::Identifier => {
+                let identifier: &Identifier = &*(arg_data as *const Identifier);
+                self.serialize_span(identifier.span(), out);
+                serialize_string(identifier.normalized_name(), out);
+            }
+            DiagnosticArgType::Integer => {
+                out.extend_from_slice(&(*(arg_data as *const usize) as u64).to_le_bytes())
+            }
+            DiagnosticArgType::OptionalSourceCodeSpan => {
+                match *(arg_data as *const Option<SourceCodeSpan>) {
+                    Some(span) => {
+                        out.push(1);
+                        self.serialize_span(span, out);
+                    }
+                    None => out.push(0),
+                }
+            }
+            DiagnosticArgType::SourceCodeSpan => {
+                self.serialize_span(*(arg_data as *const SourceCodeSpan), out)
+            }
+            DiagnosticArgType::String8View => serialize_string(*(arg_data as *const &[u8]), out),
+
+            DiagnosticArgType::EnumKind => out.push(*(arg_data as *const EnumKind) as u8),
+            DiagnosticArgType::StatementKind => out.push(*(arg_data as *const StatementKind) as u8),
+            DiagnosticArgType::TokenType => out.push(*(arg_data as *const TokenType) as u8),
+            DiagnosticArgType::VariableKind => out.push(*(arg_data as *const VariableKind) as u8),
+
+            DiagnosticArgType::Invalid => {
+                unreachable!();
+            }
+        }
+    }
+
+    fn serialize_span(&self, span: SourceCodeSpan, out: &mut Vec<u8>) {
+        let input_begin: *const u8 = self.input.c_str();
+        let input_end: *const u8 = self.input.null_terminator();
+        qljs_assert!(input_begin <= span.begin_ptr() && span.begin_ptr() <= span.end_ptr());
+        qljs_assert!(span.end_ptr() <= input_end);
+        let begin_offset: usize = span.begin_ptr() as usize - input_begin as usize;
+        let end_offset: usize = span.end_ptr() as usize - input_begin as usize;
+        out.extend_from_slice(&narrow_cast::<u32, _>(begin_offset).to_le_bytes());
+        out.extend_from_slice(&narrow_cast::<u32, _>(end_offset).to_le_bytes());
+    }
+}
+
+fn serialize_string(s: &[u8], out: &mut Vec<u8>) {
+    out.extend_from_slice(&narrow_cast::<u32, _>(s.len()).to_le_bytes());
+    out.extend_from_slice(s);
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DiagDeserializeError {
+    // The data ended in the middle of a diagnostic, or is missing the format
+    // version.
+    UnexpectedEnd,
+    // The data was serialized in a different format, probably by a different
+    // version of the program.
+    UnsupportedVersion(u8),
+    // No diagnostic has this error code.
+    UnknownCode(u16),
+    // The messages or arguments of the diagnostic with this error code do not
+    // match the diagnostic's definition. The data was probably serialized by a
+    // different version of the program.
+    MismatchedDiagnostic(u16),
+    // A span is reversed or extends past the end of the source code.
+    OffsetOutOfRange,
+    // An argument has an impossible value, such as an invalid code point or an
+    // out-of-range enum discriminant.
+    InvalidValue,
+}
+
+// Rebuild diagnostics serialized by SerializingDiagReporter.
+//
+// input must have the same contents as the source code given to the
+// SerializingDiagReporter. Strings which are not part of input (such as
+// identifiers' normalized names) are copied into memory.
+//
+// If data is malformed, no diagnostics are returned.
+pub fn deserialize_diags<'code>(
+    data: &[u8],
+    input: PaddedStringView<'code>,
+    memory: &'code MonotonicAllocator,
+) -> Result<Vec<AnyDiag<'code>>, DiagDeserializeError> {
+    let diags: Vec<DeserializedDiag> = deserialize_raw_diags(data, input, memory)?;
+    Ok(diags
+        .iter()
+        .map(|diag: &DeserializedDiag| unsafe {
+            AnyDiag::from_raw_parts(diag.type_, diag.data.0.as_ptr() as *const u8)
+        })
+        .collect())
+}
+
+// Like deserialize_diags, but report the diagnostics to reporter instead of
+// returning them.
+//
+// If data is malformed, nothing is reported.
+pub fn replay_serialized_diags<'code>(
+    data: &[u8],
+    input: PaddedStringView<'code>,
+    memory: &'code MonotonicAllocator,
+    reporter: &dyn DiagReporter,
+) -> Result<(), DiagDeserializeError> {
+    let diags: Vec<DeserializedDiag> = deserialize_raw_diags(data, input, memory)?;
+    for diag in &diags {
+        reporter.report_impl(diag.type_, diag.data.0.as_ptr() as *const u8);
+    }
+    Ok(())
+}
+
+struct DeserializedDiag {
+    type_: DiagType,
+    data: DeserializedDiagData,
+}
+
+#[repr(align(8))]
+struct DeserializedDiagData([std::mem::MaybeUninit<u8>; MAX_SIZE_OF_DIAGNOSTIC_TYPE]);
+
+qljs_const_assert!(
+    std::mem::align_of::<DeserializedDiagData>() >= std::mem::align_of::<AnyDiag>(),
+    "DeserializedDiagData should be aligned such that it can be cast to any diagnostic struct",
+);
+
+fn deserialize_raw_diags<'code>(
+    data: &[u8],
+    input: PaddedStringView<'code>,
+    memory: &'code MonotonicAllocator,
+) -> Result<Vec<DeserializedDiag>, DiagDeserializeError> {
+    let mut reader = DiagDeserializer {
+        data: data,
+        input: input,
+        memory: memory,
+    };
+    let version: u8 = reader.read_u8()?;
+    if version != DIAG_SERIALIZATION_FORMAT_VERSION {
+        return Err(DiagDeserializeError::UnsupportedVersion(version));
+    }
+    let mut diags: Vec<DeserializedDiag> = vec![];
+    while !reader.data.is_empty() {
+        diags.push(reader.deserialize_diag()?);
+    }
+    Ok(diags)
+}
+
+struct DiagDeserializer<'data, 'code> {
+    // Bytes which have not been read yet.
+    data: &'data [u8],
+    input: PaddedStringView<'code>,
+    memory: &'code MonotonicAllocator,
+}
+
+impl<'data, 'code> DiagDeserializer<'data, 'code> {
+    fn deserialize_diag(&mut self) -> Result<DeserializedDiag, DiagDeserializeError> {
+        let code: u16 = self.read_u16()?;
+        let type_: DiagType =
+            diag_type_from_code(code).ok_or(DiagDeserializeError::UnknownCode(code))?;
+        let info: &DiagnosticInfo = get_diagnostic_info(type_);
+        let mismatch: DiagDeserializeError = DiagDeserializeError::MismatchedDiagnostic(code);
+
+        let mut diag = DeserializedDiag {
+            type_: type_,
+            data: DeserializedDiagData(uninit_array()),
+        };
+        if self.read_u8()? as usize != info.messages().len() {
+            return Err(mismatch);
+        }
+        for (message_index, message) in info.messages().iter().enumerate() {
+            if self.read_u8()? as usize != message_index {
+                return Err(mismatch);
+            }
+            if self.read_u8()? as usize != message.args.len() {
+                return Err(mismatch);
+            }
+            for arg in message.args {
+                if self.read_u8()? != arg.type_() as u8 {
+                    return Err(mismatch);
+                }
+                let arg_data: *mut u8 =
+                    unsafe { (diag.data.0.as_mut_ptr() as *mut u8).add(arg.offset()) };
+                unsafe {
+                    self.deserialize_arg(arg.type_(), arg_data)?;
+                }
+            }
+        }
+        Ok(diag)
+    }
+
+    // Precondition: arg_data points to a field of type arg_type.
+    unsafe fn deserialize_arg(
+        &mut self,
+        arg_type: DiagnosticArgType,
+        arg_data: *mut u8,
+    ) -> Result<(), DiagDeserializeError> {
+        match arg_type {
+            DiagnosticArgType::Char8 => {
+                arg_data.write(self.read_u8()?);
+            }
+            DiagnosticArgType::CodePoint => {
+                let code_point: char =
+                    char::from_u32(self.read_u32()?).ok_or(DiagDeserializeError::InvalidValue)?;
+                (arg_data as *mut char).write(code_point);
+            }
+            DiagnosticArgType::Identifier => {
+                let span: SourceCodeSpan<'code> = self.read_span()?;
+                let normalized: &'code [u8] = self.read_string()?;
+                // Avoid keeping a copy of the common case where the normalized
+                // name is spelled the same as the source code.
+                let normalized: &'code [u8] = if normalized == span.as_slice() {
+                    span.as_slice()
+                } else {
+                    normalized
+                };
+                (arg_data as *mut Identifier).write(Identifier::new(span, normalized));
+            }
+            DiagnosticArgType::Integer => {
+                let value: usize = usize::try_from(self.read_u64()?)
+                    .map_err(|_| DiagDeserializeError::InvalidValue)?;
+                (arg_data as *mut usize).write(value);
+            }
+            DiagnosticArgType::OptionalSourceCodeSpan => {
+                let span: Option<SourceCodeSpan<'code>> = match self.read_u8()? {
+                    0 => None,
+                    1 => Some(self.read_span()?),
+                    _ => return Err(DiagDeserializeError::InvalidValue),
+                };
+                (arg_data as *mut Option<SourceCodeSpan>).write(span);
+            }
+            DiagnosticArgType::SourceCodeSpan => {
+                (arg_data as *mut SourceCodeSpan).write(self.read_span()?);
+            }
+            DiagnosticArgType::String8View => {
+                (arg_data as *mut &[u8]).write(self.read_string()?);
+            }
+
+            DiagnosticArgType::EnumKind => {
+                let kind: EnumKind = EnumKind::try_from(self.read_u8()?)
+                    .map_err(|_| DiagDeserializeError::InvalidValue)?;
+                (arg_data as *mut EnumKind).write(kind);
+            }
+            DiagnosticArgType::StatementKind => {
+                let kind: StatementKind = StatementKind::try_from(self.read_u8()?)
+                    .map_err(|_| DiagDeserializeError::InvalidValue)?;
+                (arg_data as *mut StatementKind).write(kind);
+            }
+            DiagnosticArgType::TokenType => {
+                let token_type: TokenType = TokenType::try_from(self.read_u8()?)
+                    .map_err(|_| DiagDeserializeError::InvalidValue)?;
+                (arg_data as *mut TokenType).write(token_type);
+            }
+            DiagnosticArgType::VariableKind => {
+                let kind: VariableKind = VariableKind::try_from(self.read_u8()?)
+                    .map_err(|_| DiagDeserializeError::InvalidValue)?;
+                (arg_data as *mut VariableKind).write(kind);
+            }
+
+            DiagnosticArgType::Invalid => {
+                unreachable!();
+            }
+        }
+        Ok(())
+    }
+
+    fn read_span(&mut self) -> Result<SourceCodeSpan<'code>, DiagDeserializeError> {
+        let begin_offset: usize = self.read_u32()? as usize;
+        let end_offset: usize = self.read_u32()? as usize;
+        if begin_offset > end_offset || end_offset > self.input.slice().len() {
+            return Err(DiagDeserializeError::OffsetOutOfRange);
+        }
+        Ok(SourceCodeSpan::from_slice(
+            &self.input.slice()[begin_offset..end_offset],
+        ))
+    }
+
+    // Returns a copy of the string allocated in self.memory.
+    fn read_string(&mut self) -> Result<&'code [u8], DiagDeserializeError> {
+        let len: usize = self.read_u32()? as usize;
+        let bytes: &[u8] = self.read_bytes(len)?;
+        if bytes.is_empty() {
+            return Ok(&[]);
+        }
+        let copy: &'code mut [std::mem::MaybeUninit<u8>] =
+            self.memory.allocate_uninitialized_array::<u8>(bytes.len());
+        write_slice(copy, bytes);
+        Ok(unsafe { slice_assume_init_ref(copy) })
+    }
+
+    fn read_u8(&mut self) -> Result<u8, DiagDeserializeError> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    fn read_u16(&mut self) -> Result<u16, DiagDeserializeError> {
+        Ok(u16::from_le_bytes(self.read_bytes(2)?.try_into().unwrap()))
+    }
+
+    fn read_u32(&mut self) -> Result<u32, DiagDeserializeError> {
+        Ok(u32::from_le_bytes(self.read_bytes(4)?.try_into().unwrap()))
+    }
+
+    fn read_u64(&mut self) -> Result<u64, DiagDeserializeError> {
+        Ok(u64::from_le_bytes(self.read_bytes(8)?.try_into().unwrap()))
+    }
+
+    fn read_bytes(&mut self, len: usize) -> Result<&'data [u8], DiagDeserializeError> {
+        if self.data.len() < len {
+            return Err(DiagDeserializeError::UnexpectedEnd);
+        }
+        let (bytes, rest): (&'data [u8], &'data [u8]) = self.data.split_at(len);
+        self.data = rest;
+        Ok(bytes)
+    }
+}
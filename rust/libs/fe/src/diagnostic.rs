@@ -1,4 +1,6 @@
 use crate::diagnostic_types::*;
+use crate::identifier::*;
+use crate::source_code_span::*;
 use cpp_vs_rust_i18n::translation::*;
 
 #[repr(u8)]
@@ -9,6 +11,80 @@ pub enum DiagnosticSeverity {
     Warning,
 }
 
+// What kind of problem a diagnostic reports.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DiagnosticCategory {
+    // The code cannot be parsed.
+    Syntax,
+    // The code can be parsed, but it probably does not do what the programmer
+    // intended.
+    Correctness,
+    // The code works, but could be written more clearly.
+    Style,
+    // The code does not work in some JavaScript environments.
+    Compatibility,
+}
+
+pub const DIAGNOSTIC_CATEGORY_COUNT: usize = 4;
+
+impl DiagnosticCategory {
+    pub const ALL: [DiagnosticCategory; DIAGNOSTIC_CATEGORY_COUNT] = [
+        DiagnosticCategory::Syntax,
+        DiagnosticCategory::Correctness,
+        DiagnosticCategory::Style,
+        DiagnosticCategory::Compatibility,
+    ];
+}
+
+// Extra information about a diagnostic which editors can use to render the
+// diagnosed code differently. Corresponds to LSP's DiagnosticTag.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DiagnosticTag {
+    // The diagnosed code is unused or unreachable. Editors may gray it out.
+    Unnecessary = 1 << 0,
+    // The diagnosed code uses a deprecated feature. Editors may strike it
+    // through.
+    Deprecated = 1 << 1,
+}
+
+// A set of DiagnosticTag-s.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct DiagnosticTags(u8);
+
+impl DiagnosticTags {
+    pub const fn none() -> DiagnosticTags {
+        DiagnosticTags(0)
+    }
+
+    pub const fn from_tags(tags: &[DiagnosticTag]) -> DiagnosticTags {
+        let mut bits: u8 = 0;
+        let mut i: usize = 0;
+        while i < tags.len() {
+            bits |= tags[i] as u8;
+            i += 1;
+        }
+        DiagnosticTags(bits)
+    }
+
+    pub const fn from_bits(bits: u8) -> DiagnosticTags {
+        DiagnosticTags(bits)
+    }
+
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    pub const fn contains(self, tag: DiagnosticTag) -> bool {
+        (self.0 & (tag as u8)) != 0
+    }
+
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+}
+
 #[repr(u8)]
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum DiagnosticArgType {
@@ -16,73 +92,113 @@ pub enum DiagnosticArgType {
 
     // TODO(port-later): Rename some of these.
     Char8,
+    CodePoint,
     EnumKind,
     Identifier,
+    Integer,
+    OptionalSourceCodeSpan,
     SourceCodeSpan,
     StatementKind,
     String8View,
+    TokenType,
     VariableKind,
 }
 
-// If we support more than two infos (i.e. more than one note), the VS Code
-// plugin needs to be updated. See NOTE(multiple notes).
-pub const DIAGNOSTIC_MAX_MESSAGE_COUNT: usize = 2;
-
-const DIAGNOSTIC_MAX_ARG_COUNT: usize = 3;
-
-// DIAGNOSTIC_MESSAGE_ARG_OFFSET_SHIFT is how many bits are removed in compact_offset.
-//
-// For example, if DIAGNOSTIC_MESSAGE_ARG_OFFSET_SHIFT is 3, then an arg must be 8-byte aligned.
-const DIAGNOSTIC_MESSAGE_ARG_OFFSET_SHIFT: u8 = 1;
-const DIAGNOSTIC_MESSAGE_ARG_OFFSET_BITS: u8 = 5;
-
+#[derive(Clone, Copy)]
 #[repr(C)]
 pub struct DiagnosticMessageArgInfo {
-    // C++ equivalent:
-    //
-    // std::uint8_t compact_offset : offset_bits;
-    // diagnostic_arg_type type : (8 - offset_bits);
-    data: u8,
+    // Byte offset of the argument's field within the diagnostic struct.
+    offset: u16,
+    type_: DiagnosticArgType,
 }
 
 impl DiagnosticMessageArgInfo {
-    pub const fn empty() -> DiagnosticMessageArgInfo {
-        DiagnosticMessageArgInfo::new(0, DiagnosticArgType::Invalid)
-    }
-
     pub const fn new(offset: usize, type_: DiagnosticArgType) -> DiagnosticMessageArgInfo {
-        let offset = offset as u8;
-        assert!(
-            (offset >> DIAGNOSTIC_MESSAGE_ARG_OFFSET_SHIFT)
-                < (1 << DIAGNOSTIC_MESSAGE_ARG_OFFSET_BITS),
-            "offset should be small",
-        );
-        assert!(
-            (offset & ((1 << DIAGNOSTIC_MESSAGE_ARG_OFFSET_SHIFT) - 1)) == 0,
-            "offset should be aligned",
-        );
+        assert!(offset <= (u16::MAX as usize), "offset should be small");
         DiagnosticMessageArgInfo {
-            data: (offset >> DIAGNOSTIC_MESSAGE_ARG_OFFSET_SHIFT)
-                | ((type_ as u8) << DIAGNOSTIC_MESSAGE_ARG_OFFSET_BITS),
+            offset: offset as u16,
+            type_: type_,
         }
     }
 
     pub const fn offset(&self) -> usize {
-        const MASK: u8 = (1 << DIAGNOSTIC_MESSAGE_ARG_OFFSET_BITS) - 1;
-        ((self.data & MASK) << DIAGNOSTIC_MESSAGE_ARG_OFFSET_SHIFT) as usize
+        self.offset as usize
     }
 
     pub const fn type_(&self) -> DiagnosticArgType {
-        unsafe { std::mem::transmute(self.data >> DIAGNOSTIC_MESSAGE_ARG_OFFSET_BITS) }
+        self.type_
+    }
+
+    // Returns the source location held by this argument, or None if this
+    // argument is not a source location or is an absent optional span.
+    //
+    // Precondition: diag points to a diagnostic struct described by this
+    //               argument.
+    pub(crate) unsafe fn get_span<'code>(&self, diag: *const u8) -> Option<SourceCodeSpan<'code>> {
+        let arg_data: *const u8 = diag.add(self.offset());
+        match self.type_ {
+            DiagnosticArgType::Identifier => Some((*(arg_data as *const Identifier)).span()),
+            DiagnosticArgType::OptionalSourceCodeSpan => {
+                *(arg_data as *const Option<SourceCodeSpan>)
+            }
+            DiagnosticArgType::SourceCodeSpan => Some(*(arg_data as *const SourceCodeSpan)),
+
+            DiagnosticArgType::Char8
+            | DiagnosticArgType::CodePoint
+            | DiagnosticArgType::EnumKind
+            | DiagnosticArgType::Integer
+            | DiagnosticArgType::Invalid
+            | DiagnosticArgType::StatementKind
+            | DiagnosticArgType::String8View
+            | DiagnosticArgType::TokenType
+            | DiagnosticArgType::VariableKind => None,
+        }
     }
 }
 
-pub type DiagnosticMessageArgs = [DiagnosticMessageArgInfo; DIAGNOSTIC_MAX_ARG_COUNT];
+// The arguments referenced by a message's placeholders. The placeholder {N}
+// refers to args[N].
+pub type DiagnosticMessageArgs = [DiagnosticMessageArgInfo];
+
+#[repr(C)]
+pub struct DiagnosticMessageInfo {
+    pub format: TranslatableMessage,
+    pub args: &'static DiagnosticMessageArgs,
+}
+
+impl DiagnosticMessageInfo {
+    pub const fn new(
+        format: TranslatableMessage,
+        args: &'static DiagnosticMessageArgs,
+    ) -> DiagnosticMessageInfo {
+        DiagnosticMessageInfo {
+            format: format,
+            args: args,
+        }
+    }
+
+    // The format string as written in the source code, before translation
+    // (e.g. "unexpected '{0}'").
+    pub fn untranslated_format(&self) -> &'static str {
+        Translator::new_using_messages_from_source_code().translate(self.format)
+    }
+
+    // The type of each argument, in placeholder order ({0}, {1}, etc.).
+    pub fn arg_types(&self) -> impl ExactSizeIterator<Item = DiagnosticArgType> + 'static {
+        self.args
+            .iter()
+            .map(|arg: &'static DiagnosticMessageArgInfo| arg.type_())
+    }
+}
 
 const DIAGNOSTIC_INFO_CODE_BITS: u16 = 14;
 const DIAGNOSTIC_INFO_CODE_MASK: u16 = (1 << DIAGNOSTIC_INFO_CODE_BITS) - 1;
 const DIAGNOSTIC_INFO_SEVERITY_SHIFT: u16 = DIAGNOSTIC_INFO_CODE_BITS;
 
+const DIAGNOSTIC_INFO_CATEGORY_BITS: u8 = 2;
+const DIAGNOSTIC_INFO_CATEGORY_MASK: u8 = (1 << DIAGNOSTIC_INFO_CATEGORY_BITS) - 1;
+const DIAGNOSTIC_INFO_TAGS_SHIFT: u8 = DIAGNOSTIC_INFO_CATEGORY_BITS;
+
 #[repr(C)]
 pub struct DiagnosticInfo {
     // C++ equivalent:
@@ -91,24 +207,49 @@ pub struct DiagnosticInfo {
     // diagnostic_severity severity : 2;
     code_and_severity: u16,
 
-    pub message_formats: [TranslatableMessage; DIAGNOSTIC_MAX_MESSAGE_COUNT],
-    pub message_args: [DiagnosticMessageArgs; DIAGNOSTIC_MAX_MESSAGE_COUNT],
+    // This diagnostic's messages are
+    // ALL_DIAGNOSTIC_MESSAGES[message_index..message_index+message_count].
+    // See DiagnosticInfo::messages.
+    message_index: u16,
+
+    // C++ equivalent:
+    //
+    // diagnostic_category category : 2;
+    // std::uint8_t tags : 6;
+    category_and_tags: u8,
+
+    message_count: u8,
 }
 
 impl DiagnosticInfo {
     pub const fn new(
         code: u16,
         severity: DiagnosticSeverity,
-        message_formats: [TranslatableMessage; DIAGNOSTIC_MAX_MESSAGE_COUNT],
-        message_args: [DiagnosticMessageArgs; DIAGNOSTIC_MAX_MESSAGE_COUNT],
+        category: DiagnosticCategory,
+        tags: DiagnosticTags,
+        message_index: u16,
+        message_count: u8,
     ) -> DiagnosticInfo {
+        assert!(message_count > 0, "diagnostic should have a message");
+        assert!(
+            tags.bits() >> (8 - DIAGNOSTIC_INFO_TAGS_SHIFT) == 0,
+            "tags should fit in DiagnosticInfo"
+        );
         DiagnosticInfo {
             code_and_severity: code | ((severity as u16) << DIAGNOSTIC_INFO_SEVERITY_SHIFT),
-            message_formats: message_formats,
-            message_args: message_args,
+            category_and_tags: (category as u8) | (tags.bits() << DIAGNOSTIC_INFO_TAGS_SHIFT),
+            message_index: message_index,
+            message_count: message_count,
         }
     }
 
+    // The first message is the diagnostic's main message. Each following
+    // message is a note.
+    pub fn messages(&self) -> &'static [DiagnosticMessageInfo] {
+        let begin: usize = self.message_index as usize;
+        &ALL_DIAGNOSTIC_MESSAGES[begin..begin + self.message_count as usize]
+    }
+
     pub const fn code_string(&self) -> [u8; 5] {
         let diag_code: u16 = self.code();
         [
@@ -129,20 +270,103 @@ impl DiagnosticInfo {
             std::mem::transmute((self.code_and_severity >> DIAGNOSTIC_INFO_SEVERITY_SHIFT) as u8)
         }
     }
+
+    pub const fn category(&self) -> DiagnosticCategory {
+        unsafe {
+            std::mem::transmute::<u8, DiagnosticCategory>(
+                self.category_and_tags & DIAGNOSTIC_INFO_CATEGORY_MASK,
+            )
+        }
+    }
+
+    pub const fn tags(&self) -> DiagnosticTags {
+        DiagnosticTags::from_bits(self.category_and_tags >> DIAGNOSTIC_INFO_TAGS_SHIFT)
+    }
+}
+
+// How to report diagnostics of a given category.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CategorySeverity {
+    // Use each diagnostic's own severity.
+    Default,
+    Error,
+    Warning,
+    // Do not report diagnostics in the category.
+    Off,
+}
+
+// Decides the severity of diagnostics (or whether to report them at all)
+// based on their category.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DiagnosticCategoryFilter {
+    // Indexed by DiagnosticCategory.
+    severities: [CategorySeverity; DIAGNOSTIC_CATEGORY_COUNT],
+}
+
+impl DiagnosticCategoryFilter {
+    // Creates a filter which reports every diagnostic with its own severity.
+    pub const fn new() -> DiagnosticCategoryFilter {
+        DiagnosticCategoryFilter {
+            severities: [CategorySeverity::Default; DIAGNOSTIC_CATEGORY_COUNT],
+        }
+    }
+
+    pub fn set(&mut self, category: DiagnosticCategory, severity: CategorySeverity) {
+        self.severities[category as usize] = severity;
+    }
+
+    pub fn get(&self, category: DiagnosticCategory) -> CategorySeverity {
+        self.severities[category as usize]
+    }
+
+    // Returns the severity to report the diagnostic with, or None if the
+    // diagnostic should not be reported.
+    pub fn severity(&self, info: &DiagnosticInfo) -> Option<DiagnosticSeverity> {
+        match self.get(info.category()) {
+            CategorySeverity::Default => Some(info.severity()),
+            CategorySeverity::Error => Some(DiagnosticSeverity::Error),
+            CategorySeverity::Warning => Some(DiagnosticSeverity::Warning),
+            CategorySeverity::Off => None,
+        }
+    }
+}
+
+impl Default for DiagnosticCategoryFilter {
+    fn default() -> Self {
+        DiagnosticCategoryFilter::new()
+    }
 }
 
 pub fn get_diagnostic_info(type_: DiagType) -> &'static DiagnosticInfo {
     &ALL_DIAGNOSTIC_INFOS[type_ as usize]
 }
 
-pub fn diag_type_from_code_slow(code: &str) -> Option<DiagType> {
-    for i in 0..(DIAG_TYPE_COUNT as usize) {
-        // TODO(strager): Parse the incoming code instead of stringifying each code
-        // in the table.
-        let diag_code_string: [u8; 5] = ALL_DIAGNOSTIC_INFOS[i].code_string();
-        if diag_code_string == code.as_bytes() {
-            return Some(unsafe { std::mem::transmute::<_, DiagType>(i as u16) });
-        }
+// Returns every diagnostic type with its static information, in DiagType
+// order.
+//
+// This is useful for tools which need to know about all diagnostics, such as
+// configuration validation and documentation generation.
+pub fn all_diagnostic_infos(
+) -> impl ExactSizeIterator<Item = (DiagType, &'static DiagnosticInfo)> + Clone {
+    ALL_DIAGNOSTIC_INFOS
+        .iter()
+        .enumerate()
+        .map(|(i, info): (usize, &'static DiagnosticInfo)| {
+            (
+                unsafe { std::mem::transmute::<u16, DiagType>(i as u16) },
+                info,
+            )
+        })
+}
+
+// Returns the DiagType for an error code string such as "E0005", if any.
+pub fn diag_type_from_code_string(code: &str) -> Option<DiagType> {
+    let code: &[u8] = code.as_bytes();
+    if code.len() != 5 || code[0] != b'E' || !code[1..].iter().all(u8::is_ascii_digit) {
+        return None;
     }
-    None
+    let number: u16 = code[1..].iter().fold(0, |number: u16, digit: &u8| {
+        number * 10 + u16::from(digit - b'0')
+    });
+    diag_type_from_code(number)
 }
@@ -2,10 +2,12 @@ use crate::diagnostic::*;
 use crate::identifier::*;
 use crate::language::*;
 use crate::source_code_span::*;
+use crate::token::*;
 use cpp_vs_rust_i18n::qljs_translatable;
 use cpp_vs_rust_i18n::translation::*;
-use cpp_vs_rust_util::narrow_cast::*;
+use cpp_vs_rust_util::message_format::*;
 use cpp_vs_rust_util::qljs_assert;
+use std::borrow::Cow;
 
 pub trait DiagnosticFormatter {
     fn write_before_message(
@@ -23,23 +25,53 @@ pub trait DiagnosticFormatter {
     );
     fn translator(&self) -> Translator;
 
+    // Called once per diagnostic, before any of its messages are written.
+    fn write_category_and_tags(&mut self, _category: DiagnosticCategory, _tags: DiagnosticTags) {}
+
     fn format(&mut self, info: &DiagnosticInfo, diagnostic: *const u8) {
+        self.format_with_severity(info, info.severity(), diagnostic);
+    }
+
+    // Like format, but report the main message with the given severity instead
+    // of the diagnostic's own severity.
+    fn format_with_severity(
+        &mut self,
+        info: &DiagnosticInfo,
+        main_severity: DiagnosticSeverity,
+        diagnostic: *const u8,
+    ) {
+        self.format_messages(info, info.messages(), main_severity, diagnostic);
+    }
+
+    // Like format_with_severity, but write the given messages instead of
+    // info.messages(). info provides the code, category, and tags.
+    fn format_messages(
+        &mut self,
+        info: &DiagnosticInfo,
+        messages: &[DiagnosticMessageInfo],
+        main_severity: DiagnosticSeverity,
+        diagnostic: *const u8,
+    ) {
         let code_string = info.code_string();
         let code_string_view: &str = unsafe { std::str::from_utf8_unchecked(&code_string) };
 
-        self.format_message(
-            code_string_view,
-            info.severity(),
-            info.message_formats[0],
-            &info.message_args[0],
-            diagnostic,
-        );
-        if info.message_formats[1].valid() {
+        self.write_category_and_tags(info.category(), info.tags());
+        for (i, message) in messages.iter().enumerate() {
+            let severity: DiagnosticSeverity = if i == 0 {
+                main_severity
+            } else {
+                DiagnosticSeverity::Note
+            };
+            if unsafe { message.args[0].get_span(diagnostic) }.is_none() {
+                // The note's location is an absent Option<SourceCodeSpan>, so skip the note.
+                qljs_assert!(i != 0, "a diagnostic's main message must have a location");
+                continue;
+            }
             self.format_message(
                 code_string_view,
-                DiagnosticSeverity::Note,
-                info.message_formats[1],
-                &info.message_args[1],
+                severity,
+                message.format,
+                message.args,
                 diagnostic,
             );
         }
@@ -53,93 +85,100 @@ pub trait DiagnosticFormatter {
         args: &DiagnosticMessageArgs,
         diagnostic: *const u8,
     ) {
-        let origin_span: SourceCodeSpan =
-            unsafe { get_argument_source_code_span(args, diagnostic, 0) };
+        let origin_span: SourceCodeSpan = match unsafe { args[0].get_span(diagnostic) } {
+            Some(span) => span,
+            None => panic!("missing source code span"),
+        };
         self.write_before_message(code, severity, origin_span);
 
-        let mut remaining_message: &str = self.translator().translate(message_format);
-        loop {
-            let Some((before_left_curly, after_left_curly))
-                = remaining_message.split_once('{') else {break;};
-            qljs_assert!(
-                !after_left_curly.is_empty(),
-                "invalid message format: { at end of string has no matching }"
-            );
-
-            if after_left_curly.as_bytes()[0] == b'{' {
-                // "{{"; the '{' is escaped.
-                let before_and_including_left_curly: &str =
-                    &remaining_message[0..(before_left_curly.len() + 1)];
-                self.write_message_part(code, severity, before_and_including_left_curly.as_bytes());
-                remaining_message = &after_left_curly[1..];
-                continue;
-            }
-
-            self.write_message_part(code, severity, before_left_curly.as_bytes());
-
-            let Some((curly_content, after_right_curly))
-                = after_left_curly.split_once('}') else {
-                    panic!("invalid message format: missing }}"); };
-
-            let expanded_parameter: &[u8] = unsafe {
-                if curly_content == "0" {
-                    expand_argument(args, diagnostic, 0)
-                } else if curly_content == "1" {
-                    expand_argument(args, diagnostic, 1)
-                } else if curly_content == "1:headlinese" {
-                    expand_argument_headlinese(self.translator(), args, diagnostic, 1)
-                } else if curly_content == "1:singular" {
-                    expand_argument_singular(self.translator(), args, diagnostic, 1)
-                } else if curly_content == "2" {
-                    expand_argument(args, diagnostic, 2)
-                } else {
-                    panic!("invalid message format: unrecognized placeholder");
+        let message: &str = self.translator().translate(message_format);
+        for part in MessageFormatParser::new(message) {
+            match part {
+                Ok(MessageFormatPart::Text(text)) => {
+                    self.write_message_part(code, severity, text.as_bytes());
                 }
-            };
-
-            self.write_message_part(code, severity, expanded_parameter);
-            remaining_message = after_right_curly;
+                Ok(MessageFormatPart::Placeholder {
+                    arg_index,
+                    modifier,
+                }) => {
+                    qljs_assert!(
+                        arg_index < args.len(),
+                        "invalid message format: placeholder refers to a missing argument"
+                    );
+                    let expanded_parameter: Cow<[u8]> = unsafe {
+                        match modifier {
+                            None => expand_argument(self.translator(), args, diagnostic, arg_index),
+                            Some(PlaceholderModifier::Headlinese) => {
+                                Cow::Borrowed(expand_argument_headlinese(
+                                    self.translator(),
+                                    args,
+                                    diagnostic,
+                                    arg_index,
+                                ))
+                            }
+                            Some(PlaceholderModifier::Singular) => {
+                                Cow::Borrowed(expand_argument_singular(
+                                    self.translator(),
+                                    args,
+                                    diagnostic,
+                                    arg_index,
+                                ))
+                            }
+                        }
+                    };
+                    self.write_message_part(code, severity, &expanded_parameter);
+                }
+                Err(error) => {
+                    // The qljs_diagnostic macro rejects malformed messages,
+                    // but a translation might still be malformed. Show the
+                    // rest of the message as-is rather than crashing.
+                    self.write_message_part(code, severity, error.rest.as_bytes());
+                }
+            }
         }
-        self.write_message_part(code, severity, remaining_message.as_bytes());
 
         self.write_after_message(code, severity, origin_span);
     }
 }
 
-unsafe fn get_argument_source_code_span<'code>(
+unsafe fn expand_argument<'diag>(
+    translator: Translator,
     args: &DiagnosticMessageArgs,
     diagnostic: *const u8,
-    arg_index: i32,
-) -> SourceCodeSpan<'code> {
+    arg_index: usize,
+) -> Cow<'diag, [u8]> {
     let (arg_data, arg_type) = get_arg(args, diagnostic, arg_index);
     match arg_type {
-        DiagnosticArgType::Identifier => (*(arg_data as *const Identifier)).span(),
+        DiagnosticArgType::Char8 => Cow::Borrowed(std::slice::from_raw_parts(arg_data, 1)),
 
-        DiagnosticArgType::SourceCodeSpan => *(arg_data as *const SourceCodeSpan),
+        DiagnosticArgType::CodePoint => {
+            Cow::Owned(format!("U+{:04X}", *(arg_data as *const char) as u32).into_bytes())
+        }
 
-        DiagnosticArgType::Char8
-        | DiagnosticArgType::EnumKind
-        | DiagnosticArgType::Invalid
-        | DiagnosticArgType::StatementKind
-        | DiagnosticArgType::String8View
-        | DiagnosticArgType::VariableKind => {
-            unreachable!();
+        DiagnosticArgType::Identifier => {
+            Cow::Borrowed((*(arg_data as *const Identifier)).span().as_slice())
         }
-    }
-}
 
-unsafe fn expand_argument<'diag>(
-    args: &DiagnosticMessageArgs,
-    diagnostic: *const u8,
-    arg_index: i32,
-) -> &'diag [u8] {
-    let (arg_data, arg_type) = get_arg(args, diagnostic, arg_index);
-    match arg_type {
-        DiagnosticArgType::Char8 => std::slice::from_raw_parts(arg_data, 1),
+        DiagnosticArgType::Integer => Cow::Owned(
+            translator
+                .format_integer(*(arg_data as *const usize) as u64)
+                .into_bytes(),
+        ),
 
-        DiagnosticArgType::Identifier => (*(arg_data as *const Identifier)).span().as_slice(),
-        DiagnosticArgType::SourceCodeSpan => (*(arg_data as *const SourceCodeSpan)).as_slice(),
-        DiagnosticArgType::String8View => *(arg_data as *const &[u8]),
+        DiagnosticArgType::OptionalSourceCodeSpan => {
+            match *(arg_data as *const Option<SourceCodeSpan>) {
+                Some(span) => Cow::Borrowed(span.as_slice()),
+                None => Cow::Borrowed(b""),
+            }
+        }
+        DiagnosticArgType::SourceCodeSpan => {
+            Cow::Borrowed((*(arg_data as *const SourceCodeSpan)).as_slice())
+        }
+        DiagnosticArgType::String8View => Cow::Borrowed(*(arg_data as *const &[u8])),
+
+        DiagnosticArgType::TokenType => {
+            Cow::Borrowed(token_type_text(*(arg_data as *const TokenType)).as_bytes())
+        }
 
         DiagnosticArgType::EnumKind
         | DiagnosticArgType::Invalid
@@ -154,7 +193,7 @@ fn expand_argument_headlinese(
     translator: Translator,
     args: &DiagnosticMessageArgs,
     diagnostic: *const u8,
-    arg_index: i32,
+    arg_index: usize,
 ) -> &'static [u8] {
     let (arg_data, arg_type) = get_arg(args, diagnostic, arg_index);
     match arg_type {
@@ -169,10 +208,14 @@ fn expand_argument_headlinese(
             .as_bytes(),
 
         DiagnosticArgType::Char8
+        | DiagnosticArgType::CodePoint
         | DiagnosticArgType::Identifier
+        | DiagnosticArgType::Integer
         | DiagnosticArgType::Invalid
+        | DiagnosticArgType::OptionalSourceCodeSpan
         | DiagnosticArgType::SourceCodeSpan
         | DiagnosticArgType::String8View
+        | DiagnosticArgType::TokenType
         | DiagnosticArgType::VariableKind => {
             unreachable!();
         }
@@ -183,7 +226,7 @@ fn expand_argument_singular(
     translator: Translator,
     args: &DiagnosticMessageArgs,
     diagnostic: *const u8,
-    arg_index: i32,
+    arg_index: usize,
 ) -> &'static [u8] {
     let (arg_data, arg_type) = get_arg(args, diagnostic, arg_index);
     match arg_type {
@@ -198,10 +241,14 @@ fn expand_argument_singular(
         }
 
         DiagnosticArgType::Char8
+        | DiagnosticArgType::CodePoint
         | DiagnosticArgType::Identifier
+        | DiagnosticArgType::Integer
         | DiagnosticArgType::Invalid
+        | DiagnosticArgType::OptionalSourceCodeSpan
         | DiagnosticArgType::SourceCodeSpan
         | DiagnosticArgType::String8View
+        | DiagnosticArgType::TokenType
         | DiagnosticArgType::VariableKind => {
             unreachable!();
         }
@@ -211,9 +258,9 @@ fn expand_argument_singular(
 fn get_arg(
     args: &DiagnosticMessageArgs,
     diagnostic: *const u8,
-    arg_index: i32,
+    arg_index: usize,
 ) -> (*const u8, DiagnosticArgType) {
-    let arg_info: &DiagnosticMessageArgInfo = &args[narrow_cast::<usize, _>(arg_index)];
+    let arg_info: &DiagnosticMessageArgInfo = &args[arg_index];
     let arg_data: *const u8 = unsafe { diagnostic.add(arg_info.offset()) };
     (arg_data, arg_info.type_())
 }
@@ -1,369 +1,509 @@
 use crate::diagnostic::*;
+use crate::language::*;
 use crate::source_code_span::*;
+use crate::token::*;
 use cpp_vs_rust_i18n::qljs_translatable;
-use cpp_vs_rust_i18n::translation::*;
 use cpp_vs_rust_port::constexpr::*;
 use cpp_vs_rust_port::qljs_offset_of;
 use cpp_vs_rust_proc_diagnostic_types::*;
 
-#[qljs_diagnostic(
-    "E0005", DiagnosticSeverity::Error,
-    (qljs_translatable!("BigInt literal contains decimal point"), where_),
-)]
-pub struct DiagBigIntLiteralContainsDecimalPoint<'code> {
-    pub where_: SourceCodeSpan<'code>,
+qljs_diagnostic_types! {
+    #[qljs_diagnostic(
+        "E0005", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Syntax,
+        (qljs_translatable!("BigInt literal contains decimal point"), where_),
+    )]
+    pub struct DiagBigIntLiteralContainsDecimalPoint<'code> {
+        pub where_: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0006", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Syntax,
+        (qljs_translatable!("BigInt literal contains exponent"), where_),
+    )]
+    pub struct DiagBigIntLiteralContainsExponent<'code> {
+        pub where_: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0011", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Syntax,
+        (qljs_translatable!("character is not allowed in identifiers"), character),
+    )]
+    pub struct DiagCharacterDisallowedInIdentifiers<'code> {
+        pub character: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0012", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Syntax,
+        (qljs_translatable!("escaped character is not allowed in identifiers"), escape_sequence),
+    )]
+    pub struct DiagEscapedCharacterDisallowedInIdentifiers<'code> {
+        pub escape_sequence: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0013", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Syntax,
+        (qljs_translatable!("code point out of range"), escape_sequence),
+    )]
+    pub struct DiagEscapedCodePointInIdentifierOutOfRange<'code> {
+        pub escape_sequence: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0207", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Syntax,
+        (qljs_translatable!("code point in Unicode escape sequence must not be greater than U+10FFFF"), escape_sequence),
+    )]
+    pub struct DiagEscapedCodePointInUnicodeOutOfRange<'code> {
+        pub escape_sequence: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0019", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Syntax,
+        (qljs_translatable!("escaping '-' is not allowed in tag names; write '-' instead"), escape_sequence),
+    )]
+    pub struct DiagEscapedHyphenNotAllowedInJSXTag<'code> {
+        pub escape_sequence: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0016", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Syntax,
+        (qljs_translatable!("expected hexadecimal digits in Unicode escape sequence"), escape_sequence),
+    )]
+    pub struct DiagExpectedHexDigitsInUnicodeEscape<'code> {
+        pub escape_sequence: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0060", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Syntax,
+        (qljs_translatable!("invalid hex escape sequence: {0}"), escape_sequence),
+    )]
+    pub struct DiagInvalidHexEscapeSequence<'code> {
+        pub escape_sequence: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0197", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Syntax,
+        (qljs_translatable!("'{0}' is not allowed for strings; use {1} instead"), opening_quote, suggested_quote),
+    )]
+    pub struct DiagInvalidQuotesAroundStringLiteral<'code> {
+        pub opening_quote: SourceCodeSpan<'code>,
+        pub suggested_quote: u8,
+    }
+
+    #[qljs_diagnostic(
+        "E0022", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Syntax,
+        (qljs_translatable!("invalid UTF-8 sequence"), sequence),
+    )]
+    pub struct DiagInvalidUTF8Sequence<'code> {
+        pub sequence: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0023", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Syntax,
+        (qljs_translatable!("keywords cannot contain escape sequences"), escape_sequence),
+    )]
+    pub struct DiagKeywordsCannotContainEscapeSequences<'code> {
+        pub escape_sequence: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0032", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Syntax,
+        (qljs_translatable!("legacy octal literal may not be BigInt"), characters),
+    )]
+    pub struct DiagLegacyOctalLiteralMayNotBeBigInt<'code> {
+        pub characters: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0152", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Syntax,
+        (qljs_translatable!("legacy octal literals may not contain underscores"), underscores),
+    )]
+    pub struct DiagLegacyOctalLiteralMayNotContainUnderscores<'code> {
+        pub underscores: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0028", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Syntax,
+        (qljs_translatable!("number literal contains consecutive underscores"), underscores),
+    )]
+    pub struct DiagNumberLiteralContainsConsecutiveUnderscores<'code> {
+        pub underscores: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0029", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Syntax,
+        tags = [DiagnosticTag::Unnecessary],
+        (qljs_translatable!("number literal contains trailing underscore(s)"), underscores),
+    )]
+    pub struct DiagNumberLiteralContainsTrailingUnderscores<'code> {
+        pub underscores: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0030", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Syntax,
+        (qljs_translatable!("octal literal may not have exponent"), characters),
+    )]
+    pub struct DiagOctalLiteralMayNotHaveExponent<'code> {
+        pub characters: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0031", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Syntax,
+        (qljs_translatable!("octal literal may not have decimal"), characters),
+    )]
+    pub struct DiagOctalLiteralMayNotHaveDecimal<'code> {
+        pub characters: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0212", DiagnosticSeverity::Warning,
+        category = DiagnosticCategory::Correctness,
+        (qljs_translatable!("integer cannot be represented and will be rounded to '{1}'"), characters, rounded_val),
+    )]
+    pub struct DiagIntegerLiteralWillLosePrecision<'code> {
+        pub characters: SourceCodeSpan<'code>,
+        pub rounded_val: &'code [u8],
+    }
+
+    #[qljs_diagnostic(
+        "E0035", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Syntax,
+        (qljs_translatable!("RegExp literal flags cannot contain Unicode escapes"), escape_sequence),
+    )]
+    pub struct DiagRegexpLiteralFlagsCannotContainUnicodeEscapes<'code> {
+        pub escape_sequence: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0037", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Syntax,
+        (qljs_translatable!("unclosed block comment"), comment_open),
+    )]
+    pub struct DiagUnclosedBlockComment<'code> {
+        pub comment_open: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0038", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Syntax,
+        (qljs_translatable!("unclosed identifier escape sequence"), escape_sequence),
+    )]
+    pub struct DiagUnclosedIdentifierEscapeSequence<'code> {
+        pub escape_sequence: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0039", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Syntax,
+        (qljs_translatable!("unclosed regexp literal"), regexp_literal),
+    )]
+    pub struct DiagUnclosedRegexpLiteral<'code> {
+        pub regexp_literal: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0040", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Syntax,
+        (qljs_translatable!("unclosed string literal"), string_literal),
+    )]
+    pub struct DiagUnclosedStringLiteral<'code> {
+        pub string_literal: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0181", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Syntax,
+        (qljs_translatable!("unclosed string literal"), string_literal_begin),
+    )]
+    pub struct DiagUnclosedJSXStringLiteral<'code> {
+        pub string_literal_begin: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0041", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Syntax,
+        (qljs_translatable!("unclosed template"), incomplete_template),
+    )]
+    pub struct DiagUnclosedTemplate<'code> {
+        pub incomplete_template: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0042", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Syntax,
+        (qljs_translatable!("unexpected '@'"), character),
+    )]
+    pub struct DiagUnexpectedAtCharacter<'code> {
+        pub character: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0043", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Syntax,
+        (qljs_translatable!("unexpected '\\' in identifier"), backslash),
+    )]
+    pub struct DiagUnexpectedBackslashInIdentifier<'code> {
+        pub backslash: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0044", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Syntax,
+        (qljs_translatable!("unexpected characters in number literal"), characters),
+    )]
+    pub struct DiagUnexpectedCharactersInNumber<'code> {
+        pub characters: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0045", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Syntax,
+        (qljs_translatable!("unexpected control character {1}"), character, code_point),
+    )]
+    pub struct DiagUnexpectedControlCharacter<'code> {
+        pub character: SourceCodeSpan<'code>,
+        pub code_point: char,
+    }
+
+    #[qljs_diagnostic(
+        "E0046", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Syntax,
+        (qljs_translatable!("unexpected characters in binary literal"), characters),
+    )]
+    pub struct DiagUnexpectedCharactersInBinaryNumber<'code> {
+        pub characters: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0047", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Syntax,
+        (qljs_translatable!("unexpected characters in octal literal"), characters),
+    )]
+    pub struct DiagUnexpectedCharactersInOctalNumber<'code> {
+        pub characters: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0048", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Syntax,
+        (qljs_translatable!("unexpected characters in hex literal"), characters),
+    )]
+    pub struct DiagUnexpectedCharactersInHexNumber<'code> {
+        pub characters: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0182", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Syntax,
+        (qljs_translatable!("'>' is not allowed directly in JSX text; write {{'>'} or &gt; instead"), greater),
+    )]
+    pub struct DiagUnexpectedGreaterInJSXText<'code> {
+        pub greater: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0183", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Syntax,
+        (qljs_translatable!("'}' is not allowed directly in JSX text; write {{'}'} instead"), right_curly),
+    )]
+    pub struct DiagUnexpectedRightCurlyInJSXText<'code> {
+        pub right_curly: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0210", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Syntax,
+        (qljs_translatable!("unopened block comment"), comment_close),
+    )]
+    pub struct DiagUnopenedBlockComment<'code> {
+        pub comment_close: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0049", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Syntax,
+        (qljs_translatable!("binary number literal has no digits"), characters),
+    )]
+    pub struct DiagNoDigitsInBinaryNumber<'code> {
+        pub characters: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0050", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Syntax,
+        (qljs_translatable!("hex number literal has no digits"), characters),
+    )]
+    pub struct DiagNoDigitsInHexNumber<'code> {
+        pub characters: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0051", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Syntax,
+        (qljs_translatable!("octal number literal has no digits"), characters),
+    )]
+    pub struct DiagNoDigitsInOctalNumber<'code> {
+        pub characters: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0052", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Syntax,
+        (qljs_translatable!("unexpected '#'"), where_),
+    )]
+    pub struct DiagUnexpectedHashCharacter<'code> {
+        pub where_: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0095", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Syntax,
+        (qljs_translatable!("unicode byte order mark (BOM) cannot appear before #! at beginning of script"), bom),
+    )]
+    pub struct DiagUnexpectedBomBeforeShebang<'code> {
+        pub bom: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0164", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Syntax,
+        (qljs_translatable!("JSON syntax error"), where_),
+    )]
+    pub struct DiagConfigJSONSyntaxError<'code> {
+        pub where_: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0168", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Correctness,
+        (qljs_translatable!("'globals' must be an object"), where_),
+    )]
+    pub struct DiagConfigGlobalsTypeMismatch<'code> {
+        pub where_: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0169", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Correctness,
+        (qljs_translatable!("'global-groups' entries must be strings"), group),
+    )]
+    pub struct DiagConfigGlobalGroupsGroupTypeMismatch<'code> {
+        pub group: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0170", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Correctness,
+        (qljs_translatable!("'global-groups' must be a boolean or an array"), where_),
+    )]
+    pub struct DiagConfigGlobalGroupsTypeMismatch<'code> {
+        pub where_: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0171", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Correctness,
+        (qljs_translatable!("'globals' descriptor must be a boolean or an object"), descriptor),
+    )]
+    pub struct DiagConfigGlobalsDescriptorTypeMismatch<'code> {
+        pub descriptor: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0172", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Correctness,
+        (qljs_translatable!("'globals' descriptor 'shadowable' property must be a boolean"), value),
+    )]
+    pub struct DiagConfigGlobalsDescriptorShadowableTypeMismatch<'code> {
+        pub value: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0173", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Correctness,
+        (qljs_translatable!("'globals' descriptor 'writable' property must be a boolean"), value),
+    )]
+    pub struct DiagConfigGlobalsDescriptorWritableTypeMismatch<'code> {
+        pub value: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0300", DiagnosticSeverity::Warning,
+        category = DiagnosticCategory::Syntax,
+        (qljs_translatable!("too many errors; {1} more were not reported"), where_, dropped_count),
+    )]
+    pub struct DiagTooManyDiagnostics<'code> {
+        pub where_: SourceCodeSpan<'code>,
+        pub dropped_count: usize,
+    }
+
+    #[qljs_diagnostic(
+        "E0301", DiagnosticSeverity::Warning,
+        category = DiagnosticCategory::Syntax,
+        (qljs_translatable!("file looks like a binary file; stopped linting"), where_),
+    )]
+    pub struct DiagFileLooksBinary<'code> {
+        pub where_: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E6969", DiagnosticSeverity::Error,
+        (qljs_translatable!("test for multiple messages"), a),
+        (qljs_translatable!("second message here"), b),
+    )]
+    pub struct DiagMultipleMessageTest<'code> {
+        pub a: SourceCodeSpan<'code>,
+        pub b: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E6970", DiagnosticSeverity::Error,
+        category = DiagnosticCategory::Style,
+        tags = [DiagnosticTag::Unnecessary, DiagnosticTag::Deprecated],
+        (qljs_translatable!("test for optional notes"), a),
+        (qljs_translatable!("optional note here"), b),
+    )]
+    pub struct DiagOptionalNoteTest<'code> {
+        pub a: SourceCodeSpan<'code>,
+        pub b: Option<SourceCodeSpan<'code>>,
+    }
+
+    #[qljs_diagnostic(
+        "E6971", DiagnosticSeverity::Error,
+        (
+            qljs_translatable!("test for enum arguments"),
+            where_, enum_kind, statement_kind, token_type, variable_kind
+        ),
+    )]
+    pub struct DiagEnumArgumentsTest<'code> {
+        pub where_: SourceCodeSpan<'code>,
+        pub enum_kind: EnumKind,
+        pub statement_kind: StatementKind,
+        pub token_type: TokenType,
+        pub variable_kind: VariableKind,
+    }
 }
 
-#[qljs_diagnostic(
-    "E0006", DiagnosticSeverity::Error,
-    (qljs_translatable!("BigInt literal contains exponent"), where_),
-)]
-pub struct DiagBigIntLiteralContainsExponent<'code> {
-    pub where_: SourceCodeSpan<'code>,
-}
-
-#[qljs_diagnostic(
-    "E0011", DiagnosticSeverity::Error,
-    (qljs_translatable!("character is not allowed in identifiers"), character),
-)]
-pub struct DiagCharacterDisallowedInIdentifiers<'code> {
-    pub character: SourceCodeSpan<'code>,
-}
-
-#[qljs_diagnostic(
-    "E0012", DiagnosticSeverity::Error,
-    (qljs_translatable!("escaped character is not allowed in identifiers"), escape_sequence),
-)]
-pub struct DiagEscapedCharacterDisallowedInIdentifiers<'code> {
-    pub escape_sequence: SourceCodeSpan<'code>,
-}
-
-#[qljs_diagnostic(
-    "E0013", DiagnosticSeverity::Error,
-    (qljs_translatable!("code point out of range"), escape_sequence),
-)]
-pub struct DiagEscapedCodePointInIdentifierOutOfRange<'code> {
-    pub escape_sequence: SourceCodeSpan<'code>,
-}
-
-#[qljs_diagnostic(
-    "E0207", DiagnosticSeverity::Error,
-    (qljs_translatable!("code point in Unicode escape sequence must not be greater than U+10FFFF"), escape_sequence),
-)]
-pub struct DiagEscapedCodePointInUnicodeOutOfRange<'code> {
-    pub escape_sequence: SourceCodeSpan<'code>,
-}
-
-#[qljs_diagnostic(
-    "E0019", DiagnosticSeverity::Error,
-    (qljs_translatable!("escaping '-' is not allowed in tag names; write '-' instead"), escape_sequence),
-)]
-pub struct DiagEscapedHyphenNotAllowedInJSXTag<'code> {
-    pub escape_sequence: SourceCodeSpan<'code>,
-}
-
-#[qljs_diagnostic(
-    "E0016", DiagnosticSeverity::Error,
-    (qljs_translatable!("expected hexadecimal digits in Unicode escape sequence"), escape_sequence),
-)]
-pub struct DiagExpectedHexDigitsInUnicodeEscape<'code> {
-    pub escape_sequence: SourceCodeSpan<'code>,
-}
-
-#[qljs_diagnostic(
-    "E0060", DiagnosticSeverity::Error,
-    (qljs_translatable!("invalid hex escape sequence: {0}"), escape_sequence),
-)]
-pub struct DiagInvalidHexEscapeSequence<'code> {
-    pub escape_sequence: SourceCodeSpan<'code>,
-}
-
-#[qljs_diagnostic(
-    "E0197", DiagnosticSeverity::Error,
-    (qljs_translatable!("'{0}' is not allowed for strings; use {1} instead"), opening_quote, suggested_quote),
-)]
-pub struct DiagInvalidQuotesAroundStringLiteral<'code> {
-    pub opening_quote: SourceCodeSpan<'code>,
-    pub suggested_quote: u8,
-}
-
-#[qljs_diagnostic(
-    "E0022", DiagnosticSeverity::Error,
-    (qljs_translatable!("invalid UTF-8 sequence"), sequence),
-)]
-pub struct DiagInvalidUTF8Sequence<'code> {
-    pub sequence: SourceCodeSpan<'code>,
-}
-
-#[qljs_diagnostic(
-    "E0023", DiagnosticSeverity::Error,
-    (qljs_translatable!("keywords cannot contain escape sequences"), escape_sequence),
-)]
-pub struct DiagKeywordsCannotContainEscapeSequences<'code> {
-    pub escape_sequence: SourceCodeSpan<'code>,
-}
-
-#[qljs_diagnostic(
-    "E0032", DiagnosticSeverity::Error,
-    (qljs_translatable!("legacy octal literal may not be BigInt"), characters),
-)]
-pub struct DiagLegacyOctalLiteralMayNotBeBigInt<'code> {
-    pub characters: SourceCodeSpan<'code>,
-}
-
-#[qljs_diagnostic(
-    "E0152", DiagnosticSeverity::Error,
-    (qljs_translatable!("legacy octal literals may not contain underscores"), underscores),
-)]
-pub struct DiagLegacyOctalLiteralMayNotContainUnderscores<'code> {
-    pub underscores: SourceCodeSpan<'code>,
-}
-
-#[qljs_diagnostic(
-    "E0028", DiagnosticSeverity::Error,
-    (qljs_translatable!("number literal contains consecutive underscores"), underscores),
-)]
-pub struct DiagNumberLiteralContainsConsecutiveUnderscores<'code> {
-    pub underscores: SourceCodeSpan<'code>,
-}
-
-#[qljs_diagnostic(
-    "E0029", DiagnosticSeverity::Error,
-    (qljs_translatable!("number literal contains trailing underscore(s)"), underscores),
-)]
-pub struct DiagNumberLiteralContainsTrailingUnderscores<'code> {
-    pub underscores: SourceCodeSpan<'code>,
-}
-
-#[qljs_diagnostic(
-    "E0030", DiagnosticSeverity::Error,
-    (qljs_translatable!("octal literal may not have exponent"), characters),
-)]
-pub struct DiagOctalLiteralMayNotHaveExponent<'code> {
-    pub characters: SourceCodeSpan<'code>,
-}
-
-#[qljs_diagnostic(
-    "E0031", DiagnosticSeverity::Error,
-    (qljs_translatable!("octal literal may not have decimal"), characters),
-)]
-pub struct DiagOctalLiteralMayNotHaveDecimal<'code> {
-    pub characters: SourceCodeSpan<'code>,
-}
-
-#[qljs_diagnostic(
-    "E0212", DiagnosticSeverity::Warning,
-    (qljs_translatable!("integer cannot be represented and will be rounded to '{1}'"), characters, rounded_val),
-)]
-pub struct DiagIntegerLiteralWillLosePrecision<'code> {
-    pub characters: SourceCodeSpan<'code>,
-    pub rounded_val: &'code [u8],
-}
-
-#[qljs_diagnostic(
-    "E0035", DiagnosticSeverity::Error,
-    (qljs_translatable!("RegExp literal flags cannot contain Unicode escapes"), escape_sequence),
-)]
-pub struct DiagRegexpLiteralFlagsCannotContainUnicodeEscapes<'code> {
-    pub escape_sequence: SourceCodeSpan<'code>,
-}
-
-#[qljs_diagnostic(
-    "E0037", DiagnosticSeverity::Error,
-    (qljs_translatable!("unclosed block comment"), comment_open),
-)]
-pub struct DiagUnclosedBlockComment<'code> {
-    pub comment_open: SourceCodeSpan<'code>,
-}
-
-#[qljs_diagnostic(
-    "E0038", DiagnosticSeverity::Error,
-    (qljs_translatable!("unclosed identifier escape sequence"), escape_sequence),
-)]
-pub struct DiagUnclosedIdentifierEscapeSequence<'code> {
-    pub escape_sequence: SourceCodeSpan<'code>,
-}
-
-#[qljs_diagnostic(
-    "E0039", DiagnosticSeverity::Error,
-    (qljs_translatable!("unclosed regexp literal"), regexp_literal),
-)]
-pub struct DiagUnclosedRegexpLiteral<'code> {
-    pub regexp_literal: SourceCodeSpan<'code>,
-}
-
-#[qljs_diagnostic(
-    "E0040", DiagnosticSeverity::Error,
-    (qljs_translatable!("unclosed string literal"), string_literal),
-)]
-pub struct DiagUnclosedStringLiteral<'code> {
-    pub string_literal: SourceCodeSpan<'code>,
-}
-
-#[qljs_diagnostic(
-    "E0181", DiagnosticSeverity::Error,
-    (qljs_translatable!("unclosed string literal"), string_literal_begin),
-)]
-pub struct DiagUnclosedJSXStringLiteral<'code> {
-    pub string_literal_begin: SourceCodeSpan<'code>,
-}
-
-#[qljs_diagnostic(
-    "E0041", DiagnosticSeverity::Error,
-    (qljs_translatable!("unclosed template"), incomplete_template),
-)]
-pub struct DiagUnclosedTemplate<'code> {
-    pub incomplete_template: SourceCodeSpan<'code>,
-}
-
-#[qljs_diagnostic(
-    "E0042", DiagnosticSeverity::Error,
-    (qljs_translatable!("unexpected '@'"), character),
-)]
-pub struct DiagUnexpectedAtCharacter<'code> {
-    pub character: SourceCodeSpan<'code>,
-}
-
-#[qljs_diagnostic(
-    "E0043", DiagnosticSeverity::Error,
-    (qljs_translatable!("unexpected '\\' in identifier"), backslash),
-)]
-pub struct DiagUnexpectedBackslashInIdentifier<'code> {
-    pub backslash: SourceCodeSpan<'code>,
-}
-
-#[qljs_diagnostic(
-    "E0044", DiagnosticSeverity::Error,
-    (qljs_translatable!("unexpected characters in number literal"), characters),
-)]
-pub struct DiagUnexpectedCharactersInNumber<'code> {
-    pub characters: SourceCodeSpan<'code>,
-}
-
-#[qljs_diagnostic(
-    "E0045", DiagnosticSeverity::Error,
-    (qljs_translatable!("unexpected control character"), character),
-)]
-pub struct DiagUnexpectedControlCharacter<'code> {
-    pub character: SourceCodeSpan<'code>,
-}
-
-#[qljs_diagnostic(
-    "E0046", DiagnosticSeverity::Error,
-    (qljs_translatable!("unexpected characters in binary literal"), characters),
-)]
-pub struct DiagUnexpectedCharactersInBinaryNumber<'code> {
-    pub characters: SourceCodeSpan<'code>,
-}
-
-#[qljs_diagnostic(
-    "E0047", DiagnosticSeverity::Error,
-    (qljs_translatable!("unexpected characters in octal literal"), characters),
-)]
-pub struct DiagUnexpectedCharactersInOctalNumber<'code> {
-    pub characters: SourceCodeSpan<'code>,
-}
-
-#[qljs_diagnostic(
-    "E0048", DiagnosticSeverity::Error,
-    (qljs_translatable!("unexpected characters in hex literal"), characters),
-)]
-pub struct DiagUnexpectedCharactersInHexNumber<'code> {
-    pub characters: SourceCodeSpan<'code>,
-}
-
-#[qljs_diagnostic(
-    "E0182", DiagnosticSeverity::Error,
-    (qljs_translatable!("'>' is not allowed directly in JSX text; write {{'>'} or &gt; instead"), greater),
-)]
-pub struct DiagUnexpectedGreaterInJSXText<'code> {
-    pub greater: SourceCodeSpan<'code>,
-}
-
-#[qljs_diagnostic(
-    "E0183", DiagnosticSeverity::Error,
-    (qljs_translatable!("'}' is not allowed directly in JSX text; write {{'}'} instead"), right_curly),
-)]
-pub struct DiagUnexpectedRightCurlyInJSXText<'code> {
-    pub right_curly: SourceCodeSpan<'code>,
-}
-
-#[qljs_diagnostic(
-    "E0210", DiagnosticSeverity::Error,
-    (qljs_translatable!("unopened block comment"), comment_close),
-)]
-pub struct DiagUnopenedBlockComment<'code> {
-    pub comment_close: SourceCodeSpan<'code>,
-}
-
-#[qljs_diagnostic(
-    "E0049", DiagnosticSeverity::Error,
-    (qljs_translatable!("binary number literal has no digits"), characters),
-)]
-pub struct DiagNoDigitsInBinaryNumber<'code> {
-    pub characters: SourceCodeSpan<'code>,
-}
-
-#[qljs_diagnostic(
-    "E0050", DiagnosticSeverity::Error,
-    (qljs_translatable!("hex number literal has no digits"), characters),
-)]
-pub struct DiagNoDigitsInHexNumber<'code> {
-    pub characters: SourceCodeSpan<'code>,
-}
-
-#[qljs_diagnostic(
-    "E0051", DiagnosticSeverity::Error,
-    (qljs_translatable!("octal number literal has no digits"), characters),
-)]
-pub struct DiagNoDigitsInOctalNumber<'code> {
-    pub characters: SourceCodeSpan<'code>,
-}
-
-#[qljs_diagnostic(
-    "E0052", DiagnosticSeverity::Error,
-    (qljs_translatable!("unexpected '#'"), where_),
-)]
-pub struct DiagUnexpectedHashCharacter<'code> {
-    pub where_: SourceCodeSpan<'code>,
-}
-
-#[qljs_diagnostic(
-    "E0095", DiagnosticSeverity::Error,
-    (qljs_translatable!("unicode byte order mark (BOM) cannot appear before #! at beginning of script"), bom),
-)]
-pub struct DiagUnexpectedBomBeforeShebang<'code> {
-    pub bom: SourceCodeSpan<'code>,
-}
-
-#[qljs_diagnostic(
-    "E6969", DiagnosticSeverity::Error,
-    (qljs_translatable!("test for multiple messages"), a),
-    (qljs_translatable!("second message here"), b),
-)]
-pub struct DiagMultipleMessageTest<'code> {
-    pub a: SourceCodeSpan<'code>,
-    pub b: SourceCodeSpan<'code>,
-}
-
-qljs_make_diag_type_enum!(DiagType);
-
 // NOTE(port): In C++, this was a function template called diag_type_from_type.
 pub trait HasDiagType {
     const TYPE_: DiagType;
 }
 
-qljs_make_has_diag_type_impls!();
-
-// NOTE(strager): For use in tests only.
-// NOTE(port): This was called diag_collector::diag in the C++ code.
-qljs_make_any_diag_enum!();
-
-pub const DIAG_TYPE_COUNT: i32 = qljs_diag_type_count!() as i32;
-
-pub(crate) const ALL_DIAGNOSTIC_INFOS: &[DiagnosticInfo] = &qljs_make_diag_type_infos!();
-
-// NOTE(port): This was called diag_sizes inside of diag_collector::report in the C++ code.
-qljs_diag_sizes_array!();
-
-pub(crate) const MAX_SIZE_OF_DIAGNOSTIC_TYPE: usize = maximum_u8(&DIAG_SIZES) as usize;
+pub(crate) const MAX_SIZE_OF_DIAGNOSTIC_TYPE: usize = maximum_u16(&DIAG_SIZES) as usize;
@@ -2,11 +2,43 @@ use crate::source_code_span::*;
 use cpp_vs_rust_util::narrow_cast::*;
 use cpp_vs_rust_util::padded_string::*;
 
+pub trait SourceRangeLike {
+    type PositionType;
+
+    fn new(begin: Self::PositionType, end: Self::PositionType) -> Self;
+    fn begin(&self) -> Self::PositionType;
+    fn end(&self) -> Self::PositionType;
+}
+
 pub trait LocatorLike<'code> {
-    type RangeType;
+    type PositionType;
+    type RangeType: SourceRangeLike<PositionType = Self::PositionType>;
 
     fn new(s: PaddedStringView<'code>) -> Self;
     fn range(&self, span: SourceCodeSpan<'_>) -> Self::RangeType;
+
+    // Returns a pointer into the input.
+    fn pointer_at_position(&self, position: Self::PositionType) -> *const u8;
+
+    // Called after the entire input was replaced with new_input.
+    //
+    // Locators with settings (such as a position encoding) should override
+    // set_input to keep their settings.
+    fn set_input(&mut self, new_input: PaddedStringView<'code>)
+    where
+        Self: Sized,
+    {
+        *self = Self::new(new_input);
+    }
+
+    // Called after range of the input was replaced with replacement_text,
+    // producing new_input. The old input is still valid during this call.
+    fn replace_text(
+        &mut self,
+        range: Self::RangeType,
+        replacement_text: &[u8],
+        new_input: PaddedStringView<'code>,
+    );
 }
 
 pub struct Document<Locator> {
@@ -37,18 +69,67 @@ impl<'code, Locator: LocatorLike<'code>> Document<Locator> {
             unsafe { &mut (*self.content_buffers.get())[self.active_content_buffer] };
         content.resize(narrow_cast::<i32, _>(new_text.len()));
         content.as_mut_slice().copy_from_slice(new_text);
-        self.locator = Locator::new(content.view());
+        self.locator.set_input(content.view());
     }
 
-    pub fn replace_text(_range: Locator::RangeType, _replacement_text: &[u8]) {
-        unimplemented!();
+    pub fn replace_text(&mut self, range: Locator::RangeType, replacement_text: &[u8]) {
+        let content_buffers: &mut [PaddedString; 2] = unsafe { &mut *self.content_buffers.get() };
+        let (old_content, new_content): (&PaddedString, &mut PaddedString) =
+            match &mut content_buffers[..] {
+                [a, b] if self.active_content_buffer == 0 => (a, b),
+                [a, b] => (b, a),
+                _ => unreachable!(),
+            };
+
+        let old_begin: *const u8 = old_content.c_str();
+        let old_text: &[u8] = old_content.as_slice();
+        // Clamp offsets so a position past the end of the input replaces
+        // through the end of the input.
+        let offset_at_position = |position: Locator::PositionType| -> usize {
+            let offset: usize = narrow_cast::<usize, _>(unsafe {
+                self.locator
+                    .pointer_at_position(position)
+                    .offset_from(old_begin)
+            });
+            std::cmp::min(offset, old_text.len())
+        };
+        let mut start_offset: usize = offset_at_position(range.begin());
+        let mut end_offset: usize = offset_at_position(range.end());
+        // Treat a reversed range like the same range with begin and end
+        // swapped.
+        let range: Locator::RangeType = if end_offset < start_offset {
+            std::mem::swap(&mut start_offset, &mut end_offset);
+            Locator::RangeType::new(range.end(), range.begin())
+        } else {
+            range
+        };
+        new_content.resize(narrow_cast::<PaddedStringSizeType, _>(
+            old_text.len() - (end_offset - start_offset) + replacement_text.len(),
+        ));
+        let new_text: &mut [u8] = new_content.as_mut_slice();
+        let replacement_end_offset: usize = start_offset + replacement_text.len();
+        new_text[..start_offset].copy_from_slice(&old_text[..start_offset]);
+        new_text[start_offset..replacement_end_offset].copy_from_slice(replacement_text);
+        new_text[replacement_end_offset..].copy_from_slice(&old_text[end_offset..]);
+
+        // NOTE(port): The locator may still refer to old_content, so only
+        // switch buffers after updating the locator.
+        let new_view: PaddedStringView = unsafe {
+            PaddedStringView::from_begin_end(new_content.c_str(), new_content.null_terminator())
+        };
+        self.locator.replace_text(range, replacement_text, new_view);
+        self.active_content_buffer = 1 - self.active_content_buffer;
     }
 
-    pub fn string<'this>(&'this mut self) -> PaddedStringView<'this> {
+    pub fn string<'this>(&'this self) -> PaddedStringView<'this> {
         unsafe { (*self.content_buffers.get().cast_const())[self.active_content_buffer].view() }
     }
 
-    pub fn locator<'this>(&'this mut self) -> &'this Locator {
+    pub fn locator<'this>(&'this self) -> &'this Locator {
         &self.locator
     }
+
+    pub fn locator_mut<'this>(&'this mut self) -> &'this mut Locator {
+        &mut self.locator
+    }
 }
@@ -0,0 +1,53 @@
+// A variable declared by the environment (such as 'window' in browsers), not
+// by the linted code.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GlobalDeclaredVariable {
+    pub name: String,
+    // If false, assigning to the variable is an error.
+    pub is_writable: bool,
+    // If false, declaring a variable with the same name in the linted code's
+    // top-level scope is an error.
+    pub is_shadowable: bool,
+}
+
+// NOTE(port): In C++, global_declared_variable_set is consulted by the variable
+// analyzer. The variable analyzer isn't ported, so nothing reads this set yet.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct GlobalDeclaredVariableSet {
+    // Keyed by GlobalDeclaredVariable::name.
+    variables: std::collections::BTreeMap<String, GlobalDeclaredVariable>,
+}
+
+impl GlobalDeclaredVariableSet {
+    pub fn new() -> GlobalDeclaredVariableSet {
+        GlobalDeclaredVariableSet {
+            variables: std::collections::BTreeMap::new(),
+        }
+    }
+
+    // Replaces any existing variable with the same name.
+    pub fn add_variable(&mut self, variable: GlobalDeclaredVariable) {
+        self.variables.insert(variable.name.clone(), variable);
+    }
+
+    pub fn remove_variable(&mut self, name: &str) {
+        self.variables.remove(name);
+    }
+
+    pub fn find(&self, name: &str) -> Option<&GlobalDeclaredVariable> {
+        self.variables.get(name)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.variables.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.variables.len()
+    }
+
+    // Visits variables in name order.
+    pub fn iter(&self) -> impl Iterator<Item = &GlobalDeclaredVariable> {
+        self.variables.values()
+    }
+}
@@ -11,6 +11,15 @@ pub struct Identifier<'lexer, 'code> {
     phantom_code: std::marker::PhantomData<&'code u8>,
 }
 
+// SAFETY: span_begin points into source code borrowed for 'code, and
+// normalized_begin points into the lexer's storage borrowed for 'lexer.
+// Identifier never writes through either pointer, and the borrows keep both
+// buffers alive and unmodified.
+unsafe impl<'lexer, 'code> Send for Identifier<'lexer, 'code> {}
+// SAFETY: Identifier is immutable after construction; &Identifier only hands
+// out the borrowed bytes as shared slices.
+unsafe impl<'lexer, 'code> Sync for Identifier<'lexer, 'code> {}
+
 impl<'lexer, 'code> Identifier<'lexer, 'code> {
     pub fn new(span: SourceCodeSpan<'code>, normalized: &'lexer [u8]) -> Identifier<'lexer, 'code> {
         Identifier {
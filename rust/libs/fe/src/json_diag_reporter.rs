@@ -0,0 +1,191 @@
+use crate::cli_location::*;
+use crate::diag_reporter::*;
+use crate::diagnostic::*;
+use crate::diagnostic_formatter::*;
+use crate::diagnostic_types::*;
+use crate::source_code_span::*;
+use cpp_vs_rust_i18n::translation::*;
+use cpp_vs_rust_util::json::*;
+use cpp_vs_rust_util::padded_string::*;
+
+// Collects diagnostics from one or more files into a JSON array:
+//
+//   [{"file":"hello.js","code":"E0040","severity":"error",
+//     "message":"unclosed string literal",
+//     "begin":{"line":1,"column":9},"end":{"line":1,"column":15},
+//     "notes":[]}]
+//
+// Lines and columns are 1-based. Columns count Unicode code points.
+pub struct JSONDiagReporter<'code> {
+    translator: Translator,
+    // Comma-separated diagnostic objects.
+    diagnostics: std::cell::RefCell<Vec<u8>>,
+    locator: Option<CLILocator<'code>>,
+    file_path: String,
+}
+
+impl<'code> JSONDiagReporter<'code> {
+    pub fn new(translator: Translator) -> JSONDiagReporter<'code> {
+        JSONDiagReporter {
+            translator: translator,
+            diagnostics: std::cell::RefCell::new(vec![]),
+            locator: None,
+            file_path: String::new(),
+        }
+    }
+
+    // Diagnostics reported after calling set_source refer to file_path.
+    pub fn set_source(&mut self, input: PaddedStringView<'code>, file_path: &str) {
+        self.locator = Some(CLILocator::new(input));
+        self.file_path = String::from(file_path);
+    }
+
+    // Returns a JSON array containing every diagnostic reported so far.
+    pub fn finish(&mut self) -> Vec<u8> {
+        let mut out: Vec<u8> = vec![b'['];
+        out.extend_from_slice(&self.diagnostics.borrow());
+        out.extend_from_slice(b"]\n");
+        out
+    }
+
+    // Returns the comma-separated diagnostic objects reported since the last
+    // call to take_diagnostics.
+    pub fn take_diagnostics(&mut self) -> Vec<u8> {
+        std::mem::take(self.diagnostics.get_mut())
+    }
+}
+
+impl<'code> DiagReporter for JSONDiagReporter<'code> {
+    fn report_impl(&self, type_: DiagType, diag: *const u8) {
+        let mut formatter = JSONDiagFormatter::new(self);
+        formatter.format(get_diagnostic_info(type_), diag);
+        formatter.write_diagnostic();
+    }
+}
+
+struct JSONMessage {
+    severity: DiagnosticSeverity,
+    text: Vec<u8>,
+    range: CLISourceRange,
+}
+
+struct JSONDiagFormatter<'code, 'reporter> {
+    reporter: &'reporter JSONDiagReporter<'code>,
+    // The first message is the diagnostic's main message. Following messages
+    // are notes.
+    messages: Vec<JSONMessage>,
+    code: [u8; 5],
+}
+
+impl<'code, 'reporter> JSONDiagFormatter<'code, 'reporter> {
+    fn new(reporter: &'reporter JSONDiagReporter<'code>) -> Self {
+        JSONDiagFormatter {
+            reporter: reporter,
+            messages: vec![],
+            code: [0; 5],
+        }
+    }
+
+    // Write a diagnostic object for the messages formatted so far.
+    fn write_diagnostic(&mut self) {
+        let Some((main_message, notes)) = self.messages.split_first() else {
+            return;
+        };
+
+        let mut diagnostics = self.reporter.diagnostics.borrow_mut();
+        if !diagnostics.is_empty() {
+            diagnostics.push(b',');
+        }
+        let out: &mut Vec<u8> = &mut diagnostics;
+        out.extend_from_slice(b"{\"file\":");
+        write_json_escaped_string(out, self.reporter.file_path.as_bytes());
+        out.extend_from_slice(b",\"code\":");
+        write_json_escaped_string(out, &self.code);
+        out.extend_from_slice(b",\"severity\":");
+        write_json_escaped_string(out, json_severity(main_message.severity).as_bytes());
+        out.push(b',');
+        write_message_fields(out, main_message);
+        out.extend_from_slice(b",\"notes\":[");
+        for (i, note) in notes.iter().enumerate() {
+            if i != 0 {
+                out.push(b',');
+            }
+            out.push(b'{');
+            write_message_fields(out, note);
+            out.push(b'}');
+        }
+        out.extend_from_slice(b"]}");
+        self.messages.clear();
+    }
+}
+
+impl<'code, 'reporter> DiagnosticFormatter for JSONDiagFormatter<'code, 'reporter> {
+    fn write_before_message(
+        &mut self,
+        code: &str,
+        severity: DiagnosticSeverity,
+        origin: SourceCodeSpan<'_>,
+    ) {
+        let range: CLISourceRange = self
+            .reporter
+            .locator
+            .as_ref()
+            .expect("JSONDiagReporter::set_source should have been called")
+            .range(origin);
+        self.code.copy_from_slice(code.as_bytes());
+        self.messages.push(JSONMessage {
+            severity: severity,
+            text: vec![],
+            range: range,
+        });
+    }
+
+    fn write_message_part(
+        &mut self,
+        _code: &str,
+        _severity: DiagnosticSeverity,
+        message_part: &[u8],
+    ) {
+        self.messages
+            .last_mut()
+            .unwrap()
+            .text
+            .extend_from_slice(message_part);
+    }
+
+    fn write_after_message(
+        &mut self,
+        _code: &str,
+        _severity: DiagnosticSeverity,
+        _origin: SourceCodeSpan<'_>,
+    ) {
+    }
+
+    fn translator(&self) -> Translator {
+        self.reporter.translator.clone()
+    }
+}
+
+// Write "message":...,"begin":...,"end":... for message.
+fn write_message_fields(out: &mut Vec<u8>, message: &JSONMessage) {
+    out.extend_from_slice(b"\"message\":");
+    write_json_escaped_string(out, &message.text);
+    out.extend_from_slice(
+        format!(
+            ",\"begin\":{{\"line\":{},\"column\":{}}},\"end\":{{\"line\":{},\"column\":{}}}",
+            message.range.begin.line_number,
+            message.range.begin.column_number,
+            message.range.end.line_number,
+            message.range.end.column_number,
+        )
+        .as_bytes(),
+    );
+}
+
+fn json_severity(severity: DiagnosticSeverity) -> &'static str {
+    match severity {
+        DiagnosticSeverity::Error => "error",
+        DiagnosticSeverity::Note => "note",
+        DiagnosticSeverity::Warning => "warning",
+    }
+}
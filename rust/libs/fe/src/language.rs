@@ -1,4 +1,6 @@
-#[derive(Clone, Copy, Eq, PartialEq)]
+use cpp_vs_rust_util::qljs_impl_try_from_u8;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum StatementKind {
     DoWhileLoop,
     ForLoop, // TODO(strager): c_style_for_loop + for_in_loop + for_of_loop?
@@ -8,7 +10,16 @@ pub enum StatementKind {
     LabelledStatement,
 }
 
-#[derive(Clone, Copy, Eq, PartialEq)]
+qljs_impl_try_from_u8!(StatementKind {
+    DoWhileLoop,
+    ForLoop,
+    IfStatement,
+    WhileLoop,
+    WithStatement,
+    LabelledStatement,
+});
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum EnumKind {
     DeclareConstEnum,
     ConstEnum,
@@ -16,6 +27,13 @@ pub enum EnumKind {
     Normal,
 }
 
+qljs_impl_try_from_u8!(EnumKind {
+    DeclareConstEnum,
+    ConstEnum,
+    DeclareEnum,
+    Normal,
+});
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum VariableKind {
     ArrowParameter,
@@ -38,6 +56,27 @@ pub enum VariableKind {
     Var,
 }
 
+qljs_impl_try_from_u8!(VariableKind {
+    ArrowParameter,
+    Catch,
+    Class,
+    Const,
+    Enum,
+    Function,
+    FunctionParameter,
+    FunctionTypeParameter,
+    GenericParameter,
+    Import,
+    ImportAlias,
+    ImportType,
+    IndexSignatureParameter,
+    Interface,
+    Let,
+    Namespace,
+    TypeAlias,
+    Var,
+});
+
 #[derive(Clone, Copy, Eq, PartialEq)]
 pub enum VariableInitKind {
     // Examples:
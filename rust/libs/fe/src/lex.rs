@@ -107,6 +107,28 @@ impl LexerAllocator {
             transaction_allocator: MonotonicAllocator::new("LexerAllocator::transaction_allocator"),
         }
     }
+
+    pub fn prepare_for_rewind(&self) -> LexerAllocatorRewindState {
+        LexerAllocatorRewindState {
+            allocator: self.allocator.prepare_for_rewind(),
+            transaction_allocator: self.transaction_allocator.prepare_for_rewind(),
+        }
+    }
+
+    // Deallocate everything allocated since the call to prepare_for_rewind
+    // which returned r. Memory chunks are kept for future allocations.
+    //
+    // Safety: Nothing allocated since prepare_for_rewind may be used after
+    // rewinding. In particular, no Lexer using this allocator may be alive.
+    pub unsafe fn rewind(&self, r: LexerAllocatorRewindState) {
+        self.allocator.rewind(r.allocator);
+        self.transaction_allocator.rewind(r.transaction_allocator);
+    }
+}
+
+pub struct LexerAllocatorRewindState {
+    allocator: LinkedBumpAllocatorRewindState,
+    transaction_allocator: LinkedBumpAllocatorRewindState,
 }
 
 // A Lexer reads JavaScript source code one token at a time.
@@ -132,7 +154,45 @@ impl<'alloc, 'code, 'reporter: 'alloc> Lexer<'alloc, 'code, 'reporter> {
         diag_reporter: &'reporter dyn DiagReporter,
         allocator: &'alloc LexerAllocator,
     ) -> Lexer<'alloc, 'code, 'reporter> {
-        let mut lexer = Lexer {
+        let mut lexer = Lexer::new_without_lexing(input, diag_reporter, allocator);
+        lexer.parse_bom_before_shebang();
+        lexer.parse_current_token();
+        lexer
+    }
+
+    // Like Lexer::new, but if the input looks like a binary file (see
+    // looks_like_binary), report DiagFileLooksBinary and skip to the end of
+    // the file instead of lexing the input.
+    //
+    // This avoids reporting a flood of diagnostics for files which aren't
+    // source code.
+    pub fn new_bailing_out_on_binary(
+        input: PaddedStringView<'code>,
+        diag_reporter: &'reporter dyn DiagReporter,
+        allocator: &'alloc LexerAllocator,
+    ) -> Lexer<'alloc, 'code, 'reporter> {
+        if !looks_like_binary(input) {
+            return Lexer::new(input, diag_reporter, allocator);
+        }
+        let mut lexer = Lexer::new_without_lexing(input, diag_reporter, allocator);
+        report(
+            diag_reporter,
+            DiagFileLooksBinary {
+                where_: unsafe { SourceCodeSpan::new(input.c_str(), input.c_str()) },
+            },
+        );
+        lexer.input = InputPointer(input.null_terminator());
+        lexer.last_token.begin = input.null_terminator();
+        lexer.last_token.end = input.null_terminator();
+        lexer
+    }
+
+    fn new_without_lexing(
+        input: PaddedStringView<'code>,
+        diag_reporter: &'reporter dyn DiagReporter,
+        allocator: &'alloc LexerAllocator,
+    ) -> Lexer<'alloc, 'code, 'reporter> {
+        Lexer {
             last_token: Token {
                 type_: TokenType::EndOfFile,
                 begin: std::ptr::null(),
@@ -146,10 +206,7 @@ impl<'alloc, 'code, 'reporter: 'alloc> Lexer<'alloc, 'code, 'reporter> {
             diag_reporter: diag_reporter,
             original_input: input,
             allocator: allocator,
-        };
-        lexer.parse_bom_before_shebang();
-        lexer.parse_current_token();
-        lexer
+        }
     }
 
     // Return information about the current token.
@@ -738,6 +795,8 @@ impl<'alloc, 'code, 'reporter: 'alloc> Lexer<'alloc, 'code, 'reporter> {
             self.diag_reporter,
             DiagUnexpectedControlCharacter {
                 character: unsafe { SourceCodeSpan::new(self.input.0, end.0) },
+                // Control characters are all ASCII.
+                code_point: self.input[0] as char,
             },
         );
         self.input = end;
@@ -2727,6 +2786,26 @@ impl<'alloc: 'reporter, 'code, 'reporter> LexerTransaction<'alloc, 'code, 'repor
     }
 }
 
+// How many bytes at the beginning of the input looks_like_binary inspects.
+const BINARY_SNIFF_SIZE: usize = 1024;
+
+// Returns true if the beginning of input looks like a binary file (such as an
+// image or a compiled program) instead of source code.
+//
+// A file looks binary if more than 1/8 of the inspected bytes (and at least 16
+// bytes) are null bytes or control characters other than whitespace. Source
+// code occasionally contains a few control characters (e.g. in string
+// literals), so a handful is not enough.
+pub fn looks_like_binary(input: PaddedStringView) -> bool {
+    let input_bytes: &[u8] = input.slice();
+    let sniffed: &[u8] = &input_bytes[0..std::cmp::min(input_bytes.len(), BINARY_SNIFF_SIZE)];
+    let suspicious_count: usize = sniffed
+        .iter()
+        .filter(|c: &&u8| matches!(**c, 0x00..=0x08 | 0x0e..=0x1f | 0x7f))
+        .count();
+    suspicious_count >= 16 && suspicious_count * 8 > sniffed.len()
+}
+
 fn is_binary_digit(c: u8) -> bool {
     c == b'0' || c == b'1'
 }
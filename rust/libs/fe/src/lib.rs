@@ -1,14 +1,27 @@
 pub mod buffering_diag_reporter;
+pub mod cancellation;
+pub mod cli_location;
 pub mod diag_reporter;
+pub mod diag_serialization;
 pub mod diagnostic;
 pub mod diagnostic_formatter;
 pub mod diagnostic_types;
+pub mod configuration;
+pub mod configuration_loader;
 pub mod document;
+pub mod global_declared_variable_set;
 pub mod identifier;
+pub mod json_diag_reporter;
 pub mod language;
 pub mod lex;
 pub mod lex_keyword;
 pub mod lex_unicode_generated;
+pub mod limiting_diag_reporter;
+pub mod lint_cache;
 pub mod linter;
+pub mod lsp_location;
+pub mod sarif_diag_reporter;
+pub mod sorting_diag_reporter;
 pub mod source_code_span;
+pub mod text_diag_reporter;
 pub mod token;
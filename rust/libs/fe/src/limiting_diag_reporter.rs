@@ -0,0 +1,151 @@
+use crate::diag_reporter::*;
+use crate::diagnostic::*;
+use crate::diagnostic_types::*;
+use crate::source_code_span::*;
+use cpp_vs_rust_util::padded_string::*;
+
+// How many diagnostics a LimitingDiagReporter lets through.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct DiagnosticLimits {
+    // The maximum number of diagnostics to report, or None for no limit.
+    pub max_total: Option<usize>,
+
+    // The maximum number of diagnostics with the same error code to report,
+    // or None for no limit.
+    pub max_per_code: Option<usize>,
+}
+
+impl DiagnosticLimits {
+    pub const fn unlimited() -> DiagnosticLimits {
+        DiagnosticLimits {
+            max_total: None,
+            max_per_code: None,
+        }
+    }
+}
+
+impl Default for DiagnosticLimits {
+    fn default() -> DiagnosticLimits {
+        DiagnosticLimits {
+            max_total: Some(1000),
+            max_per_code: Some(100),
+        }
+    }
+}
+
+// LimitingDiagReporter forwards diagnostics to another DiagReporter until a
+// limit is hit.
+//
+// Once a limit is hit, the diagnostic which hit the limit is dropped. After
+// that:
+//
+// * If max_total was hit, all further diagnostics are dropped.
+// * If max_per_code was hit, further diagnostics with the same code are
+//   dropped. Diagnostics with other codes are still reported.
+//
+// Diagnostics which the category filter (see set_category_filter) turns off are
+// forwarded without counting towards the limits. Duplicates are counted, so if
+// diagnostics are deduplicated (see SortingDiagReporter), put the
+// LimitingDiagReporter after the deduplication.
+//
+// LimitingDiagReporter does not report DiagTooManyDiagnostics itself. After
+// linting (and after sorting, if the target sorts), report the diagnostic
+// returned by LimitingDiagReporter::summary so that it comes last.
+pub struct LimitingDiagReporter<'reporter> {
+    target: &'reporter dyn DiagReporter,
+    limits: DiagnosticLimits,
+    category_filter: DiagnosticCategoryFilter,
+    state: std::cell::UnsafeCell<LimitingDiagReporterState>,
+}
+
+struct LimitingDiagReporterState {
+    total_count: usize,
+    // Indexed by DiagType.
+    count_by_type: Vec<usize>,
+    dropped_count: usize,
+}
+
+impl<'reporter> LimitingDiagReporter<'reporter> {
+    pub fn new(
+        target: &'reporter dyn DiagReporter,
+        limits: DiagnosticLimits,
+    ) -> LimitingDiagReporter<'reporter> {
+        LimitingDiagReporter {
+            target: target,
+            limits: limits,
+            category_filter: DiagnosticCategoryFilter::new(),
+            state: std::cell::UnsafeCell::new(LimitingDiagReporterState {
+                total_count: 0,
+                count_by_type: vec![0; DIAG_TYPE_COUNT as usize],
+                dropped_count: 0,
+            }),
+        }
+    }
+
+    // Do not count diagnostics which category_filter turns off. The target
+    // should drop these diagnostics (using the same filter).
+    pub fn set_category_filter(&mut self, category_filter: DiagnosticCategoryFilter) {
+        self.category_filter = category_filter;
+    }
+
+    // The number of diagnostics which were not forwarded because a limit was
+    // hit.
+    pub fn dropped_count(&self) -> usize {
+        unsafe { &*self.state.get() }.dropped_count
+    }
+
+    // If any diagnostics were dropped, returns a DiagTooManyDiagnostics at the
+    // end of input, the code which was linted.
+    pub fn summary<'code>(
+        &self,
+        input: PaddedStringView<'code>,
+    ) -> Option<DiagTooManyDiagnostics<'code>> {
+        if self.dropped_count() == 0 {
+            return None;
+        }
+        let input: &'code [u8] = input.slice();
+        Some(DiagTooManyDiagnostics {
+            where_: SourceCodeSpan::from_slice(&input[input.len()..]),
+            dropped_count: self.dropped_count(),
+        })
+    }
+
+    // Returns true if max_total was hit. If so, every further diagnostic will
+    // be dropped.
+    pub fn is_total_limit_reached(&self) -> bool {
+        match self.limits.max_total {
+            Some(max_total) => unsafe { &*self.state.get() }.total_count >= max_total,
+            None => false,
+        }
+    }
+}
+
+impl<'reporter> DiagReporter for LimitingDiagReporter<'reporter> {
+    fn report_impl(&self, type_: DiagType, diag: *const u8) {
+        if self
+            .category_filter
+            .severity(get_diagnostic_info(type_))
+            .is_none()
+        {
+            self.target.report_impl(type_, diag);
+            return;
+        }
+
+        let is_total_limit_reached: bool = self.is_total_limit_reached();
+        let state: &mut LimitingDiagReporterState = unsafe { &mut *self.state.get() };
+        let count_for_type: &mut usize = &mut state.count_by_type[type_ as usize];
+        let is_code_limit_reached: bool = match self.limits.max_per_code {
+            Some(max_per_code) => *count_for_type >= max_per_code,
+            None => false,
+        };
+
+        if is_total_limit_reached || is_code_limit_reached {
+            state.dropped_count += 1;
+            return;
+        }
+
+        *count_for_type += 1;
+        state.total_count += 1;
+        self.target.report_impl(type_, diag);
+    }
+}
@@ -0,0 +1,221 @@
+use crate::diag_reporter::*;
+use crate::diag_serialization::*;
+use crate::diagnostic_types::*;
+use crate::linter::*;
+use cpp_vs_rust_container::monotonic_allocator::*;
+use cpp_vs_rust_util::fnv::*;
+use cpp_vs_rust_util::padded_string::*;
+use cpp_vs_rust_util::sha256::*;
+
+// Bump this if the cache file format or the meaning of a cache key changes.
+const LINT_CACHE_FORMAT_VERSION: u32 = 5;
+
+// Identifies the diagnostics for one combination of source code, linter
+// options, configuration, and linter version.
+//
+// Different combinations can have the same LintCacheKey. See
+// LintCacheEntryHeader.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct LintCacheKey(pub u64);
+
+impl LintCacheKey {
+    // config is the text of the quick-lint-js.config file which applies to
+    // code, or empty if there is none.
+    pub fn new(code: PaddedStringView<'_>, linter_options: &LinterOptions, config: &[u8]) -> Self {
+        let mut hasher: FNV1a64 = FNV1a64::new();
+        write_key_material(code, linter_options, config, &mut |data: &[u8]| {
+            hasher.update(data)
+        });
+        LintCacheKey(hasher.finish())
+    }
+}
+
+// The start of each cache entry. It records the full key material so that an
+// entry is only used for the combination which created it, even if another
+// combination has the same LintCacheKey.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct LintCacheEntryHeader {
+    pub code_len: u64,
+    // SHA-256 of the code, linter options, configuration, and linter version.
+    pub digest: [u8; SHA256_DIGEST_SIZE],
+}
+
+pub const LINT_CACHE_ENTRY_HEADER_SIZE: usize = 8 + SHA256_DIGEST_SIZE;
+
+impl LintCacheEntryHeader {
+    // See LintCacheKey::new.
+    pub fn new(
+        code: PaddedStringView<'_>,
+        linter_options: &LinterOptions,
+        config: &[u8],
+    ) -> LintCacheEntryHeader {
+        let mut hasher: SHA256 = SHA256::new();
+        write_key_material(code, linter_options, config, &mut |data: &[u8]| {
+            hasher.update(data)
+        });
+        LintCacheEntryHeader {
+            code_len: code.slice().len() as u64,
+            digest: hasher.finish(),
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; LINT_CACHE_ENTRY_HEADER_SIZE] {
+        let mut bytes: [u8; LINT_CACHE_ENTRY_HEADER_SIZE] = [0; LINT_CACHE_ENTRY_HEADER_SIZE];
+        bytes[..8].copy_from_slice(&self.code_len.to_le_bytes());
+        bytes[8..].copy_from_slice(&self.digest);
+        bytes
+    }
+}
+
+// Pass everything which affects diagnostics to update.
+fn write_key_material(
+    code: PaddedStringView<'_>,
+    linter_options: &LinterOptions,
+    config: &[u8],
+    update: &mut dyn FnMut(&[u8]),
+) {
+    update(&LINT_CACHE_FORMAT_VERSION.to_le_bytes());
+    // Diagnostics might change between versions of the linter.
+    update_length_prefixed(update, env!("CARGO_PKG_VERSION").as_bytes());
+    update(&[
+        linter_options.jsx as u8,
+        linter_options.typescript as u8,
+        linter_options.print_parser_visits as u8,
+        linter_options.bail_out_on_binary as u8,
+    ]);
+    // linter_options.cancellation does not affect diagnostics, so it is not
+    // part of the key.
+    update(&(linter_options.globals.len() as u64).to_le_bytes());
+    for global in linter_options.globals.iter() {
+        update_length_prefixed(update, global.name.as_bytes());
+        update(&[global.is_writable as u8, global.is_shadowable as u8]);
+    }
+    update_length_prefixed(update, config);
+    update_length_prefixed(update, code.slice());
+}
+
+// Without length prefixes, ("ab", "c") and ("a", "bc") would collide.
+fn update_length_prefixed(update: &mut dyn FnMut(&[u8]), data: &[u8]) {
+    update(&(data.len() as u64).to_le_bytes());
+    update(data);
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LintCacheOutcome {
+    // Diagnostics were replayed from the cache. The code was not lexed.
+    Hit,
+    // The code was linted, and the diagnostics were stored in the cache.
+    Miss,
+    // The code was partially linted because linter_options.cancellation was
+    // cancelled. Nothing was stored in the cache.
+    Cancelled,
+}
+
+// LintCache remembers the diagnostics reported by parse_and_lint in a
+// directory, one file per LintCacheKey. Each file is a LintCacheEntryHeader
+// followed by serialized diagnostics.
+//
+// The cache is never pruned. Callers may delete the directory at any time.
+pub struct LintCache {
+    directory: std::path::PathBuf,
+}
+
+impl LintCache {
+    // directory is created when the first entry is stored.
+    pub fn new<P: Into<std::path::PathBuf>>(directory: P) -> LintCache {
+        LintCache {
+            directory: directory.into(),
+        }
+    }
+
+    pub fn directory(&self) -> &std::path::Path {
+        &self.directory
+    }
+
+    pub fn entry_path(&self, key: LintCacheKey) -> std::path::PathBuf {
+        self.directory.join(format!("{:016x}.diags", key.0))
+    }
+
+    // Like parse_and_lint, but reuse diagnostics from a previous call with the
+    // same code, linter_options, and config.
+    //
+    // Unreadable or corrupt cache entries are treated as misses and are
+    // overwritten.
+    //
+    // If storing the diagnostics fails, the diagnostics have still been
+    // reported, and the error is returned.
+    pub fn parse_and_lint(
+        &self,
+        code: PaddedStringView<'_>,
+        reporter: &dyn DiagReporter,
+        linter_options: LinterOptions,
+        config: &[u8],
+    ) -> std::io::Result<LintCacheOutcome> {
+        let key: LintCacheKey = LintCacheKey::new(code, &linter_options, config);
+        let header: [u8; LINT_CACHE_ENTRY_HEADER_SIZE] =
+            LintCacheEntryHeader::new(code, &linter_options, config).to_bytes();
+        let path: std::path::PathBuf = self.entry_path(key);
+
+        if let Ok(data) = std::fs::read(&path) {
+            // If the header differs, the entry is for different code (with the
+            // same LintCacheKey), so it is a miss.
+            if let Some(diags_data) = data.strip_prefix(&header[..]) {
+                let memory = MonotonicAllocator::new("LintCache::parse_and_lint");
+                if replay_serialized_diags(diags_data, code, &memory, reporter).is_ok() {
+                    return Ok(LintCacheOutcome::Hit);
+                }
+            }
+        }
+
+        let mut serializer: SerializingDiagReporter = SerializingDiagReporter::new(code);
+        let status: LintStatus = parse_and_lint(
+            code,
+            &TeeDiagReporter {
+                a: &serializer,
+                b: reporter,
+            },
+            linter_options,
+        );
+        if status == LintStatus::Cancelled {
+            return Ok(LintCacheOutcome::Cancelled);
+        }
+        let mut data: Vec<u8> = header.to_vec();
+        data.extend_from_slice(&serializer.take_output());
+        self.store(&path, &data)?;
+        Ok(LintCacheOutcome::Miss)
+    }
+
+    fn store(&self, path: &std::path::Path, data: &[u8]) -> std::io::Result<()> {
+        static TEMP_FILE_COUNTER: std::sync::atomic::AtomicU64 =
+            std::sync::atomic::AtomicU64::new(0);
+
+        std::fs::create_dir_all(&self.directory)?;
+        // Write then rename so concurrent linters never read a partial entry.
+        // The temporary name is unique to this store call, so threads and
+        // processes storing the same entry don't write to the same file.
+        let temp_path: std::path::PathBuf = path.with_extension(format!(
+            "tmp{}_{}",
+            std::process::id(),
+            TEMP_FILE_COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+        ));
+        std::fs::write(&temp_path, data)?;
+        let result: std::io::Result<()> = std::fs::rename(&temp_path, path);
+        if result.is_err() {
+            let _ = std::fs::remove_file(&temp_path);
+        }
+        result
+    }
+}
+
+// Reports each diagnostic to a then to b.
+struct TeeDiagReporter<'a> {
+    a: &'a dyn DiagReporter,
+    b: &'a dyn DiagReporter,
+}
+
+impl<'a> DiagReporter for TeeDiagReporter<'a> {
+    fn report_impl(&self, type_: DiagType, diag: *const u8) {
+        self.a.report_impl(type_, diag);
+        self.b.report_impl(type_, diag);
+    }
+}
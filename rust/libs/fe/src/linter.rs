@@ -1,10 +1,11 @@
+use crate::cancellation::*;
 use crate::diag_reporter::*;
+use crate::global_declared_variable_set::*;
 use crate::lex::*;
 use crate::token::*;
 use cpp_vs_rust_util::padded_string::*;
 
-// TODO(#465): Accept parser options from quick-lint-js.config or CLI options.
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub struct LinterOptions {
     // If true, parse and lint JSX language extensions:
     // https://facebook.github.io/jsx/
@@ -15,6 +16,18 @@ pub struct LinterOptions {
 
     // If true, print a human-readable representation of parser visits to stderr.
     pub print_parser_visits: bool,
+
+    // If true and the input looks like a binary file (see looks_like_binary),
+    // report DiagFileLooksBinary instead of linting the input. See
+    // Lexer::new_bailing_out_on_binary.
+    pub bail_out_on_binary: bool,
+
+    // If set, parse_and_lint stops soon after this token is cancelled.
+    pub cancellation: Option<CancellationToken>,
+
+    // Variables declared by the environment, usually from quick-lint-js.config
+    // (see Configuration::apply_to).
+    pub globals: GlobalDeclaredVariableSet,
 }
 
 impl Default for LinterOptions {
@@ -23,21 +36,81 @@ impl Default for LinterOptions {
             jsx: true,
             typescript: true,
             print_parser_visits: true,
+            bail_out_on_binary: false,
+            cancellation: None,
+            globals: GlobalDeclaredVariableSet::new(),
         }
     }
 }
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LintStatus {
+    // All diagnostics were reported.
+    Completed,
+    // LinterOptions::cancellation was cancelled. Some diagnostics might not
+    // have been reported.
+    Cancelled,
+}
+
+// How many tokens parse_and_lint lexes between checks of
+// LinterOptions::cancellation. Must be a power of two.
+const TOKENS_PER_CANCELLATION_CHECK: usize = 1024;
+
 pub fn parse_and_lint(
     code: PaddedStringView<'_>,
     reporter: &'_ dyn DiagReporter,
-    _linter_options: LinterOptions,
-) {
+    linter_options: LinterOptions,
+) -> LintStatus {
+    let allocator = LexerAllocator::new();
+    parse_and_lint_with_allocator(code, reporter, linter_options, &allocator)
+}
+
+// Like parse_and_lint, but allocate from allocator instead of a new
+// LexerAllocator.
+//
+// Memory allocated while linting is given back to allocator before returning,
+// so one allocator can be reused to lint many files.
+pub fn parse_and_lint_with_allocator(
+    code: PaddedStringView<'_>,
+    reporter: &'_ dyn DiagReporter,
+    linter_options: LinterOptions,
+    allocator: &LexerAllocator,
+) -> LintStatus {
+    let rewind_state: LexerAllocatorRewindState = allocator.prepare_for_rewind();
+    let status: LintStatus = lint_tokens(code, reporter, linter_options, allocator);
+    // lint_tokens's Lexer (and its tokens) are gone, so nothing refers to the
+    // rewound memory.
+    unsafe {
+        allocator.rewind(rewind_state);
+    }
+    status
+}
+
+fn lint_tokens(
+    code: PaddedStringView<'_>,
+    reporter: &'_ dyn DiagReporter,
+    linter_options: LinterOptions,
+    allocator: &LexerAllocator,
+) -> LintStatus {
     // NOTE(port): This is trimmed down because we aren't porting the parser or
     // the variable analyzer. Just lex the whole document. This won't work if
     // there are regexp literals or template literals, but whatever.
-    let allocator = LexerAllocator::new();
-    let mut l: Lexer = Lexer::new(code, reporter, &allocator);
+    let mut l: Lexer = if linter_options.bail_out_on_binary {
+        Lexer::new_bailing_out_on_binary(code, reporter, allocator)
+    } else {
+        Lexer::new(code, reporter, allocator)
+    };
+    let mut token_count: usize = 0;
     while l.peek().type_ != TokenType::EndOfFile {
+        if token_count & (TOKENS_PER_CANCELLATION_CHECK - 1) == 0 {
+            if let Some(cancellation) = &linter_options.cancellation {
+                if cancellation.is_cancelled() {
+                    return LintStatus::Cancelled;
+                }
+            }
+        }
+        token_count += 1;
         l.skip();
     }
+    LintStatus::Completed
 }
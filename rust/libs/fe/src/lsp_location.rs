@@ -0,0 +1,331 @@
+use crate::document::*;
+use crate::source_code_span::*;
+use cpp_vs_rust_util::narrow_cast::*;
+use cpp_vs_rust_util::padded_string::*;
+use cpp_vs_rust_util::utf_8::*;
+
+pub type LSPOffset = usize;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct LSPPosition {
+    // 0-based.
+    pub line: u32,
+    // 0-based. Counts code units of the locator's position encoding (UTF-16
+    // by default).
+    pub character: u32,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct LSPRange {
+    pub start: LSPPosition,
+    pub end: LSPPosition,
+}
+
+impl SourceRangeLike for LSPRange {
+    type PositionType = LSPPosition;
+
+    fn new(begin: LSPPosition, end: LSPPosition) -> LSPRange {
+        LSPRange {
+            start: begin,
+            end: end,
+        }
+    }
+
+    fn begin(&self) -> LSPPosition {
+        self.start
+    }
+
+    fn end(&self) -> LSPPosition {
+        self.end
+    }
+}
+
+// Converts between pointers into the input and LSP positions
+// ({line, character}).
+//
+// Lines are terminated by CR, LF, CRLF, LS (U+2028), or PS (U+2029).
+//
+// LSPLocator caches the beginning of each line, so converting a position takes
+// O(log(line count) + line length) time. replace_text updates the cache
+// incrementally.
+//
+// By default, characters are UTF-16 code units. Clients may negotiate a
+// different encoding with the positionEncoding capability.
+pub struct LSPLocator<'code> {
+    input: PaddedStringView<'code>,
+    encoding: PositionEncoding,
+    // offset_of_lines[i] is the byte offset of the beginning of line i.
+    offset_of_lines: Vec<LSPOffset>,
+    // line_is_ascii[i] is true if line i (including its terminator) contains
+    // only ASCII bytes. For such lines, characters are bytes.
+    line_is_ascii: Vec<bool>,
+}
+
+impl<'code> LSPLocator<'code> {
+    pub fn new(input: PaddedStringView<'code>) -> LSPLocator<'code> {
+        LSPLocator::new_with_encoding(input, PositionEncoding::UTF16)
+    }
+
+    pub fn new_with_encoding(
+        input: PaddedStringView<'code>,
+        encoding: PositionEncoding,
+    ) -> LSPLocator<'code> {
+        let mut locator = LSPLocator {
+            input: input,
+            encoding: encoding,
+            offset_of_lines: vec![0],
+            line_is_ascii: vec![],
+        };
+        locator.cache_offsets_of_lines(
+            /*rescan_line=*/ 0, /*first_old_line_after_end=*/ 1,
+            /*old_end_offset=*/ 0, /*new_end_offset=*/ 0,
+        );
+        locator
+    }
+
+    pub fn position_encoding(&self) -> PositionEncoding {
+        self.encoding
+    }
+
+    // Line information does not depend on the encoding, so changing the
+    // encoding is cheap.
+    pub fn set_position_encoding(&mut self, encoding: PositionEncoding) {
+        self.encoding = encoding;
+    }
+
+    pub fn range(&self, span: SourceCodeSpan<'_>) -> LSPRange {
+        LSPRange {
+            start: self.position(span.begin_ptr()),
+            end: self.position(span.end_ptr()),
+        }
+    }
+
+    pub fn position(&self, c: *const u8) -> LSPPosition {
+        let offset: LSPOffset =
+            narrow_cast::<LSPOffset, _>(unsafe { c.offset_from(self.input.c_str()) });
+        let line_index: usize = self.find_line_at_offset(offset);
+        let line_begin_offset: LSPOffset = self.offset_of_lines[line_index];
+        let character: usize = if self.line_is_ascii[line_index] {
+            offset - line_begin_offset
+        } else {
+            count_characters_in_utf_8(
+                self.input
+                    .substr(narrow_cast::<PaddedStringSizeType, _>(line_begin_offset)),
+                offset - line_begin_offset,
+                self.encoding,
+            )
+        };
+        LSPPosition {
+            line: narrow_cast::<u32, _>(line_index),
+            character: narrow_cast::<u32, _>(character),
+        }
+    }
+
+    // Returns a pointer into the input.
+    //
+    // If position.line is past the last line, returns a pointer to the end of
+    // the input. If position.character is past the end of the line, returns a
+    // pointer to the line's terminator (or the end of the input for the last
+    // line). With the UTF-16 encoding, if position.character is in the middle
+    // of a surrogate pair, returns a pointer to the beginning of the pair's
+    // UTF-8 sequence.
+    pub fn pointer_at_position(&self, position: LSPPosition) -> *const u8 {
+        let line_index: usize = position.line as usize;
+        if line_index >= self.offset_of_lines.len() {
+            return self.input.null_terminator();
+        }
+        let line_begin_offset: LSPOffset = self.offset_of_lines[line_index];
+        let line_end_offset: LSPOffset = self.line_end_offset_excluding_terminator(line_index);
+        let line: PaddedStringView<'code> = unsafe {
+            PaddedStringView::from_begin_end(
+                self.input.c_str().add(line_begin_offset),
+                self.input.c_str().add(line_end_offset),
+            )
+        };
+        let offset_in_line: usize = if self.line_is_ascii[line_index] {
+            std::cmp::min(position.character as usize, line.len() as usize)
+        } else {
+            narrow_cast::<usize, _>(advance_characters_in_utf_8(
+                line,
+                position.character as usize,
+                self.encoding,
+            ))
+        };
+        unsafe { line.c_str().add(offset_in_line) }
+    }
+
+    // Returns the byte offset of the beginning of each line.
+    pub fn offset_of_lines(&self) -> &[LSPOffset] {
+        &self.offset_of_lines
+    }
+
+    pub fn replace_text(
+        &mut self,
+        range: LSPRange,
+        replacement_text: &[u8],
+        new_input: PaddedStringView<'code>,
+    ) {
+        let old_begin: *const u8 = self.input.c_str();
+        let start_offset: LSPOffset = narrow_cast::<LSPOffset, _>(unsafe {
+            self.pointer_at_position(range.start).offset_from(old_begin)
+        });
+        let end_offset: LSPOffset = narrow_cast::<LSPOffset, _>(unsafe {
+            self.pointer_at_position(range.end).offset_from(old_begin)
+        });
+        let new_end_offset: LSPOffset = start_offset + replacement_text.len();
+
+        // Lines beginning after the replaced text keep their text, so they
+        // might be reused after shifting.
+        let first_old_line_after_end: usize = self
+            .offset_of_lines
+            .partition_point(|line_offset: &LSPOffset| *line_offset <= end_offset);
+
+        // Rescan from the line containing start_offset. If the replacement
+        // begins at the beginning of a line, rescan the previous line too,
+        // because replacement_text might turn the previous line's CR into
+        // CRLF.
+        let mut rescan_line: usize = self.find_line_at_offset(start_offset);
+        if rescan_line > 0 && self.offset_of_lines[rescan_line] == start_offset {
+            rescan_line -= 1;
+        }
+
+        self.input = new_input;
+        self.cache_offsets_of_lines(
+            rescan_line,
+            first_old_line_after_end,
+            end_offset,
+            new_end_offset,
+        );
+    }
+
+    // Recompute line information for lines starting at line rescan_line.
+    //
+    // Lines from first_old_line_after_end onwards are lines of the old input
+    // which began after the replaced text (which ended at old_end_offset). If
+    // a line begins after new_end_offset at the same text as one of these old
+    // lines, then that old line and the lines after it are kept (with their
+    // offsets shifted) instead of being scanned.
+    fn cache_offsets_of_lines(
+        &mut self,
+        rescan_line: usize,
+        first_old_line_after_end: usize,
+        old_end_offset: LSPOffset,
+        new_end_offset: LSPOffset,
+    ) {
+        // Offsets of the rescanned lines after line rescan_line.
+        let mut new_offsets: Vec<LSPOffset> = vec![];
+        // Whether each rescanned line, including line rescan_line, is ASCII.
+        let mut new_line_is_ascii: Vec<bool> = vec![];
+
+        let input: &[u8] = self.input.slice_with_padding();
+        let input_len: usize = narrow_cast::<usize, _>(self.input.len());
+        let mut i: usize = self.offset_of_lines[rescan_line];
+        let mut is_ascii: bool = true;
+        while i < input_len {
+            let terminator_len: usize = match input[i] {
+                b'\r' if input[i + 1] == b'\n' => 2,
+                b'\n' | b'\r' => 1,
+                // U+2028 Line Separator or U+2029 Paragraph Separator.
+                0xe2 if input[i + 1] == 0x80 && (input[i + 2] == 0xa8 || input[i + 2] == 0xa9) => 3,
+                _ => 0,
+            };
+            if terminator_len == 0 {
+                if input[i] >= 0x80 {
+                    is_ascii = false;
+                }
+                i += 1;
+                continue;
+            }
+            if input[i] >= 0x80 {
+                is_ascii = false;
+            }
+            i += terminator_len;
+            new_line_is_ascii.push(is_ascii);
+            is_ascii = true;
+
+            if i > new_end_offset {
+                // Text from i onwards is unchanged. If an old line began at
+                // the same text, the old and new lines from here on are the
+                // same.
+                let old_offset: LSPOffset = i - new_end_offset + old_end_offset;
+                if let Ok(old_line_index) =
+                    self.offset_of_lines[first_old_line_after_end..].binary_search(&old_offset)
+                {
+                    let reused_line: usize = first_old_line_after_end + old_line_index;
+                    let first_shifted_line: usize = rescan_line + 1 + new_offsets.len();
+                    self.offset_of_lines
+                        .splice((rescan_line + 1)..reused_line, new_offsets);
+                    self.line_is_ascii
+                        .splice(rescan_line..reused_line, new_line_is_ascii);
+                    for line_offset in &mut self.offset_of_lines[first_shifted_line..] {
+                        *line_offset = *line_offset - old_end_offset + new_end_offset;
+                    }
+                    return;
+                }
+            }
+            new_offsets.push(i);
+        }
+        new_line_is_ascii.push(is_ascii);
+
+        self.offset_of_lines.truncate(rescan_line + 1);
+        self.offset_of_lines.extend_from_slice(&new_offsets);
+        self.line_is_ascii.truncate(rescan_line);
+        self.line_is_ascii.extend_from_slice(&new_line_is_ascii);
+    }
+
+    fn find_line_at_offset(&self, offset: LSPOffset) -> usize {
+        self.offset_of_lines
+            .partition_point(|line_offset: &LSPOffset| *line_offset <= offset)
+            - 1
+    }
+
+    fn line_end_offset_excluding_terminator(&self, line_index: usize) -> LSPOffset {
+        let input: &[u8] = self.input.slice();
+        match self.offset_of_lines.get(line_index + 1) {
+            Some(next_line_offset) => {
+                let line: &[u8] = &input[self.offset_of_lines[line_index]..*next_line_offset];
+                let terminator_len: usize = if line.ends_with(b"\r\n") {
+                    2
+                } else if line.ends_with("\u{2028}".as_bytes())
+                    || line.ends_with("\u{2029}".as_bytes())
+                {
+                    3
+                } else {
+                    1
+                };
+                next_line_offset - terminator_len
+            }
+            None => input.len(),
+        }
+    }
+}
+
+impl<'code> LocatorLike<'code> for LSPLocator<'code> {
+    type PositionType = LSPPosition;
+    type RangeType = LSPRange;
+
+    fn new(s: PaddedStringView<'code>) -> Self {
+        LSPLocator::new(s)
+    }
+
+    fn range(&self, s: SourceCodeSpan<'_>) -> Self::RangeType {
+        self.range(s)
+    }
+
+    fn pointer_at_position(&self, position: LSPPosition) -> *const u8 {
+        self.pointer_at_position(position)
+    }
+
+    fn set_input(&mut self, new_input: PaddedStringView<'code>) {
+        *self = LSPLocator::new_with_encoding(new_input, self.encoding);
+    }
+
+    fn replace_text(
+        &mut self,
+        range: LSPRange,
+        replacement_text: &[u8],
+        new_input: PaddedStringView<'code>,
+    ) {
+        self.replace_text(range, replacement_text, new_input)
+    }
+}
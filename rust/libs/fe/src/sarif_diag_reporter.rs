@@ -0,0 +1,248 @@
+use crate::cli_location::*;
+use crate::diag_reporter::*;
+use crate::diagnostic::*;
+use crate::diagnostic_formatter::*;
+use crate::diagnostic_types::*;
+use crate::source_code_span::*;
+use cpp_vs_rust_i18n::translation::*;
+use cpp_vs_rust_util::json::*;
+use cpp_vs_rust_util::padded_string::*;
+
+// Collects diagnostics from one or more files into a SARIF 2.1.0 log with a
+// single run.
+//
+// https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html
+pub struct SarifDiagReporter<'code> {
+    translator: Translator,
+    // Comma-separated result objects.
+    results: std::cell::RefCell<Vec<u8>>,
+    locator: Option<CLILocator<'code>>,
+    file_uri: String,
+}
+
+impl<'code> SarifDiagReporter<'code> {
+    pub fn new(translator: Translator) -> SarifDiagReporter<'code> {
+        SarifDiagReporter {
+            translator: translator,
+            results: std::cell::RefCell::new(vec![]),
+            locator: None,
+            file_uri: String::new(),
+        }
+    }
+
+    // Diagnostics reported after calling set_source refer to file_path.
+    pub fn set_source(&mut self, input: PaddedStringView<'code>, file_path: &str) {
+        self.locator = Some(CLILocator::new(input));
+        self.file_uri = path_to_uri_reference(file_path);
+    }
+
+    // Returns the SARIF log containing every diagnostic reported so far.
+    pub fn finish(&mut self) -> Vec<u8> {
+        sarif_log_with_results(&self.results.borrow())
+    }
+
+    // Returns the comma-separated result objects reported since the last call
+    // to take_results. Combine results from several reporters with
+    // sarif_log_with_results.
+    pub fn take_results(&mut self) -> Vec<u8> {
+        std::mem::take(self.results.get_mut())
+    }
+}
+
+// Returns a SARIF log with a single run containing the given comma-separated
+// result objects.
+pub fn sarif_log_with_results(results: &[u8]) -> Vec<u8> {
+    let mut out: Vec<u8> = vec![];
+    out.extend_from_slice(
+        b"{\"$schema\":\"https://json.schemastore.org/sarif-2.1.0.json\",\
+          \"version\":\"2.1.0\",\
+          \"runs\":[{\
+          \"tool\":{\"driver\":{\
+          \"name\":\"quick-lint-js\",\
+          \"informationUri\":\"https://quick-lint-js.com/\",\
+          \"rules\":",
+    );
+    write_sarif_rules(&mut out);
+    out.extend_from_slice(b"}},\"columnKind\":\"unicodeCodePoints\",\"results\":[");
+    out.extend_from_slice(results);
+    out.extend_from_slice(b"]}]}\n");
+    out
+}
+
+impl<'code> DiagReporter for SarifDiagReporter<'code> {
+    fn report_impl(&self, type_: DiagType, diag: *const u8) {
+        let mut formatter = SarifDiagFormatter::new(self);
+        formatter.format(get_diagnostic_info(type_), diag);
+        formatter.write_result(type_);
+    }
+}
+
+struct SarifMessage {
+    severity: DiagnosticSeverity,
+    text: Vec<u8>,
+    range: CLISourceRange,
+}
+
+pub struct SarifDiagFormatter<'code, 'reporter> {
+    reporter: &'reporter SarifDiagReporter<'code>,
+    // The first message is the diagnostic's main message. Following messages
+    // are notes.
+    messages: Vec<SarifMessage>,
+}
+
+impl<'code, 'reporter> SarifDiagFormatter<'code, 'reporter> {
+    pub fn new(reporter: &'reporter SarifDiagReporter<'code>) -> Self {
+        SarifDiagFormatter {
+            reporter: reporter,
+            messages: vec![],
+        }
+    }
+
+    // Write a result object for the messages formatted so far.
+    pub fn write_result(&mut self, type_: DiagType) {
+        let Some((main_message, notes)) = self.messages.split_first() else {
+            return;
+        };
+
+        let mut results = self.reporter.results.borrow_mut();
+        if !results.is_empty() {
+            results.push(b',');
+        }
+        let out: &mut Vec<u8> = &mut results;
+        let code_string: [u8; 5] = get_diagnostic_info(type_).code_string();
+        out.extend_from_slice(b"{\"ruleId\":");
+        write_json_escaped_string(out, &code_string);
+        out.extend_from_slice(format!(",\"ruleIndex\":{}", type_ as usize).as_bytes());
+        out.extend_from_slice(b",\"level\":");
+        write_json_escaped_string(out, sarif_level(main_message.severity).as_bytes());
+        out.extend_from_slice(b",\"message\":{\"text\":");
+        write_json_escaped_string(out, &main_message.text);
+        out.extend_from_slice(b"},\"locations\":[{");
+        self.write_physical_location(out, main_message.range);
+        out.extend_from_slice(b"}]");
+        if !notes.is_empty() {
+            out.extend_from_slice(b",\"relatedLocations\":[");
+            for (i, note) in notes.iter().enumerate() {
+                if i != 0 {
+                    out.push(b',');
+                }
+                out.extend_from_slice(format!("{{\"id\":{},", i).as_bytes());
+                self.write_physical_location(out, note.range);
+                out.extend_from_slice(b",\"message\":{\"text\":");
+                write_json_escaped_string(out, &note.text);
+                out.extend_from_slice(b"}}");
+            }
+            out.push(b']');
+        }
+        out.push(b'}');
+        self.messages.clear();
+    }
+
+    fn write_physical_location(&self, out: &mut Vec<u8>, range: CLISourceRange) {
+        out.extend_from_slice(b"\"physicalLocation\":{\"artifactLocation\":{\"uri\":");
+        write_json_escaped_string(out, self.reporter.file_uri.as_bytes());
+        out.extend_from_slice(
+            format!(
+                "}},\"region\":{{\"startLine\":{},\"startColumn\":{},\"endLine\":{},\"endColumn\":{}}}}}",
+                range.begin.line_number,
+                range.begin.column_number,
+                range.end.line_number,
+                range.end.column_number,
+            )
+            .as_bytes(),
+        );
+    }
+}
+
+impl<'code, 'reporter> DiagnosticFormatter for SarifDiagFormatter<'code, 'reporter> {
+    fn write_before_message(
+        &mut self,
+        _code: &str,
+        severity: DiagnosticSeverity,
+        origin: SourceCodeSpan<'_>,
+    ) {
+        let range: CLISourceRange = self
+            .reporter
+            .locator
+            .as_ref()
+            .expect("SarifDiagReporter::set_source should have been called")
+            .range(origin);
+        self.messages.push(SarifMessage {
+            severity: severity,
+            text: vec![],
+            range: range,
+        });
+    }
+
+    fn write_message_part(
+        &mut self,
+        _code: &str,
+        _severity: DiagnosticSeverity,
+        message_part: &[u8],
+    ) {
+        self.messages
+            .last_mut()
+            .unwrap()
+            .text
+            .extend_from_slice(message_part);
+    }
+
+    fn write_after_message(
+        &mut self,
+        _code: &str,
+        _severity: DiagnosticSeverity,
+        _origin: SourceCodeSpan<'_>,
+    ) {
+    }
+
+    fn translator(&self) -> Translator {
+        self.reporter.translator.clone()
+    }
+}
+
+// Write a reportingDescriptor for every diagnostic type, in DiagType order.
+fn write_sarif_rules(out: &mut Vec<u8>) {
+    out.push(b'[');
+    for (i, (_type, info)) in all_diagnostic_infos().enumerate() {
+        if i != 0 {
+            out.push(b',');
+        }
+        let code_string: [u8; 5] = info.code_string();
+        let code_string_view: &str = unsafe { std::str::from_utf8_unchecked(&code_string) };
+        out.extend_from_slice(b"{\"id\":");
+        write_json_escaped_string(out, &code_string);
+        out.extend_from_slice(b",\"shortDescription\":{\"text\":");
+        write_json_escaped_string(out, info.messages()[0].untranslated_format().as_bytes());
+        out.extend_from_slice(b"},\"helpUri\":");
+        write_json_escaped_string(
+            out,
+            format!("https://quick-lint-js.com/errors/{code_string_view}/").as_bytes(),
+        );
+        out.extend_from_slice(b",\"defaultConfiguration\":{\"level\":");
+        write_json_escaped_string(out, sarif_level(info.severity()).as_bytes());
+        out.extend_from_slice(b"}}");
+    }
+    out.push(b']');
+}
+
+fn sarif_level(severity: DiagnosticSeverity) -> &'static str {
+    match severity {
+        DiagnosticSeverity::Error => "error",
+        DiagnosticSeverity::Note => "note",
+        DiagnosticSeverity::Warning => "warning",
+    }
+}
+
+// Percent-encode bytes which may not appear in a URI reference.
+fn path_to_uri_reference(path: &str) -> String {
+    let mut uri = String::new();
+    for c in path.bytes() {
+        match c {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                uri.push(c as char);
+            }
+            _ => uri.push_str(&format!("%{:02X}", c)),
+        }
+    }
+    uri
+}
@@ -0,0 +1,116 @@
+use crate::buffering_diag_reporter::*;
+use crate::diag_reporter::*;
+use crate::diagnostic::*;
+use crate::diagnostic_types::*;
+use crate::source_code_span::*;
+
+// SortingDiagReporter buffers diagnostics, then reports them in source order.
+//
+// Diagnostics are sorted by the beginning of their main message's location,
+// then by severity (errors first), then by error code. Diagnostics which
+// compare equal keep the order in which they were reported.
+//
+// If the same diagnostic (same type and same source locations) is reported
+// more than once, for example because a transaction was rolled back and
+// re-lexed, then only the first is kept.
+pub struct SortingDiagReporter<'code> {
+    diagnostics: std::cell::UnsafeCell<Vec<StoredDiag<'code>>>,
+}
+
+impl<'code> SortingDiagReporter<'code> {
+    pub fn new() -> SortingDiagReporter<'code> {
+        SortingDiagReporter {
+            diagnostics: std::cell::UnsafeCell::new(vec![]),
+        }
+    }
+
+    // Report the buffered diagnostics to other in sorted order, then forget
+    // them.
+    pub fn move_into(&mut self, other: &dyn DiagReporter) {
+        let diagnostics: &mut Vec<StoredDiag<'code>> = self.diagnostics.get_mut();
+        diagnostics.sort_by_key(|diag: &StoredDiag| sort_key(diag));
+
+        let mut kept: Vec<&StoredDiag<'code>> = Vec::with_capacity(diagnostics.len());
+        // Index into kept of the first diagnostic with the same sort key as the
+        // current diagnostic. Duplicates have the same sort key, so only
+        // kept[run_begin..] needs to be searched for duplicates.
+        let mut run_begin: usize = 0;
+        for diag in diagnostics.iter() {
+            if let Some(previous) = kept.last() {
+                if sort_key(previous) != sort_key(diag) {
+                    run_begin = kept.len();
+                }
+            }
+            let is_duplicate: bool = kept[run_begin..]
+                .iter()
+                .any(|other_diag: &&StoredDiag| is_same_diag(other_diag, diag));
+            if !is_duplicate {
+                kept.push(diag);
+            }
+        }
+
+        for diag in kept {
+            // TODO(strager): Make report_impl accept a const pointer to reduce casting.
+            other.report_impl(diag.type_, diag.data() as *mut u8);
+        }
+        diagnostics.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        unsafe { &*self.diagnostics.get() }.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.diagnostics.get_mut().clear();
+    }
+}
+
+impl<'code> Default for SortingDiagReporter<'code> {
+    fn default() -> Self {
+        SortingDiagReporter::new()
+    }
+}
+
+impl<'code> DiagReporter for SortingDiagReporter<'code> {
+    fn report_impl(&self, type_: DiagType, diag: *const u8) {
+        unsafe { &mut *self.diagnostics.get() }.push(unsafe { StoredDiag::new(type_, diag) });
+    }
+}
+
+// (begin, severity rank, code)
+type SortKey = (usize, u8, u16);
+
+fn sort_key(diag: &StoredDiag) -> SortKey {
+    let info: &DiagnosticInfo = get_diagnostic_info(diag.type_);
+    let origin: Option<SourceCodeSpan> =
+        unsafe { info.messages()[0].args[0].get_span(diag.data()) };
+    let begin: usize = match origin {
+        Some(span) => span.begin_ptr() as usize,
+        None => 0,
+    };
+    let severity_rank: u8 = match info.severity() {
+        DiagnosticSeverity::Error => 0,
+        DiagnosticSeverity::Warning => 1,
+        DiagnosticSeverity::Note => 2,
+    };
+    (begin, severity_rank, info.code())
+}
+
+// Returns true if a and b have the same type and their source locations are
+// the same.
+fn is_same_diag(a: &StoredDiag, b: &StoredDiag) -> bool {
+    if a.type_ != b.type_ {
+        return false;
+    }
+    let info: &DiagnosticInfo = get_diagnostic_info(a.type_);
+    info.messages()
+        .iter()
+        .flat_map(|message: &DiagnosticMessageInfo| message.args.iter())
+        .all(|arg: &DiagnosticMessageArgInfo| unsafe {
+            match (arg.get_span(a.data()), arg.get_span(b.data())) {
+                (Some(a_span), Some(b_span)) => same_pointers(a_span, b_span),
+                (None, None) => true,
+                _ => false,
+            }
+        })
+}
@@ -10,6 +10,13 @@ pub struct SourceCodeSpan<'code> {
     phantom: std::marker::PhantomData<&'code u8>,
 }
 
+// SAFETY: begin and end point into source code borrowed for 'code.
+// SourceCodeSpan never writes through them.
+unsafe impl<'code> Send for SourceCodeSpan<'code> {}
+// SAFETY: SourceCodeSpan is an immutable pair of pointers; &SourceCodeSpan
+// allows nothing but reading the pointers themselves.
+unsafe impl<'code> Sync for SourceCodeSpan<'code> {}
+
 impl<'code> SourceCodeSpan<'code> {
     // A source_code_span with no contained characters.
     // TODO(port-later): Is this interface sane?
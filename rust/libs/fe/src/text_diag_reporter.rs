@@ -0,0 +1,208 @@
+use crate::cli_location::*;
+use crate::diag_reporter::*;
+use crate::diagnostic::*;
+use crate::diagnostic_formatter::*;
+use crate::diagnostic_types::*;
+use crate::source_code_span::*;
+use cpp_vs_rust_i18n::translation::*;
+use cpp_vs_rust_util::padded_string::*;
+
+const ANSI_RESET: &[u8] = b"\x1b[0m";
+const ANSI_BOLD: &[u8] = b"\x1b[1m";
+const ANSI_BOLD_RED: &[u8] = b"\x1b[1;31m";
+const ANSI_BOLD_GREEN: &[u8] = b"\x1b[1;32m";
+const ANSI_BOLD_MAGENTA: &[u8] = b"\x1b[1;35m";
+const ANSI_BOLD_CYAN: &[u8] = b"\x1b[1;36m";
+
+// Writes diagnostics in a format similar to GCC's:
+//
+//   hello.js:1:9: error: unclosed string literal [E0040]
+//   let x = 'hello
+//           ^~~~~~
+//
+// Notes are written after their diagnostic, indented by two spaces and without
+// the error code.
+pub struct TextDiagReporter<'code> {
+    translator: Translator,
+    output: std::cell::RefCell<Vec<u8>>,
+    locator: Option<CLILocator<'code>>,
+    file_path: String,
+    colors: bool,
+}
+
+impl<'code> TextDiagReporter<'code> {
+    pub fn new(translator: Translator) -> TextDiagReporter<'code> {
+        TextDiagReporter {
+            translator: translator,
+            output: std::cell::RefCell::new(vec![]),
+            locator: None,
+            file_path: String::new(),
+            colors: false,
+        }
+    }
+
+    pub fn set_source(&mut self, input: PaddedStringView<'code>, file_path: &str) {
+        self.locator = Some(CLILocator::new(input));
+        self.file_path = String::from(file_path);
+    }
+
+    // If true, use ANSI escape sequences to colorize the output.
+    pub fn set_colors(&mut self, colors: bool) {
+        self.colors = colors;
+    }
+
+    // Returns everything written since the last call to take_output.
+    pub fn take_output(&mut self) -> Vec<u8> {
+        std::mem::take(self.output.get_mut())
+    }
+}
+
+impl<'code> DiagReporter for TextDiagReporter<'code> {
+    fn report_impl(&self, type_: DiagType, diag: *const u8) {
+        let mut formatter = TextDiagFormatter::new(self);
+        formatter.format(get_diagnostic_info(type_), diag);
+    }
+}
+
+pub struct TextDiagFormatter<'code, 'reporter> {
+    reporter: &'reporter TextDiagReporter<'code>,
+}
+
+impl<'code, 'reporter> TextDiagFormatter<'code, 'reporter> {
+    pub fn new(reporter: &'reporter TextDiagReporter<'code>) -> Self {
+        TextDiagFormatter { reporter: reporter }
+    }
+
+    fn locator(&self) -> &'reporter CLILocator<'code> {
+        self.reporter
+            .locator
+            .as_ref()
+            .expect("TextDiagReporter::set_source should have been called")
+    }
+
+    fn write_colored(&self, out: &mut Vec<u8>, color: &[u8], text: &[u8]) {
+        if self.reporter.colors {
+            out.extend_from_slice(color);
+            out.extend_from_slice(text);
+            out.extend_from_slice(ANSI_RESET);
+        } else {
+            out.extend_from_slice(text);
+        }
+    }
+
+    fn write_indentation(&self, out: &mut Vec<u8>, severity: DiagnosticSeverity) {
+        if severity == DiagnosticSeverity::Note {
+            out.extend_from_slice(b"  ");
+        }
+    }
+
+    // Write the line containing origin, then a line with a caret underlining
+    // origin.
+    fn write_snippet(
+        &self,
+        out: &mut Vec<u8>,
+        severity: DiagnosticSeverity,
+        origin: SourceCodeSpan<'_>,
+    ) {
+        let locator: &CLILocator = self.locator();
+        let line_number: CLILineNumber = locator.position(origin.begin_ptr()).line_number;
+        let line: &[u8] = locator.line_text(line_number);
+        // line excludes its terminator, but origin might begin on the
+        // terminator (such as the '\n' in a CRLF).
+        let origin_begin_in_line: usize = std::cmp::min(
+            unsafe { origin.begin_ptr().offset_from(line.as_ptr()) } as usize,
+            line.len(),
+        );
+        let origin_end_in_line: usize = std::cmp::min(
+            unsafe { origin.end_ptr().offset_from(line.as_ptr()) } as usize,
+            line.len(),
+        );
+
+        self.write_indentation(out, severity);
+        out.extend_from_slice(line);
+        out.push(b'\n');
+
+        self.write_indentation(out, severity);
+        for c in &line[..origin_begin_in_line] {
+            match *c {
+                b'\t' => out.push(b'\t'),
+                c if is_utf_8_continuation_byte(c) => {}
+                _ => out.push(b' '),
+            }
+        }
+        let mut underline: Vec<u8> = vec![b'^'];
+        let origin_character_count: usize = line
+            [origin_begin_in_line..std::cmp::max(origin_begin_in_line, origin_end_in_line)]
+            .iter()
+            .filter(|c: &&u8| !is_utf_8_continuation_byte(**c))
+            .count();
+        underline.resize(std::cmp::max(origin_character_count, 1), b'~');
+        self.write_colored(out, ANSI_BOLD_GREEN, &underline);
+        out.push(b'\n');
+    }
+}
+
+impl<'code, 'reporter> DiagnosticFormatter for TextDiagFormatter<'code, 'reporter> {
+    fn write_before_message(
+        &mut self,
+        _code: &str,
+        severity: DiagnosticSeverity,
+        origin: SourceCodeSpan<'_>,
+    ) {
+        let r: CLISourceRange = self.locator().range(origin);
+        let mut out = self.reporter.output.borrow_mut();
+        self.write_indentation(&mut out, severity);
+        let location: String = format!(
+            "{}:{}:{}:",
+            self.reporter.file_path, r.begin.line_number, r.begin.column_number
+        );
+        self.write_colored(&mut out, ANSI_BOLD, location.as_bytes());
+        out.push(b' ');
+        match severity {
+            DiagnosticSeverity::Error => self.write_colored(&mut out, ANSI_BOLD_RED, b"error:"),
+            DiagnosticSeverity::Note => self.write_colored(&mut out, ANSI_BOLD_CYAN, b"note:"),
+            DiagnosticSeverity::Warning => {
+                self.write_colored(&mut out, ANSI_BOLD_MAGENTA, b"warning:")
+            }
+        }
+        out.push(b' ');
+    }
+
+    fn write_message_part(
+        &mut self,
+        _code: &str,
+        _severity: DiagnosticSeverity,
+        message_part: &[u8],
+    ) {
+        self.reporter
+            .output
+            .borrow_mut()
+            .extend_from_slice(message_part);
+    }
+
+    fn write_after_message(
+        &mut self,
+        code: &str,
+        severity: DiagnosticSeverity,
+        origin: SourceCodeSpan<'_>,
+    ) {
+        let mut out = self.reporter.output.borrow_mut();
+        // The code identifies the diagnostic, so only write it once, on the
+        // error or warning line.
+        if severity != DiagnosticSeverity::Note {
+            out.extend_from_slice(b" [");
+            out.extend_from_slice(code.as_bytes());
+            out.extend_from_slice(b"]");
+        }
+        out.push(b'\n');
+        self.write_snippet(&mut out, severity, origin);
+    }
+
+    fn translator(&self) -> Translator {
+        self.reporter.translator.clone()
+    }
+}
+
+fn is_utf_8_continuation_byte(c: u8) -> bool {
+    (c & 0xc0) == 0x80
+}
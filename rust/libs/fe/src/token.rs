@@ -6,6 +6,7 @@ use crate::source_code_span::*;
 use cpp_vs_rust_container::monotonic_allocator::*;
 use cpp_vs_rust_container::vector::*;
 use cpp_vs_rust_util::qljs_assert;
+use cpp_vs_rust_util::qljs_impl_try_from_u8;
 
 #[macro_export]
 macro_rules! qljs_case_reserved_keyword_except_await_and_function_and_yield {
@@ -426,6 +427,155 @@ pub enum TokenType {
     StarStarEqual,              // **=
 }
 
+qljs_impl_try_from_u8!(TokenType {
+    Ampersand,
+    Bang,
+    Circumflex,
+    Colon,
+    Comma,
+    Slash,
+    Dot,
+    Equal,
+    Greater,
+    LeftCurly,
+    LeftParen,
+    LeftSquare,
+    Less,
+    Minus,
+    Percent,
+    Pipe,
+    Plus,
+    Question,
+    RightCurly,
+    RightParen,
+    RightSquare,
+    Semicolon,
+    Star,
+    Tilde,
+    CompleteTemplate,
+    EndOfFile,
+    Identifier,
+    IncompleteTemplate,
+    Number,
+    PrivateIdentifier,
+    Regexp,
+    String,
+    ReservedKeywordWithEscapeSequence,
+    KWAs,
+    KWAsync,
+    KWAwait,
+    KWBreak,
+    KWCase,
+    KWCatch,
+    KWClass,
+    KWConst,
+    KWContinue,
+    KWDebugger,
+    KWDefault,
+    KWDelete,
+    KWDo,
+    KWElse,
+    KWEnum,
+    KWExport,
+    KWExtends,
+    KWFalse,
+    KWFinally,
+    KWFor,
+    KWFrom,
+    KWFunction,
+    KWGet,
+    KWIf,
+    KWImplements,
+    KWImport,
+    KWIn,
+    KWInstanceof,
+    KWInterface,
+    KWLet,
+    KWNew,
+    KWNull,
+    KWOf,
+    KWPackage,
+    KWPrivate,
+    KWProtected,
+    KWPublic,
+    KWReturn,
+    KWSet,
+    KWStatic,
+    KWSuper,
+    KWSwitch,
+    KWThis,
+    KWThrow,
+    KWTrue,
+    KWTry,
+    KWTypeof,
+    KWVar,
+    KWVoid,
+    KWWhile,
+    KWWith,
+    KWYield,
+    KWAbstract,
+    KWAny,
+    KWAssert,
+    KWAsserts,
+    KWBigint,
+    KWBoolean,
+    KWConstructor,
+    KWDeclare,
+    KWGlobal,
+    KWInfer,
+    KWIntrinsic,
+    KWIs,
+    KWKeyof,
+    KWModule,
+    KWNamespace,
+    KWNever,
+    KWNumber,
+    KWObject,
+    KWOut,
+    KWOverride,
+    KWReadonly,
+    KWRequire,
+    KWString,
+    KWSymbol,
+    KWType,
+    KWUndefined,
+    KWUnique,
+    KWUnknown,
+    AmpersandAmpersand,
+    AmpersandAmpersandEqual,
+    AmpersandEqual,
+    BangEqual,
+    BangEqualEqual,
+    CircumflexEqual,
+    DotDotDot,
+    EqualEqual,
+    EqualEqualEqual,
+    EqualGreater,
+    GreaterEqual,
+    GreaterGreater,
+    GreaterGreaterEqual,
+    GreaterGreaterGreater,
+    GreaterGreaterGreaterEqual,
+    LessEqual,
+    LessLess,
+    LessLessEqual,
+    MinusEqual,
+    MinusMinus,
+    PercentEqual,
+    PipeEqual,
+    PipePipe,
+    PipePipeEqual,
+    PlusEqual,
+    PlusPlus,
+    QuestionDot,
+    QuestionQuestion,
+    QuestionQuestionEqual,
+    SlashEqual,
+    StarEqual,
+    StarStar,
+    StarStarEqual,
+});
+
 impl std::fmt::Display for TokenType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
         // NOTE(port): This used to be in lex-debug.cpp, not token.h.
@@ -433,6 +583,161 @@ impl std::fmt::Display for TokenType {
     }
 }
 
+// Returns the token as it would appear in source code (e.g. "&&=" for
+// TokenType::AmpersandAmpersandEqual), or a description of the token if its
+// text varies (e.g. "number" for TokenType::Number).
+pub fn token_type_text(token: TokenType) -> &'static str {
+    match token {
+        TokenType::Ampersand => "&",
+        TokenType::AmpersandAmpersand => "&&",
+        TokenType::AmpersandAmpersandEqual => "&&=",
+        TokenType::AmpersandEqual => "&=",
+        TokenType::Bang => "!",
+        TokenType::BangEqual => "!=",
+        TokenType::BangEqualEqual => "!==",
+        TokenType::Circumflex => "^",
+        TokenType::CircumflexEqual => "^=",
+        TokenType::Colon => ":",
+        TokenType::Comma => ",",
+        TokenType::Dot => ".",
+        TokenType::DotDotDot => "...",
+        TokenType::Equal => "=",
+        TokenType::EqualEqual => "==",
+        TokenType::EqualEqualEqual => "===",
+        TokenType::EqualGreater => "=>",
+        TokenType::Greater => ">",
+        TokenType::GreaterEqual => ">=",
+        TokenType::GreaterGreater => ">>",
+        TokenType::GreaterGreaterEqual => ">>=",
+        TokenType::GreaterGreaterGreater => ">>>",
+        TokenType::GreaterGreaterGreaterEqual => ">>>=",
+        TokenType::LeftCurly => "{",
+        TokenType::LeftParen => "(",
+        TokenType::LeftSquare => "[",
+        TokenType::Less => "<",
+        TokenType::LessEqual => "<=",
+        TokenType::LessLess => "<<",
+        TokenType::LessLessEqual => "<<=",
+        TokenType::Minus => "-",
+        TokenType::MinusEqual => "-=",
+        TokenType::MinusMinus => "--",
+        TokenType::Percent => "%",
+        TokenType::PercentEqual => "%=",
+        TokenType::Pipe => "|",
+        TokenType::PipeEqual => "|=",
+        TokenType::PipePipe => "||",
+        TokenType::PipePipeEqual => "||=",
+        TokenType::Plus => "+",
+        TokenType::PlusEqual => "+=",
+        TokenType::PlusPlus => "++",
+        TokenType::Question => "?",
+        TokenType::QuestionDot => "?.",
+        TokenType::QuestionQuestion => "??",
+        TokenType::QuestionQuestionEqual => "??=",
+        TokenType::RightCurly => "}",
+        TokenType::RightParen => ")",
+        TokenType::RightSquare => "]",
+        TokenType::Semicolon => ";",
+        TokenType::Slash => "/",
+        TokenType::SlashEqual => "/=",
+        TokenType::Star => "*",
+        TokenType::StarEqual => "*=",
+        TokenType::StarStar => "**",
+        TokenType::StarStarEqual => "**=",
+        TokenType::Tilde => "~",
+
+        TokenType::CompleteTemplate | TokenType::IncompleteTemplate => "template",
+        TokenType::EndOfFile => "end of file",
+        TokenType::Identifier => "identifier",
+        TokenType::Number => "number",
+        TokenType::PrivateIdentifier => "private identifier",
+        TokenType::Regexp => "regular expression",
+        TokenType::ReservedKeywordWithEscapeSequence => "keyword",
+        TokenType::String => "string",
+
+        TokenType::KWAbstract => "abstract",
+        TokenType::KWAny => "any",
+        TokenType::KWAs => "as",
+        TokenType::KWAssert => "assert",
+        TokenType::KWAsserts => "asserts",
+        TokenType::KWAsync => "async",
+        TokenType::KWAwait => "await",
+        TokenType::KWBigint => "bigint",
+        TokenType::KWBoolean => "boolean",
+        TokenType::KWBreak => "break",
+        TokenType::KWCase => "case",
+        TokenType::KWCatch => "catch",
+        TokenType::KWClass => "class",
+        TokenType::KWConst => "const",
+        TokenType::KWConstructor => "constructor",
+        TokenType::KWContinue => "continue",
+        TokenType::KWDebugger => "debugger",
+        TokenType::KWDeclare => "declare",
+        TokenType::KWDefault => "default",
+        TokenType::KWDelete => "delete",
+        TokenType::KWDo => "do",
+        TokenType::KWElse => "else",
+        TokenType::KWEnum => "enum",
+        TokenType::KWExport => "export",
+        TokenType::KWExtends => "extends",
+        TokenType::KWFalse => "false",
+        TokenType::KWFinally => "finally",
+        TokenType::KWFor => "for",
+        TokenType::KWFrom => "from",
+        TokenType::KWFunction => "function",
+        TokenType::KWGet => "get",
+        TokenType::KWGlobal => "global",
+        TokenType::KWIf => "if",
+        TokenType::KWImplements => "implements",
+        TokenType::KWImport => "import",
+        TokenType::KWIn => "in",
+        TokenType::KWInfer => "infer",
+        TokenType::KWInstanceof => "instanceof",
+        TokenType::KWInterface => "interface",
+        TokenType::KWIntrinsic => "intrinsic",
+        TokenType::KWIs => "is",
+        TokenType::KWKeyof => "keyof",
+        TokenType::KWLet => "let",
+        TokenType::KWModule => "module",
+        TokenType::KWNamespace => "namespace",
+        TokenType::KWNever => "never",
+        TokenType::KWNew => "new",
+        TokenType::KWNull => "null",
+        TokenType::KWNumber => "number",
+        TokenType::KWObject => "object",
+        TokenType::KWOf => "of",
+        TokenType::KWOut => "out",
+        TokenType::KWOverride => "override",
+        TokenType::KWPackage => "package",
+        TokenType::KWPrivate => "private",
+        TokenType::KWProtected => "protected",
+        TokenType::KWPublic => "public",
+        TokenType::KWReadonly => "readonly",
+        TokenType::KWRequire => "require",
+        TokenType::KWReturn => "return",
+        TokenType::KWSet => "set",
+        TokenType::KWStatic => "static",
+        TokenType::KWString => "string",
+        TokenType::KWSuper => "super",
+        TokenType::KWSwitch => "switch",
+        TokenType::KWSymbol => "symbol",
+        TokenType::KWThis => "this",
+        TokenType::KWThrow => "throw",
+        TokenType::KWTrue => "true",
+        TokenType::KWTry => "try",
+        TokenType::KWType => "type",
+        TokenType::KWTypeof => "typeof",
+        TokenType::KWUndefined => "undefined",
+        TokenType::KWUnique => "unique",
+        TokenType::KWUnknown => "unknown",
+        TokenType::KWVar => "var",
+        TokenType::KWVoid => "void",
+        TokenType::KWWhile => "while",
+        TokenType::KWWith => "with",
+        TokenType::KWYield => "yield",
+    }
+}
+
 // NOTE(port): This used to be in lex.cpp, not token.h.
 fn to_string(token: TokenType) -> &'static str {
     match token {
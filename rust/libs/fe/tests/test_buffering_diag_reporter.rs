@@ -2,9 +2,16 @@ use cpp_vs_rust_container::monotonic_allocator::*;
 use cpp_vs_rust_fe::buffering_diag_reporter::*;
 use cpp_vs_rust_fe::diag_reporter::*;
 use cpp_vs_rust_fe::diagnostic_types::*;
+use cpp_vs_rust_fe::linter::*;
+use cpp_vs_rust_fe::sarif_diag_reporter::*;
+use cpp_vs_rust_fe::sorting_diag_reporter::*;
 use cpp_vs_rust_fe::source_code_span::*;
+use cpp_vs_rust_fe::text_diag_reporter::*;
+use cpp_vs_rust_i18n::translation::*;
+use cpp_vs_rust_port::allocator::*;
 use cpp_vs_rust_port::assert_matches;
 use cpp_vs_rust_test::diag_collector::*;
+use cpp_vs_rust_test::span::*;
 use cpp_vs_rust_util::padded_string::*;
 
 #[test]
@@ -17,13 +24,13 @@ fn buffers_all_visits() {
     report(
         &diag_reporter,
         DiagUnexpectedBomBeforeShebang {
-            bom: span_of(&bom_code),
+            bom: span_of(&bom_code, 0, bom_code.len() as usize),
         },
     );
     report(
         &diag_reporter,
         DiagInvalidQuotesAroundStringLiteral {
-            opening_quote: span_of(&string_code),
+            opening_quote: span_of(&string_code, 0, string_code.len() as usize),
             suggested_quote: b'\'',
         },
     );
@@ -35,12 +42,12 @@ fn buffers_all_visits() {
     assert_matches!(
         collector.index(0),
         AnyDiag::DiagUnexpectedBomBeforeShebang(diag)
-            if same_pointers(diag.bom, span_of(&bom_code)),
+            if same_pointers(diag.bom, span_of(&bom_code, 0, bom_code.len() as usize)),
     );
     assert_matches!(
         collector.index(1),
         AnyDiag::DiagInvalidQuotesAroundStringLiteral(diag)
-            if same_pointers(diag.opening_quote, span_of(&string_code))
+            if same_pointers(diag.opening_quote, span_of(&string_code, 0, string_code.len() as usize))
                 && diag.suggested_quote == b'\'',
     );
 }
@@ -56,7 +63,7 @@ fn not_destructing_does_not_leak() {
     report(
         &diag_reporter,
         DiagUnexpectedBomBeforeShebang {
-            bom: span_of(&bom_code),
+            bom: span_of(&bom_code, 0, bom_code.len() as usize),
         },
     );
 
@@ -64,6 +71,116 @@ fn not_destructing_does_not_leak() {
     std::mem::forget(diag_reporter);
 }
 
-fn span_of<'a>(code: &'a PaddedString) -> SourceCodeSpan<'a> {
-    unsafe { SourceCodeSpan::new(code.c_str(), code.null_terminator()) }
+#[test]
+fn move_into_forgets_diagnostics() {
+    let bom_code = PaddedString::from_slice(b"bom");
+
+    let memory = MonotonicAllocator::new("test");
+    let mut diag_reporter = BufferingDiagReporter::new(&memory);
+    report(
+        &diag_reporter,
+        DiagUnexpectedBomBeforeShebang {
+            bom: span_of(&bom_code, 0, bom_code.len() as usize),
+        },
+    );
+
+    let collector = DiagCollector::new();
+    diag_reporter.move_into(&collector);
+    assert_eq!(collector.len(), 1);
+    assert!(diag_reporter.is_empty());
+
+    let collector_2 = DiagCollector::new();
+    diag_reporter.move_into(&collector_2);
+    assert_eq!(collector_2.len(), 0);
+}
+
+#[test]
+fn moving_into_another_buffering_reporter_merges() {
+    let bom_code = PaddedString::from_slice(b"bom");
+    let string_code = PaddedString::from_slice(b"\"");
+
+    let memory = MonotonicAllocator::new("test");
+    let mut merged = BufferingDiagReporter::new(&memory);
+    report(
+        &merged,
+        DiagUnexpectedBomBeforeShebang {
+            bom: span_of(&bom_code, 0, bom_code.len() as usize),
+        },
+    );
+    let mut other = BufferingDiagReporter::new_in(global_allocator());
+    report(
+        &other,
+        DiagInvalidQuotesAroundStringLiteral {
+            opening_quote: span_of(&string_code, 0, string_code.len() as usize),
+            suggested_quote: b'\'',
+        },
+    );
+    other.move_into(&merged);
+    assert!(other.is_empty());
+
+    let collector = DiagCollector::new();
+    merged.move_into(&collector);
+    assert_eq!(collector.len(), 2);
+    assert_matches!(
+        collector.index(0),
+        AnyDiag::DiagUnexpectedBomBeforeShebang(_)
+    );
+    assert_matches!(
+        collector.index(1),
+        AnyDiag::DiagInvalidQuotesAroundStringLiteral(_),
+    );
+}
+
+#[test]
+fn reporters_can_be_sent_to_other_threads() {
+    fn assert_send<T: Send>(_: &T) {}
+
+    let buffering = BufferingDiagReporter::new_in(global_allocator());
+    assert_send(&buffering);
+    assert_send(&SortingDiagReporter::new());
+    assert_send(&TextDiagReporter::new(
+        Translator::new_using_messages_from_source_code(),
+    ));
+    assert_send(&SarifDiagReporter::new(
+        Translator::new_using_messages_from_source_code(),
+    ));
+    assert_send(&DiagCollector::new());
+}
+
+#[test]
+fn lint_files_concurrently_then_merge() {
+    let files: Vec<PaddedString> = (0..8)
+        .map(|i: usize| PaddedString::from_slice(format!("let x{} = 'unclosed", i).as_bytes()))
+        .collect();
+
+    let collector = DiagCollector::new();
+    std::thread::scope(|scope| {
+        let threads: Vec<std::thread::ScopedJoinHandle<BufferingDiagReporter<'_, '_, _>>> = files
+            .iter()
+            .map(|file: &PaddedString| {
+                scope.spawn(move || {
+                    let reporter = BufferingDiagReporter::new_in(global_allocator());
+                    parse_and_lint(file.view(), &reporter, LinterOptions::default());
+                    reporter
+                })
+            })
+            .collect();
+        // Merge in file order, regardless of which thread finished first.
+        for thread in threads {
+            let mut reporter = thread.join().unwrap();
+            reporter.move_into(&collector);
+        }
+    });
+
+    assert_eq!(collector.len(), files.len());
+    for (i, file) in files.iter().enumerate() {
+        assert_matches!(
+            collector.index(i),
+            AnyDiag::DiagUnclosedStringLiteral(diag)
+                if same_pointers(
+                    diag.string_literal,
+                    span_of(&file, b"let x0 = ".len(), file.len() as usize),
+                ),
+        );
+    }
 }
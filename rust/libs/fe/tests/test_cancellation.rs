@@ -0,0 +1,47 @@
+use cpp_vs_rust_fe::cancellation::*;
+
+#[test]
+fn new_token_is_not_cancelled() {
+    let token = CancellationToken::new();
+    assert!(!token.is_cancelled());
+}
+
+#[test]
+fn cancelling_a_clone_cancels_the_original() {
+    let token = CancellationToken::new();
+    let clone: CancellationToken = token.clone();
+    clone.cancel();
+    assert!(token.is_cancelled());
+}
+
+#[test]
+fn reset_uncancels() {
+    let token = CancellationToken::new();
+    token.cancel();
+    token.reset();
+    assert!(!token.is_cancelled());
+}
+
+#[test]
+fn token_is_cancelled_once_deadline_passes() {
+    let now: std::time::Instant = std::time::Instant::now();
+    assert!(CancellationToken::with_deadline(now).is_cancelled());
+    let far_future: std::time::Instant = now + std::time::Duration::from_secs(60 * 60);
+    assert!(!CancellationToken::with_deadline(far_future).is_cancelled());
+}
+
+#[test]
+fn reset_does_not_extend_deadline() {
+    let token = CancellationToken::with_time_budget(std::time::Duration::ZERO);
+    token.reset();
+    assert!(token.is_cancelled());
+}
+
+#[test]
+fn token_can_be_cancelled_from_another_thread() {
+    let token = CancellationToken::new();
+    std::thread::scope(|scope| {
+        scope.spawn(|| token.cancel());
+    });
+    assert!(token.is_cancelled());
+}
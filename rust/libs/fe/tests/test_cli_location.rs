@@ -0,0 +1,127 @@
+use cpp_vs_rust_fe::cli_location::*;
+use cpp_vs_rust_fe::source_code_span::*;
+use cpp_vs_rust_test::characters::*;
+use cpp_vs_rust_util::padded_string::*;
+
+#[test]
+fn ranges_on_first_line() {
+    let code = PaddedString::from_slice(b"let x = 2;");
+    let l: CLILocator = CLILocator::new(code.view());
+    let x_range: CLISourceRange =
+        l.range(unsafe { SourceCodeSpan::new(code.c_str().add(4), code.c_str().add(5)) });
+
+    assert_eq!(x_range.begin.line_number, 1);
+    assert_eq!(x_range.begin.column_number, 5);
+    assert_eq!(x_range.begin.offset, 4);
+    assert_eq!(x_range.end.line_number, 1);
+    assert_eq!(x_range.end.column_number, 6);
+    assert_eq!(x_range.end.offset, 5);
+}
+
+#[test]
+fn ranges_on_second_line() {
+    for line_terminator in LINE_TERMINATORS_EXCEPT_LS_PS {
+        let code: PaddedString =
+            PaddedString::from_slice(format!("let x = 2;{line_terminator}let y = 3;").as_bytes());
+        let y: *const u8 = strchr(code.view(), b'y');
+        let l: CLILocator = CLILocator::new(code.view());
+        let y_range: CLISourceRange = l.range(unsafe { SourceCodeSpan::new(y, y.add(1)) });
+
+        assert_eq!(y_range.begin.line_number, 2, "{line_terminator:?}");
+        assert_eq!(y_range.begin.column_number, 5, "{line_terminator:?}");
+        assert_eq!(y_range.end.line_number, 2, "{line_terminator:?}");
+        assert_eq!(y_range.end.column_number, 6, "{line_terminator:?}");
+    }
+}
+
+#[test]
+fn first_character_on_line_has_column_1() {
+    let code: PaddedString = PaddedString::from_slice(b"let f = 0;\nif (x) {}");
+    let if_: *const u8 = strchr(code.view(), b'i');
+    let l: CLILocator = CLILocator::new(code.view());
+    let p: CLISourcePosition = l.position(if_);
+    assert_eq!(p.line_number, 2);
+    assert_eq!(p.column_number, 1);
+}
+
+#[test]
+fn lf_cr_is_two_line_terminators() {
+    let code: PaddedString = PaddedString::from_slice(b"let x = 2;\n\rlet y = 3;");
+    let y: *const u8 = strchr(code.view(), b'y');
+    let l: CLILocator = CLILocator::new(code.view());
+    assert_eq!(l.position(y).line_number, 3);
+}
+
+#[test]
+fn ls_and_ps_are_not_line_terminators() {
+    for line_terminator in LS_AND_PS {
+        let code: PaddedString =
+            PaddedString::from_slice(format!("x{line_terminator}y").as_bytes());
+        let y: *const u8 = strchr(code.view(), b'y');
+        let l: CLILocator = CLILocator::new(code.view());
+        let p: CLISourcePosition = l.position(y);
+        assert_eq!(p.line_number, 1, "{line_terminator:?}");
+        assert_eq!(p.column_number, 3, "{line_terminator:?}");
+    }
+}
+
+#[test]
+fn location_after_null_byte() {
+    let code: PaddedString = PaddedString::from_slice(b"hello\0beautiful\nworld");
+    let r: *const u8 = unsafe { code.c_str().add(18) };
+    assert_eq!(unsafe { *r }, b'r');
+
+    let l: CLILocator = CLILocator::new(code.view());
+    let p: CLISourcePosition = l.position(r);
+    assert_eq!(p.line_number, 2);
+    assert_eq!(p.column_number, 3);
+}
+
+#[test]
+fn position_of_end_of_file() {
+    let code: PaddedString = PaddedString::from_slice(b"hello\n");
+    let l: CLILocator = CLILocator::new(code.view());
+    let p: CLISourcePosition = l.position(code.null_terminator());
+    assert_eq!(p.line_number, 2);
+    assert_eq!(p.column_number, 1);
+    assert_eq!(p.offset, 6);
+}
+
+#[test]
+fn column_counts_utf_8_characters() {
+    // U+2603 has three UTF-8 code units: e2 98 83
+    // U+1f496 has four UTF-8 code units: f0 9f 92 96
+    let code: PaddedString = PaddedString::from_slice("\u{2603}\u{01f496} x".as_bytes());
+    let x: *const u8 = strchr(code.view(), b'x');
+    let l: CLILocator = CLILocator::new(code.view());
+    let p: CLISourcePosition = l.position(x);
+    assert_eq!(p.column_number, 4);
+    assert_eq!(p.offset, 8);
+}
+
+#[test]
+fn line_text_excludes_line_terminator() {
+    for line_terminator in LINE_TERMINATORS_EXCEPT_LS_PS {
+        let code: PaddedString = PaddedString::from_slice(
+            format!("first{line_terminator}second{line_terminator}third").as_bytes(),
+        );
+        let l: CLILocator = CLILocator::new(code.view());
+        assert_eq!(l.line_text(1), b"first", "{line_terminator:?}");
+        assert_eq!(l.line_text(2), b"second", "{line_terminator:?}");
+        assert_eq!(l.line_text(3), b"third", "{line_terminator:?}");
+    }
+}
+
+#[test]
+fn line_text_of_empty_lines() {
+    let code: PaddedString = PaddedString::from_slice(b"\n\r\n");
+    let l: CLILocator = CLILocator::new(code.view());
+    assert_eq!(l.line_text(1), b"");
+    assert_eq!(l.line_text(2), b"");
+    assert_eq!(l.line_text(3), b"");
+}
+
+fn strchr(haystack: PaddedStringView, needle: u8) -> *const u8 {
+    let position: Option<usize> = haystack.slice().iter().position(|c: &u8| *c == needle);
+    unsafe { haystack.c_str().add(position.unwrap()) }
+}
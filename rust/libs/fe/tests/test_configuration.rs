@@ -0,0 +1,176 @@
+use cpp_vs_rust_fe::configuration::*;
+use cpp_vs_rust_fe::diag_reporter::*;
+use cpp_vs_rust_fe::diagnostic_types::*;
+use cpp_vs_rust_fe::global_declared_variable_set::*;
+use cpp_vs_rust_fe::linter::*;
+use cpp_vs_rust_port::assert_matches;
+use cpp_vs_rust_test::diag_collector::*;
+use cpp_vs_rust_util::padded_string::*;
+
+fn load(json: &PaddedString) -> Configuration {
+    Configuration::load_from_json(json.view(), null_diag_reporter())
+}
+
+fn global(name: &str, is_writable: bool, is_shadowable: bool) -> GlobalDeclaredVariable {
+    GlobalDeclaredVariable {
+        name: name.to_string(),
+        is_writable: is_writable,
+        is_shadowable: is_shadowable,
+    }
+}
+
+#[test]
+fn empty_config_has_defaults() {
+    let json = PaddedString::from_slice(b"{}");
+    let collector = DiagCollector::new();
+    let config: Configuration = Configuration::load_from_json(json.view(), &collector);
+    assert_eq!(collector.len(), 0);
+    assert_eq!(config, Configuration::new());
+    assert!(config.globals().is_empty());
+    assert_eq!(config.global_groups(), &GlobalGroups::All);
+}
+
+#[test]
+fn globals_can_be_booleans_or_descriptors() {
+    let json = PaddedString::from_slice(
+        br#"{"globals": {
+            "a": true,
+            "b": {},
+            "c": {"writable": false},
+            "d": {"shadowable": false, "writable": true},
+            "e": false
+        }}"#,
+    );
+    let config: Configuration = load(&json);
+    let globals: Vec<&GlobalDeclaredVariable> = config.globals().iter().collect();
+    assert_eq!(
+        globals,
+        vec![
+            &global("a", true, true),
+            &global("b", true, true),
+            &global("c", false, true),
+            &global("d", true, false),
+        ]
+    );
+}
+
+#[test]
+fn false_global_removes_earlier_duplicate() {
+    let json = PaddedString::from_slice(br#"{"globals": {"a": true, "a": false}}"#);
+    assert!(load(&json).globals().find("a").is_none());
+}
+
+#[test]
+fn global_groups() {
+    let groups = |json: &[u8]| -> GlobalGroups {
+        load(&PaddedString::from_slice(json))
+            .global_groups()
+            .clone()
+    };
+    assert_eq!(groups(br#"{"global-groups": true}"#), GlobalGroups::All);
+    assert_eq!(
+        groups(br#"{"global-groups": false}"#),
+        GlobalGroups::Only(vec![])
+    );
+    assert_eq!(
+        groups(br#"{"global-groups": ["browser", "node.js"]}"#),
+        GlobalGroups::Only(vec!["browser".to_string(), "node.js".to_string()])
+    );
+}
+
+#[test]
+fn apply_to_sets_linter_options_globals() {
+    let json = PaddedString::from_slice(br#"{"globals": {"gsap": true}}"#);
+    let mut options: LinterOptions = LinterOptions::default();
+    load(&json).apply_to(&mut options);
+    assert_eq!(
+        options.globals.find("gsap"),
+        Some(&global("gsap", true, true))
+    );
+}
+
+#[test]
+fn json_syntax_error_is_reported_and_defaults_are_used() {
+    let json = PaddedString::from_slice(br#"{"globals": {"a": true,}}"#);
+    let collector = DiagCollector::new();
+    let config: Configuration = Configuration::load_from_json(json.view(), &collector);
+    assert_eq!(config, Configuration::new());
+    assert_eq!(collector.len(), 1);
+    assert_matches!(
+        collector.index(0),
+        AnyDiag::DiagConfigJSONSyntaxError(diag) if diag.where_.as_slice() == b"}",
+    );
+
+    let json = PaddedString::from_slice(b"{");
+    let collector = DiagCollector::new();
+    Configuration::load_from_json(json.view(), &collector);
+    assert_matches!(
+        collector.index(0),
+        AnyDiag::DiagConfigJSONSyntaxError(diag) if diag.where_.as_slice() == b"",
+    );
+}
+
+#[test]
+fn type_mismatches_are_reported_with_spans() {
+    let json = PaddedString::from_slice(br#"{"globals": [], "global-groups": "browser"}"#);
+    let collector = DiagCollector::new();
+    Configuration::load_from_json(json.view(), &collector);
+    assert_eq!(collector.len(), 2);
+    assert_matches!(
+        collector.index(0),
+        AnyDiag::DiagConfigGlobalGroupsTypeMismatch(diag)
+            if diag.where_.as_slice() == br#""browser""#,
+    );
+    assert_matches!(
+        collector.index(1),
+        AnyDiag::DiagConfigGlobalsTypeMismatch(diag) if diag.where_.as_slice() == b"[]",
+    );
+}
+
+#[test]
+fn invalid_entries_are_reported_and_valid_entries_still_apply() {
+    let json = PaddedString::from_slice(
+        br#"{
+            "global-groups": ["browser", 42],
+            "globals": {
+                "a": "yes",
+                "b": {"shadowable": "no", "writable": null},
+                "c": true
+            }
+        }"#,
+    );
+    let collector = DiagCollector::new();
+    let config: Configuration = Configuration::load_from_json(json.view(), &collector);
+    assert_eq!(collector.len(), 4);
+    assert_matches!(
+        collector.index(0),
+        AnyDiag::DiagConfigGlobalGroupsGroupTypeMismatch(diag)
+            if diag.group.as_slice() == b"42",
+    );
+    assert_matches!(
+        collector.index(1),
+        AnyDiag::DiagConfigGlobalsDescriptorTypeMismatch(diag)
+            if diag.descriptor.as_slice() == br#""yes""#,
+    );
+    assert_matches!(
+        collector.index(2),
+        AnyDiag::DiagConfigGlobalsDescriptorShadowableTypeMismatch(diag)
+            if diag.value.as_slice() == br#""no""#,
+    );
+    assert_matches!(
+        collector.index(3),
+        AnyDiag::DiagConfigGlobalsDescriptorWritableTypeMismatch(diag)
+            if diag.value.as_slice() == b"null",
+    );
+
+    assert_eq!(
+        config.global_groups(),
+        &GlobalGroups::Only(vec!["browser".to_string()])
+    );
+    let names: Vec<&str> = config
+        .globals()
+        .iter()
+        .map(|global: &GlobalDeclaredVariable| global.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["b", "c"]);
+}
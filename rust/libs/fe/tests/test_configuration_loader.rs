@@ -0,0 +1,126 @@
+use cpp_vs_rust_fe::configuration_loader::*;
+use cpp_vs_rust_fe::global_declared_variable_set::*;
+use cpp_vs_rust_test::temp_directory::*;
+
+fn fail_on_error(path: &std::path::Path, error: std::io::Error) {
+    panic!("{}: {error}", path.display());
+}
+
+fn config_globals(config_file: &ConfigFile) -> Vec<String> {
+    config_file
+        .configuration()
+        .globals()
+        .iter()
+        .map(|global: &GlobalDeclaredVariable| global.name.clone())
+        .collect()
+}
+
+#[test]
+fn no_config_file() {
+    let temp_dir: TempDirectory = TempDirectory::new("config-loader-none");
+    let file: String = temp_dir.write_file("src/hello.js", b"");
+    let mut loader: ConfigurationLoader = ConfigurationLoader::new();
+    // NOTE(port): This assumes the temporary directory's ancestors have no
+    // quick-lint-js.config file.
+    assert!(loader
+        .load_for_file(std::path::Path::new(&file), &mut fail_on_error)
+        .is_none());
+}
+
+#[test]
+fn nearest_ancestor_config_file_is_used() {
+    let temp_dir: TempDirectory = TempDirectory::new("config-loader-ancestor");
+    temp_dir.write_file("quick-lint-js.config", br#"{"globals": {"outer": true}}"#);
+    temp_dir.write_file(
+        "src/quick-lint-js.config",
+        br#"{"globals": {"inner": true}}"#,
+    );
+    let deep_file: String = temp_dir.write_file("src/a/b.js", b"");
+    let shallow_file: String = temp_dir.write_file("other/c.js", b"");
+    let mut loader: ConfigurationLoader = ConfigurationLoader::new();
+
+    let config: std::sync::Arc<ConfigFile> = loader
+        .load_for_file(std::path::Path::new(&deep_file), &mut fail_on_error)
+        .unwrap();
+    assert_eq!(config_globals(&config), vec!["inner"]);
+    assert_eq!(
+        config.path(),
+        std::fs::canonicalize(temp_dir.path().join("src/quick-lint-js.config")).unwrap()
+    );
+
+    let config: std::sync::Arc<ConfigFile> = loader
+        .load_for_file(std::path::Path::new(&shallow_file), &mut fail_on_error)
+        .unwrap();
+    assert_eq!(config_globals(&config), vec!["outer"]);
+}
+
+#[test]
+fn unchanged_config_file_is_cached() {
+    let temp_dir: TempDirectory = TempDirectory::new("config-loader-cache");
+    temp_dir.write_file("quick-lint-js.config", br#"{"globals": {"a": true}}"#);
+    let a: String = temp_dir.write_file("a.js", b"");
+    let b: String = temp_dir.write_file("b.js", b"");
+    let mut loader: ConfigurationLoader = ConfigurationLoader::new();
+    let config_a: std::sync::Arc<ConfigFile> = loader
+        .load_for_file(std::path::Path::new(&a), &mut fail_on_error)
+        .unwrap();
+    let config_b: std::sync::Arc<ConfigFile> = loader
+        .load_for_file(std::path::Path::new(&b), &mut fail_on_error)
+        .unwrap();
+    assert!(std::sync::Arc::ptr_eq(&config_a, &config_b));
+}
+
+#[test]
+fn changed_config_file_is_reloaded() {
+    let temp_dir: TempDirectory = TempDirectory::new("config-loader-reload");
+    let config_path: String =
+        temp_dir.write_file("quick-lint-js.config", br#"{"globals": {"a": true}}"#);
+    let a: String = temp_dir.write_file("a.js", b"");
+    let mut loader: ConfigurationLoader = ConfigurationLoader::new();
+    let old_config: std::sync::Arc<ConfigFile> = loader
+        .load_for_file(std::path::Path::new(&a), &mut fail_on_error)
+        .unwrap();
+    assert_eq!(config_globals(&old_config), vec!["a"]);
+
+    // A different size is noticed even if the modification time is the same.
+    std::fs::write(&config_path, br#"{"globals": {"bb": true}}"#).unwrap();
+    let new_config: std::sync::Arc<ConfigFile> = loader
+        .load_for_file(std::path::Path::new(&a), &mut fail_on_error)
+        .unwrap();
+    assert_eq!(config_globals(&new_config), vec!["bb"]);
+}
+
+#[test]
+fn config_errors_are_kept_for_reporting() {
+    let temp_dir: TempDirectory = TempDirectory::new("config-loader-errors");
+    temp_dir.write_file("good/quick-lint-js.config", b"{}");
+    temp_dir.write_file("bad/quick-lint-js.config", b"{\"globals\": 42}");
+    let mut loader: ConfigurationLoader = ConfigurationLoader::new();
+    let good: std::sync::Arc<ConfigFile> = loader
+        .load_config_file(
+            &temp_dir.path().join("good/quick-lint-js.config"),
+            &mut fail_on_error,
+        )
+        .unwrap();
+    assert!(!good.has_errors());
+    let bad: std::sync::Arc<ConfigFile> = loader
+        .load_config_file(
+            &temp_dir.path().join("bad/quick-lint-js.config"),
+            &mut fail_on_error,
+        )
+        .unwrap();
+    assert!(bad.has_errors());
+    assert_eq!(bad.content().slice(), b"{\"globals\": 42}");
+}
+
+#[test]
+fn unreadable_directory_is_reported() {
+    let mut loader: ConfigurationLoader = ConfigurationLoader::new();
+    let mut errors: Vec<std::path::PathBuf> = vec![];
+    let config: Option<std::sync::Arc<ConfigFile>> = loader.load_for_file(
+        std::path::Path::new("/does/not/exist/hello.js"),
+        &mut |path: &std::path::Path, _error: std::io::Error| errors.push(path.to_path_buf()),
+    );
+    assert!(config.is_none());
+    assert_eq!(errors, vec![std::path::PathBuf::from("/does/not/exist")]);
+}
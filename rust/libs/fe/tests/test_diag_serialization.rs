@@ -0,0 +1,440 @@
+use cpp_vs_rust_container::monotonic_allocator::*;
+use cpp_vs_rust_fe::diag_reporter::*;
+use cpp_vs_rust_fe::diag_serialization::*;
+use cpp_vs_rust_fe::diagnostic_types::*;
+use cpp_vs_rust_fe::language::*;
+use cpp_vs_rust_fe::source_code_span::*;
+use cpp_vs_rust_fe::token::*;
+use cpp_vs_rust_port::assert_matches;
+use cpp_vs_rust_test::diag_collector::*;
+use cpp_vs_rust_test::span::*;
+use cpp_vs_rust_util::padded_string::*;
+
+#[test]
+fn round_trip_against_same_input() {
+    let code = PaddedString::from_slice(b"\x01 123456789012345678901 'x' a");
+
+    let mut serializer = SerializingDiagReporter::new(code.view());
+    report(
+        &serializer,
+        DiagUnexpectedControlCharacter {
+            character: span_of(&code, 0, 1),
+            code_point: '\x01',
+        },
+    );
+    report(
+        &serializer,
+        DiagIntegerLiteralWillLosePrecision {
+            characters: span_of(&code, 2, 23),
+            rounded_val: b"123456789012345680000",
+        },
+    );
+    report(
+        &serializer,
+        DiagInvalidQuotesAroundStringLiteral {
+            opening_quote: span_of(&code, 24, 25),
+            suggested_quote: b'"',
+        },
+    );
+    report(
+        &serializer,
+        DiagOptionalNoteTest {
+            a: span_of(&code, 28, 29),
+            b: None,
+        },
+    );
+    report(
+        &serializer,
+        DiagOptionalNoteTest {
+            a: span_of(&code, 28, 29),
+            b: Some(span_of(&code, 24, 27)),
+        },
+    );
+    let data: Vec<u8> = serializer.take_output();
+
+    let memory = MonotonicAllocator::new("test");
+    let diags: Vec<AnyDiag> = deserialize_diags(&data, code.view(), &memory).unwrap();
+    assert_eq!(diags.len(), 5);
+    assert_matches!(
+        &diags[0],
+        AnyDiag::DiagUnexpectedControlCharacter(diag)
+            if same_pointers(diag.character, span_of(&code, 0, 1)),
+    );
+    assert_matches!(
+        &diags[1],
+        AnyDiag::DiagIntegerLiteralWillLosePrecision(diag)
+            if same_pointers(diag.characters, span_of(&code, 2, 23))
+                && diag.rounded_val == b"123456789012345680000",
+    );
+    assert_matches!(
+        &diags[2],
+        AnyDiag::DiagInvalidQuotesAroundStringLiteral(diag)
+            if same_pointers(diag.opening_quote, span_of(&code, 24, 25))
+                && diag.suggested_quote == b'"',
+    );
+    assert_matches!(
+        &diags[3],
+        AnyDiag::DiagOptionalNoteTest(diag)
+            if same_pointers(diag.a, span_of(&code, 28, 29)) && diag.b.is_none(),
+    );
+    assert_matches!(
+        &diags[4],
+        AnyDiag::DiagOptionalNoteTest(DiagOptionalNoteTest { a: _, b: Some(b) })
+            if same_pointers(*b, span_of(&code, 24, 27)),
+    );
+}
+
+#[test]
+fn deserialized_spans_point_into_given_input() {
+    let original_code = PaddedString::from_slice(b"hello 'world");
+    let mut serializer = SerializingDiagReporter::new(original_code.view());
+    report(
+        &serializer,
+        DiagUnclosedStringLiteral {
+            string_literal: span_of(&original_code, 6, 12),
+        },
+    );
+    let data: Vec<u8> = serializer.take_output();
+    drop(serializer);
+    drop(original_code);
+
+    let copied_code = PaddedString::from_slice(b"hello 'world");
+    let memory = MonotonicAllocator::new("test");
+    let diags: Vec<AnyDiag> = deserialize_diags(&data, copied_code.view(), &memory).unwrap();
+    assert_eq!(diags.len(), 1);
+    assert_matches!(
+        &diags[0],
+        AnyDiag::DiagUnclosedStringLiteral(diag)
+            if same_pointers(diag.string_literal, span_of(&copied_code, 6, 12)),
+    );
+}
+
+#[test]
+fn take_output_resets_output() {
+    let code = PaddedString::from_slice(b"'");
+    let mut serializer = SerializingDiagReporter::new(code.view());
+    report(
+        &serializer,
+        DiagUnclosedStringLiteral {
+            string_literal: span_of(&code, 0, 1),
+        },
+    );
+    let first_output: Vec<u8> = serializer.take_output();
+    let second_output: Vec<u8> = serializer.take_output();
+    assert!(second_output.len() < first_output.len());
+
+    let memory = MonotonicAllocator::new("test");
+    assert_eq!(
+        deserialize_diags(&first_output, code.view(), &memory)
+            .unwrap()
+            .len(),
+        1
+    );
+    assert_eq!(
+        deserialize_diags(&second_output, code.view(), &memory)
+            .unwrap()
+            .len(),
+        0
+    );
+}
+
+#[test]
+fn replay_reports_diagnostics_in_order() {
+    let code = PaddedString::from_slice(b"\x01'");
+    let mut serializer = SerializingDiagReporter::new(code.view());
+    report(
+        &serializer,
+        DiagUnclosedStringLiteral {
+            string_literal: span_of(&code, 1, 2),
+        },
+    );
+    report(
+        &serializer,
+        DiagUnexpectedControlCharacter {
+            character: span_of(&code, 0, 1),
+            code_point: '\x01',
+        },
+    );
+    let data: Vec<u8> = serializer.take_output();
+
+    let memory = MonotonicAllocator::new("test");
+    let collector = DiagCollector::new();
+    replay_serialized_diags(&data, code.view(), &memory, &collector).unwrap();
+    assert_eq!(collector.len(), 2);
+    assert_matches!(collector.index(0), AnyDiag::DiagUnclosedStringLiteral(_));
+    assert_matches!(
+        collector.index(1),
+        AnyDiag::DiagUnexpectedControlCharacter(_)
+    );
+}
+
+#[test]
+fn truncated_data_is_rejected() {
+    let code = PaddedString::from_slice(b"\x01'");
+    let data: Vec<u8> = serialize_control_character_and_unclosed_string(&code);
+
+    let memory = MonotonicAllocator::new("test");
+    let collector = DiagCollector::new();
+    let first_diag_len: usize = serialize_control_character(&code).len();
+    for len in 0..data.len() {
+        if len == 1 || len == first_diag_len {
+            // The data ends between diagnostics.
+            continue;
+        }
+        assert_eq!(
+            replay_serialized_diags(&data[..len], code.view(), &memory, &collector),
+            Err(DiagDeserializeError::UnexpectedEnd),
+            "len={len}",
+        );
+    }
+    assert_eq!(collector.len(), 0, "nothing should be reported on error");
+}
+
+#[test]
+fn unknown_code_is_rejected() {
+    let code = PaddedString::from_slice(b"");
+    let memory = MonotonicAllocator::new("test");
+    assert_eq!(
+        deserialize_diags(
+            &[DIAG_SERIALIZATION_FORMAT_VERSION, 0x0f, 0x27, 0],
+            code.view(),
+            &memory
+        )
+        .err(),
+        Some(DiagDeserializeError::UnknownCode(9999)),
+    );
+}
+
+#[test]
+fn mismatched_message_count_is_rejected() {
+    let code = PaddedString::from_slice(b"\x01");
+    let mut data: Vec<u8> = serialize_control_character(&code);
+    // The message count follows the version and the u16 code (E0045).
+    data[3] += 1;
+
+    let memory = MonotonicAllocator::new("test");
+    assert_eq!(
+        deserialize_diags(&data, code.view(), &memory).err(),
+        Some(DiagDeserializeError::MismatchedDiagnostic(45)),
+    );
+}
+
+#[test]
+fn span_past_end_of_input_is_rejected() {
+    let code = PaddedString::from_slice(b"\x01");
+    let data: Vec<u8> = serialize_control_character(&code);
+
+    let shorter_code = PaddedString::from_slice(b"");
+    let memory = MonotonicAllocator::new("test");
+    assert_eq!(
+        deserialize_diags(&data, shorter_code.view(), &memory).err(),
+        Some(DiagDeserializeError::OffsetOutOfRange),
+    );
+}
+
+#[test]
+fn other_format_version_is_rejected() {
+    let code = PaddedString::from_slice(b"\x01");
+    let mut data: Vec<u8> = serialize_control_character(&code);
+    data[0] = DIAG_SERIALIZATION_FORMAT_VERSION.wrapping_add(1);
+
+    let memory = MonotonicAllocator::new("test");
+    assert_eq!(
+        deserialize_diags(&data, code.view(), &memory).err(),
+        Some(DiagDeserializeError::UnsupportedVersion(
+            DIAG_SERIALIZATION_FORMAT_VERSION.wrapping_add(1)
+        )),
+    );
+}
+
+#[test]
+fn enum_kind_round_trips() {
+    for enum_kind in [
+        EnumKind::DeclareConstEnum,
+        EnumKind::ConstEnum,
+        EnumKind::DeclareEnum,
+        EnumKind::Normal,
+    ] {
+        let diag: DiagEnumArgumentsTest = round_trip_enum_arguments(DiagEnumArgumentsTest {
+            enum_kind: enum_kind,
+            ..default_enum_arguments()
+        });
+        assert_eq!(diag.enum_kind, enum_kind);
+    }
+    assert_enum_argument_is_range_checked(ENUM_KIND_ARG_INDEX);
+}
+
+#[test]
+fn statement_kind_round_trips() {
+    for statement_kind in [
+        StatementKind::DoWhileLoop,
+        StatementKind::ForLoop,
+        StatementKind::IfStatement,
+        StatementKind::WhileLoop,
+        StatementKind::WithStatement,
+        StatementKind::LabelledStatement,
+    ] {
+        let diag: DiagEnumArgumentsTest = round_trip_enum_arguments(DiagEnumArgumentsTest {
+            statement_kind: statement_kind,
+            ..default_enum_arguments()
+        });
+        assert_eq!(diag.statement_kind, statement_kind);
+    }
+    assert_enum_argument_is_range_checked(STATEMENT_KIND_ARG_INDEX);
+}
+
+#[test]
+fn token_type_round_trips() {
+    for token_type in [
+        TokenType::Ampersand,
+        TokenType::Tilde,
+        TokenType::CompleteTemplate,
+        TokenType::KWYield,
+        TokenType::StarStarEqual,
+    ] {
+        let diag: DiagEnumArgumentsTest = round_trip_enum_arguments(DiagEnumArgumentsTest {
+            token_type: token_type,
+            ..default_enum_arguments()
+        });
+        assert_eq!(diag.token_type, token_type);
+    }
+    assert_enum_argument_is_range_checked(TOKEN_TYPE_ARG_INDEX);
+
+    // Characters which are not single-character symbols are rejected.
+    let code = PaddedString::from_slice(b"x");
+    let mut data: Vec<u8> = serialize_enum_arguments(&code, default_enum_arguments_in(&code));
+    let token_type_offset: usize = enum_argument_offset(TOKEN_TYPE_ARG_INDEX, &data);
+    data[token_type_offset] = b'#';
+    let memory = MonotonicAllocator::new("test");
+    assert_eq!(
+        deserialize_diags(&data, code.view(), &memory).err(),
+        Some(DiagDeserializeError::InvalidValue),
+    );
+}
+
+#[test]
+fn variable_kind_round_trips() {
+    for variable_kind in [
+        VariableKind::ArrowParameter,
+        VariableKind::Interface,
+        VariableKind::Var,
+    ] {
+        let diag: DiagEnumArgumentsTest = round_trip_enum_arguments(DiagEnumArgumentsTest {
+            variable_kind: variable_kind,
+            ..default_enum_arguments()
+        });
+        assert_eq!(diag.variable_kind, variable_kind);
+    }
+    assert_enum_argument_is_range_checked(VARIABLE_KIND_ARG_INDEX);
+}
+
+// Indexes of DiagEnumArgumentsTest's arguments after where_.
+const ENUM_KIND_ARG_INDEX: usize = 0;
+const STATEMENT_KIND_ARG_INDEX: usize = 1;
+const TOKEN_TYPE_ARG_INDEX: usize = 2;
+const VARIABLE_KIND_ARG_INDEX: usize = 3;
+
+static ENUM_ARGUMENTS_CODE: &[u8] = b"x";
+
+fn default_enum_arguments() -> DiagEnumArgumentsTest<'static> {
+    DiagEnumArgumentsTest {
+        where_: SourceCodeSpan::from_slice(&ENUM_ARGUMENTS_CODE[0..1]),
+        enum_kind: EnumKind::Normal,
+        statement_kind: StatementKind::IfStatement,
+        token_type: TokenType::Identifier,
+        variable_kind: VariableKind::Let,
+    }
+}
+
+fn default_enum_arguments_in(code: &PaddedString) -> DiagEnumArgumentsTest<'_> {
+    DiagEnumArgumentsTest {
+        where_: span_of(code, 0, 1),
+        ..default_enum_arguments()
+    }
+}
+
+// Serialize diag against ENUM_ARGUMENTS_CODE, then deserialize it.
+fn round_trip_enum_arguments(diag: DiagEnumArgumentsTest) -> DiagEnumArgumentsTest {
+    let code = PaddedString::from_slice(ENUM_ARGUMENTS_CODE);
+    let data: Vec<u8> = serialize_enum_arguments(
+        &code,
+        DiagEnumArgumentsTest {
+            where_: span_of(&code, 0, 1),
+            ..diag
+        },
+    );
+    let memory = MonotonicAllocator::new("test");
+    let diags: Vec<AnyDiag> = deserialize_diags(&data, code.view(), &memory).unwrap();
+    assert_eq!(diags.len(), 1);
+    match &diags[0] {
+        AnyDiag::DiagEnumArgumentsTest(deserialized) => DiagEnumArgumentsTest {
+            where_: diag.where_,
+            ..deserialized.clone()
+        },
+        other => panic!("expected DiagEnumArgumentsTest, got {other:?}"),
+    }
+}
+
+fn serialize_enum_arguments(code: &PaddedString, diag: DiagEnumArgumentsTest) -> Vec<u8> {
+    let mut serializer = SerializingDiagReporter::new(code.view());
+    report(&serializer, diag);
+    serializer.take_output()
+}
+
+// Returns the index in data of the u8 discriminant of the enum argument with the
+// given index. data must contain one serialized DiagEnumArgumentsTest.
+fn enum_argument_offset(enum_arg_index: usize, data: &[u8]) -> usize {
+    // version, code, message_count, message_index, arg_count, then where_'s
+    // type and span.
+    let first_enum_arg_offset: usize = 1 + 2 + 1 + 1 + 1 + (1 + 8);
+    let offset: usize = first_enum_arg_offset + enum_arg_index * 2 + 1;
+    assert!(offset < data.len());
+    offset
+}
+
+// Check that an out-of-range discriminant for the enum argument is rejected.
+fn assert_enum_argument_is_range_checked(enum_arg_index: usize) {
+    let code = PaddedString::from_slice(b"x");
+    let mut data: Vec<u8> = serialize_enum_arguments(&code, default_enum_arguments_in(&code));
+    let offset: usize = enum_argument_offset(enum_arg_index, &data);
+    data[offset] = 0xff;
+
+    let memory = MonotonicAllocator::new("test");
+    let collector = DiagCollector::new();
+    assert_eq!(
+        replay_serialized_diags(&data, code.view(), &memory, &collector),
+        Err(DiagDeserializeError::InvalidValue),
+        "enum_arg_index={enum_arg_index}",
+    );
+    assert_eq!(collector.len(), 0);
+}
+
+fn serialize_control_character(code: &PaddedString) -> Vec<u8> {
+    let mut serializer = SerializingDiagReporter::new(code.view());
+    report(
+        &serializer,
+        DiagUnexpectedControlCharacter {
+            character: span_of(code, 0, 1),
+            code_point: '\x01',
+        },
+    );
+    serializer.take_output()
+}
+
+fn serialize_control_character_and_unclosed_string(code: &PaddedString) -> Vec<u8> {
+    let mut serializer = SerializingDiagReporter::new(code.view());
+    report(
+        &serializer,
+        DiagUnexpectedControlCharacter {
+            character: span_of(code, 0, 1),
+            code_point: '\x01',
+        },
+    );
+    report(
+        &serializer,
+        DiagUnclosedStringLiteral {
+            string_literal: span_of(code, 1, 2),
+        },
+    );
+    serializer.take_output()
+}
@@ -1,7 +1,9 @@
 use cpp_vs_rust_fe::diagnostic::*;
 use cpp_vs_rust_fe::diagnostic_types::*;
 use cpp_vs_rust_i18n::translation::*;
+use cpp_vs_rust_i18n::translation_table_generated::*;
 use cpp_vs_rust_port::qljs_offset_of;
+use cpp_vs_rust_util::message_format::*;
 
 #[test]
 fn diagnostic_info() {
@@ -14,18 +16,18 @@ fn diagnostic_info() {
         assert_eq!(info.code(), 5);
         assert_eq!(info.severity(), DiagnosticSeverity::Error);
         assert_eq!(
-            source_code_translator.translate(info.message_formats[0]),
+            source_code_translator.translate(info.messages()[0].format),
             "BigInt literal contains decimal point"
         );
         assert_eq!(
-            info.message_args[0][0].offset(),
+            info.messages()[0].args[0].offset(),
             qljs_offset_of!(DiagBigIntLiteralContainsDecimalPoint, where_)
         );
         assert_eq!(
-            info.message_args[0][0].type_(),
+            info.messages()[0].args[0].type_(),
             DiagnosticArgType::SourceCodeSpan
         );
-        assert!(!info.message_formats[1].valid());
+        assert_eq!(info.messages().len(), 1);
     }
 
     {
@@ -34,23 +36,23 @@ fn diagnostic_info() {
         assert_eq!(info.code(), 197);
         assert_eq!(info.severity(), DiagnosticSeverity::Error);
         assert_eq!(
-            source_code_translator.translate(info.message_formats[0]),
+            source_code_translator.translate(info.messages()[0].format),
             "'{0}' is not allowed for strings; use {1} instead"
         );
         assert_eq!(
-            info.message_args[0][0].offset(),
+            info.messages()[0].args[0].offset(),
             qljs_offset_of!(DiagInvalidQuotesAroundStringLiteral, opening_quote)
         );
         assert_eq!(
-            info.message_args[0][0].type_(),
+            info.messages()[0].args[0].type_(),
             DiagnosticArgType::SourceCodeSpan
         );
         assert_eq!(
-            info.message_args[0][1].offset(),
+            info.messages()[0].args[1].offset(),
             qljs_offset_of!(DiagInvalidQuotesAroundStringLiteral, suggested_quote)
         );
-        assert_eq!(info.message_args[0][1].type_(), DiagnosticArgType::Char8);
-        assert!(!info.message_formats[1].valid());
+        assert_eq!(info.messages()[0].args[1].type_(), DiagnosticArgType::Char8);
+        assert_eq!(info.messages().len(), 1);
     }
 
     {
@@ -58,45 +60,151 @@ fn diagnostic_info() {
         assert_eq!(info.code(), 6969);
         assert_eq!(info.severity(), DiagnosticSeverity::Error);
         assert_eq!(
-            source_code_translator.translate(info.message_formats[0]),
+            source_code_translator.translate(info.messages()[0].format),
             "test for multiple messages"
         );
         assert_eq!(
-            info.message_args[0][0].offset(),
+            info.messages()[0].args[0].offset(),
             qljs_offset_of!(DiagMultipleMessageTest, a)
         );
         assert_eq!(
-            info.message_args[0][0].type_(),
+            info.messages()[0].args[0].type_(),
             DiagnosticArgType::SourceCodeSpan
         );
         assert_eq!(
-            source_code_translator.translate(info.message_formats[1]),
+            source_code_translator.translate(info.messages()[1].format),
             "second message here"
         );
         assert_eq!(
-            info.message_args[1][0].offset(),
+            info.messages()[1].args[0].offset(),
             qljs_offset_of!(DiagMultipleMessageTest, b)
         );
         assert_eq!(
-            info.message_args[1][0].type_(),
+            info.messages()[1].args[0].type_(),
             DiagnosticArgType::SourceCodeSpan
         );
+        assert_eq!(info.messages().len(), 2);
     }
 }
 
+#[test]
+fn diagnostic_info_category_and_tags() {
+    let info: &DiagnosticInfo =
+        get_diagnostic_info(DiagType::DiagBigIntLiteralContainsDecimalPoint);
+    assert_eq!(info.category(), DiagnosticCategory::Syntax);
+    assert!(info.tags().is_empty());
+
+    let info: &DiagnosticInfo = get_diagnostic_info(DiagType::DiagIntegerLiteralWillLosePrecision);
+    assert_eq!(info.category(), DiagnosticCategory::Correctness);
+    assert_eq!(info.severity(), DiagnosticSeverity::Warning);
+
+    let info: &DiagnosticInfo =
+        get_diagnostic_info(DiagType::DiagNumberLiteralContainsTrailingUnderscores);
+    assert_eq!(info.category(), DiagnosticCategory::Syntax);
+    assert_eq!(
+        info.tags(),
+        DiagnosticTags::from_tags(&[DiagnosticTag::Unnecessary])
+    );
+
+    let info: &DiagnosticInfo = get_diagnostic_info(DiagType::DiagConfigJSONSyntaxError);
+    assert_eq!(info.category(), DiagnosticCategory::Syntax);
+    let info: &DiagnosticInfo = get_diagnostic_info(DiagType::DiagConfigGlobalsTypeMismatch);
+    assert_eq!(info.category(), DiagnosticCategory::Correctness);
+
+    let info: &DiagnosticInfo = get_diagnostic_info(DiagType::DiagOptionalNoteTest);
+    assert_eq!(info.category(), DiagnosticCategory::Style);
+    assert!(info.tags().contains(DiagnosticTag::Unnecessary));
+    assert!(info.tags().contains(DiagnosticTag::Deprecated));
+    assert_eq!(info.code(), 6970);
+    assert_eq!(info.severity(), DiagnosticSeverity::Error);
+}
+
+#[test]
+fn diagnostic_info_packs_every_category_and_tag_combination() {
+    for category in DiagnosticCategory::ALL {
+        for tags in [
+            &[][..],
+            &[DiagnosticTag::Unnecessary],
+            &[DiagnosticTag::Deprecated],
+            &[DiagnosticTag::Unnecessary, DiagnosticTag::Deprecated],
+        ] {
+            let info = DiagnosticInfo::new(
+                9999,
+                DiagnosticSeverity::Warning,
+                category,
+                DiagnosticTags::from_tags(tags),
+                0,
+                1,
+            );
+            assert_eq!(info.code(), 9999);
+            assert_eq!(info.severity(), DiagnosticSeverity::Warning);
+            assert_eq!(info.category(), category);
+            assert_eq!(info.tags(), DiagnosticTags::from_tags(tags));
+            for tag in [DiagnosticTag::Unnecessary, DiagnosticTag::Deprecated] {
+                assert_eq!(info.tags().contains(tag), tags.contains(&tag));
+            }
+        }
+    }
+}
+
+#[test]
+fn category_filter_overrides_severity() {
+    let syntax_info: &DiagnosticInfo =
+        get_diagnostic_info(DiagType::DiagBigIntLiteralContainsDecimalPoint);
+    let correctness_info: &DiagnosticInfo =
+        get_diagnostic_info(DiagType::DiagIntegerLiteralWillLosePrecision);
+
+    let mut filter = DiagnosticCategoryFilter::new();
+    assert_eq!(
+        filter.severity(syntax_info),
+        Some(DiagnosticSeverity::Error)
+    );
+    assert_eq!(
+        filter.severity(correctness_info),
+        Some(DiagnosticSeverity::Warning)
+    );
+
+    filter.set(DiagnosticCategory::Correctness, CategorySeverity::Error);
+    assert_eq!(
+        filter.severity(syntax_info),
+        Some(DiagnosticSeverity::Error)
+    );
+    assert_eq!(
+        filter.severity(correctness_info),
+        Some(DiagnosticSeverity::Error)
+    );
+
+    filter.set(DiagnosticCategory::Syntax, CategorySeverity::Off);
+    assert_eq!(filter.severity(syntax_info), None);
+    assert_eq!(
+        filter.get(DiagnosticCategory::Syntax),
+        CategorySeverity::Off
+    );
+    assert_eq!(
+        filter.get(DiagnosticCategory::Style),
+        CategorySeverity::Default
+    );
+}
+
 #[test]
 fn diagnostic_message_arg_info_type() {
     for arg_type in [
         DiagnosticArgType::Invalid,
         DiagnosticArgType::Char8,
+        DiagnosticArgType::CodePoint,
         DiagnosticArgType::EnumKind,
         DiagnosticArgType::Identifier,
+        DiagnosticArgType::Integer,
+        DiagnosticArgType::OptionalSourceCodeSpan,
         DiagnosticArgType::SourceCodeSpan,
         DiagnosticArgType::StatementKind,
         DiagnosticArgType::String8View,
+        DiagnosticArgType::TokenType,
         DiagnosticArgType::VariableKind,
     ] {
-        for offset in [0, 2, 4, 6, 8, 10, 12, 14, 16, 24, 32] {
+        for offset in [
+            0, 2, 4, 6, 8, 10, 12, 14, 16, 24, 32, 33, 255, 256, 1000, 65535,
+        ] {
             assert_eq!(
                 DiagnosticMessageArgInfo::new(offset, arg_type).type_(),
                 arg_type,
@@ -104,6 +212,160 @@ fn diagnostic_message_arg_info_type() {
                 arg_type,
                 offset
             );
+            assert_eq!(
+                DiagnosticMessageArgInfo::new(offset, arg_type).offset(),
+                offset,
+                "arg_type={:?} offset={:?}",
+                arg_type,
+                offset
+            );
+        }
+    }
+}
+
+#[test]
+fn diag_type_from_code_finds_registered_codes() {
+    assert_eq!(
+        diag_type_from_code(5),
+        Some(DiagType::DiagBigIntLiteralContainsDecimalPoint)
+    );
+    assert_eq!(
+        diag_type_from_code(197),
+        Some(DiagType::DiagInvalidQuotesAroundStringLiteral)
+    );
+    assert_eq!(
+        diag_type_from_code(6969),
+        Some(DiagType::DiagMultipleMessageTest)
+    );
+    assert_eq!(diag_type_from_code(0), None);
+    assert_eq!(diag_type_from_code(9999), None);
+}
+
+#[test]
+fn diag_type_from_code_string_parses_error_codes() {
+    assert_eq!(
+        diag_type_from_code_string("E0005"),
+        Some(DiagType::DiagBigIntLiteralContainsDecimalPoint)
+    );
+    assert_eq!(
+        diag_type_from_code_string("E6969"),
+        Some(DiagType::DiagMultipleMessageTest)
+    );
+    for bad_code in [
+        "", "E", "E005", "E00005", "e0005", "X0005", "E00a5", "E+005",
+    ] {
+        assert_eq!(diag_type_from_code_string(bad_code), None, "{bad_code:?}");
+    }
+}
+
+#[test]
+fn all_diagnostic_infos_covers_every_diag_type() {
+    assert_eq!(all_diagnostic_infos().len(), DIAG_TYPE_COUNT as usize);
+    for (i, (type_, info)) in all_diagnostic_infos().enumerate() {
+        assert_eq!(type_ as usize, i);
+        assert!(std::ptr::eq(info, get_diagnostic_info(type_)));
+        assert_eq!(diag_type_from_code(info.code()), Some(type_));
+        let code_string: [u8; 5] = info.code_string();
+        assert_eq!(
+            diag_type_from_code_string(std::str::from_utf8(&code_string).unwrap()),
+            Some(type_)
+        );
+        assert!(!info.messages().is_empty());
+    }
+}
+
+#[test]
+fn all_diagnostic_infos_has_untranslated_formats_and_arg_types() {
+    let (_type, info) = all_diagnostic_infos()
+        .find(|(type_, _info)| *type_ == DiagType::DiagInvalidQuotesAroundStringLiteral)
+        .unwrap();
+    assert_eq!(info.severity(), DiagnosticSeverity::Error);
+    assert_eq!(
+        info.messages()[0].untranslated_format(),
+        "'{0}' is not allowed for strings; use {1} instead"
+    );
+    assert_eq!(
+        info.messages()[0].arg_types().collect::<Vec<_>>(),
+        [DiagnosticArgType::SourceCodeSpan, DiagnosticArgType::Char8]
+    );
+
+    let (_type, info) = all_diagnostic_infos()
+        .find(|(type_, _info)| *type_ == DiagType::DiagMultipleMessageTest)
+        .unwrap();
+    let formats: Vec<&str> = info
+        .messages()
+        .iter()
+        .map(|message: &DiagnosticMessageInfo| message.untranslated_format())
+        .collect();
+    assert_eq!(
+        formats,
+        ["test for multiple messages", "second message here"]
+    );
+}
+
+#[test]
+fn diagnostic_info_is_compact() {
+    assert_eq!(std::mem::size_of::<DiagnosticInfo>(), 6);
+}
+
+// The qljs_diagnostic macro checks each message's format, but translations are
+// only checked here.
+#[test]
+fn every_translated_message_format_matches_its_arguments() {
+    let mut translators: Vec<Translator> = vec![Translator::new_using_messages_from_source_code()];
+    for locale_name in TRANSLATION_DATA_LOCALE_TABLE.split('\0') {
+        if !locale_name.is_empty() {
+            let mut translator = Translator::new_using_messages_from_source_code();
+            assert!(translator.use_messages_from_locale(locale_name));
+            translators.push(translator);
+        }
+    }
+
+    for translator in &translators {
+        for (type_, info) in all_diagnostic_infos() {
+            for message in info.messages() {
+                let format: &str = translator.translate(message.format);
+                for part in MessageFormatParser::new(format) {
+                    let (arg_index, modifier): (usize, Option<PlaceholderModifier>) = match part {
+                        Ok(MessageFormatPart::Text(_)) => continue,
+                        Ok(MessageFormatPart::Placeholder {
+                            arg_index,
+                            modifier,
+                        }) => (arg_index, modifier),
+                        Err(error) => panic!(
+                            "{type_:?} in locale {:?}: {format:?}: {error}",
+                            translator.locale_name()
+                        ),
+                    };
+                    assert!(
+                        arg_index < message.args.len(),
+                        "{type_:?} in locale {:?}: {format:?}: placeholder {arg_index} has no argument",
+                        translator.locale_name()
+                    );
+                    let arg_type: DiagnosticArgType = message.args[arg_index].type_();
+                    let is_allowed: bool = match modifier {
+                        None => !matches!(
+                            arg_type,
+                            DiagnosticArgType::EnumKind
+                                | DiagnosticArgType::Invalid
+                                | DiagnosticArgType::StatementKind
+                                | DiagnosticArgType::VariableKind
+                        ),
+                        Some(PlaceholderModifier::Headlinese) => matches!(
+                            arg_type,
+                            DiagnosticArgType::EnumKind | DiagnosticArgType::StatementKind
+                        ),
+                        Some(PlaceholderModifier::Singular) => {
+                            matches!(arg_type, DiagnosticArgType::StatementKind)
+                        }
+                    };
+                    assert!(
+                        is_allowed,
+                        "{type_:?} in locale {:?}: {format:?}: placeholder {arg_index} cannot format {arg_type:?}",
+                        translator.locale_name()
+                    );
+                }
+            }
         }
     }
 }
@@ -5,6 +5,7 @@ use cpp_vs_rust_fe::diagnostic_formatter::*;
 use cpp_vs_rust_fe::identifier::*;
 use cpp_vs_rust_fe::language::*;
 use cpp_vs_rust_fe::source_code_span::*;
+use cpp_vs_rust_fe::token::*;
 use cpp_vs_rust_i18n::qljs_translatable;
 use cpp_vs_rust_i18n::translation::*;
 use cpp_vs_rust_port::qljs_offset_of;
@@ -111,11 +112,10 @@ fn origin_span() {
         "E9999",
         DiagnosticSeverity::Error,
         qljs_translatable!("something happened"),
-        &[
-            DiagnosticMessageArgInfo::new(0, DiagnosticArgType::SourceCodeSpan),
-            DiagnosticMessageArgInfo::empty(),
-            DiagnosticMessageArgInfo::empty(),
-        ],
+        &[DiagnosticMessageArgInfo::new(
+            0,
+            DiagnosticArgType::SourceCodeSpan,
+        )],
         &span as *const _ as *const u8,
     );
 
@@ -130,69 +130,277 @@ fn single_span_simple_message() {
         "E9999",
         DiagnosticSeverity::Error,
         qljs_translatable!("something happened"),
-        &[
-            DiagnosticMessageArgInfo::new(0, DiagnosticArgType::SourceCodeSpan),
-            DiagnosticMessageArgInfo::empty(),
-            DiagnosticMessageArgInfo::empty(),
-        ],
+        &[DiagnosticMessageArgInfo::new(
+            0,
+            DiagnosticArgType::SourceCodeSpan,
+        )],
         &empty_span() as *const _ as *const u8,
     );
     assert_eq!(formatter.message, b"something happened\n");
 }
 
+const SPAN_ARGS: &DiagnosticMessageArgs = &[DiagnosticMessageArgInfo::new(
+    0,
+    DiagnosticArgType::SourceCodeSpan,
+)];
+
+// Tests pass their own messages to format_messages, so the returned
+// DiagnosticInfo's messages are never used.
+fn test_diagnostic_info(
+    severity: DiagnosticSeverity,
+    category: DiagnosticCategory,
+    tags: DiagnosticTags,
+) -> DiagnosticInfo {
+    DiagnosticInfo::new(9999, severity, category, tags, 0, 1)
+}
+
 #[test]
 fn diagnostic_with_single_message() {
-    let info = DiagnosticInfo::new(
-        9999,
+    const MESSAGES: &[DiagnosticMessageInfo] = &[DiagnosticMessageInfo::new(
+        qljs_translatable!("something happened"),
+        SPAN_ARGS,
+    )];
+    let info = test_diagnostic_info(
         DiagnosticSeverity::Error,
-        [
-            qljs_translatable!("something happened"),
-            TranslatableMessage::unallocated(),
-        ],
-        [
-            [
-                DiagnosticMessageArgInfo::new(0, DiagnosticArgType::SourceCodeSpan),
-                DiagnosticMessageArgInfo::empty(),
-                DiagnosticMessageArgInfo::empty(),
-            ],
-            [
-                DiagnosticMessageArgInfo::empty(),
-                DiagnosticMessageArgInfo::empty(),
-                DiagnosticMessageArgInfo::empty(),
-            ],
-        ],
+        DiagnosticCategory::Correctness,
+        DiagnosticTags::none(),
     );
 
     let mut formatter = StringDiagnosticFormatter::new();
-    formatter.format(&info, &empty_span() as *const _ as *const u8);
+    formatter.format_messages(
+        &info,
+        MESSAGES,
+        info.severity(),
+        &empty_span() as *const _ as *const u8,
+    );
     assert_eq!(formatter.message, b"something happened\n");
 }
 
 #[test]
 fn diagnostic_with_two_messages() {
-    let info = DiagnosticInfo::new(
-        9999,
+    const MESSAGES: &[DiagnosticMessageInfo] = &[
+        DiagnosticMessageInfo::new(qljs_translatable!("something happened"), SPAN_ARGS),
+        DiagnosticMessageInfo::new(qljs_translatable!("see here"), SPAN_ARGS),
+    ];
+    let info = test_diagnostic_info(
         DiagnosticSeverity::Error,
+        DiagnosticCategory::Correctness,
+        DiagnosticTags::none(),
+    );
+
+    let mut formatter = StringDiagnosticFormatter::new();
+    formatter.format_messages(
+        &info,
+        MESSAGES,
+        info.severity(),
+        &empty_span() as *const _ as *const u8,
+    );
+    assert_eq!(formatter.message, b"something happened\nsee here\n");
+}
+
+#[test]
+fn diagnostic_with_many_notes() {
+    struct SeverityRecordingFormatter {
+        severities: Vec<DiagnosticSeverity>,
+    }
+
+    impl DiagnosticFormatter for SeverityRecordingFormatter {
+        fn write_before_message(
+            &mut self,
+            _code: &str,
+            severity: DiagnosticSeverity,
+            _origin: SourceCodeSpan<'_>,
+        ) {
+            self.severities.push(severity);
+        }
+
+        fn write_message_part(
+            &mut self,
+            _code: &str,
+            _severity: DiagnosticSeverity,
+            _message_part: &[u8],
+        ) {
+        }
+
+        fn write_after_message(
+            &mut self,
+            _code: &str,
+            _severity: DiagnosticSeverity,
+            _origin: SourceCodeSpan<'_>,
+        ) {
+        }
+
+        fn translator(&self) -> Translator {
+            Translator::new_using_messages_from_source_code()
+        }
+    }
+
+    const MESSAGES: &[DiagnosticMessageInfo] = &[
+        DiagnosticMessageInfo::new(qljs_translatable!("something happened"), SPAN_ARGS),
+        DiagnosticMessageInfo::new(qljs_translatable!("see here"), SPAN_ARGS),
+        DiagnosticMessageInfo::new(qljs_translatable!("see here"), SPAN_ARGS),
+        DiagnosticMessageInfo::new(qljs_translatable!("see here"), SPAN_ARGS),
+    ];
+    let info = test_diagnostic_info(
+        DiagnosticSeverity::Warning,
+        DiagnosticCategory::Correctness,
+        DiagnosticTags::none(),
+    );
+
+    let mut formatter = StringDiagnosticFormatter::new();
+    formatter.format_messages(
+        &info,
+        MESSAGES,
+        info.severity(),
+        &empty_span() as *const _ as *const u8,
+    );
+    assert_eq!(
+        formatter.message,
+        b"something happened\nsee here\nsee here\nsee here\n"
+    );
+
+    let mut formatter = SeverityRecordingFormatter { severities: vec![] };
+    formatter.format_messages(
+        &info,
+        MESSAGES,
+        info.severity(),
+        &empty_span() as *const _ as *const u8,
+    );
+    assert_eq!(
+        formatter.severities,
         [
-            qljs_translatable!("something happened"),
-            qljs_translatable!("see here"),
-        ],
-        [
-            [
-                DiagnosticMessageArgInfo::new(0, DiagnosticArgType::SourceCodeSpan),
-                DiagnosticMessageArgInfo::empty(),
-                DiagnosticMessageArgInfo::empty(),
-            ],
-            [
-                DiagnosticMessageArgInfo::new(0, DiagnosticArgType::SourceCodeSpan),
-                DiagnosticMessageArgInfo::empty(),
-                DiagnosticMessageArgInfo::empty(),
-            ],
-        ],
+            DiagnosticSeverity::Warning,
+            DiagnosticSeverity::Note,
+            DiagnosticSeverity::Note,
+            DiagnosticSeverity::Note,
+        ]
+    );
+}
+
+#[test]
+fn format_reports_category_and_tags_before_messages() {
+    struct RecordingFormatter {
+        events: Vec<String>,
+    }
+
+    impl DiagnosticFormatter for RecordingFormatter {
+        fn write_before_message(
+            &mut self,
+            _code: &str,
+            severity: DiagnosticSeverity,
+            _origin: SourceCodeSpan<'_>,
+        ) {
+            self.events.push(format!("message {severity:?}"));
+        }
+
+        fn write_message_part(
+            &mut self,
+            _code: &str,
+            _severity: DiagnosticSeverity,
+            _message_part: &[u8],
+        ) {
+        }
+
+        fn write_after_message(
+            &mut self,
+            _code: &str,
+            _severity: DiagnosticSeverity,
+            _origin: SourceCodeSpan<'_>,
+        ) {
+        }
+
+        fn translator(&self) -> Translator {
+            Translator::new_using_messages_from_source_code()
+        }
+
+        fn write_category_and_tags(&mut self, category: DiagnosticCategory, tags: DiagnosticTags) {
+            self.events.push(format!("{category:?} {:?}", tags.bits()));
+        }
+    }
+
+    const MESSAGES: &[DiagnosticMessageInfo] = &[
+        DiagnosticMessageInfo::new(qljs_translatable!("something happened"), SPAN_ARGS),
+        DiagnosticMessageInfo::new(qljs_translatable!("see here"), SPAN_ARGS),
+    ];
+    let info = test_diagnostic_info(
+        DiagnosticSeverity::Error,
+        DiagnosticCategory::Style,
+        DiagnosticTags::from_tags(&[DiagnosticTag::Deprecated]),
+    );
+
+    let mut formatter = RecordingFormatter { events: vec![] };
+    formatter.format_messages(
+        &info,
+        MESSAGES,
+        info.severity(),
+        &empty_span() as *const _ as *const u8,
+    );
+    assert_eq!(
+        formatter.events,
+        ["Style 2", "message Error", "message Note"]
+    );
+
+    let mut formatter = RecordingFormatter { events: vec![] };
+    formatter.format_messages(
+        &info,
+        MESSAGES,
+        DiagnosticSeverity::Warning,
+        &empty_span() as *const _ as *const u8,
+    );
+    assert_eq!(
+        formatter.events,
+        ["Style 2", "message Warning", "message Note"]
+    );
+}
+
+#[test]
+fn diagnostic_with_missing_optional_note() {
+    struct TestDiag {
+        span: SourceCodeSpan<'static>,
+        note_span: Option<SourceCodeSpan<'static>>,
+    }
+    const MAIN_ARGS: &DiagnosticMessageArgs = &[DiagnosticMessageArgInfo::new(
+        qljs_offset_of!(TestDiag, span),
+        DiagnosticArgType::SourceCodeSpan,
+    )];
+    const NOTE_ARGS: &DiagnosticMessageArgs = &[DiagnosticMessageArgInfo::new(
+        qljs_offset_of!(TestDiag, note_span),
+        DiagnosticArgType::OptionalSourceCodeSpan,
+    )];
+    const MESSAGES: &[DiagnosticMessageInfo] = &[
+        DiagnosticMessageInfo::new(qljs_translatable!("something happened"), MAIN_ARGS),
+        DiagnosticMessageInfo::new(qljs_translatable!("see here"), NOTE_ARGS),
+    ];
+    let info = test_diagnostic_info(
+        DiagnosticSeverity::Error,
+        DiagnosticCategory::Correctness,
+        DiagnosticTags::none(),
+    );
+
+    let diag = TestDiag {
+        span: empty_span(),
+        note_span: None,
+    };
+    let mut formatter = StringDiagnosticFormatter::new();
+    formatter.format_messages(
+        &info,
+        MESSAGES,
+        info.severity(),
+        &diag as *const _ as *const u8,
     );
+    assert_eq!(formatter.message, b"something happened\n");
 
+    let diag = TestDiag {
+        span: empty_span(),
+        note_span: Some(empty_span()),
+    };
     let mut formatter = StringDiagnosticFormatter::new();
-    formatter.format(&info, &empty_span() as *const _ as *const u8);
+    formatter.format_messages(
+        &info,
+        MESSAGES,
+        info.severity(),
+        &diag as *const _ as *const u8,
+    );
     assert_eq!(formatter.message, b"something happened\nsee here\n");
 }
 
@@ -206,11 +414,10 @@ fn message_with_zero_placeholder() {
         "E9999",
         DiagnosticSeverity::Error,
         qljs_translatable!("this {0} looks fishy"),
-        &[
-            DiagnosticMessageArgInfo::new(0, DiagnosticArgType::SourceCodeSpan),
-            DiagnosticMessageArgInfo::empty(),
-            DiagnosticMessageArgInfo::empty(),
-        ],
+        &[DiagnosticMessageArgInfo::new(
+            0,
+            DiagnosticArgType::SourceCodeSpan,
+        )],
         &hello_span as *const _ as *const u8,
     );
     assert_eq!(formatter.message, b"this hello looks fishy\n");
@@ -243,7 +450,6 @@ fn message_with_extra_identifier_placeholder() {
                 qljs_offset_of!(TestDiag, world),
                 DiagnosticArgType::Identifier,
             ),
-            DiagnosticMessageArgInfo::empty(),
         ],
         &diag as *const _ as *const u8,
     );
@@ -291,6 +497,56 @@ fn message_with_multiple_span_placeholders() {
     assert_eq!(formatter.message, b"free me and let me be\n");
 }
 
+#[test]
+fn message_with_many_placeholders() {
+    const CODE: &'static [u8] = b"a b c d e";
+    struct TestDiag {
+        a: SourceCodeSpan<'static>,
+        b: SourceCodeSpan<'static>,
+        c: SourceCodeSpan<'static>,
+        d: SourceCodeSpan<'static>,
+        e: SourceCodeSpan<'static>,
+    }
+    let diag = TestDiag {
+        a: SourceCodeSpan::from_slice(&CODE[0..1]),
+        b: SourceCodeSpan::from_slice(&CODE[2..3]),
+        c: SourceCodeSpan::from_slice(&CODE[4..5]),
+        d: SourceCodeSpan::from_slice(&CODE[6..7]),
+        e: SourceCodeSpan::from_slice(&CODE[8..9]),
+    };
+
+    let mut formatter = StringDiagnosticFormatter::new();
+    formatter.format_message(
+        "E9999",
+        DiagnosticSeverity::Error,
+        qljs_translatable!("{4}{3}{2}{1}{0}"),
+        &[
+            DiagnosticMessageArgInfo::new(
+                qljs_offset_of!(TestDiag, a),
+                DiagnosticArgType::SourceCodeSpan,
+            ),
+            DiagnosticMessageArgInfo::new(
+                qljs_offset_of!(TestDiag, b),
+                DiagnosticArgType::SourceCodeSpan,
+            ),
+            DiagnosticMessageArgInfo::new(
+                qljs_offset_of!(TestDiag, c),
+                DiagnosticArgType::SourceCodeSpan,
+            ),
+            DiagnosticMessageArgInfo::new(
+                qljs_offset_of!(TestDiag, d),
+                DiagnosticArgType::SourceCodeSpan,
+            ),
+            DiagnosticMessageArgInfo::new(
+                qljs_offset_of!(TestDiag, e),
+                DiagnosticArgType::SourceCodeSpan,
+            ),
+        ],
+        &diag as *const _ as *const u8,
+    );
+    assert_eq!(formatter.message, b"edcba\n");
+}
+
 #[test]
 fn message_with_char_placeholder() {
     struct TestDiag {
@@ -312,13 +568,129 @@ fn message_with_char_placeholder() {
                 DiagnosticArgType::SourceCodeSpan,
             ),
             DiagnosticMessageArgInfo::new(qljs_offset_of!(TestDiag, c), DiagnosticArgType::Char8),
-            DiagnosticMessageArgInfo::empty(),
         ],
         &diag as *const _ as *const u8,
     );
     assert_eq!(formatter.message, b"what is this 'Q' nonsense?\n");
 }
 
+#[test]
+fn message_with_integer_placeholder() {
+    struct TestDiag {
+        span: SourceCodeSpan<'static>,
+        count: usize,
+    }
+    let message_args: [DiagnosticMessageArgInfo; 2] = [
+        DiagnosticMessageArgInfo::new(
+            qljs_offset_of!(TestDiag, span),
+            DiagnosticArgType::SourceCodeSpan,
+        ),
+        DiagnosticMessageArgInfo::new(qljs_offset_of!(TestDiag, count), DiagnosticArgType::Integer),
+    ];
+    for (count_value, expected_message) in [
+        (0, "found 0 problems\n"),
+        (42, "found 42 problems\n"),
+        (12345, "found 12,345 problems\n"),
+    ] {
+        let diag = TestDiag {
+            span: empty_span(),
+            count: count_value,
+        };
+        let mut formatter = StringDiagnosticFormatter::new();
+        formatter.format_message(
+            "E9999",
+            DiagnosticSeverity::Error,
+            qljs_translatable!("found {1} problems"),
+            &message_args,
+            &diag as *const _ as *const u8,
+        );
+        assert_eq!(
+            String::from_utf8(formatter.message).unwrap(),
+            expected_message
+        );
+    }
+}
+
+#[test]
+fn message_with_code_point_placeholder() {
+    struct TestDiag {
+        span: SourceCodeSpan<'static>,
+        character: char,
+    }
+    let message_args: [DiagnosticMessageArgInfo; 2] = [
+        DiagnosticMessageArgInfo::new(
+            qljs_offset_of!(TestDiag, span),
+            DiagnosticArgType::SourceCodeSpan,
+        ),
+        DiagnosticMessageArgInfo::new(
+            qljs_offset_of!(TestDiag, character),
+            DiagnosticArgType::CodePoint,
+        ),
+    ];
+    for (character_value, expected_message) in [
+        ('\u{00a0}', "unexpected character U+00A0\n"),
+        ('\u{2603}', "unexpected character U+2603\n"),
+        ('\u{1f600}', "unexpected character U+1F600\n"),
+    ] {
+        let diag = TestDiag {
+            span: empty_span(),
+            character: character_value,
+        };
+        let mut formatter = StringDiagnosticFormatter::new();
+        formatter.format_message(
+            "E9999",
+            DiagnosticSeverity::Error,
+            qljs_translatable!("unexpected character {1}"),
+            &message_args,
+            &diag as *const _ as *const u8,
+        );
+        assert_eq!(
+            String::from_utf8(formatter.message).unwrap(),
+            expected_message
+        );
+    }
+}
+
+#[test]
+fn message_with_token_type_placeholder() {
+    struct TestDiag {
+        span: SourceCodeSpan<'static>,
+        token: TokenType,
+    }
+    let message_args: [DiagnosticMessageArgInfo; 2] = [
+        DiagnosticMessageArgInfo::new(
+            qljs_offset_of!(TestDiag, span),
+            DiagnosticArgType::SourceCodeSpan,
+        ),
+        DiagnosticMessageArgInfo::new(
+            qljs_offset_of!(TestDiag, token),
+            DiagnosticArgType::TokenType,
+        ),
+    ];
+    for (token_value, expected_message) in [
+        (TokenType::QuestionQuestionEqual, "unexpected '??='\n"),
+        (TokenType::Semicolon, "unexpected ';'\n"),
+        (TokenType::KWAwait, "unexpected 'await'\n"),
+    ] {
+        let diag = TestDiag {
+            span: empty_span(),
+            token: token_value,
+        };
+        let mut formatter = StringDiagnosticFormatter::new();
+        formatter.format_message(
+            "E9999",
+            DiagnosticSeverity::Error,
+            qljs_translatable!("unexpected '{1}'"),
+            &message_args,
+            &diag as *const _ as *const u8,
+        );
+        assert_eq!(
+            String::from_utf8(formatter.message).unwrap(),
+            expected_message
+        );
+    }
+}
+
 #[test]
 fn message_with_escaped_curlies() {
     const CODE: &'static [u8] = b"hello world";
@@ -329,29 +701,46 @@ fn message_with_escaped_curlies() {
         "E9999",
         DiagnosticSeverity::Error,
         qljs_translatable!("a {{0} b }} c"),
-        &[
-            DiagnosticMessageArgInfo::new(0, DiagnosticArgType::SourceCodeSpan),
-            DiagnosticMessageArgInfo::empty(),
-            DiagnosticMessageArgInfo::empty(),
-        ],
+        &[DiagnosticMessageArgInfo::new(
+            0,
+            DiagnosticArgType::SourceCodeSpan,
+        )],
         &code_span as *const _ as *const u8,
     );
     assert_eq!(formatter.message, b"a {0} b }} c\n");
 }
 
+#[test]
+fn malformed_placeholder_is_written_verbatim() {
+    const CODE: &'static [u8] = b"hello world";
+    let code_span = SourceCodeSpan::from_slice(&CODE[0..5]);
+
+    let mut formatter = StringDiagnosticFormatter::new();
+    formatter.format_message(
+        "E9999",
+        DiagnosticSeverity::Error,
+        qljs_translatable!("{0} is {0:plural} and {1}"),
+        &[DiagnosticMessageArgInfo::new(
+            0,
+            DiagnosticArgType::SourceCodeSpan,
+        )],
+        &code_span as *const _ as *const u8,
+    );
+    assert_eq!(formatter.message, b"hello is {0:plural} and {1}\n");
+}
+
 #[test]
 fn enum_kind_placeholder() {
     struct TestDiag {
         empty_span: SourceCodeSpan<'static>,
         kind: EnumKind,
     }
-    let message_args: DiagnosticMessageArgs = [
+    let message_args: [DiagnosticMessageArgInfo; 2] = [
         DiagnosticMessageArgInfo::new(
             qljs_offset_of!(TestDiag, empty_span),
             DiagnosticArgType::SourceCodeSpan,
         ),
         DiagnosticMessageArgInfo::new(qljs_offset_of!(TestDiag, kind), DiagnosticArgType::EnumKind),
-        DiagnosticMessageArgInfo::empty(),
     ];
 
     {
@@ -377,7 +766,7 @@ fn statement_kind_placeholder() {
         empty_span: SourceCodeSpan<'static>,
         statement: StatementKind,
     }
-    let message_args: DiagnosticMessageArgs = [
+    let message_args: [DiagnosticMessageArgInfo; 2] = [
         DiagnosticMessageArgInfo::new(
             qljs_offset_of!(TestDiag, empty_span),
             DiagnosticArgType::SourceCodeSpan,
@@ -386,7 +775,6 @@ fn statement_kind_placeholder() {
             qljs_offset_of!(TestDiag, statement),
             DiagnosticArgType::StatementKind,
         ),
-        DiagnosticMessageArgInfo::empty(),
     ];
 
     {
@@ -1,7 +1,9 @@
 use cpp_vs_rust_c_api::web_demo_location::*;
+use cpp_vs_rust_fe::cli_location::*;
 use cpp_vs_rust_fe::document::*;
-
-// TODO(port-later): Test on LSPLocator in addition to WebDemoLocator.
+use cpp_vs_rust_fe::lsp_location::*;
+use cpp_vs_rust_fe::source_code_span::*;
+use cpp_vs_rust_util::padded_string::*;
 
 #[test]
 fn set_text() {
@@ -19,3 +21,188 @@ fn set_text_multiple_times() {
     doc.set_text(b"finally");
     assert_eq!(doc.string().slice(), b"finally");
 }
+
+#[test]
+fn replace_text_at_start() {
+    let mut doc: Document<WebDemoLocator> = Document::new();
+    doc.set_text(b"content goes here");
+    doc.replace_text(WebDemoSourceRange { begin: 0, end: 7 }, b"stuff");
+    assert_eq!(doc.string().slice(), b"stuff goes here");
+}
+
+#[test]
+fn replace_text_in_middle() {
+    let mut doc: Document<WebDemoLocator> = Document::new();
+    doc.set_text(b"content goes here");
+    doc.replace_text(WebDemoSourceRange { begin: 8, end: 12 }, b"was");
+    assert_eq!(doc.string().slice(), b"content was here");
+}
+
+#[test]
+fn replace_text_at_end() {
+    let mut doc: Document<WebDemoLocator> = Document::new();
+    doc.set_text(b"content goes here");
+    doc.replace_text(WebDemoSourceRange { begin: 17, end: 17 }, b"!!!");
+    assert_eq!(doc.string().slice(), b"content goes here!!!");
+}
+
+#[test]
+fn replace_text_deleting_everything() {
+    let mut doc: Document<WebDemoLocator> = Document::new();
+    doc.set_text(b"content goes here");
+    doc.replace_text(WebDemoSourceRange { begin: 0, end: 17 }, b"");
+    assert_eq!(doc.string().slice(), b"");
+}
+
+#[test]
+fn replace_text_with_reversed_range_swaps_begin_and_end() {
+    let mut doc: Document<WebDemoLocator> = Document::new();
+    doc.set_text(b"content goes here");
+    doc.replace_text(WebDemoSourceRange { begin: 12, end: 8 }, b"was");
+    assert_eq!(doc.string().slice(), b"content was here");
+}
+
+#[test]
+fn replace_text_past_end_is_clamped_to_end() {
+    let mut doc: Document<WebDemoLocator> = Document::new();
+    doc.set_text(b"content goes here");
+    doc.replace_text(
+        WebDemoSourceRange {
+            begin: 13,
+            end: 100,
+        },
+        b"there",
+    );
+    assert_eq!(doc.string().slice(), b"content goes there");
+    doc.replace_text(
+        WebDemoSourceRange {
+            begin: 200,
+            end: 100,
+        },
+        b"!",
+    );
+    assert_eq!(doc.string().slice(), b"content goes there!");
+}
+
+#[test]
+fn replace_text_multiple_times() {
+    let mut doc: Document<WebDemoLocator> = Document::new();
+    doc.set_text(b"abc");
+    doc.replace_text(WebDemoSourceRange { begin: 1, end: 2 }, b"BBB");
+    assert_eq!(doc.string().slice(), b"aBBBc");
+    doc.replace_text(WebDemoSourceRange { begin: 0, end: 1 }, b"");
+    assert_eq!(doc.string().slice(), b"BBBc");
+    doc.replace_text(WebDemoSourceRange { begin: 4, end: 4 }, b"d");
+    assert_eq!(doc.string().slice(), b"BBBcd");
+}
+
+#[test]
+fn replace_text_after_multi_byte_characters() {
+    let mut doc: Document<WebDemoLocator> = Document::new();
+    // U+00E9 is one UTF-16 code unit. U+1F430 is two UTF-16 code units.
+    doc.set_text("\u{00e9}\u{01f430}x".as_bytes());
+    doc.replace_text(WebDemoSourceRange { begin: 3, end: 4 }, b"y");
+    assert_eq!(doc.string().slice(), "\u{00e9}\u{01f430}y".as_bytes());
+}
+
+#[test]
+fn replace_text_across_multi_byte_characters() {
+    let mut doc: Document<WebDemoLocator> = Document::new();
+    doc.set_text("a\u{00e9}\u{01f430}b".as_bytes());
+    doc.replace_text(
+        WebDemoSourceRange { begin: 1, end: 4 },
+        "\u{2306}".as_bytes(),
+    );
+    assert_eq!(doc.string().slice(), "a\u{2306}b".as_bytes());
+}
+
+#[test]
+fn locator_refers_to_new_text_after_replace_text() {
+    let mut doc: Document<WebDemoLocator> = Document::new();
+    doc.set_text(b"hello");
+    doc.replace_text(
+        WebDemoSourceRange { begin: 0, end: 0 },
+        "\u{01f430}".as_bytes(),
+    );
+    let text: PaddedStringView = doc.string();
+    let o_span: SourceCodeSpan =
+        unsafe { SourceCodeSpan::new(text.c_str().add(8), text.c_str().add(9)) };
+    let range: WebDemoSourceRange = doc.locator().range(o_span);
+    assert_eq!(range.begin, 6);
+    assert_eq!(range.end, 7);
+}
+
+#[test]
+fn cli_locator_line_numbers_update_after_replace_text() {
+    let mut doc: Document<CLILocator> = Document::new();
+    doc.set_text(b"one\ntwo\nthree");
+    let two_position: CLISourcePosition = CLISourcePosition {
+        line_number: 2,
+        column_number: 1,
+        offset: 4,
+    };
+    doc.replace_text(
+        CLISourceRange {
+            begin: two_position,
+            end: two_position,
+        },
+        b"inserted\n",
+    );
+    assert_eq!(doc.string().slice(), b"one\ninserted\ntwo\nthree");
+    assert_eq!(doc.locator().line_text(3), b"two");
+    assert_eq!(doc.locator().line_text(4), b"three");
+}
+
+#[test]
+fn cli_locator_pointer_at_position_past_end_is_end_of_input() {
+    let code = PaddedString::from_slice(b"one\ntwo");
+    let l = CLILocator::new(code.view());
+    let past_end: CLISourcePosition = CLISourcePosition {
+        line_number: 2,
+        column_number: 100,
+        offset: 100,
+    };
+    assert_eq!(l.pointer_at_position(past_end), code.null_terminator());
+}
+
+#[test]
+fn lsp_replace_text_on_later_line() {
+    let mut doc: Document<LSPLocator> = Document::new();
+    doc.set_text(b"first\nsecond\nthird");
+    doc.replace_text(
+        LSPRange {
+            start: LSPPosition {
+                line: 1,
+                character: 0,
+            },
+            end: LSPPosition {
+                line: 2,
+                character: 2,
+            },
+        },
+        b"2nd\nTH",
+    );
+    assert_eq!(doc.string().slice(), b"first\n2nd\nTHird");
+    assert_eq!(doc.locator().offset_of_lines(), &[0, 6, 10]);
+}
+
+#[test]
+fn lsp_replace_text_with_reversed_range_swaps_start_and_end() {
+    let mut doc: Document<LSPLocator> = Document::new();
+    doc.set_text(b"first\nsecond\nthird");
+    doc.replace_text(
+        LSPRange {
+            start: LSPPosition {
+                line: 2,
+                character: 2,
+            },
+            end: LSPPosition {
+                line: 1,
+                character: 0,
+            },
+        },
+        b"2nd\nTH",
+    );
+    assert_eq!(doc.string().slice(), b"first\n2nd\nTHird");
+    assert_eq!(doc.locator().offset_of_lines(), &[0, 6, 10]);
+}
@@ -0,0 +1,105 @@
+use cpp_vs_rust_fe::diag_reporter::*;
+use cpp_vs_rust_fe::diagnostic_types::*;
+use cpp_vs_rust_fe::json_diag_reporter::*;
+use cpp_vs_rust_i18n::translation::*;
+use cpp_vs_rust_test::span::*;
+use cpp_vs_rust_util::json::*;
+use cpp_vs_rust_util::padded_string::*;
+
+#[test]
+fn no_diagnostics_is_empty_array() {
+    let mut reporter = JSONDiagReporter::new(Translator::new_using_messages_from_source_code());
+    assert_eq!(reporter.finish(), b"[]\n");
+}
+
+#[test]
+fn diagnostic_has_file_code_severity_message_and_range() {
+    let input = PaddedString::from_slice(b"let x;\nlet y = 'hi");
+    let mut reporter = JSONDiagReporter::new(Translator::new_using_messages_from_source_code());
+    reporter.set_source(input.view(), "src/hello.js");
+    report(
+        &reporter,
+        DiagUnclosedStringLiteral {
+            string_literal: span_of(&input, 15, 18),
+        },
+    );
+    assert_eq!(
+        String::from_utf8(reporter.finish()).unwrap(),
+        "[{\"file\":\"src/hello.js\",\"code\":\"E0040\",\"severity\":\"error\",\
+         \"message\":\"unclosed string literal\",\
+         \"begin\":{\"line\":2,\"column\":9},\"end\":{\"line\":2,\"column\":12},\
+         \"notes\":[]}]\n"
+    );
+}
+
+#[test]
+fn notes_have_message_and_range() {
+    let input = PaddedString::from_slice(b"aaa\nbbb");
+    let mut reporter = JSONDiagReporter::new(Translator::new_using_messages_from_source_code());
+    reporter.set_source(input.view(), "test.js");
+    report(
+        &reporter,
+        DiagMultipleMessageTest {
+            a: span_of(&input, 0, 3),
+            b: span_of(&input, 5, 6),
+        },
+    );
+    let output: Vec<u8> = reporter.finish();
+    assert!(String::from_utf8(output).unwrap().contains(
+        "\"notes\":[{\"message\":\"second message here\",\
+         \"begin\":{\"line\":2,\"column\":2},\"end\":{\"line\":2,\"column\":3}}]"
+    ));
+}
+
+#[test]
+fn output_is_valid_json_with_escaped_strings() {
+    let input = PaddedString::from_slice(b"\\");
+    let mut translator = Translator::new_using_messages_from_source_code();
+    assert!(translator.use_messages_from_locale("en_US@snarky"));
+    let mut reporter = JSONDiagReporter::new(translator);
+    reporter.set_source(input.view(), "dir\\\"quoted\".js");
+    report(
+        &reporter,
+        DiagUnclosedStringLiteral {
+            string_literal: span_of(&input, 0, 1),
+        },
+    );
+    let output: Vec<u8> = reporter.finish();
+    let diagnostics: JSONValue = parse_json(&output).unwrap();
+    let diagnostic: &JSONValue = &diagnostics.as_array().unwrap()[0];
+    assert_eq!(
+        diagnostic.get("file").and_then(JSONValue::as_str),
+        Some("dir\\\"quoted\".js")
+    );
+    assert_eq!(
+        diagnostic.get("message").and_then(JSONValue::as_str),
+        Some("\"unclosed string literal")
+    );
+}
+
+#[test]
+fn take_diagnostics_returns_diagnostics_from_each_file() {
+    let input_1 = PaddedString::from_slice(b"@");
+    let input_2 = PaddedString::from_slice(b"#");
+    let mut reporter = JSONDiagReporter::new(Translator::new_using_messages_from_source_code());
+    reporter.set_source(input_1.view(), "one.js");
+    report(
+        &reporter,
+        DiagUnexpectedAtCharacter {
+            character: span_of(&input_1, 0, 1),
+        },
+    );
+    let one: String = String::from_utf8(reporter.take_diagnostics()).unwrap();
+    assert!(one.starts_with("{\"file\":\"one.js\",\"code\":\"E0042\""));
+
+    reporter.set_source(input_2.view(), "two.js");
+    report(
+        &reporter,
+        DiagUnexpectedHashCharacter {
+            where_: span_of(&input_2, 0, 1),
+        },
+    );
+    let two: String = String::from_utf8(reporter.take_diagnostics()).unwrap();
+    assert!(two.starts_with("{\"file\":\"two.js\",\"code\":\"E0052\""));
+    assert_eq!(reporter.finish(), b"[]\n");
+}
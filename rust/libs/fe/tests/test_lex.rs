@@ -3278,6 +3278,12 @@ fn ascii_control_characters_are_disallowed() {
                 character: 0..(control_character.as_bytes()),
             },
         );
+        let expected_code_point: char = control_character.chars().next().unwrap();
+        assert_matches!(
+            v.index(0),
+            AnyDiag::DiagUnexpectedControlCharacter(diag)
+                if diag.code_point == expected_code_point,
+        );
     }
 }
 
@@ -3694,6 +3700,63 @@ fn unfinished_transaction_does_not_leak_memory() {
     // Don't end either transaction. The leak checker should report no leaks.
 }
 
+#[test]
+fn source_code_does_not_look_binary() {
+    assert!(!looks_like_binary(PaddedStringView::from_slice(b"")));
+    assert!(!looks_like_binary(PaddedStringView::from_slice(
+        b"let x = 42;\r\n\tconsole.log(x);\x0b\x0c\n"
+    )));
+    // A few control characters, such as in string literals, are fine.
+    assert!(!looks_like_binary(PaddedStringView::from_slice(
+        b"'hello\0world' '\x01\x02'"
+    )));
+}
+
+#[test]
+fn mostly_control_characters_looks_binary() {
+    assert!(looks_like_binary(PaddedStringView::from_slice(&[0u8; 64])));
+    assert!(looks_like_binary(PaddedStringView::from_slice(
+        b"\x7fELF\x02\x01\x01\0\0\0\0\0\0\0\0\0\x03\0>\0\x01\0\0\0"
+    )));
+}
+
+#[test]
+fn only_beginning_of_input_is_checked_for_binary() {
+    let mut input: Vec<u8> = vec![b' '; 1024];
+    input.extend_from_slice(&[0u8; 1024]);
+    assert!(!looks_like_binary(PaddedStringView::from_slice(&input)));
+}
+
+#[test]
+fn lexer_bails_out_on_binary_input() {
+    let v = DiagCollector::new();
+    let input = PaddedString::from_slice(&[1u8; 100]);
+    let allocator = LexerAllocator::new();
+    let l = Lexer::new_bailing_out_on_binary(input.view(), &v, &allocator);
+    assert_eq!(l.peek().type_, TokenType::EndOfFile);
+
+    assert_eq!(v.len(), 1);
+    assert_matches!(
+        v.index(0),
+        AnyDiag::DiagFileLooksBinary(diag)
+            if same_pointers(diag.where_, unsafe { SourceCodeSpan::new(input.c_str(), input.c_str()) }),
+    );
+}
+
+#[test]
+fn lexer_does_not_bail_out_on_source_code() {
+    let v = DiagCollector::new();
+    let input = PaddedString::from_slice(b"hello world");
+    let allocator = LexerAllocator::new();
+    let mut l = Lexer::new_bailing_out_on_binary(input.view(), &v, &allocator);
+    assert_eq!(l.peek().type_, TokenType::Identifier);
+    l.skip();
+    assert_eq!(l.peek().type_, TokenType::Identifier);
+    l.skip();
+    assert_eq!(l.peek().type_, TokenType::EndOfFile);
+    assert_eq!(v.len(), 0);
+}
+
 #[test]
 fn is_initial_identifier_byte_agrees_with_is_initial_identifier_character() {
     const MIN_CODE_POINT: char = '\0';
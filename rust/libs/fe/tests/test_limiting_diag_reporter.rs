@@ -0,0 +1,142 @@
+use cpp_vs_rust_fe::diag_reporter::*;
+use cpp_vs_rust_fe::diagnostic::*;
+use cpp_vs_rust_fe::diagnostic_types::*;
+use cpp_vs_rust_fe::limiting_diag_reporter::*;
+use cpp_vs_rust_fe::source_code_span::*;
+use cpp_vs_rust_port::assert_matches;
+use cpp_vs_rust_test::diag_collector::*;
+use cpp_vs_rust_test::span::*;
+use cpp_vs_rust_util::padded_string::*;
+
+#[test]
+fn unlimited_reporter_forwards_everything() {
+    let code = PaddedString::from_slice(b"\x01\x02\x03");
+
+    let collector = DiagCollector::new();
+    let limiter = LimitingDiagReporter::new(&collector, DiagnosticLimits::unlimited());
+    for i in 0..3 {
+        report_control_character(&limiter, &code, i);
+    }
+
+    assert_eq!(collector.len(), 3);
+    assert_eq!(limiter.dropped_count(), 0);
+    assert!(!limiter.is_total_limit_reached());
+    assert!(limiter.summary(code.view()).is_none());
+}
+
+#[test]
+fn total_limit_drops_later_diagnostics_and_reports_summary_once() {
+    let code = PaddedString::from_slice(b"\x01\x02\x03\x04\x05'");
+
+    let collector = DiagCollector::new();
+    let limiter = LimitingDiagReporter::new(
+        &collector,
+        DiagnosticLimits {
+            max_total: Some(2),
+            max_per_code: None,
+        },
+    );
+    for i in 0..5 {
+        report_control_character(&limiter, &code, i);
+    }
+    report(
+        &limiter,
+        DiagUnclosedStringLiteral {
+            string_literal: span_of(&code, 5, 6),
+        },
+    );
+
+    assert_eq!(collector.len(), 2);
+    assert_matches!(
+        collector.index(0),
+        AnyDiag::DiagUnexpectedControlCharacter(diag)
+            if same_pointers(diag.character, span_of(&code, 0, 1)),
+    );
+    assert_matches!(
+        collector.index(1),
+        AnyDiag::DiagUnexpectedControlCharacter(diag)
+            if same_pointers(diag.character, span_of(&code, 1, 2)),
+    );
+    assert_eq!(limiter.dropped_count(), 4);
+    assert!(limiter.is_total_limit_reached());
+
+    // The summary is at the end of the input.
+    let summary: DiagTooManyDiagnostics = limiter.summary(code.view()).unwrap();
+    assert!(same_pointers(summary.where_, span_of(&code, 6, 6)));
+    assert_eq!(summary.dropped_count, 4);
+}
+
+#[test]
+fn per_code_limit_drops_only_that_code() {
+    let code = PaddedString::from_slice(b"\x01\x02\x03'");
+
+    let collector = DiagCollector::new();
+    let limiter = LimitingDiagReporter::new(
+        &collector,
+        DiagnosticLimits {
+            max_total: None,
+            max_per_code: Some(1),
+        },
+    );
+    for i in 0..3 {
+        report_control_character(&limiter, &code, i);
+    }
+    report(
+        &limiter,
+        DiagUnclosedStringLiteral {
+            string_literal: span_of(&code, 3, 4),
+        },
+    );
+
+    assert_eq!(collector.len(), 2);
+    assert_matches!(
+        collector.index(0),
+        AnyDiag::DiagUnexpectedControlCharacter(_)
+    );
+    assert_matches!(collector.index(1), AnyDiag::DiagUnclosedStringLiteral(_));
+    assert_eq!(limiter.dropped_count(), 2);
+    assert!(!limiter.is_total_limit_reached());
+    assert!(limiter.summary(code.view()).is_some());
+}
+
+#[test]
+fn diagnostics_turned_off_by_category_filter_are_not_counted() {
+    let code = PaddedString::from_slice(b"\x01\x02\x03");
+
+    let collector = DiagCollector::new();
+    let mut limiter = LimitingDiagReporter::new(
+        &collector,
+        DiagnosticLimits {
+            max_total: Some(1),
+            max_per_code: None,
+        },
+    );
+    let mut category_filter: DiagnosticCategoryFilter = DiagnosticCategoryFilter::new();
+    category_filter.set(DiagnosticCategory::Syntax, CategorySeverity::Off);
+    limiter.set_category_filter(category_filter);
+    for i in 0..3 {
+        report_control_character(&limiter, &code, i);
+    }
+
+    // The target is responsible for dropping the diagnostics.
+    assert_eq!(collector.len(), 3);
+    assert_eq!(limiter.dropped_count(), 0);
+    assert!(!limiter.is_total_limit_reached());
+}
+
+#[test]
+fn default_limits_are_finite() {
+    let limits: DiagnosticLimits = DiagnosticLimits::default();
+    assert!(limits.max_total.is_some());
+    assert!(limits.max_per_code.is_some());
+}
+
+fn report_control_character(reporter: &dyn DiagReporter, code: &PaddedString, offset: usize) {
+    report(
+        reporter,
+        DiagUnexpectedControlCharacter {
+            character: span_of(code, offset, offset + 1),
+            code_point: code.as_slice()[offset] as char,
+        },
+    );
+}
@@ -0,0 +1,266 @@
+use cpp_vs_rust_fe::diag_reporter::*;
+use cpp_vs_rust_fe::diag_serialization::*;
+use cpp_vs_rust_fe::diagnostic_types::*;
+use cpp_vs_rust_fe::global_declared_variable_set::*;
+use cpp_vs_rust_fe::lint_cache::*;
+use cpp_vs_rust_fe::linter::*;
+use cpp_vs_rust_fe::source_code_span::*;
+use cpp_vs_rust_port::assert_matches;
+use cpp_vs_rust_test::diag_collector::*;
+use cpp_vs_rust_test::span::*;
+use cpp_vs_rust_test::temp_directory::*;
+use cpp_vs_rust_util::padded_string::*;
+
+#[test]
+fn second_lint_of_same_code_hits_cache() {
+    let cache_dir = TempDirectory::new("second_lint_of_same_code_hits_cache");
+    let cache = LintCache::new(cache_dir.path());
+    let code = PaddedString::from_slice(b"let x = 'unclosed");
+
+    let first_collector = DiagCollector::new();
+    assert_eq!(
+        cache
+            .parse_and_lint(code.view(), &first_collector, LinterOptions::default(), b"")
+            .unwrap(),
+        LintCacheOutcome::Miss,
+    );
+    let second_collector = DiagCollector::new();
+    assert_eq!(
+        cache
+            .parse_and_lint(
+                code.view(),
+                &second_collector,
+                LinterOptions::default(),
+                b""
+            )
+            .unwrap(),
+        LintCacheOutcome::Hit,
+    );
+
+    assert_eq!(first_collector.len(), 1);
+    assert_eq!(second_collector.len(), 1);
+    assert_matches!(
+        second_collector.index(0),
+        AnyDiag::DiagUnclosedStringLiteral(diag)
+            if same_pointers(diag.string_literal, span_of(&code, 8, 17)),
+    );
+}
+
+#[test]
+fn hit_replays_stored_diagnostics_without_linting() {
+    let cache_dir = TempDirectory::new("hit_replays_stored_diagnostics_without_linting");
+    let cache = LintCache::new(cache_dir.path());
+    let code = PaddedString::from_slice(b"\x01");
+
+    // Plant an entry which linting would not produce.
+    let mut serializer = SerializingDiagReporter::new(code.view());
+    report(
+        &serializer,
+        DiagUnclosedStringLiteral {
+            string_literal: span_of(&code, 0, 1),
+        },
+    );
+    let key: LintCacheKey = LintCacheKey::new(code.view(), &LinterOptions::default(), b"");
+    let mut entry: Vec<u8> = LintCacheEntryHeader::new(code.view(), &LinterOptions::default(), b"")
+        .to_bytes()
+        .to_vec();
+    entry.extend_from_slice(&serializer.take_output());
+    std::fs::create_dir_all(cache.directory()).unwrap();
+    std::fs::write(cache.entry_path(key), entry).unwrap();
+
+    let collector = DiagCollector::new();
+    assert_eq!(
+        cache
+            .parse_and_lint(code.view(), &collector, LinterOptions::default(), b"")
+            .unwrap(),
+        LintCacheOutcome::Hit,
+    );
+    assert_eq!(collector.len(), 1);
+    assert_matches!(collector.index(0), AnyDiag::DiagUnclosedStringLiteral(_));
+}
+
+#[test]
+fn entry_for_other_code_with_same_key_misses_cache() {
+    let cache_dir = TempDirectory::new("entry_for_other_code_with_same_key_misses_cache");
+    let cache = LintCache::new(cache_dir.path());
+    let code = PaddedString::from_slice(b"'");
+    let other_code = PaddedString::from_slice(b"\x01");
+
+    // Simulate a LintCacheKey collision: store other_code's entry where
+    // code's entry would go. other_code's diagnostic also fits within code.
+    let mut serializer = SerializingDiagReporter::new(other_code.view());
+    report(
+        &serializer,
+        DiagUnexpectedControlCharacter {
+            character: span_of(&other_code, 0, 1),
+            code_point: '\x01',
+        },
+    );
+    let mut entry: Vec<u8> =
+        LintCacheEntryHeader::new(other_code.view(), &LinterOptions::default(), b"")
+            .to_bytes()
+            .to_vec();
+    entry.extend_from_slice(&serializer.take_output());
+    let key: LintCacheKey = LintCacheKey::new(code.view(), &LinterOptions::default(), b"");
+    std::fs::create_dir_all(cache.directory()).unwrap();
+    std::fs::write(cache.entry_path(key), entry).unwrap();
+
+    let collector = DiagCollector::new();
+    assert_eq!(
+        cache
+            .parse_and_lint(code.view(), &collector, LinterOptions::default(), b"")
+            .unwrap(),
+        LintCacheOutcome::Miss,
+    );
+    assert_eq!(collector.len(), 1);
+    assert_matches!(collector.index(0), AnyDiag::DiagUnclosedStringLiteral(_));
+}
+
+#[test]
+fn entry_headers_differ_when_inputs_differ() {
+    let code = PaddedString::from_slice(b"x");
+    let other_code = PaddedString::from_slice(b"y");
+    let header = |code: &PaddedString, config: &[u8]| -> LintCacheEntryHeader {
+        LintCacheEntryHeader::new(code.view(), &LinterOptions::default(), config)
+    };
+    assert_eq!(header(&code, b""), header(&code, b""));
+    assert_eq!(header(&code, b"").code_len, 1);
+    assert_ne!(header(&code, b""), header(&other_code, b""));
+    assert_ne!(header(&code, b""), header(&code, b"{}"));
+}
+
+#[test]
+fn changing_code_misses_cache() {
+    let cache_dir = TempDirectory::new("changing_code_misses_cache");
+    let cache = LintCache::new(cache_dir.path());
+    let code = PaddedString::from_slice(b"'");
+    let changed_code = PaddedString::from_slice(b"\x01");
+
+    let collector = DiagCollector::new();
+    cache
+        .parse_and_lint(code.view(), &collector, LinterOptions::default(), b"")
+        .unwrap();
+    let changed_collector = DiagCollector::new();
+    assert_eq!(
+        cache
+            .parse_and_lint(
+                changed_code.view(),
+                &changed_collector,
+                LinterOptions::default(),
+                b""
+            )
+            .unwrap(),
+        LintCacheOutcome::Miss,
+    );
+    assert_eq!(changed_collector.len(), 1);
+    assert_matches!(
+        changed_collector.index(0),
+        AnyDiag::DiagUnexpectedControlCharacter(_)
+    );
+}
+
+#[test]
+fn changing_linter_options_misses_cache() {
+    let cache_dir = TempDirectory::new("changing_linter_options_misses_cache");
+    let cache = LintCache::new(cache_dir.path());
+    let code = PaddedString::from_slice(b"x");
+    let options: LinterOptions = LinterOptions::default();
+    let mut changed_options: LinterOptions = options.clone();
+    changed_options.jsx = !options.jsx;
+
+    let collector = DiagCollector::new();
+    cache
+        .parse_and_lint(code.view(), &collector, options.clone(), b"")
+        .unwrap();
+    assert_eq!(
+        cache
+            .parse_and_lint(code.view(), &collector, changed_options, b"")
+            .unwrap(),
+        LintCacheOutcome::Miss,
+    );
+    assert_eq!(
+        cache
+            .parse_and_lint(code.view(), &collector, options, b"")
+            .unwrap(),
+        LintCacheOutcome::Hit,
+    );
+}
+
+#[test]
+fn changing_config_misses_cache() {
+    let cache_dir = TempDirectory::new("changing_config_misses_cache");
+    let cache = LintCache::new(cache_dir.path());
+    let code = PaddedString::from_slice(b"x");
+
+    let collector = DiagCollector::new();
+    cache
+        .parse_and_lint(code.view(), &collector, LinterOptions::default(), b"{}")
+        .unwrap();
+    assert_eq!(
+        cache
+            .parse_and_lint(
+                code.view(),
+                &collector,
+                LinterOptions::default(),
+                br#"{"globals": {}}"#
+            )
+            .unwrap(),
+        LintCacheOutcome::Miss,
+    );
+}
+
+#[test]
+fn keys_differ_when_bytes_move_between_config_and_code() {
+    let code_a = PaddedString::from_slice(b"bc");
+    let code_b = PaddedString::from_slice(b"c");
+    assert_ne!(
+        LintCacheKey::new(code_a.view(), &LinterOptions::default(), b"a"),
+        LintCacheKey::new(code_b.view(), &LinterOptions::default(), b"ab"),
+    );
+}
+
+#[test]
+fn keys_differ_when_globals_differ() {
+    let code = PaddedString::from_slice(b"x = 1;");
+    let key = |globals: &[(&str, bool)]| -> LintCacheKey {
+        let mut options: LinterOptions = LinterOptions::default();
+        for (name, is_writable) in globals {
+            options.globals.add_variable(GlobalDeclaredVariable {
+                name: name.to_string(),
+                is_writable: *is_writable,
+                is_shadowable: true,
+            });
+        }
+        LintCacheKey::new(code.view(), &options, b"")
+    };
+    assert_eq!(key(&[("x", true)]), key(&[("x", true)]));
+    assert_ne!(key(&[]), key(&[("x", true)]));
+    assert_ne!(key(&[("x", true)]), key(&[("x", false)]));
+    assert_ne!(key(&[("ab", true)]), key(&[("a", true), ("b", true)]));
+}
+
+#[test]
+fn corrupt_entry_is_relinted_and_overwritten() {
+    let cache_dir = TempDirectory::new("corrupt_entry_is_relinted_and_overwritten");
+    let cache = LintCache::new(cache_dir.path());
+    let code = PaddedString::from_slice(b"'");
+    let key: LintCacheKey = LintCacheKey::new(code.view(), &LinterOptions::default(), b"");
+    std::fs::create_dir_all(cache.directory()).unwrap();
+    std::fs::write(cache.entry_path(key), b"\xff\xff\x00").unwrap();
+
+    let collector = DiagCollector::new();
+    assert_eq!(
+        cache
+            .parse_and_lint(code.view(), &collector, LinterOptions::default(), b"")
+            .unwrap(),
+        LintCacheOutcome::Miss,
+    );
+    assert_eq!(collector.len(), 1);
+    assert_eq!(
+        cache
+            .parse_and_lint(code.view(), &collector, LinterOptions::default(), b"")
+            .unwrap(),
+        LintCacheOutcome::Hit,
+    );
+    assert_eq!(collector.len(), 2);
+}
@@ -0,0 +1,131 @@
+use cpp_vs_rust_fe::cancellation::*;
+use cpp_vs_rust_fe::diag_reporter::*;
+use cpp_vs_rust_fe::diagnostic_types::*;
+use cpp_vs_rust_fe::lex::*;
+use cpp_vs_rust_fe::linter::*;
+use cpp_vs_rust_port::assert_matches;
+use cpp_vs_rust_test::diag_collector::*;
+use cpp_vs_rust_util::padded_string::*;
+
+#[test]
+fn uncancelled_lint_completes() {
+    let code = PaddedString::from_slice(b"let x = 'unclosed");
+    let collector = DiagCollector::new();
+    let options = LinterOptions {
+        cancellation: Some(CancellationToken::new()),
+        ..LinterOptions::default()
+    };
+    assert_eq!(
+        parse_and_lint(code.view(), &collector, options),
+        LintStatus::Completed
+    );
+    assert_eq!(collector.len(), 1);
+}
+
+#[test]
+fn lint_with_cancelled_token_is_cancelled() {
+    let code = PaddedString::from_slice(b"let x = 'unclosed");
+    let token = CancellationToken::new();
+    token.cancel();
+    let options = LinterOptions {
+        cancellation: Some(token),
+        ..LinterOptions::default()
+    };
+    assert_eq!(
+        parse_and_lint(code.view(), &DiagCollector::new(), options),
+        LintStatus::Cancelled
+    );
+}
+
+#[test]
+fn lint_with_expired_deadline_is_cancelled() {
+    let code = PaddedString::from_slice(b"x");
+    let options = LinterOptions {
+        cancellation: Some(CancellationToken::with_time_budget(
+            std::time::Duration::ZERO,
+        )),
+        ..LinterOptions::default()
+    };
+    assert_eq!(
+        parse_and_lint(code.view(), &DiagCollector::new(), options),
+        LintStatus::Cancelled
+    );
+}
+
+#[test]
+fn cancelling_during_lint_stops_lexing_soon() {
+    const ERROR_COUNT: usize = 100_000;
+    let code = PaddedString::from_slice(&b"@ x ".repeat(ERROR_COUNT));
+    let token = CancellationToken::new();
+    let reporter = CancellingDiagReporter {
+        token: token.clone(),
+        collector: DiagCollector::new(),
+    };
+    let options = LinterOptions {
+        cancellation: Some(token),
+        ..LinterOptions::default()
+    };
+    assert_eq!(
+        parse_and_lint(code.view(), &reporter, options),
+        LintStatus::Cancelled
+    );
+    assert!(reporter.collector.len() >= 1);
+    assert!(
+        reporter.collector.len() < ERROR_COUNT,
+        "{} diagnostics should have been reported before lint was cancelled",
+        reporter.collector.len(),
+    );
+}
+
+// Cancels token after forwarding the first diagnostic to collector.
+struct CancellingDiagReporter<'code> {
+    token: CancellationToken,
+    collector: DiagCollector<'code>,
+}
+
+impl<'code> DiagReporter for CancellingDiagReporter<'code> {
+    fn report_impl(&self, type_: DiagType, diag: *const u8) {
+        self.collector.report_impl(type_, diag);
+        self.token.cancel();
+    }
+}
+
+#[test]
+fn binary_input_is_lexed_unless_bailing_out_is_enabled() {
+    let code = PaddedString::from_slice(&b"\x00\x01\x02\x03 @".repeat(8));
+
+    let collector = DiagCollector::new();
+    parse_and_lint(code.view(), &collector, LinterOptions::default());
+    assert!(collector.len() > 1, "{}", collector.len());
+
+    let collector = DiagCollector::new();
+    let options = LinterOptions {
+        bail_out_on_binary: true,
+        ..LinterOptions::default()
+    };
+    assert_eq!(
+        parse_and_lint(code.view(), &collector, options),
+        LintStatus::Completed
+    );
+    assert_eq!(collector.len(), 1);
+    assert_matches!(collector.index(0), AnyDiag::DiagFileLooksBinary(_));
+}
+
+#[test]
+fn one_allocator_can_lint_many_files() {
+    let allocator = LexerAllocator::new();
+    for i in 0..100 {
+        let code = PaddedString::from_slice(format!("let x{i} = 'unclosed\n@").as_bytes());
+        let collector = DiagCollector::new();
+        assert_eq!(
+            parse_and_lint_with_allocator(
+                code.view(),
+                &collector,
+                LinterOptions::default(),
+                &allocator
+            ),
+            LintStatus::Completed
+        );
+        assert_eq!(collector.len(), 2);
+    }
+}
@@ -0,0 +1,265 @@
+use cpp_vs_rust_fe::document::*;
+use cpp_vs_rust_fe::lsp_location::*;
+use cpp_vs_rust_test::scoped_trace;
+use cpp_vs_rust_test::span::*;
+use cpp_vs_rust_util::padded_string::*;
+use cpp_vs_rust_util::utf_8::*;
+
+#[test]
+fn ranges_on_first_line() {
+    let code = PaddedString::from_slice(b"let x = 2;");
+    let l = LSPLocator::new(code.view());
+    let x_range: LSPRange = l.range(span_of(&code, 4, 5));
+
+    assert_eq!(x_range.start, position(0, 4));
+    assert_eq!(x_range.end, position(0, 5));
+}
+
+#[test]
+fn ranges_on_second_line() {
+    for line_terminator in [
+        "\n", "\r", "\r\n", "\u{2028}", // 0xe2 0x80 0xa8 Line Separator
+        "\u{2029}", // 0xe2 0x80 0xa9 Paragraph Separator
+    ] {
+        scoped_trace!(line_terminator);
+        let code =
+            PaddedString::from_slice(format!("let x = 2;{line_terminator}let y = 3;").as_bytes());
+        let y_offset: usize = b"let x = 2;".len() + line_terminator.len() + b"let ".len();
+        let l = LSPLocator::new(code.view());
+        let y_range: LSPRange = l.range(span_of(&code, y_offset, y_offset + 1));
+
+        assert_eq!(y_range.start, position(1, 4));
+        assert_eq!(y_range.end, position(1, 5));
+    }
+}
+
+#[test]
+fn first_character_on_line_has_character_zero() {
+    let code = PaddedString::from_slice(b"function f() {}\nf();\n\nx");
+    let l = LSPLocator::new(code.view());
+    assert_eq!(l.position(ptr_at(&code, 16)), position(1, 0));
+    assert_eq!(l.position(ptr_at(&code, 21)), position(2, 0));
+    assert_eq!(l.position(ptr_at(&code, 22)), position(3, 0));
+}
+
+#[test]
+fn lf_cr_is_two_line_terminators() {
+    let code = PaddedString::from_slice(b"a\n\rb");
+    let l = LSPLocator::new(code.view());
+    assert_eq!(l.offset_of_lines(), &[0, 2, 3]);
+    assert_eq!(l.position(ptr_at(&code, 3)), position(2, 0));
+}
+
+#[test]
+fn trailing_line_terminator_starts_empty_line() {
+    let code = PaddedString::from_slice(b"a\n");
+    let l = LSPLocator::new(code.view());
+    assert_eq!(l.offset_of_lines(), &[0, 2]);
+    assert_eq!(l.position(ptr_at(&code, 2)), position(1, 0));
+}
+
+#[test]
+fn character_counts_utf_16_code_units() {
+    // U+00E9 is one UTF-16 code unit. U+1F430 is two UTF-16 code units.
+    let code = PaddedString::from_slice("\n\u{00e9}\u{1f430}x".as_bytes());
+    let l = LSPLocator::new(code.view());
+    let x_offset: usize = "\n\u{00e9}\u{1f430}".len();
+    assert_eq!(l.position(ptr_at(&code, x_offset)), position(1, 3));
+    assert_eq!(
+        l.pointer_at_position(position(1, 3)),
+        ptr_at(&code, x_offset)
+    );
+}
+
+#[test]
+fn character_counts_bytes_with_utf_8_encoding() {
+    let code = PaddedString::from_slice("\n\u{00e9}\u{1f430}x".as_bytes());
+    let l = LSPLocator::new_with_encoding(code.view(), PositionEncoding::UTF8);
+    let x_offset: usize = "\n\u{00e9}\u{1f430}".len();
+    assert_eq!(l.position(ptr_at(&code, x_offset)), position(1, 6));
+    assert_eq!(
+        l.pointer_at_position(position(1, 6)),
+        ptr_at(&code, x_offset)
+    );
+}
+
+#[test]
+fn character_counts_code_points_with_utf_32_encoding() {
+    let code = PaddedString::from_slice("\n\u{00e9}\u{1f430}x".as_bytes());
+    let l = LSPLocator::new_with_encoding(code.view(), PositionEncoding::UTF32);
+    let x_offset: usize = "\n\u{00e9}\u{1f430}".len();
+    assert_eq!(l.position(ptr_at(&code, x_offset)), position(1, 2));
+    assert_eq!(
+        l.pointer_at_position(position(1, 2)),
+        ptr_at(&code, x_offset)
+    );
+}
+
+#[test]
+fn ascii_lines_are_the_same_in_every_encoding() {
+    let code = PaddedString::from_slice(b"abc\ndef");
+    for encoding in [
+        PositionEncoding::UTF8,
+        PositionEncoding::UTF16,
+        PositionEncoding::UTF32,
+    ] {
+        scoped_trace!(encoding);
+        let l = LSPLocator::new_with_encoding(code.view(), encoding);
+        assert_eq!(l.position(ptr_at(&code, 6)), position(1, 2));
+        assert_eq!(l.pointer_at_position(position(1, 2)), ptr_at(&code, 6));
+    }
+}
+
+#[test]
+fn document_set_text_keeps_position_encoding() {
+    let mut doc: Document<LSPLocator> = Document::new();
+    doc.locator_mut()
+        .set_position_encoding(PositionEncoding::UTF32);
+    doc.set_text("\u{1f430}x".as_bytes());
+    assert_eq!(doc.locator().position_encoding(), PositionEncoding::UTF32);
+    doc.replace_text(
+        LSPRange {
+            start: position(0, 1),
+            end: position(0, 2),
+        },
+        b"y",
+    );
+    assert_eq!(doc.string().slice(), "\u{1f430}y".as_bytes());
+}
+
+#[test]
+fn pointer_at_position_inverts_position() {
+    let code =
+        PaddedString::from_slice("ab\r\n\u{00e9}\u{2028}\u{1f430}\rx\n\ny\u{2029}".as_bytes());
+    let l = LSPLocator::new(code.view());
+    for offset in 0..=(code.len() as usize) {
+        scoped_trace!(offset);
+        let c: *const u8 = ptr_at(&code, offset);
+        let p: LSPPosition = l.position(c);
+        if is_character_boundary(&code, offset) && !is_between_cr_and_lf(&code, offset) {
+            assert_eq!(l.pointer_at_position(p), c);
+        }
+    }
+}
+
+#[test]
+fn pointer_at_position_clamps_character_to_end_of_line() {
+    let code = PaddedString::from_slice("abc\r\n\u{00e9}\nxyz".as_bytes());
+    let l = LSPLocator::new(code.view());
+    assert_eq!(l.pointer_at_position(position(0, 100)), ptr_at(&code, 3));
+    assert_eq!(l.pointer_at_position(position(1, 100)), ptr_at(&code, 7));
+    assert_eq!(l.pointer_at_position(position(2, 100)), ptr_at(&code, 11));
+}
+
+#[test]
+fn pointer_at_position_past_last_line_is_end_of_input() {
+    let code = PaddedString::from_slice(b"abc\ndef");
+    let l = LSPLocator::new(code.view());
+    assert_eq!(
+        l.pointer_at_position(position(2, 0)),
+        code.null_terminator()
+    );
+    assert_eq!(
+        l.pointer_at_position(position(100, 5)),
+        code.null_terminator()
+    );
+}
+
+#[test]
+fn replace_text_updates_lines_like_rebuilding() {
+    let texts: &[&[u8]] = &[
+        b"",
+        b"abc",
+        b"a\nb\nc",
+        b"a\r\nb\rc\n",
+        b"a\r",
+        b"\nb",
+        "x\u{2028}y\u{2029}z".as_bytes(),
+        "\u{00e9}\n\u{1f430}\r\n".as_bytes(),
+    ];
+    let replacements: &[&[u8]] = &[
+        b"",
+        b"x",
+        b"\n",
+        b"\r",
+        b"\r\n",
+        b"one\ntwo\r\nthree",
+        "\u{2028}".as_bytes(),
+        "\u{00e9}".as_bytes(),
+        // Halves of U+2028 which might form a line separator with
+        // neighboring text.
+        b"\xe2",
+        b"\x80\xa8",
+    ];
+    for text in texts {
+        let text_string = PaddedString::from_slice(text);
+        let text_locator = LSPLocator::new(text_string.view());
+        let boundaries: Vec<usize> = (0..=text.len())
+            .filter(|offset: &usize| {
+                // LSP positions can't refer to the middle of a CRLF.
+                is_character_boundary(&text_string, *offset)
+                    && !is_between_cr_and_lf(&text_string, *offset)
+            })
+            .collect();
+        for (i, begin) in boundaries.iter().enumerate() {
+            for end in &boundaries[i..] {
+                for replacement in replacements {
+                    let range: LSPRange = text_locator.range(span_of(&text_string, *begin, *end));
+                    scoped_trace!(text_string);
+                    scoped_trace!(begin);
+                    scoped_trace!(end);
+                    scoped_trace!(PaddedString::from_slice(replacement));
+
+                    let mut doc: Document<LSPLocator> = Document::new();
+                    doc.set_text(text);
+                    doc.replace_text(range, replacement);
+
+                    let mut expected_text: Vec<u8> = text[..*begin].to_vec();
+                    expected_text.extend_from_slice(replacement);
+                    expected_text.extend_from_slice(&text[*end..]);
+                    assert_eq!(doc.string().slice(), &expected_text[..]);
+
+                    // Detach new_text from doc's borrow so doc.locator() can be
+                    // called. doc is not modified while new_text is in use.
+                    let new_text: PaddedStringView = unsafe {
+                        let text: PaddedStringView = doc.string();
+                        PaddedStringView::from_begin_end(text.c_str(), text.null_terminator())
+                    };
+                    let rebuilt_locator = LSPLocator::new(new_text);
+                    assert_eq!(
+                        doc.locator().offset_of_lines(),
+                        rebuilt_locator.offset_of_lines()
+                    );
+                    for offset in 0..=(new_text.len() as usize) {
+                        let c: *const u8 = unsafe { new_text.c_str().add(offset) };
+                        assert_eq!(doc.locator().position(c), rebuilt_locator.position(c));
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn position(line: u32, character: u32) -> LSPPosition {
+    LSPPosition {
+        line: line,
+        character: character,
+    }
+}
+
+fn ptr_at(code: &PaddedString, offset: usize) -> *const u8 {
+    unsafe { code.c_str().add(offset) }
+}
+
+fn is_character_boundary(code: &PaddedString, offset: usize) -> bool {
+    match code.as_slice().get(offset) {
+        Some(byte) => (byte & 0b1100_0000) != 0b1000_0000,
+        None => true,
+    }
+}
+
+fn is_between_cr_and_lf(code: &PaddedString, offset: usize) -> bool {
+    offset > 0
+        && code.as_slice()[offset - 1] == b'\r'
+        && code.as_slice().get(offset) == Some(&b'\n')
+}
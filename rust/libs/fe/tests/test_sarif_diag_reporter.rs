@@ -0,0 +1,165 @@
+use cpp_vs_rust_fe::diag_reporter::*;
+use cpp_vs_rust_fe::diagnostic_types::*;
+use cpp_vs_rust_fe::sarif_diag_reporter::*;
+use cpp_vs_rust_i18n::translation::*;
+use cpp_vs_rust_test::span::*;
+use cpp_vs_rust_util::padded_string::*;
+
+fn results_of(log: &str) -> &str {
+    let results_begin: usize = log.find("\"results\":").unwrap();
+    &log[results_begin..]
+}
+
+#[test]
+fn empty_log_has_one_run_with_no_results() {
+    let mut reporter = SarifDiagReporter::new(Translator::new_using_messages_from_source_code());
+    let log: String = String::from_utf8(reporter.finish()).unwrap();
+    assert!(log.starts_with(
+        "{\"$schema\":\"https://json.schemastore.org/sarif-2.1.0.json\",\"version\":\"2.1.0\",\"runs\":[{"
+    ));
+    assert_eq!(results_of(&log), "\"results\":[]}]}\n");
+}
+
+#[test]
+fn rules_describe_every_diagnostic_type() {
+    let mut reporter = SarifDiagReporter::new(Translator::new_using_messages_from_source_code());
+    let log: String = String::from_utf8(reporter.finish()).unwrap();
+    assert_eq!(log.matches("{\"id\":\"E").count(), DIAG_TYPE_COUNT as usize);
+    assert!(log.contains(
+        "{\"id\":\"E0005\",\
+         \"shortDescription\":{\"text\":\"BigInt literal contains decimal point\"},\
+         \"helpUri\":\"https://quick-lint-js.com/errors/E0005/\",\
+         \"defaultConfiguration\":{\"level\":\"error\"}}"
+    ));
+    assert!(log.contains(
+        "{\"id\":\"E0212\",\
+         \"shortDescription\":{\"text\":\"integer cannot be represented and will be rounded to '{1}'\"},\
+         \"helpUri\":\"https://quick-lint-js.com/errors/E0212/\",\
+         \"defaultConfiguration\":{\"level\":\"warning\"}}"
+    ));
+}
+
+#[test]
+fn result_has_physical_location() {
+    let input = PaddedString::from_slice(b"let x;\nlet y = 'hi");
+    let mut reporter = SarifDiagReporter::new(Translator::new_using_messages_from_source_code());
+    reporter.set_source(input.view(), "src/hello world.js");
+    report(
+        &reporter,
+        DiagUnclosedStringLiteral {
+            string_literal: span_of(&input, 15, 18),
+        },
+    );
+    let log: String = String::from_utf8(reporter.finish()).unwrap();
+    assert_eq!(
+        results_of(&log),
+        format!(
+            "\"results\":[{{\"ruleId\":\"E0040\",\"ruleIndex\":{},\"level\":\"error\",\
+             \"message\":{{\"text\":\"unclosed string literal\"}},\
+             \"locations\":[{{\"physicalLocation\":{{\
+             \"artifactLocation\":{{\"uri\":\"src/hello%20world.js\"}},\
+             \"region\":{{\"startLine\":2,\"startColumn\":9,\"endLine\":2,\"endColumn\":12}}}}}}]}}]}}]}}\n",
+            DiagType::DiagUnclosedStringLiteral as usize,
+        )
+    );
+}
+
+#[test]
+fn notes_become_related_locations() {
+    let input = PaddedString::from_slice(b"aaa\nbbb");
+    let mut reporter = SarifDiagReporter::new(Translator::new_using_messages_from_source_code());
+    reporter.set_source(input.view(), "test.js");
+    report(
+        &reporter,
+        DiagMultipleMessageTest {
+            a: span_of(&input, 0, 3),
+            b: span_of(&input, 5, 6),
+        },
+    );
+    let log: String = String::from_utf8(reporter.finish()).unwrap();
+    assert!(results_of(&log).contains(
+        "\"relatedLocations\":[{\"id\":0,\"physicalLocation\":{\
+         \"artifactLocation\":{\"uri\":\"test.js\"},\
+         \"region\":{\"startLine\":2,\"startColumn\":2,\"endLine\":2,\"endColumn\":3}},\
+         \"message\":{\"text\":\"second message here\"}}]"
+    ));
+}
+
+#[test]
+fn results_from_multiple_files() {
+    let input_1 = PaddedString::from_slice(b"@");
+    let input_2 = PaddedString::from_slice(b"#");
+    let mut reporter = SarifDiagReporter::new(Translator::new_using_messages_from_source_code());
+    reporter.set_source(input_1.view(), "one.js");
+    report(
+        &reporter,
+        DiagUnexpectedAtCharacter {
+            character: span_of(&input_1, 0, 1),
+        },
+    );
+    reporter.set_source(input_2.view(), "two.js");
+    report(
+        &reporter,
+        DiagUnexpectedHashCharacter {
+            where_: span_of(&input_2, 0, 1),
+        },
+    );
+    let log: String = String::from_utf8(reporter.finish()).unwrap();
+    let results: &str = results_of(&log);
+    assert_eq!(results.matches("\"ruleId\"").count(), 2);
+    let one: usize = results.find("\"uri\":\"one.js\"").unwrap();
+    let two: usize = results.find("\"uri\":\"two.js\"").unwrap();
+    assert!(one < two);
+    assert!(results.contains("\"ruleId\":\"E0042\""));
+    assert!(results.contains("\"ruleId\":\"E0052\""));
+}
+
+#[test]
+fn messages_are_translated_and_escaped() {
+    let input = PaddedString::from_slice(b"\\");
+    let mut translator = Translator::new_using_messages_from_source_code();
+    assert!(translator.use_messages_from_locale("en_US@snarky"));
+    let mut reporter = SarifDiagReporter::new(translator);
+    reporter.set_source(input.view(), "test.js");
+    report(
+        &reporter,
+        DiagUnclosedStringLiteral {
+            string_literal: span_of(&input, 0, 1),
+        },
+    );
+    let log: String = String::from_utf8(reporter.finish()).unwrap();
+    assert!(results_of(&log).contains("\"message\":{\"text\":\"\\\"unclosed string literal\"}"));
+}
+
+#[test]
+fn results_taken_from_several_reporters_can_be_combined() {
+    let input_1 = PaddedString::from_slice(b"@");
+    let input_2 = PaddedString::from_slice(b"#");
+    let mut reporter_1 = SarifDiagReporter::new(Translator::new_using_messages_from_source_code());
+    reporter_1.set_source(input_1.view(), "one.js");
+    report(
+        &reporter_1,
+        DiagUnexpectedAtCharacter {
+            character: span_of(&input_1, 0, 1),
+        },
+    );
+    let mut reporter_2 = SarifDiagReporter::new(Translator::new_using_messages_from_source_code());
+    reporter_2.set_source(input_2.view(), "two.js");
+    report(
+        &reporter_2,
+        DiagUnexpectedHashCharacter {
+            where_: span_of(&input_2, 0, 1),
+        },
+    );
+
+    let mut results: Vec<u8> = reporter_1.take_results();
+    results.push(b',');
+    results.extend_from_slice(&reporter_2.take_results());
+    let log: String = String::from_utf8(sarif_log_with_results(&results)).unwrap();
+    assert_eq!(results_of(&log).matches("\"ruleId\"").count(), 2);
+    assert!(reporter_1.take_results().is_empty());
+    assert_eq!(
+        results_of(&String::from_utf8(reporter_1.finish()).unwrap()),
+        "\"results\":[]}]}\n"
+    );
+}
@@ -0,0 +1,281 @@
+use cpp_vs_rust_fe::diag_reporter::*;
+use cpp_vs_rust_fe::diagnostic_types::*;
+use cpp_vs_rust_fe::sorting_diag_reporter::*;
+use cpp_vs_rust_fe::source_code_span::*;
+use cpp_vs_rust_port::assert_matches;
+use cpp_vs_rust_test::diag_collector::*;
+use cpp_vs_rust_test::span::*;
+use cpp_vs_rust_util::padded_string::*;
+
+#[test]
+fn sorts_by_begin_offset() {
+    let code = PaddedString::from_slice(b"'abc' 'def");
+
+    let mut diag_reporter = SortingDiagReporter::new();
+    report(
+        &diag_reporter,
+        DiagUnclosedStringLiteral {
+            string_literal: span_of(&code, 6, 10),
+        },
+    );
+    report(
+        &diag_reporter,
+        DiagUnclosedStringLiteral {
+            string_literal: span_of(&code, 0, 5),
+        },
+    );
+
+    let collector = DiagCollector::new();
+    diag_reporter.move_into(&collector);
+
+    assert_eq!(collector.len(), 2);
+    assert_matches!(
+        collector.index(0),
+        AnyDiag::DiagUnclosedStringLiteral(diag)
+            if same_pointers(diag.string_literal, span_of(&code, 0, 5)),
+    );
+    assert_matches!(
+        collector.index(1),
+        AnyDiag::DiagUnclosedStringLiteral(diag)
+            if same_pointers(diag.string_literal, span_of(&code, 6, 10)),
+    );
+}
+
+#[test]
+fn errors_are_sorted_before_warnings_at_same_offset() {
+    let code = PaddedString::from_slice(b"9007199254740993");
+
+    let mut diag_reporter = SortingDiagReporter::new();
+    report(
+        &diag_reporter,
+        DiagIntegerLiteralWillLosePrecision {
+            characters: span_of(&code, 0, 16),
+            rounded_val: b"9007199254740992",
+        },
+    );
+    report(
+        &diag_reporter,
+        DiagUnexpectedCharactersInNumber {
+            characters: span_of(&code, 0, 16),
+        },
+    );
+
+    let collector = DiagCollector::new();
+    diag_reporter.move_into(&collector);
+
+    assert_eq!(collector.len(), 2);
+    assert_matches!(
+        collector.index(0),
+        AnyDiag::DiagUnexpectedCharactersInNumber(_),
+    );
+    assert_matches!(
+        collector.index(1),
+        AnyDiag::DiagIntegerLiteralWillLosePrecision(_),
+    );
+}
+
+#[test]
+fn lower_codes_are_sorted_first_at_same_offset_and_severity() {
+    let code = PaddedString::from_slice(b"\"abc");
+
+    let mut diag_reporter = SortingDiagReporter::new();
+    report(
+        &diag_reporter,
+        DiagInvalidQuotesAroundStringLiteral {
+            opening_quote: span_of(&code, 0, 1),
+            suggested_quote: b'\'',
+        },
+    );
+    report(
+        &diag_reporter,
+        DiagUnclosedStringLiteral {
+            string_literal: span_of(&code, 0, 4),
+        },
+    );
+
+    let collector = DiagCollector::new();
+    diag_reporter.move_into(&collector);
+
+    assert_eq!(collector.len(), 2);
+    // E0040 < E0197
+    assert_matches!(collector.index(0), AnyDiag::DiagUnclosedStringLiteral(_));
+    assert_matches!(
+        collector.index(1),
+        AnyDiag::DiagInvalidQuotesAroundStringLiteral(_),
+    );
+}
+
+#[test]
+fn equal_diagnostics_keep_report_order() {
+    let code = PaddedString::from_slice(b"abcdef");
+
+    let mut diag_reporter = SortingDiagReporter::new();
+    report(
+        &diag_reporter,
+        DiagMultipleMessageTest {
+            a: span_of(&code, 0, 1),
+            b: span_of(&code, 4, 5),
+        },
+    );
+    report(
+        &diag_reporter,
+        DiagMultipleMessageTest {
+            a: span_of(&code, 0, 1),
+            b: span_of(&code, 2, 3),
+        },
+    );
+
+    let collector = DiagCollector::new();
+    diag_reporter.move_into(&collector);
+
+    assert_eq!(collector.len(), 2);
+    assert_matches!(
+        collector.index(0),
+        AnyDiag::DiagMultipleMessageTest(diag) if same_pointers(diag.b, span_of(&code, 4, 5)),
+    );
+    assert_matches!(
+        collector.index(1),
+        AnyDiag::DiagMultipleMessageTest(diag) if same_pointers(diag.b, span_of(&code, 2, 3)),
+    );
+}
+
+#[test]
+fn exact_duplicates_are_reported_once() {
+    let code = PaddedString::from_slice(b"'abc");
+
+    let mut diag_reporter = SortingDiagReporter::new();
+    for _ in 0..3 {
+        report(
+            &diag_reporter,
+            DiagUnclosedStringLiteral {
+                string_literal: span_of(&code, 0, 4),
+            },
+        );
+    }
+
+    let collector = DiagCollector::new();
+    diag_reporter.move_into(&collector);
+
+    assert_eq!(collector.len(), 1);
+    assert_matches!(collector.index(0), AnyDiag::DiagUnclosedStringLiteral(_));
+}
+
+#[test]
+fn duplicates_are_detected_when_not_adjacent() {
+    let code = PaddedString::from_slice(b"'abc");
+
+    let mut diag_reporter = SortingDiagReporter::new();
+    report(
+        &diag_reporter,
+        DiagMultipleMessageTest {
+            a: span_of(&code, 0, 1),
+            b: span_of(&code, 1, 2),
+        },
+    );
+    report(
+        &diag_reporter,
+        DiagMultipleMessageTest {
+            a: span_of(&code, 0, 1),
+            b: span_of(&code, 2, 3),
+        },
+    );
+    report(
+        &diag_reporter,
+        DiagMultipleMessageTest {
+            a: span_of(&code, 0, 1),
+            b: span_of(&code, 1, 2),
+        },
+    );
+
+    let collector = DiagCollector::new();
+    diag_reporter.move_into(&collector);
+
+    assert_eq!(collector.len(), 2);
+}
+
+#[test]
+fn same_type_with_different_spans_is_not_a_duplicate() {
+    let code = PaddedString::from_slice(b"'abc");
+
+    let mut diag_reporter = SortingDiagReporter::new();
+    report(
+        &diag_reporter,
+        DiagUnclosedStringLiteral {
+            string_literal: span_of(&code, 0, 2),
+        },
+    );
+    report(
+        &diag_reporter,
+        DiagUnclosedStringLiteral {
+            string_literal: span_of(&code, 0, 4),
+        },
+    );
+
+    let collector = DiagCollector::new();
+    diag_reporter.move_into(&collector);
+
+    assert_eq!(collector.len(), 2);
+}
+
+#[test]
+fn optional_spans_are_compared_for_duplicates() {
+    let code = PaddedString::from_slice(b"abc");
+
+    let mut diag_reporter = SortingDiagReporter::new();
+    report(
+        &diag_reporter,
+        DiagOptionalNoteTest {
+            a: span_of(&code, 0, 1),
+            b: None,
+        },
+    );
+    report(
+        &diag_reporter,
+        DiagOptionalNoteTest {
+            a: span_of(&code, 0, 1),
+            b: Some(span_of(&code, 1, 2)),
+        },
+    );
+    report(
+        &diag_reporter,
+        DiagOptionalNoteTest {
+            a: span_of(&code, 0, 1),
+            b: None,
+        },
+    );
+
+    let collector = DiagCollector::new();
+    diag_reporter.move_into(&collector);
+
+    assert_eq!(collector.len(), 2);
+    assert_matches!(
+        collector.index(0),
+        AnyDiag::DiagOptionalNoteTest(diag) if diag.b.is_none(),
+    );
+    assert_matches!(
+        collector.index(1),
+        AnyDiag::DiagOptionalNoteTest(diag) if diag.b.is_some(),
+    );
+}
+
+#[test]
+fn move_into_empties_reporter() {
+    let code = PaddedString::from_slice(b"'abc");
+
+    let mut diag_reporter = SortingDiagReporter::new();
+    report(
+        &diag_reporter,
+        DiagUnclosedStringLiteral {
+            string_literal: span_of(&code, 0, 4),
+        },
+    );
+    assert!(!diag_reporter.is_empty());
+
+    let collector = DiagCollector::new();
+    diag_reporter.move_into(&collector);
+    assert!(diag_reporter.is_empty());
+
+    let collector_2 = DiagCollector::new();
+    diag_reporter.move_into(&collector_2);
+    assert_eq!(collector_2.len(), 0);
+}
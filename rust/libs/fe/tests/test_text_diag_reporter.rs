@@ -0,0 +1,251 @@
+use cpp_vs_rust_fe::diag_reporter::*;
+use cpp_vs_rust_fe::diagnostic_types::*;
+use cpp_vs_rust_fe::text_diag_reporter::*;
+use cpp_vs_rust_i18n::translation::*;
+use cpp_vs_rust_test::span::*;
+use cpp_vs_rust_util::padded_string::*;
+
+fn make_reporter<'code>(input: &'code PaddedString) -> TextDiagReporter<'code> {
+    let mut reporter = TextDiagReporter::new(Translator::new_using_messages_from_source_code());
+    reporter.set_source(input.view(), "FILE");
+    reporter
+}
+
+#[test]
+fn change_source() {
+    let input_1 = PaddedString::from_slice(b"aaaaaaaa");
+    let input_2 = PaddedString::from_slice(b"bbbbbbbb");
+    let mut reporter = make_reporter(&input_1);
+    report(
+        &reporter,
+        DiagUnclosedBlockComment {
+            comment_open: span_of(&input_1, 0, 2),
+        },
+    );
+    reporter.set_source(input_2.view(), "OTHER");
+    report(
+        &reporter,
+        DiagUnclosedBlockComment {
+            comment_open: span_of(&input_2, 3, 5),
+        },
+    );
+    assert_eq!(
+        String::from_utf8(reporter.take_output()).unwrap(),
+        "FILE:1:1: error: unclosed block comment [E0037]\n\
+         aaaaaaaa\n\
+         ^~\n\
+         OTHER:1:4: error: unclosed block comment [E0037]\n\
+         bbbbbbbb\n   \
+         ^~\n"
+    );
+}
+
+#[test]
+fn format_error_on_second_line() {
+    let input = PaddedString::from_slice(b"let x = 1;\nlet y = 'hello\nz;");
+    let mut reporter = make_reporter(&input);
+    report(
+        &reporter,
+        DiagUnclosedStringLiteral {
+            string_literal: span_of(&input, 19, 25),
+        },
+    );
+    assert_eq!(
+        String::from_utf8(reporter.take_output()).unwrap(),
+        "FILE:2:9: error: unclosed string literal [E0040]\n\
+         let y = 'hello\n        \
+         ^~~~~~\n"
+    );
+}
+
+#[test]
+fn format_warning() {
+    let input = PaddedString::from_slice(b"9007199254740993");
+    let mut reporter = make_reporter(&input);
+    report(
+        &reporter,
+        DiagIntegerLiteralWillLosePrecision {
+            characters: span_of(&input, 0, 16),
+            rounded_val: b"9007199254740992",
+        },
+    );
+    assert_eq!(
+        String::from_utf8(reporter.take_output()).unwrap(),
+        "FILE:1:1: warning: integer cannot be represented and will be rounded to \
+         '9007199254740992' [E0212]\n\
+         9007199254740993\n\
+         ^~~~~~~~~~~~~~~~\n"
+    );
+}
+
+#[test]
+fn empty_span_is_underlined_with_single_caret() {
+    let input = PaddedString::from_slice(b"abc");
+    let mut reporter = make_reporter(&input);
+    report(
+        &reporter,
+        DiagUnexpectedHashCharacter {
+            where_: span_of(&input, 1, 1),
+        },
+    );
+    assert_eq!(
+        String::from_utf8(reporter.take_output()).unwrap(),
+        "FILE:1:2: error: unexpected '#' [E0052]\n\
+         abc\n \
+         ^\n"
+    );
+}
+
+#[test]
+fn span_beginning_on_line_terminator_is_underlined_after_line() {
+    for (input, begin) in [
+        (&b"ab\r\ncd"[..], 3),
+        (&b"ab\r\ncd"[..], 2),
+        (&b"ab\ncd"[..], 2),
+    ] {
+        let input = PaddedString::from_slice(input);
+        let mut reporter = make_reporter(&input);
+        report(
+            &reporter,
+            DiagUnexpectedHashCharacter {
+                where_: span_of(&input, begin, begin + 1),
+            },
+        );
+        let output: String = String::from_utf8(reporter.take_output()).unwrap();
+        assert!(output.ends_with("\nab\n  ^\n"), "{output:?}");
+    }
+}
+
+#[test]
+fn multi_line_span_is_underlined_until_end_of_first_line() {
+    let input = PaddedString::from_slice(b"x = /* hello\nworld");
+    let mut reporter = make_reporter(&input);
+    report(
+        &reporter,
+        DiagUnclosedBlockComment {
+            comment_open: span_of(&input, 4, 18),
+        },
+    );
+    assert_eq!(
+        String::from_utf8(reporter.take_output()).unwrap(),
+        "FILE:1:5: error: unclosed block comment [E0037]\n\
+         x = /* hello\n    \
+         ^~~~~~~~\n"
+    );
+}
+
+#[test]
+fn underline_counts_characters_and_preserves_tabs() {
+    let input = PaddedString::from_slice("\t\u{2603} @\u{00e9}\u{00e9}".as_bytes());
+    let mut reporter = make_reporter(&input);
+    report(
+        &reporter,
+        DiagUnexpectedAtCharacter {
+            character: span_of(&input, 5, 10),
+        },
+    );
+    assert_eq!(
+        String::from_utf8(reporter.take_output()).unwrap(),
+        "FILE:1:4: error: unexpected '@' [E0042]\n\
+         \t\u{2603} @\u{00e9}\u{00e9}\n\
+         \t  ^~~\n"
+    );
+}
+
+#[test]
+fn notes_are_indented() {
+    let input = PaddedString::from_slice(b"aaa\nbbb");
+    let mut reporter = make_reporter(&input);
+    report(
+        &reporter,
+        DiagMultipleMessageTest {
+            a: span_of(&input, 0, 3),
+            b: span_of(&input, 5, 6),
+        },
+    );
+    assert_eq!(
+        String::from_utf8(reporter.take_output()).unwrap(),
+        "FILE:1:1: error: test for multiple messages [E6969]\n\
+         aaa\n\
+         ^~~\n  \
+         FILE:2:2: note: second message here\n  \
+         bbb\n   \
+         ^\n"
+    );
+}
+
+#[test]
+fn colors() {
+    let input = PaddedString::from_slice(b"@");
+    let mut reporter = make_reporter(&input);
+    reporter.set_colors(true);
+    report(
+        &reporter,
+        DiagUnexpectedAtCharacter {
+            character: span_of(&input, 0, 1),
+        },
+    );
+    assert_eq!(
+        String::from_utf8(reporter.take_output()).unwrap(),
+        "\x1b[1mFILE:1:1:\x1b[0m \x1b[1;31merror:\x1b[0m unexpected '@' [E0042]\n\
+         @\n\
+         \x1b[1;32m^\x1b[0m\n"
+    );
+}
+
+#[test]
+fn translates_messages() {
+    let input = PaddedString::from_slice(b"'hello");
+    let mut translator = Translator::new_using_messages_from_source_code();
+    assert!(translator.use_messages_from_locale("en_US@snarky"));
+    let mut reporter = TextDiagReporter::new(translator);
+    reporter.set_source(input.view(), "FILE");
+    report(
+        &reporter,
+        DiagUnclosedStringLiteral {
+            string_literal: span_of(&input, 0, 6),
+        },
+    );
+    assert_eq!(
+        String::from_utf8(reporter.take_output()).unwrap(),
+        "FILE:1:1: error: \"unclosed string literal [E0040]\n\
+         'hello\n\
+         ^~~~~~\n"
+    );
+}
+
+#[test]
+fn control_character_message_names_code_point() {
+    let input = PaddedString::from_slice(b"a\x07b");
+    let mut reporter = make_reporter(&input);
+    report(
+        &reporter,
+        DiagUnexpectedControlCharacter {
+            character: span_of(&input, 1, 2),
+            code_point: '\x07',
+        },
+    );
+    let output: String = String::from_utf8(reporter.take_output()).unwrap();
+    assert_eq!(
+        output.lines().next().unwrap(),
+        "FILE:1:2: error: unexpected control character U+0007 [E0045]"
+    );
+}
+
+#[test]
+fn too_many_diagnostics_message_counts_dropped_diagnostics() {
+    let input = PaddedString::from_slice(b"x");
+    let mut reporter = make_reporter(&input);
+    report(
+        &reporter,
+        DiagTooManyDiagnostics {
+            where_: span_of(&input, 1, 1),
+            dropped_count: 1234,
+        },
+    );
+    let output: String = String::from_utf8(reporter.take_output()).unwrap();
+    assert_eq!(
+        output.lines().next().unwrap(),
+        "FILE:1:2: warning: too many errors; 1,234 more were not reported [E0300]"
+    );
+}
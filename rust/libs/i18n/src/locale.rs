@@ -96,6 +96,17 @@ fn parse_locale<'a>(locale_name: &'a str) -> LocaleParts<'a> {
     parts
 }
 
+// Returns the string which separates groups of three digits in a large
+// integer (e.g. "," in "1,234,567").
+pub fn digit_group_separator(locale_name: &str) -> &'static str {
+    match parse_locale(locale_name).language() {
+        "de" | "pt" => ".",
+        "fr" => "\u{202f}", // Narrow no-break space.
+        "sv" => "\u{00a0}", // No-break space.
+        _ => ",",
+    }
+}
+
 pub fn locale_name_combinations(locale_name: &str) -> Vec<String> {
     let mut locale_names: Vec<String> = vec![];
     iterate_locale_name_combinations(locale_name, |current_locale| {
@@ -13,8 +13,20 @@ macro_rules! qljs_translatable {
     }};
 }
 
-// Global instance.
-pub static mut QLJS_MESSAGES: Translator = Translator::new_using_messages_from_source_code();
+// Global instance, shared by all threads. Read it with qljs_messages.
+//
+// NOTE(port): In C++, this was a global translator object. In Rust, we store
+// only the locale index so that any thread can read it without a lock.
+static QLJS_MESSAGES_LOCALE_INDEX: std::sync::atomic::AtomicI32 =
+    std::sync::atomic::AtomicI32::new(TRANSLATION_TABLE_LOCALE_COUNT as i32);
+
+// Returns a copy of the global translator configured by
+// initialize_translations_from_locale.
+pub fn qljs_messages() -> Translator {
+    Translator {
+        locale_index: QLJS_MESSAGES_LOCALE_INDEX.load(std::sync::atomic::Ordering::Relaxed),
+    }
+}
 
 pub fn initialize_locale() {
     unsafe {
@@ -24,13 +36,49 @@ pub fn initialize_locale() {
 
 pub fn initialize_translations_from_locale(locale_name: &str) {
     initialize_locale();
-    unsafe {
-        if !QLJS_MESSAGES.use_messages_from_locale(locale_name) {
-            QLJS_MESSAGES.use_messages_from_source_code();
+    let mut messages: Translator = Translator::new_using_messages_from_source_code();
+    if !messages.use_messages_from_locale(locale_name) {
+        messages.use_messages_from_source_code();
+    }
+    QLJS_MESSAGES_LOCALE_INDEX.store(messages.locale_index, std::sync::atomic::Ordering::Relaxed);
+}
+
+// Returns the user's preferred locales, most preferred first, from the
+// LANGUAGE, LC_ALL, LC_MESSAGES, and LANG environment variables.
+pub fn get_user_locale_preferences() -> Vec<String> {
+    user_locale_preferences_from_environment(|name: &str| std::env::var(name).ok())
+}
+
+// Like get_user_locale_preferences, but reads environment variables using
+// getenv.
+//
+// Like GNU gettext, LANGUAGE is a colon-separated list of locales, and only the
+// first non-empty of LC_ALL, LC_MESSAGES, and LANG is used.
+pub fn user_locale_preferences_from_environment<GetEnv: Fn(&str) -> Option<String>>(
+    getenv: GetEnv,
+) -> Vec<String> {
+    let mut locales: Vec<String> = vec![];
+    if let Some(language) = getenv("LANGUAGE") {
+        locales.extend(
+            language
+                .split(':')
+                .filter(|locale: &&str| !locale.is_empty())
+                .map(String::from),
+        );
+    }
+    for name in ["LC_ALL", "LC_MESSAGES", "LANG"] {
+        if let Some(locale) = getenv(name) {
+            if !locale.is_empty() {
+                locales.push(locale);
+                break;
+            }
         }
     }
+    locales
 }
 
+// A Translator is a small value (Send and Sync), so each thread can hold its
+// own copy.
 #[derive(Clone)]
 pub struct Translator {
     locale_index: i32,
@@ -72,6 +120,30 @@ impl Translator {
         false
     }
 
+    // Returns the name of the locale whose messages are used, or "" if messages
+    // from the source code are used.
+    pub fn locale_name(&self) -> &'static str {
+        TRANSLATION_DATA_LOCALE_TABLE
+            .split('\0')
+            .nth(self.locale_index as usize)
+            .unwrap()
+    }
+
+    // Formats value in decimal, grouping digits according to the locale (e.g.
+    // "1,234,567" for English).
+    pub fn format_integer(&self, value: u64) -> String {
+        let digits: String = value.to_string();
+        let separator: &str = digit_group_separator(self.locale_name());
+        let mut result: String = String::with_capacity(digits.len() * 2);
+        for (i, digit) in digits.chars().enumerate() {
+            if i != 0 && (digits.len() - i).is_multiple_of(3) {
+                result.push_str(separator);
+            }
+            result.push(digit);
+        }
+        result
+    }
+
     pub fn translate(&self, message: TranslatableMessage) -> &'static str {
         // If the following assertion fails, it's likely that
         // translation-table-generated.h is out of date. Run
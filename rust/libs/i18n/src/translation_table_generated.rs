@@ -7,12 +7,18 @@
 use crate::translation_table::*;
 
 pub const TRANSLATION_TABLE_LOCALE_COUNT: u32 = 5;
-pub const TRANSLATION_TABLE_MAPPING_TABLE_SIZE: u16 = 64;
+pub const TRANSLATION_TABLE_MAPPING_TABLE_SIZE: u16 = 81;
 
-pub const UNTRANSLATED_STRINGS: [&str; 63] = [
+pub const UNTRANSLATED_STRINGS: [&str; 80] = [
     "'>' is not allowed directly in JSX text; write {{'>'} or &gt; instead",
     "'do-while' loop",
     "'for' loop",
+    "'global-groups' entries must be strings",
+    "'global-groups' must be a boolean or an array",
+    "'globals' descriptor 'shadowable' property must be a boolean",
+    "'globals' descriptor 'writable' property must be a boolean",
+    "'globals' descriptor must be a boolean or an object",
+    "'globals' must be an object",
     "'if' statement",
     "'while' loop",
     "'with' statement",
@@ -20,6 +26,7 @@ pub const UNTRANSLATED_STRINGS: [&str; 63] = [
     "'}' is not allowed directly in JSX text; write {{'}'} instead",
     "BigInt literal contains decimal point",
     "BigInt literal contains exponent",
+    "JSON syntax error",
     "RegExp literal flags cannot contain Unicode escapes",
     "a 'do-while' loop",
     "a 'for' loop",
@@ -37,6 +44,8 @@ pub const UNTRANSLATED_STRINGS: [&str; 63] = [
     "expected hexadecimal digits in Unicode escape sequence",
     "expected {1:headlinese}",
     "expected {1:singular}",
+    "file looks like a binary file; stopped linting",
+    "found {1} problems",
     "free {1} and {0} {1} {2}",
     "hex number literal has no digits",
     "integer cannot be represented and will be rounded to '{1}'",
@@ -51,12 +60,16 @@ pub const UNTRANSLATED_STRINGS: [&str; 63] = [
     "octal literal may not have decimal",
     "octal literal may not have exponent",
     "octal number literal has no digits",
+    "optional note here",
     "second message here",
     "see here",
     "something happened",
+    "test for enum arguments",
     "test for multiple messages",
+    "test for optional notes",
     "this {0} looks fishy",
     "this {1} looks fishy",
+    "too many errors; {1} more were not reported",
     "unclosed block comment",
     "unclosed identifier escape sequence",
     "unclosed regexp literal",
@@ -65,82 +78,103 @@ pub const UNTRANSLATED_STRINGS: [&str; 63] = [
     "unexpected '#'",
     "unexpected '@'",
     "unexpected '\\' in identifier",
+    "unexpected '{1}'",
+    "unexpected character {1}",
     "unexpected characters in binary literal",
     "unexpected characters in hex literal",
     "unexpected characters in number literal",
     "unexpected characters in octal literal",
-    "unexpected control character",
+    "unexpected control character {1}",
     "unicode byte order mark (BOM) cannot appear before #! at beginning of script",
     "unopened block comment",
     "what is this '{1}' nonsense?",
+    "{0} is {0:plural} and {1}",
+    "{4}{3}{2}{1}{0}",
 ];
 
 pub const TRANSLATION_DATA_MAPPING_TABLE: [TranslationTableMappingEntry;
     TRANSLATION_TABLE_MAPPING_TABLE_SIZE as usize] = [
     TranslationTableMappingEntry([0, 0, 0, 0, 0, 0]), //
-    TranslationTableMappingEntry([1, 2177, 0, 5627, 0, 8936]), //
-    TranslationTableMappingEntry([94, 2240, 0, 5697, 0, 9006]), //
-    TranslationTableMappingEntry([112, 2253, 0, 5713, 0, 9022]), //
-    TranslationTableMappingEntry([125, 2269, 0, 5724, 0, 9033]), //
-    TranslationTableMappingEntry([138, 2293, 0, 5741, 0, 9048]), //
-    TranslationTableMappingEntry([153, 2307, 0, 5754, 0, 9061]), //
-    TranslationTableMappingEntry([168, 2345, 0, 5773, 0, 9078]), //
-    TranslationTableMappingEntry([237, 2393, 0, 5819, 0, 9128]), //
-    TranslationTableMappingEntry([320, 2448, 3949, 5881, 7694, 9190]), //
-    TranslationTableMappingEntry([352, 2480, 4006, 5915, 7739, 9228]), //
-    TranslationTableMappingEntry([382, 2513, 4046, 5945, 0, 9261]), //
-    TranslationTableMappingEntry([438, 2551, 0, 6009, 0, 9313]), //
-    TranslationTableMappingEntry([461, 2566, 0, 6028, 0, 9331]), //
-    TranslationTableMappingEntry([479, 2584, 0, 6042, 0, 9344]), //
-    TranslationTableMappingEntry([499, 2600, 0, 6058, 0, 9359]), //
-    TranslationTableMappingEntry([0, 0, 0, 6081, 0, 9378]), //
-    TranslationTableMappingEntry([519, 2640, 0, 0, 0, 9399]), //
-    TranslationTableMappingEntry([533, 2654, 0, 6107, 0, 9413]), //
-    TranslationTableMappingEntry([551, 2680, 4109, 6128, 7783, 9431]), //
-    TranslationTableMappingEntry([587, 2712, 4161, 6163, 7825, 9467]), //
-    TranslationTableMappingEntry([621, 2742, 4208, 6209, 0, 9507]), //
-    TranslationTableMappingEntry([705, 2800, 4304, 6286, 7869, 9579]), //
-    TranslationTableMappingEntry([751, 2818, 4330, 6325, 7887, 9603]), //
-    TranslationTableMappingEntry([815, 2871, 0, 6379, 0, 9651]), //
-    TranslationTableMappingEntry([889, 2907, 4384, 6435, 7935, 9711]), //
-    TranslationTableMappingEntry([944, 2988, 0, 6508, 0, 9766]), //
-    TranslationTableMappingEntry([968, 3012, 0, 6532, 0, 9790]), //
-    TranslationTableMappingEntry([990, 3034, 0, 0, 0, 9812]), //
-    TranslationTableMappingEntry([1017, 3059, 4456, 6554, 7992, 9837]), //
-    TranslationTableMappingEntry([0, 3092, 0, 6592, 0, 9870]), //
-    TranslationTableMappingEntry([1058, 3160, 4504, 6660, 8028, 9929]), //
-    TranslationTableMappingEntry([1083, 3206, 4529, 6687, 8050, 9952]), //
-    TranslationTableMappingEntry([1117, 3221, 4572, 6727, 8087, 9985]), //
-    TranslationTableMappingEntry([0, 0, 0, 6782, 0, 10026]), //
-    TranslationTableMappingEntry([1174, 3293, 4639, 6804, 8133, 10045]), //
-    TranslationTableMappingEntry([1228, 3298, 4701, 6846, 8185, 10084]), //
-    TranslationTableMappingEntry([1289, 3348, 4776, 6895, 8249, 10134]), //
-    TranslationTableMappingEntry([1358, 3371, 4855, 6936, 8300, 10182]), //
-    TranslationTableMappingEntry([1398, 3397, 4916, 6975, 8356, 10229]), //
-    TranslationTableMappingEntry([1448, 3418, 4969, 7016, 8400, 10264]), //
-    TranslationTableMappingEntry([1496, 3445, 5013, 7053, 8443, 10300]), //
-    TranslationTableMappingEntry([0, 0, 0, 0, 0, 10335]), //
-    TranslationTableMappingEntry([1531, 3482, 0, 7085, 0, 10355]), //
-    TranslationTableMappingEntry([1542, 3491, 0, 7095, 0, 10364]), //
-    TranslationTableMappingEntry([0, 0, 0, 0, 0, 10383]), //
-    TranslationTableMappingEntry([1556, 3517, 0, 7110, 0, 10410]), //
-    TranslationTableMappingEntry([1589, 3538, 0, 7135, 0, 10431]), //
-    TranslationTableMappingEntry([1622, 3559, 5063, 7160, 8481, 10452]), //
-    TranslationTableMappingEntry([1647, 3609, 5094, 7196, 8511, 10475]), //
-    TranslationTableMappingEntry([1683, 3621, 5145, 7234, 8548, 10511]), //
-    TranslationTableMappingEntry([1710, 3646, 5173, 7256, 8573, 10535]), //
-    TranslationTableMappingEntry([1733, 3671, 5201, 7278, 8599, 10559]), //
-    TranslationTableMappingEntry([1752, 3690, 5221, 7304, 8614, 10577]), //
-    TranslationTableMappingEntry([1768, 3702, 5235, 7319, 8631, 10592]), //
-    TranslationTableMappingEntry([1785, 3713, 5249, 7334, 8648, 10607]), //
-    TranslationTableMappingEntry([1816, 3725, 5283, 7369, 8681, 10636]), //
-    TranslationTableMappingEntry([1862, 3765, 5332, 7415, 8718, 10676]), //
-    TranslationTableMappingEntry([1913, 3802, 5377, 7464, 8752, 10713]), //
-    TranslationTableMappingEntry([1950, 3824, 5429, 7501, 8789, 10753]), //
-    TranslationTableMappingEntry([1995, 3850, 5476, 7544, 8825, 10792]), //
-    TranslationTableMappingEntry([2022, 3880, 5510, 7575, 8853, 10821]), //
-    TranslationTableMappingEntry([2120, 3917, 5596, 7657, 0, 10898]), //
-    TranslationTableMappingEntry([2147, 3920, 0, 0, 0, 10921]), //
+    TranslationTableMappingEntry([1, 2150, 0, 5536, 0, 8786]), //
+    TranslationTableMappingEntry([94, 2213, 0, 5606, 0, 8856]), //
+    TranslationTableMappingEntry([112, 2226, 0, 5622, 0, 8872]), //
+    TranslationTableMappingEntry([0, 0, 0, 0, 0, 8883]), //
+    TranslationTableMappingEntry([0, 0, 0, 0, 0, 8923]), //
+    TranslationTableMappingEntry([0, 0, 0, 0, 0, 8969]), //
+    TranslationTableMappingEntry([0, 0, 0, 0, 0, 9030]), //
+    TranslationTableMappingEntry([0, 0, 0, 0, 0, 9089]), //
+    TranslationTableMappingEntry([0, 0, 0, 0, 0, 9141]), //
+    TranslationTableMappingEntry([125, 2242, 0, 5633, 0, 9169]), //
+    TranslationTableMappingEntry([138, 2266, 0, 5650, 0, 9184]), //
+    TranslationTableMappingEntry([153, 2280, 0, 5663, 0, 9197]), //
+    TranslationTableMappingEntry([168, 2318, 0, 5682, 0, 9214]), //
+    TranslationTableMappingEntry([237, 2366, 0, 5728, 0, 9264]), //
+    TranslationTableMappingEntry([320, 2421, 3892, 5790, 7572, 9326]), //
+    TranslationTableMappingEntry([352, 2453, 3949, 5824, 7617, 9364]), //
+    TranslationTableMappingEntry([0, 0, 0, 0, 0, 9397]), //
+    TranslationTableMappingEntry([382, 2486, 3989, 5854, 0, 9415]), //
+    TranslationTableMappingEntry([438, 2524, 0, 5918, 0, 9467]), //
+    TranslationTableMappingEntry([461, 2539, 0, 5937, 0, 9485]), //
+    TranslationTableMappingEntry([479, 2557, 0, 5951, 0, 9498]), //
+    TranslationTableMappingEntry([499, 2573, 0, 5967, 0, 9513]), //
+    TranslationTableMappingEntry([0, 0, 0, 5990, 0, 9532]), //
+    TranslationTableMappingEntry([519, 2613, 0, 0, 0, 9553]), //
+    TranslationTableMappingEntry([533, 2627, 0, 6016, 0, 9567]), //
+    TranslationTableMappingEntry([551, 2653, 4052, 6037, 7661, 9585]), //
+    TranslationTableMappingEntry([587, 2685, 4104, 6072, 7703, 9621]), //
+    TranslationTableMappingEntry([621, 2715, 4151, 6118, 0, 9661]), //
+    TranslationTableMappingEntry([705, 2773, 4247, 6195, 7747, 9733]), //
+    TranslationTableMappingEntry([751, 2791, 4273, 6234, 7765, 9757]), //
+    TranslationTableMappingEntry([815, 2844, 0, 6288, 0, 9805]), //
+    TranslationTableMappingEntry([889, 2880, 4327, 6344, 7813, 9865]), //
+    TranslationTableMappingEntry([944, 2961, 0, 6417, 0, 9920]), //
+    TranslationTableMappingEntry([968, 2985, 0, 6441, 0, 9944]), //
+    TranslationTableMappingEntry([0, 0, 0, 0, 0, 9966]), //
+    TranslationTableMappingEntry([0, 0, 0, 0, 0, 10013]), //
+    TranslationTableMappingEntry([990, 3007, 0, 0, 0, 10032]), //
+    TranslationTableMappingEntry([1017, 3032, 4399, 6463, 7870, 10057]), //
+    TranslationTableMappingEntry([0, 3065, 0, 6501, 0, 10090]), //
+    TranslationTableMappingEntry([1058, 3133, 4447, 6569, 7906, 10149]), //
+    TranslationTableMappingEntry([1083, 3179, 4472, 6596, 7928, 10172]), //
+    TranslationTableMappingEntry([1117, 3194, 4515, 6636, 7965, 10205]), //
+    TranslationTableMappingEntry([0, 0, 0, 6691, 0, 10246]), //
+    TranslationTableMappingEntry([1174, 3266, 4582, 6713, 8011, 10265]), //
+    TranslationTableMappingEntry([1228, 3271, 4644, 6755, 8063, 10304]), //
+    TranslationTableMappingEntry([1289, 3321, 4719, 6804, 8127, 10354]), //
+    TranslationTableMappingEntry([1358, 3344, 4798, 6845, 8178, 10402]), //
+    TranslationTableMappingEntry([1398, 3370, 4859, 6884, 8234, 10449]), //
+    TranslationTableMappingEntry([1448, 3391, 4912, 6925, 8278, 10484]), //
+    TranslationTableMappingEntry([1496, 3418, 4956, 6962, 8321, 10520]), //
+    TranslationTableMappingEntry([0, 0, 0, 0, 0, 10555]), //
+    TranslationTableMappingEntry([0, 0, 0, 0, 0, 10574]), //
+    TranslationTableMappingEntry([1531, 3455, 0, 6994, 0, 10594]), //
+    TranslationTableMappingEntry([1542, 3464, 0, 7004, 0, 10603]), //
+    TranslationTableMappingEntry([0, 0, 0, 0, 0, 10622]), //
+    TranslationTableMappingEntry([0, 0, 0, 0, 0, 10646]), //
+    TranslationTableMappingEntry([0, 0, 0, 0, 0, 10673]), //
+    TranslationTableMappingEntry([1556, 3490, 0, 7019, 0, 10697]), //
+    TranslationTableMappingEntry([1589, 3511, 0, 7044, 0, 10718]), //
+    TranslationTableMappingEntry([0, 0, 0, 0, 0, 10739]), //
+    TranslationTableMappingEntry([1622, 3532, 5006, 7069, 8359, 10783]), //
+    TranslationTableMappingEntry([1647, 3582, 5037, 7105, 8389, 10806]), //
+    TranslationTableMappingEntry([1683, 3594, 5088, 7143, 8426, 10842]), //
+    TranslationTableMappingEntry([1710, 3619, 5116, 7165, 8451, 10866]), //
+    TranslationTableMappingEntry([1733, 3644, 5144, 7187, 8477, 10890]), //
+    TranslationTableMappingEntry([1752, 3663, 5164, 7213, 8492, 10908]), //
+    TranslationTableMappingEntry([1768, 3675, 5178, 7228, 8509, 10923]), //
+    TranslationTableMappingEntry([1785, 3686, 5192, 7243, 8526, 10938]), //
+    TranslationTableMappingEntry([0, 0, 0, 0, 0, 10967]), //
+    TranslationTableMappingEntry([0, 0, 0, 0, 0, 10984]), //
+    TranslationTableMappingEntry([1816, 3698, 5226, 7278, 8559, 11009]), //
+    TranslationTableMappingEntry([1862, 3738, 5275, 7324, 8596, 11049]), //
+    TranslationTableMappingEntry([1913, 3775, 5320, 7373, 8630, 11086]), //
+    TranslationTableMappingEntry([1950, 3797, 5372, 7410, 8667, 11126]), //
+    TranslationTableMappingEntry([0, 0, 0, 0, 0, 11165]), //
+    TranslationTableMappingEntry([1995, 3823, 5419, 7453, 8703, 11198]), //
+    TranslationTableMappingEntry([2093, 3860, 5505, 7535, 0, 11275]), //
+    TranslationTableMappingEntry([2120, 3863, 0, 0, 0, 11298]), //
+    TranslationTableMappingEntry([0, 0, 0, 0, 0, 11327]), //
+    TranslationTableMappingEntry([0, 0, 0, 0, 0, 11353]), //
 ];
 
 pub const TRANSLATION_DATA_STRING_TABLE: &[u8] = "\
@@ -199,7 +233,6 @@ pub const TRANSLATION_DATA_STRING_TABLE: &[u8] = "\
         Unerwartete Zeichen in hexadezimalem Zahlenliteral\0\
         Unerwartete Zeichen in Zahlenliteral\0\
         Unerwartete Zeichen in oktalem Zahlenliteral\0\
-        Unerwartetes Steuerzeichen\0\
         Die Unicode Bytereihenfolge-Markierung (BOM) darf nicht vor #! zu Beginn eines Skripts erscheinen\0\
         Blockkommentar ohne Beginn\0\
         Was soll dieser '{1}' Humbug?\0\
@@ -258,7 +291,6 @@ pub const TRANSLATION_DATA_STRING_TABLE: &[u8] = "\
         unexpected characters in hex literal\0\
         does not compute \u{1f916}\0\
         Cthulhu \u{1f419} is not happy\0\
-        you lost control of your code\0\
         your editor BOMd \u{1f4a3} your s#!t \u{1f4a9}\0\
         /*\0\
         what is this '{1}' nonsense?\0\
@@ -294,7 +326,6 @@ pub const TRANSLATION_DATA_STRING_TABLE: &[u8] = "\
         caract\u{00e8}res inattendus dans un litt\u{00e9}ral hex\0\
         caract\u{00e8}res inattendus dans un litt\u{00e9}ral num\u{00e9}rique\0\
         caract\u{00e8}res inattendus dans un litt\u{00e9}ral octal\0\
-        caract\u{00e8}re de contr\u{00f4}le inattendu\0\
         un indicateur d'ordre des octets (BOM) ne peut figurer avant #! au d\u{00e9}but d'un script\0\
         commentaire de bloc non ouvert\0\
         '>' n\u{00e3}o \u{00e9} permitido diretamente em um texto JSX; use {{'>'} ou &gt;\0\
@@ -352,7 +383,6 @@ pub const TRANSLATION_DATA_STRING_TABLE: &[u8] = "\
         caracteres inesperados em um n\u{00fa}mero hexadecimal\0\
         caracteres inesperados em um n\u{00fa}mero\0\
         caracteres inesperados em um n\u{00fa}mero octal\0\
-        caracter de control inesperado\0\
         unicode byte order mark (BOM) n\u{00e3}o pode aparecer antes do #! no come\u{00e7}o do script\0\
         bloco de coment\u{00e1}rio n\u{00e3}o foi aberto\0\
         BigInt heltallitter\u{00e4}r inneh\u{00e5}ller decimaler\0\
@@ -385,11 +415,16 @@ pub const TRANSLATION_DATA_STRING_TABLE: &[u8] = "\
         of\u{00f6}rv\u{00e4}ntat tecken i hexlitteral\0\
         of\u{00f6}rv\u{00e4}ntat tecken i nummerlitteral\0\
         of\u{00f6}rv\u{00e4}ntat tecken i oktallitteral\0\
-        of\u{00f6}rv\u{00e4}ntat kontrolltecken\0\
         unicode byte ordningsm\u{00e4}rke (BOM) kan inte f\u{00f6}rekomma f\u{00f6}re #! i b\u{00f6}rjan av skript\0\
         '>' is not allowed directly in JSX text; write {{'>'} or &gt; instead\0\
         'do-while' loop\0\
         'for' loop\0\
+        'global-groups' entries must be strings\0\
+        'global-groups' must be a boolean or an array\0\
+        'globals' descriptor 'shadowable' property must be a boolean\0\
+        'globals' descriptor 'writable' property must be a boolean\0\
+        'globals' descriptor must be a boolean or an object\0\
+        'globals' must be an object\0\
         'if' statement\0\
         'while' loop\0\
         'with' statement\0\
@@ -397,6 +432,7 @@ pub const TRANSLATION_DATA_STRING_TABLE: &[u8] = "\
         '}' is not allowed directly in JSX text; write {{'}'} instead\0\
         BigInt literal contains decimal point\0\
         BigInt literal contains exponent\0\
+        JSON syntax error\0\
         RegExp literal flags cannot contain Unicode escapes\0\
         a 'do-while' loop\0\
         a 'for' loop\0\
@@ -414,6 +450,8 @@ pub const TRANSLATION_DATA_STRING_TABLE: &[u8] = "\
         expected hexadecimal digits in Unicode escape sequence\0\
         expected {1:headlinese}\0\
         expected {1:singular}\0\
+        file looks like a binary file; stopped linting\0\
+        found {1} problems\0\
         free {1} and {0} {1} {2}\0\
         hex number literal has no digits\0\
         integer cannot be represented and will be rounded to '{1}'\0\
@@ -428,12 +466,16 @@ pub const TRANSLATION_DATA_STRING_TABLE: &[u8] = "\
         octal literal may not have decimal\0\
         octal literal may not have exponent\0\
         octal number literal has no digits\0\
+        optional note here\0\
         second message here\0\
         see here\0\
         something happened\0\
+        test for enum arguments\0\
         test for multiple messages\0\
+        test for optional notes\0\
         this {0} looks fishy\0\
         this {1} looks fishy\0\
+        too many errors; {1} more were not reported\0\
         unclosed block comment\0\
         unclosed identifier escape sequence\0\
         unclosed regexp literal\0\
@@ -442,14 +484,18 @@ pub const TRANSLATION_DATA_STRING_TABLE: &[u8] = "\
         unexpected '#'\0\
         unexpected '@'\0\
         unexpected '\\' in identifier\0\
+        unexpected '{1}'\0\
+        unexpected character {1}\0\
         unexpected characters in binary literal\0\
         unexpected characters in hex literal\0\
         unexpected characters in number literal\0\
         unexpected characters in octal literal\0\
-        unexpected control character\0\
+        unexpected control character {1}\0\
         unicode byte order mark (BOM) cannot appear before #! at beginning of script\0\
         unopened block comment\0\
         what is this '{1}' nonsense?\0\
+        {0} is {0:plural} and {1}\0\
+        {4}{3}{2}{1}{0}\0\
 ".as_bytes();
 
 pub const TRANSLATION_DATA_LOCALE_TABLE: &str = "\
@@ -87,6 +87,16 @@ fn match_subset_of_locale_name() {
     });
 }
 
+#[test]
+fn digit_group_separator_depends_on_language() {
+    assert_eq!(digit_group_separator(""), ",");
+    assert_eq!(digit_group_separator("en_US@snarky"), ",");
+    assert_eq!(digit_group_separator("de"), ".");
+    assert_eq!(digit_group_separator("pt_BR"), ".");
+    assert_eq!(digit_group_separator("fr_FR.utf8"), "\u{202f}");
+    assert_eq!(digit_group_separator("sv_SE"), "\u{00a0}");
+}
+
 fn make_locales_string(locales: &[&str]) -> Vec<u8> {
     let mut locales_string: Vec<u8> = vec![];
     for locale in locales {
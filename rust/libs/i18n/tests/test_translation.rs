@@ -104,6 +104,99 @@ fn english_snarky_translates() {
     assert_eq!(reporter.get_messages(), vec![b"#unexpected"],);
 }
 
+#[test]
+fn locale_name_reflects_chosen_locale() {
+    let mut t = Translator::new_using_messages_from_source_code();
+    assert_eq!(t.locale_name(), "");
+    assert!(t.use_messages_from_locale("de_DE.utf8"));
+    assert_eq!(t.locale_name(), "de");
+    t.use_messages_from_source_code();
+    assert_eq!(t.locale_name(), "");
+}
+
+#[test]
+fn format_integer_groups_digits_by_locale() {
+    let mut t = Translator::new_using_messages_from_source_code();
+    assert_eq!(t.format_integer(0), "0");
+    assert_eq!(t.format_integer(999), "999");
+    assert_eq!(t.format_integer(1000), "1,000");
+    assert_eq!(t.format_integer(1234567), "1,234,567");
+    assert_eq!(t.format_integer(u64::MAX), "18,446,744,073,709,551,615");
+
+    assert!(t.use_messages_from_locale("de"));
+    assert_eq!(t.format_integer(1234567), "1.234.567");
+
+    assert!(t.use_messages_from_locale("fr_FR"));
+    assert_eq!(t.format_integer(1234), "1\u{202f}234");
+}
+
+#[test]
+fn translator_can_be_used_from_several_threads() {
+    fn assert_send_and_sync<T: Send + Sync>() {}
+    assert_send_and_sync::<Translator>();
+
+    let mut t = Translator::new_using_messages_from_source_code();
+    assert!(t.use_messages_from_locale("en_US.utf8@snarky"));
+    let translator: &Translator = &t;
+    std::thread::scope(|scope| {
+        let threads: Vec<std::thread::ScopedJoinHandle<Vec<Vec<u8>>>> = (0..4)
+            .map(|_| {
+                scope.spawn(move || {
+                    let reporter = BasicTextDiagReporter::new(translator.clone());
+                    for _ in 0..100 {
+                        report(
+                            &reporter,
+                            DiagUnexpectedHashCharacter {
+                                where_: dummy_span(),
+                            },
+                        );
+                    }
+                    reporter.get_messages()
+                })
+            })
+            .collect();
+        for thread in threads {
+            let messages: Vec<Vec<u8>> = thread.join().unwrap();
+            assert_eq!(messages.len(), 100);
+            assert!(messages
+                .iter()
+                .all(|message: &Vec<u8>| message == b"#unexpected"));
+        }
+    });
+}
+
+#[test]
+fn global_translator_is_visible_from_other_threads() {
+    initialize_translations_from_locale("de_DE.utf8");
+    let locale_name: &str = std::thread::spawn(|| qljs_messages().locale_name())
+        .join()
+        .unwrap();
+    assert_eq!(locale_name, "de");
+
+    initialize_translations_from_locale("xx_XX");
+    assert_eq!(qljs_messages().locale_name(), "");
+}
+
+#[test]
+fn user_locale_preferences_list_language_then_first_lc_variable() {
+    let environment: [(&str, &str); 4] = [
+        ("LANGUAGE", "fr_FR:::de"),
+        ("LC_ALL", ""),
+        ("LC_MESSAGES", "sv_SE.UTF-8"),
+        ("LANG", "en_US"),
+    ];
+    let locales: Vec<String> = user_locale_preferences_from_environment(|name: &str| {
+        environment
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, value)| value.to_string())
+    });
+    assert_eq!(locales, vec!["fr_FR", "de", "sv_SE.UTF-8"]);
+
+    let locales: Vec<String> = user_locale_preferences_from_environment(|_name: &str| None);
+    assert!(locales.is_empty());
+}
+
 fn dummy_span() -> SourceCodeSpan<'static> {
     const HELLO: &'static [u8] = b"hello";
     SourceCodeSpan::from_slice(&HELLO[0..5])
@@ -25,7 +25,7 @@ pub struct TranslatedString {
     pub expected_per_locale: [&'static str; 6],
 }
 
-pub const TEST_TRANSLATION_TABLE: [TranslatedString; 63] = [
+pub const TEST_TRANSLATION_TABLE: [TranslatedString; 80] = [
     TranslatedString{
         translatable: qljs_translatable!("'>' is not allowed directly in JSX text; write {{'>'} or &gt; instead"),
         expected_per_locale: [
@@ -59,6 +59,72 @@ pub const TEST_TRANSLATION_TABLE: [TranslatedString; 63] = [
             "'for' loop",
         ],
     },
+    TranslatedString{
+        translatable: qljs_translatable!("'global-groups' entries must be strings"),
+        expected_per_locale: [
+            "'global-groups' entries must be strings",
+            "'global-groups' entries must be strings",
+            "'global-groups' entries must be strings",
+            "'global-groups' entries must be strings",
+            "'global-groups' entries must be strings",
+            "'global-groups' entries must be strings",
+        ],
+    },
+    TranslatedString{
+        translatable: qljs_translatable!("'global-groups' must be a boolean or an array"),
+        expected_per_locale: [
+            "'global-groups' must be a boolean or an array",
+            "'global-groups' must be a boolean or an array",
+            "'global-groups' must be a boolean or an array",
+            "'global-groups' must be a boolean or an array",
+            "'global-groups' must be a boolean or an array",
+            "'global-groups' must be a boolean or an array",
+        ],
+    },
+    TranslatedString{
+        translatable: qljs_translatable!("'globals' descriptor 'shadowable' property must be a boolean"),
+        expected_per_locale: [
+            "'globals' descriptor 'shadowable' property must be a boolean",
+            "'globals' descriptor 'shadowable' property must be a boolean",
+            "'globals' descriptor 'shadowable' property must be a boolean",
+            "'globals' descriptor 'shadowable' property must be a boolean",
+            "'globals' descriptor 'shadowable' property must be a boolean",
+            "'globals' descriptor 'shadowable' property must be a boolean",
+        ],
+    },
+    TranslatedString{
+        translatable: qljs_translatable!("'globals' descriptor 'writable' property must be a boolean"),
+        expected_per_locale: [
+            "'globals' descriptor 'writable' property must be a boolean",
+            "'globals' descriptor 'writable' property must be a boolean",
+            "'globals' descriptor 'writable' property must be a boolean",
+            "'globals' descriptor 'writable' property must be a boolean",
+            "'globals' descriptor 'writable' property must be a boolean",
+            "'globals' descriptor 'writable' property must be a boolean",
+        ],
+    },
+    TranslatedString{
+        translatable: qljs_translatable!("'globals' descriptor must be a boolean or an object"),
+        expected_per_locale: [
+            "'globals' descriptor must be a boolean or an object",
+            "'globals' descriptor must be a boolean or an object",
+            "'globals' descriptor must be a boolean or an object",
+            "'globals' descriptor must be a boolean or an object",
+            "'globals' descriptor must be a boolean or an object",
+            "'globals' descriptor must be a boolean or an object",
+        ],
+    },
+    TranslatedString{
+        translatable: qljs_translatable!("'globals' must be an object"),
+        expected_per_locale: [
+            "'globals' must be an object",
+            "'globals' must be an object",
+            "'globals' must be an object",
+            "'globals' must be an object",
+            "'globals' must be an object",
+            "'globals' must be an object",
+        ],
+    },
     TranslatedString{
         translatable: qljs_translatable!("'if' statement"),
         expected_per_locale: [
@@ -136,6 +202,17 @@ pub const TEST_TRANSLATION_TABLE: [TranslatedString; 63] = [
             "BigInt heltallitter\u{00e4}r inneh\u{00e5}ller exponent",
         ],
     },
+    TranslatedString{
+        translatable: qljs_translatable!("JSON syntax error"),
+        expected_per_locale: [
+            "JSON syntax error",
+            "JSON syntax error",
+            "JSON syntax error",
+            "JSON syntax error",
+            "JSON syntax error",
+            "JSON syntax error",
+        ],
+    },
     TranslatedString{
         translatable: qljs_translatable!("RegExp literal flags cannot contain Unicode escapes"),
         expected_per_locale: [
@@ -323,6 +400,28 @@ pub const TEST_TRANSLATION_TABLE: [TranslatedString; 63] = [
             "expected {1:singular}",
         ],
     },
+    TranslatedString{
+        translatable: qljs_translatable!("file looks like a binary file; stopped linting"),
+        expected_per_locale: [
+            "file looks like a binary file; stopped linting",
+            "file looks like a binary file; stopped linting",
+            "file looks like a binary file; stopped linting",
+            "file looks like a binary file; stopped linting",
+            "file looks like a binary file; stopped linting",
+            "file looks like a binary file; stopped linting",
+        ],
+    },
+    TranslatedString{
+        translatable: qljs_translatable!("found {1} problems"),
+        expected_per_locale: [
+            "found {1} problems",
+            "found {1} problems",
+            "found {1} problems",
+            "found {1} problems",
+            "found {1} problems",
+            "found {1} problems",
+        ],
+    },
     TranslatedString{
         translatable: qljs_translatable!("free {1} and {0} {1} {2}"),
         expected_per_locale: [
@@ -477,6 +576,17 @@ pub const TEST_TRANSLATION_TABLE: [TranslatedString; 63] = [
             "oktal nummerlitteral har inga siffror",
         ],
     },
+    TranslatedString{
+        translatable: qljs_translatable!("optional note here"),
+        expected_per_locale: [
+            "optional note here",
+            "optional note here",
+            "optional note here",
+            "optional note here",
+            "optional note here",
+            "optional note here",
+        ],
+    },
     TranslatedString{
         translatable: qljs_translatable!("second message here"),
         expected_per_locale: [
@@ -510,6 +620,17 @@ pub const TEST_TRANSLATION_TABLE: [TranslatedString; 63] = [
             "something happened",
         ],
     },
+    TranslatedString{
+        translatable: qljs_translatable!("test for enum arguments"),
+        expected_per_locale: [
+            "test for enum arguments",
+            "test for enum arguments",
+            "test for enum arguments",
+            "test for enum arguments",
+            "test for enum arguments",
+            "test for enum arguments",
+        ],
+    },
     TranslatedString{
         translatable: qljs_translatable!("test for multiple messages"),
         expected_per_locale: [
@@ -521,6 +642,17 @@ pub const TEST_TRANSLATION_TABLE: [TranslatedString; 63] = [
             "test for multiple messages",
         ],
     },
+    TranslatedString{
+        translatable: qljs_translatable!("test for optional notes"),
+        expected_per_locale: [
+            "test for optional notes",
+            "test for optional notes",
+            "test for optional notes",
+            "test for optional notes",
+            "test for optional notes",
+            "test for optional notes",
+        ],
+    },
     TranslatedString{
         translatable: qljs_translatable!("this {0} looks fishy"),
         expected_per_locale: [
@@ -543,6 +675,17 @@ pub const TEST_TRANSLATION_TABLE: [TranslatedString; 63] = [
             "this {1} looks fishy",
         ],
     },
+    TranslatedString{
+        translatable: qljs_translatable!("too many errors; {1} more were not reported"),
+        expected_per_locale: [
+            "too many errors; {1} more were not reported",
+            "too many errors; {1} more were not reported",
+            "too many errors; {1} more were not reported",
+            "too many errors; {1} more were not reported",
+            "too many errors; {1} more were not reported",
+            "too many errors; {1} more were not reported",
+        ],
+    },
     TranslatedString{
         translatable: qljs_translatable!("unclosed block comment"),
         expected_per_locale: [
@@ -631,6 +774,28 @@ pub const TEST_TRANSLATION_TABLE: [TranslatedString; 63] = [
             "of\u{00f6}rv\u{00e4}ntad '\\' i identifierare",
         ],
     },
+    TranslatedString{
+        translatable: qljs_translatable!("unexpected '{1}'"),
+        expected_per_locale: [
+            "unexpected '{1}'",
+            "unexpected '{1}'",
+            "unexpected '{1}'",
+            "unexpected '{1}'",
+            "unexpected '{1}'",
+            "unexpected '{1}'",
+        ],
+    },
+    TranslatedString{
+        translatable: qljs_translatable!("unexpected character {1}"),
+        expected_per_locale: [
+            "unexpected character {1}",
+            "unexpected character {1}",
+            "unexpected character {1}",
+            "unexpected character {1}",
+            "unexpected character {1}",
+            "unexpected character {1}",
+        ],
+    },
     TranslatedString{
         translatable: qljs_translatable!("unexpected characters in binary literal"),
         expected_per_locale: [
@@ -676,14 +841,14 @@ pub const TEST_TRANSLATION_TABLE: [TranslatedString; 63] = [
         ],
     },
     TranslatedString{
-        translatable: qljs_translatable!("unexpected control character"),
+        translatable: qljs_translatable!("unexpected control character {1}"),
         expected_per_locale: [
-            "unexpected control character",
-            "Unerwartetes Steuerzeichen",
-            "you lost control of your code",
-            "caract\u{00e8}re de contr\u{00f4}le inattendu",
-            "caracter de control inesperado",
-            "of\u{00f6}rv\u{00e4}ntat kontrolltecken",
+            "unexpected control character {1}",
+            "unexpected control character {1}",
+            "unexpected control character {1}",
+            "unexpected control character {1}",
+            "unexpected control character {1}",
+            "unexpected control character {1}",
         ],
     },
     TranslatedString{
@@ -719,6 +884,28 @@ pub const TEST_TRANSLATION_TABLE: [TranslatedString; 63] = [
             "what is this '{1}' nonsense?",
         ],
     },
+    TranslatedString{
+        translatable: qljs_translatable!("{0} is {0:plural} and {1}"),
+        expected_per_locale: [
+            "{0} is {0:plural} and {1}",
+            "{0} is {0:plural} and {1}",
+            "{0} is {0:plural} and {1}",
+            "{0} is {0:plural} and {1}",
+            "{0} is {0:plural} and {1}",
+            "{0} is {0:plural} and {1}",
+        ],
+    },
+    TranslatedString{
+        translatable: qljs_translatable!("{4}{3}{2}{1}{0}"),
+        expected_per_locale: [
+            "{4}{3}{2}{1}{0}",
+            "{4}{3}{2}{1}{0}",
+            "{4}{3}{2}{1}{0}",
+            "{4}{3}{2}{1}{0}",
+            "{4}{3}{2}{1}{0}",
+            "{4}{3}{2}{1}{0}",
+        ],
+    },
 ];
 
 #[test]
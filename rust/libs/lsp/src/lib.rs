@@ -0,0 +1,3 @@
+pub mod lsp_diag_reporter;
+pub mod lsp_message;
+pub mod lsp_server;
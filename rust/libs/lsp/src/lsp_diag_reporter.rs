@@ -0,0 +1,210 @@
+use cpp_vs_rust_fe::diag_reporter::*;
+use cpp_vs_rust_fe::diagnostic::*;
+use cpp_vs_rust_fe::diagnostic_formatter::*;
+use cpp_vs_rust_fe::diagnostic_types::*;
+use cpp_vs_rust_fe::lsp_location::*;
+use cpp_vs_rust_fe::source_code_span::*;
+use cpp_vs_rust_i18n::translation::*;
+use cpp_vs_rust_util::json::*;
+
+// Collects diagnostics for one document as a JSON array of LSP Diagnostic
+// objects, suitable for a textDocument/publishDiagnostics notification.
+//
+// https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#diagnostic
+pub struct LSPDiagReporter<'code, 'locator> {
+    translator: Translator,
+    category_filter: DiagnosticCategoryFilter,
+    // Comma-separated Diagnostic objects.
+    diagnostics: std::cell::RefCell<Vec<u8>>,
+    locator: &'locator LSPLocator<'code>,
+    // Used for the locations of notes (relatedInformation).
+    document_uri: String,
+}
+
+impl<'code, 'locator> LSPDiagReporter<'code, 'locator> {
+    // locator's position encoding determines the encoding of reported ranges.
+    pub fn new(
+        translator: Translator,
+        category_filter: DiagnosticCategoryFilter,
+        locator: &'locator LSPLocator<'code>,
+        document_uri: &str,
+    ) -> LSPDiagReporter<'code, 'locator> {
+        LSPDiagReporter {
+            translator: translator,
+            category_filter: category_filter,
+            diagnostics: std::cell::RefCell::new(vec![]),
+            locator: locator,
+            document_uri: document_uri.to_string(),
+        }
+    }
+
+    // Returns a JSON array containing every diagnostic reported so far.
+    pub fn finish(&mut self) -> Vec<u8> {
+        let mut out: Vec<u8> = vec![b'['];
+        out.extend_from_slice(&self.diagnostics.borrow());
+        out.push(b']');
+        out
+    }
+}
+
+impl<'code, 'locator> DiagReporter for LSPDiagReporter<'code, 'locator> {
+    fn report_impl(&self, type_: DiagType, diag: *const u8) {
+        let info: &DiagnosticInfo = get_diagnostic_info(type_);
+        let Some(severity) = self.category_filter.severity(info) else {
+            return;
+        };
+        let mut formatter = LSPDiagFormatter::new(self);
+        formatter.format_with_severity(info, severity, diag);
+        formatter.write_diagnostic();
+    }
+}
+
+struct LSPMessage {
+    severity: DiagnosticSeverity,
+    text: Vec<u8>,
+    range: LSPRange,
+}
+
+struct LSPDiagFormatter<'code, 'locator, 'reporter> {
+    reporter: &'reporter LSPDiagReporter<'code, 'locator>,
+    // The first message is the diagnostic's main message. Following messages
+    // are notes.
+    messages: Vec<LSPMessage>,
+    code: [u8; 5],
+    tags: DiagnosticTags,
+}
+
+impl<'code, 'locator, 'reporter> LSPDiagFormatter<'code, 'locator, 'reporter> {
+    fn new(reporter: &'reporter LSPDiagReporter<'code, 'locator>) -> Self {
+        LSPDiagFormatter {
+            reporter: reporter,
+            messages: vec![],
+            code: [0; 5],
+            tags: DiagnosticTags::none(),
+        }
+    }
+
+    // Write a Diagnostic object for the messages formatted so far.
+    fn write_diagnostic(&mut self) {
+        let Some((main_message, notes)) = self.messages.split_first() else {
+            return;
+        };
+
+        let mut diagnostics = self.reporter.diagnostics.borrow_mut();
+        if !diagnostics.is_empty() {
+            diagnostics.push(b',');
+        }
+        let out: &mut Vec<u8> = &mut diagnostics;
+        let code_string_view: &str = unsafe { std::str::from_utf8_unchecked(&self.code) };
+        out.extend_from_slice(b"{\"range\":");
+        write_lsp_range(out, main_message.range);
+        out.extend_from_slice(
+            format!(",\"severity\":{}", lsp_severity(main_message.severity)).as_bytes(),
+        );
+        out.extend_from_slice(b",\"code\":");
+        write_json_escaped_string(out, &self.code);
+        out.extend_from_slice(b",\"codeDescription\":{\"href\":");
+        write_json_escaped_string(
+            out,
+            format!("https://quick-lint-js.com/errors/{code_string_view}/").as_bytes(),
+        );
+        out.extend_from_slice(b"},\"source\":\"quick-lint-js\",\"message\":");
+        write_json_escaped_string(out, &main_message.text);
+        if !self.tags.is_empty() {
+            let mut lsp_tags: Vec<&str> = vec![];
+            if self.tags.contains(DiagnosticTag::Unnecessary) {
+                lsp_tags.push("1");
+            }
+            if self.tags.contains(DiagnosticTag::Deprecated) {
+                lsp_tags.push("2");
+            }
+            out.extend_from_slice(format!(",\"tags\":[{}]", lsp_tags.join(",")).as_bytes());
+        }
+        if !notes.is_empty() {
+            out.extend_from_slice(b",\"relatedInformation\":[");
+            for (i, note) in notes.iter().enumerate() {
+                if i != 0 {
+                    out.push(b',');
+                }
+                out.extend_from_slice(b"{\"location\":{\"uri\":");
+                write_json_escaped_string(out, self.reporter.document_uri.as_bytes());
+                out.extend_from_slice(b",\"range\":");
+                write_lsp_range(out, note.range);
+                out.extend_from_slice(b"},\"message\":");
+                write_json_escaped_string(out, &note.text);
+                out.push(b'}');
+            }
+            out.push(b']');
+        }
+        out.push(b'}');
+        self.messages.clear();
+    }
+}
+
+impl<'code, 'locator, 'reporter> DiagnosticFormatter
+    for LSPDiagFormatter<'code, 'locator, 'reporter>
+{
+    fn write_before_message(
+        &mut self,
+        code: &str,
+        severity: DiagnosticSeverity,
+        origin: SourceCodeSpan<'_>,
+    ) {
+        self.code.copy_from_slice(code.as_bytes());
+        self.messages.push(LSPMessage {
+            severity: severity,
+            text: vec![],
+            range: self.reporter.locator.range(origin),
+        });
+    }
+
+    fn write_message_part(
+        &mut self,
+        _code: &str,
+        _severity: DiagnosticSeverity,
+        message_part: &[u8],
+    ) {
+        self.messages
+            .last_mut()
+            .unwrap()
+            .text
+            .extend_from_slice(message_part);
+    }
+
+    fn write_after_message(
+        &mut self,
+        _code: &str,
+        _severity: DiagnosticSeverity,
+        _origin: SourceCodeSpan<'_>,
+    ) {
+    }
+
+    fn translator(&self) -> Translator {
+        self.reporter.translator.clone()
+    }
+
+    fn write_category_and_tags(&mut self, _category: DiagnosticCategory, tags: DiagnosticTags) {
+        self.tags = tags;
+    }
+}
+
+pub fn write_lsp_range(out: &mut Vec<u8>, range: LSPRange) {
+    out.extend_from_slice(
+        format!(
+            "{{\"start\":{{\"line\":{},\"character\":{}}},\"end\":{{\"line\":{},\"character\":{}}}}}",
+            range.start.line, range.start.character, range.end.line, range.end.character,
+        )
+        .as_bytes(),
+    );
+}
+
+// Returns an LSP DiagnosticSeverity.
+fn lsp_severity(severity: DiagnosticSeverity) -> u8 {
+    match severity {
+        DiagnosticSeverity::Error => 1,
+        DiagnosticSeverity::Warning => 2,
+        // Notes are normally reported as relatedInformation. Report any other
+        // note as Information.
+        DiagnosticSeverity::Note => 3,
+    }
+}
@@ -0,0 +1,77 @@
+// Reads and writes Language Server Protocol base protocol messages: a header
+// section with a Content-Length header, followed by a JSON-RPC body.
+//
+// https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#baseProtocol
+
+use std::io::BufRead;
+use std::io::Read;
+
+// Header lines longer than this are rejected so a client cannot make the
+// server buffer unbounded data.
+const MAX_HEADER_LINE_LEN: usize = 1024;
+
+// Reads the body of the next message from input.
+//
+// Returns Ok(None) if input ends before the next message begins. Returns an
+// error with kind InvalidData if the header section is malformed, or with kind
+// UnexpectedEof if input ends in the middle of a message.
+pub fn read_lsp_message(input: &mut dyn std::io::BufRead) -> std::io::Result<Option<Vec<u8>>> {
+    let mut content_length: Option<usize> = None;
+    let mut read_any_header: bool = false;
+    loop {
+        let mut line: Vec<u8> = vec![];
+        let line_len: usize = (&mut *input)
+            .take(MAX_HEADER_LINE_LEN as u64)
+            .read_until(b'\n', &mut line)?;
+        if line_len == 0 {
+            if read_any_header {
+                return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof));
+            }
+            return Ok(None);
+        }
+        read_any_header = true;
+        let Some(line) = line.strip_suffix(b"\r\n") else {
+            return Err(invalid_data("LSP header line is not terminated by CRLF"));
+        };
+        if line.is_empty() {
+            break;
+        }
+        let Some(colon_index) = line.iter().position(|c: &u8| *c == b':') else {
+            return Err(invalid_data("LSP header line is missing ':'"));
+        };
+        let name: &[u8] = &line[..colon_index];
+        let value: &[u8] = line[colon_index + 1..].trim_ascii();
+        // Other headers, such as Content-Type, are ignored.
+        if name.eq_ignore_ascii_case(b"Content-Length") {
+            content_length = std::str::from_utf8(value)
+                .ok()
+                .and_then(|value: &str| value.parse::<usize>().ok());
+            if content_length.is_none() {
+                return Err(invalid_data("LSP Content-Length header is not a number"));
+            }
+        }
+    }
+    let Some(content_length) = content_length else {
+        return Err(invalid_data("LSP message is missing Content-Length header"));
+    };
+    // NOTE(port): Don't allocate content_length bytes up front. A bogus
+    // Content-Length should not allocate a huge buffer.
+    let mut body: Vec<u8> = vec![];
+    (&mut *input)
+        .take(content_length as u64)
+        .read_to_end(&mut body)?;
+    if body.len() != content_length {
+        return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof));
+    }
+    Ok(Some(body))
+}
+
+// Writes a message with the given JSON-RPC body to out.
+pub fn write_lsp_message(out: &mut dyn std::io::Write, body: &[u8]) -> std::io::Result<()> {
+    write!(out, "Content-Length: {}\r\n\r\n", body.len())?;
+    out.write_all(body)
+}
+
+fn invalid_data(message: &'static str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+}
@@ -0,0 +1,504 @@
+use crate::lsp_diag_reporter::*;
+use crate::lsp_message::*;
+use cpp_vs_rust_fe::diagnostic::*;
+use cpp_vs_rust_fe::document::*;
+use cpp_vs_rust_fe::global_declared_variable_set::*;
+use cpp_vs_rust_fe::linter::*;
+use cpp_vs_rust_fe::lsp_location::*;
+use cpp_vs_rust_i18n::translation::*;
+use cpp_vs_rust_util::json::*;
+use cpp_vs_rust_util::padded_string::*;
+use cpp_vs_rust_util::utf_8::*;
+
+// JSON-RPC and LSP error codes.
+const PARSE_ERROR: i32 = -32700;
+const INVALID_REQUEST: i32 = -32600;
+const METHOD_NOT_FOUND: i32 = -32601;
+const SERVER_NOT_INITIALIZED: i32 = -32002;
+
+// LSP's TextDocumentSyncKind.Incremental.
+const TEXT_DOCUMENT_SYNC_KIND_INCREMENTAL: i32 = 2;
+
+// LSP's MessageType.Error.
+const MESSAGE_TYPE_ERROR: i32 = 1;
+
+struct LintedDocument {
+    document: Document<LSPLocator</* HACK(strager) */ 'static>>,
+    linter_options: LinterOptions,
+    // The version from the client's most recent didOpen or didChange.
+    version: JSONValue,
+    // Whether a didChange had a change we couldn't apply. If true, our copy of
+    // the text differs from the client's, so incremental changes are ignored
+    // and diagnostics are not published until a change replaces the whole
+    // text.
+    out_of_sync: bool,
+}
+
+// Handles Language Server Protocol messages for a server which lints
+// JavaScript and TypeScript documents.
+//
+// Supported messages:
+//
+// * initialize, initialized, shutdown, exit
+// * textDocument/didOpen, textDocument/didChange (incremental or full),
+//   textDocument/didClose
+// * workspace/didChangeConfiguration
+//
+// After each change to a document, the server sends a
+// textDocument/publishDiagnostics notification for that document.
+//
+// If a textDocument/didChange has a malformed change, the server sends a
+// window/logMessage notification and ignores the document's incremental
+// changes until a change replaces the whole text.
+pub struct LintingLSPServer {
+    // Keyed by URI. Sorted so documents are relinted in a deterministic order.
+    documents: std::collections::BTreeMap<String, LintedDocument>,
+    translator: Translator,
+    category_filter: DiagnosticCategoryFilter,
+    position_encoding: PositionEncoding,
+    initialized: bool,
+    shutdown_requested: bool,
+    exit_requested: bool,
+}
+
+impl LintingLSPServer {
+    pub fn new() -> LintingLSPServer {
+        LintingLSPServer {
+            documents: std::collections::BTreeMap::new(),
+            translator: Translator::new_using_messages_from_source_code(),
+            category_filter: DiagnosticCategoryFilter::new(),
+            position_encoding: PositionEncoding::UTF16,
+            initialized: false,
+            shutdown_requested: false,
+            exit_requested: false,
+        }
+    }
+
+    // Handles one JSON-RPC message. Bodies of messages to send to the client
+    // (responses and notifications) are appended to outgoing.
+    pub fn handle_message(&mut self, message: &[u8], outgoing: &mut Vec<Vec<u8>>) {
+        let message: JSONValue = match parse_json(message) {
+            Ok(message) => message,
+            Err(_) => {
+                outgoing.push(error_response(&JSONValue::Null, PARSE_ERROR, "parse error"));
+                return;
+            }
+        };
+        let Some(method) = message.get("method").and_then(JSONValue::as_str) else {
+            // A response to a request we sent, or garbage. We don't send
+            // requests, so ignore the message.
+            return;
+        };
+        let params: &JSONValue = message.get("params").unwrap_or(&JSONValue::Null);
+        match message.get("id") {
+            Some(id) => self.handle_request(method, id, params, outgoing),
+            None => self.handle_notification(method, params, outgoing),
+        }
+    }
+
+    // Whether the client sent the exit notification.
+    pub fn exit_requested(&self) -> bool {
+        self.exit_requested
+    }
+
+    // The process exit code: 0 if the client requested shutdown before
+    // exiting, 1 otherwise.
+    pub fn exit_code(&self) -> i32 {
+        if self.shutdown_requested {
+            0
+        } else {
+            1
+        }
+    }
+
+    fn handle_request(
+        &mut self,
+        method: &str,
+        id: &JSONValue,
+        params: &JSONValue,
+        outgoing: &mut Vec<Vec<u8>>,
+    ) {
+        if self.shutdown_requested {
+            outgoing.push(error_response(
+                id,
+                INVALID_REQUEST,
+                "server is shutting down",
+            ));
+            return;
+        }
+        if !self.initialized && method != "initialize" {
+            outgoing.push(error_response(
+                id,
+                SERVER_NOT_INITIALIZED,
+                "server not initialized",
+            ));
+            return;
+        }
+        match method {
+            "initialize" if self.initialized => outgoing.push(error_response(
+                id,
+                INVALID_REQUEST,
+                "server already initialized",
+            )),
+            "initialize" => {
+                let result: Vec<u8> = self.initialize(params);
+                outgoing.push(result_response(id, &result));
+            }
+            "shutdown" => {
+                self.shutdown_requested = true;
+                outgoing.push(result_response(id, b"null"));
+            }
+            _ => outgoing.push(error_response(id, METHOD_NOT_FOUND, "method not found")),
+        }
+    }
+
+    fn handle_notification(
+        &mut self,
+        method: &str,
+        params: &JSONValue,
+        outgoing: &mut Vec<Vec<u8>>,
+    ) {
+        if method == "exit" {
+            self.exit_requested = true;
+            return;
+        }
+        if !self.initialized || self.shutdown_requested {
+            return;
+        }
+        match method {
+            "textDocument/didOpen" => self.did_open(params, outgoing),
+            "textDocument/didChange" => self.did_change(params, outgoing),
+            "textDocument/didClose" => self.did_close(params, outgoing),
+            "workspace/didChangeConfiguration" => self.did_change_configuration(params, outgoing),
+            // Includes initialized, $/cancelRequest, and $/setTrace.
+            _ => {}
+        }
+    }
+
+    // Returns the JSON of the InitializeResult.
+    fn initialize(&mut self, params: &JSONValue) -> Vec<u8> {
+        self.initialized = true;
+
+        if let Some(locale) = params.get("locale").and_then(JSONValue::as_str) {
+            // LSP locales look like "pt-BR", but our locales look like
+            // "pt_BR".
+            let locale: String = locale.replace('-', "_");
+            if !self.translator.use_messages_from_locale(&locale) {
+                self.translator.use_messages_from_source_code();
+            }
+        }
+
+        // Use the first encoding the client supports. If the client didn't
+        // say, then it only supports UTF-16.
+        self.position_encoding = params
+            .get("capabilities")
+            .and_then(|capabilities: &JSONValue| capabilities.get("general"))
+            .and_then(|general: &JSONValue| general.get("positionEncodings"))
+            .and_then(JSONValue::as_array)
+            .and_then(|encodings: &[JSONValue]| {
+                encodings.iter().find_map(|encoding: &JSONValue| {
+                    PositionEncoding::from_lsp_name(encoding.as_str()?.as_bytes())
+                })
+            })
+            .unwrap_or(PositionEncoding::UTF16);
+
+        let mut result: Vec<u8> = vec![];
+        result.extend_from_slice(b"{\"capabilities\":{\"positionEncoding\":");
+        write_json_escaped_string(&mut result, self.position_encoding.lsp_name().as_bytes());
+        result.extend_from_slice(
+            format!(
+                ",\"textDocumentSync\":{{\"openClose\":true,\"change\":{}}}}},",
+                TEXT_DOCUMENT_SYNC_KIND_INCREMENTAL
+            )
+            .as_bytes(),
+        );
+        result.extend_from_slice(b"\"serverInfo\":{\"name\":\"quick-lint-js\",\"version\":");
+        write_json_escaped_string(&mut result, env!("CARGO_PKG_VERSION").as_bytes());
+        result.extend_from_slice(b"}}");
+        result
+    }
+
+    fn did_open(&mut self, params: &JSONValue, outgoing: &mut Vec<Vec<u8>>) {
+        let Some(text_document) = params.get("textDocument") else {
+            return;
+        };
+        let (Some(uri), Some(language_id), Some(text)) = (
+            text_document.get("uri").and_then(JSONValue::as_str),
+            text_document.get("languageId").and_then(JSONValue::as_str),
+            text_document.get("text").and_then(JSONValue::as_str),
+        ) else {
+            return;
+        };
+        let Some(linter_options) = linter_options_for_language_id(language_id) else {
+            // We don't lint this kind of document. Ignore it, including future
+            // changes.
+            return;
+        };
+        let mut document: Document<LSPLocator> = Document::new();
+        document
+            .locator_mut()
+            .set_position_encoding(self.position_encoding);
+        document.set_text(text.as_bytes());
+        let doc: LintedDocument = LintedDocument {
+            document: document,
+            linter_options: linter_options,
+            version: text_document
+                .get("version")
+                .cloned()
+                .unwrap_or(JSONValue::Null),
+            out_of_sync: false,
+        };
+        self.documents.insert(uri.to_string(), doc);
+        self.lint_and_publish(uri, outgoing);
+    }
+
+    fn did_change(&mut self, params: &JSONValue, outgoing: &mut Vec<Vec<u8>>) {
+        let Some(text_document) = params.get("textDocument") else {
+            return;
+        };
+        let Some(uri) = text_document.get("uri").and_then(JSONValue::as_str) else {
+            return;
+        };
+        let Some(doc) = self.documents.get_mut(uri) else {
+            return;
+        };
+        let changes: &[JSONValue] = params
+            .get("contentChanges")
+            .and_then(JSONValue::as_array)
+            .unwrap_or(&[]);
+        for change in changes {
+            let text: Option<&str> = change.get("text").and_then(JSONValue::as_str);
+            let problem: &str = match (change.get("range"), text) {
+                (None, Some(text)) => {
+                    doc.document.set_text(text.as_bytes());
+                    doc.out_of_sync = false;
+                    continue;
+                }
+                // We already logged that we're out of sync. Wait for a
+                // full-text change.
+                _ if doc.out_of_sync => continue,
+                (Some(range), Some(text)) => match parse_lsp_range(range) {
+                    Some(range) => {
+                        doc.document.replace_text(range, text.as_bytes());
+                        continue;
+                    }
+                    None => "invalid range",
+                },
+                (_, None) => "missing text",
+            };
+            outgoing.push(log_message_notification(
+                MESSAGE_TYPE_ERROR,
+                &format!(
+                    "textDocument/didChange for {}: {}; ignoring incremental changes until the next full-text change",
+                    uri, problem
+                ),
+            ));
+            doc.out_of_sync = true;
+        }
+        if let Some(version) = text_document.get("version") {
+            doc.version = version.clone();
+        }
+        if !doc.out_of_sync {
+            self.lint_and_publish(uri, outgoing);
+        }
+    }
+
+    fn did_close(&mut self, params: &JSONValue, outgoing: &mut Vec<Vec<u8>>) {
+        let Some(uri) = params
+            .get("textDocument")
+            .and_then(|text_document: &JSONValue| text_document.get("uri"))
+            .and_then(JSONValue::as_str)
+        else {
+            return;
+        };
+        if self.documents.remove(uri).is_some() {
+            // Clear the editor's diagnostics for the closed document.
+            outgoing.push(publish_diagnostics_notification(
+                uri,
+                &JSONValue::Null,
+                b"[]",
+            ));
+        }
+    }
+
+    // Settings look like this:
+    //
+    // {"quick-lint-js": {"categorySeverity": {"style": "off", ...}}}
+    //
+    // Keys of categorySeverity are "syntax", "correctness", "style", or
+    // "compatibility". Values are "default", "error", "warning", or "off".
+    // Unrecognized settings are ignored.
+    fn did_change_configuration(&mut self, params: &JSONValue, outgoing: &mut Vec<Vec<u8>>) {
+        let mut category_filter: DiagnosticCategoryFilter = DiagnosticCategoryFilter::new();
+        let severities: &[(String, JSONValue)] = params
+            .get("settings")
+            .and_then(|settings: &JSONValue| settings.get("quick-lint-js"))
+            .and_then(|settings: &JSONValue| settings.get("categorySeverity"))
+            .and_then(JSONValue::as_object)
+            .unwrap_or(&[]);
+        for (category_name, severity) in severities {
+            let (Some(category), Some(severity)) = (
+                parse_category(category_name),
+                severity.as_str().and_then(parse_category_severity),
+            ) else {
+                continue;
+            };
+            category_filter.set(category, severity);
+        }
+        self.category_filter = category_filter;
+
+        let uris: Vec<String> = self.documents.keys().cloned().collect();
+        for uri in uris {
+            self.lint_and_publish(&uri, outgoing);
+        }
+    }
+
+    fn lint_and_publish(&mut self, uri: &str, outgoing: &mut Vec<Vec<u8>>) {
+        let doc: &LintedDocument = &self.documents[uri];
+        let input: PaddedStringView = doc.document.string();
+        // Reuse the document's locator, which replace_text keeps up to date,
+        // instead of finding every line again.
+        let mut reporter: LSPDiagReporter = LSPDiagReporter::new(
+            self.translator.clone(),
+            self.category_filter.clone(),
+            doc.document.locator(),
+            uri,
+        );
+        parse_and_lint(input, &reporter, doc.linter_options.clone());
+        outgoing.push(publish_diagnostics_notification(
+            uri,
+            &doc.version,
+            &reporter.finish(),
+        ));
+    }
+}
+
+impl Default for LintingLSPServer {
+    fn default() -> Self {
+        LintingLSPServer::new()
+    }
+}
+
+// Reads messages from input and writes responses and notifications to output
+// until the client sends the exit notification or input ends.
+//
+// Returns the process exit code (see LintingLSPServer::exit_code).
+pub fn run_lsp_server(
+    input: &mut dyn std::io::BufRead,
+    output: &mut dyn std::io::Write,
+) -> std::io::Result<i32> {
+    let mut server: LintingLSPServer = LintingLSPServer::new();
+    let mut outgoing: Vec<Vec<u8>> = vec![];
+    while let Some(message) = read_lsp_message(input)? {
+        server.handle_message(&message, &mut outgoing);
+        for body in outgoing.drain(..) {
+            write_lsp_message(output, &body)?;
+        }
+        output.flush()?;
+        if server.exit_requested() {
+            break;
+        }
+    }
+    Ok(server.exit_code())
+}
+
+// Returns None if we don't lint documents with the given LSP languageId.
+fn linter_options_for_language_id(language_id: &str) -> Option<LinterOptions> {
+    let (jsx, typescript): (bool, bool) = match language_id {
+        "javascript" | "javascriptreact" => (true, false),
+        "typescript" => (false, true),
+        "typescriptreact" => (true, true),
+        _ => return None,
+    };
+    Some(LinterOptions {
+        jsx: jsx,
+        typescript: typescript,
+        print_parser_visits: false,
+        bail_out_on_binary: true,
+        cancellation: None,
+        globals: GlobalDeclaredVariableSet::new(),
+    })
+}
+
+fn parse_lsp_range(range: &JSONValue) -> Option<LSPRange> {
+    let parse_position = |position: &JSONValue| -> Option<LSPPosition> {
+        Some(LSPPosition {
+            line: u32::try_from(position.get("line")?.as_u64()?).ok()?,
+            character: u32::try_from(position.get("character")?.as_u64()?).ok()?,
+        })
+    };
+    let start: LSPPosition = parse_position(range.get("start")?)?;
+    let end: LSPPosition = parse_position(range.get("end")?)?;
+    if (end.line, end.character) < (start.line, start.character) {
+        return None;
+    }
+    Some(LSPRange {
+        start: start,
+        end: end,
+    })
+}
+
+fn parse_category(name: &str) -> Option<DiagnosticCategory> {
+    match name {
+        "syntax" => Some(DiagnosticCategory::Syntax),
+        "correctness" => Some(DiagnosticCategory::Correctness),
+        "style" => Some(DiagnosticCategory::Style),
+        "compatibility" => Some(DiagnosticCategory::Compatibility),
+        _ => None,
+    }
+}
+
+fn parse_category_severity(name: &str) -> Option<CategorySeverity> {
+    match name {
+        "default" => Some(CategorySeverity::Default),
+        "error" => Some(CategorySeverity::Error),
+        "warning" => Some(CategorySeverity::Warning),
+        "off" => Some(CategorySeverity::Off),
+        _ => None,
+    }
+}
+
+fn result_response(id: &JSONValue, result: &[u8]) -> Vec<u8> {
+    let mut out: Vec<u8> = b"{\"jsonrpc\":\"2.0\",\"id\":".to_vec();
+    id.write(&mut out);
+    out.extend_from_slice(b",\"result\":");
+    out.extend_from_slice(result);
+    out.push(b'}');
+    out
+}
+
+fn error_response(id: &JSONValue, code: i32, message: &str) -> Vec<u8> {
+    let mut out: Vec<u8> = b"{\"jsonrpc\":\"2.0\",\"id\":".to_vec();
+    id.write(&mut out);
+    out.extend_from_slice(format!(",\"error\":{{\"code\":{},\"message\":", code).as_bytes());
+    write_json_escaped_string(&mut out, message.as_bytes());
+    out.extend_from_slice(b"}}");
+    out
+}
+
+fn log_message_notification(message_type: i32, message: &str) -> Vec<u8> {
+    let mut out: Vec<u8> = format!(
+        "{{\"jsonrpc\":\"2.0\",\"method\":\"window/logMessage\",\"params\":{{\"type\":{},\"message\":",
+        message_type
+    )
+    .into_bytes();
+    write_json_escaped_string(&mut out, message.as_bytes());
+    out.extend_from_slice(b"}}");
+    out
+}
+
+// If version is null, the notification has no version.
+fn publish_diagnostics_notification(uri: &str, version: &JSONValue, diagnostics: &[u8]) -> Vec<u8> {
+    let mut out: Vec<u8> =
+        b"{\"jsonrpc\":\"2.0\",\"method\":\"textDocument/publishDiagnostics\",\"params\":{\"uri\":"
+            .to_vec();
+    write_json_escaped_string(&mut out, uri.as_bytes());
+    if !version.is_null() {
+        out.extend_from_slice(b",\"version\":");
+        version.write(&mut out);
+    }
+    out.extend_from_slice(b",\"diagnostics\":");
+    out.extend_from_slice(diagnostics);
+    out.extend_from_slice(b"}}");
+    out
+}
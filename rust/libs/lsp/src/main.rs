@@ -0,0 +1,14 @@
+use cpp_vs_rust_lsp::lsp_server::*;
+
+fn main() {
+    let stdin: std::io::Stdin = std::io::stdin();
+    let stdout: std::io::Stdout = std::io::stdout();
+    let exit_code: i32 = match run_lsp_server(&mut stdin.lock(), &mut stdout.lock()) {
+        Ok(exit_code) => exit_code,
+        Err(error) => {
+            eprintln!("error: {error}");
+            1
+        }
+    };
+    std::process::exit(exit_code);
+}
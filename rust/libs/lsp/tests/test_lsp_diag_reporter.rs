@@ -0,0 +1,150 @@
+use cpp_vs_rust_fe::diag_reporter::*;
+use cpp_vs_rust_fe::diagnostic::*;
+use cpp_vs_rust_fe::diagnostic_types::*;
+use cpp_vs_rust_fe::lsp_location::*;
+use cpp_vs_rust_i18n::translation::*;
+use cpp_vs_rust_lsp::lsp_diag_reporter::*;
+use cpp_vs_rust_test::span::*;
+use cpp_vs_rust_util::json::*;
+use cpp_vs_rust_util::padded_string::*;
+use cpp_vs_rust_util::utf_8::*;
+
+#[test]
+fn no_diagnostics_is_empty_array() {
+    let input = PaddedString::from_slice(b"");
+    let locator: LSPLocator = LSPLocator::new(input.view());
+    let mut reporter: LSPDiagReporter = new_reporter(&locator);
+    assert_eq!(reporter.finish(), b"[]");
+}
+
+#[test]
+fn diagnostic_has_range_severity_code_and_message() {
+    let input = PaddedString::from_slice(b"x\n'hello");
+    let locator: LSPLocator = LSPLocator::new(input.view());
+    let mut reporter: LSPDiagReporter = new_reporter(&locator);
+    report(
+        &reporter,
+        DiagUnclosedStringLiteral {
+            string_literal: span_of(&input, 2, 8),
+        },
+    );
+    let diagnostics: JSONValue = parse_json(&reporter.finish()).unwrap();
+    let diag: &JSONValue = &diagnostics.as_array().unwrap()[0];
+    assert_eq!(
+        json_string(diag.get("range").unwrap()),
+        r#"{"start":{"line":1,"character":0},"end":{"line":1,"character":6}}"#
+    );
+    assert_eq!(diag.get("severity").and_then(JSONValue::as_i64), Some(1));
+    assert_eq!(diag.get("code").and_then(JSONValue::as_str), Some("E0040"));
+    assert_eq!(
+        diag.get("codeDescription")
+            .and_then(|description: &JSONValue| description.get("href"))
+            .and_then(JSONValue::as_str),
+        Some("https://quick-lint-js.com/errors/E0040/")
+    );
+    assert_eq!(
+        diag.get("source").and_then(JSONValue::as_str),
+        Some("quick-lint-js")
+    );
+    assert_eq!(
+        diag.get("message").and_then(JSONValue::as_str),
+        Some("unclosed string literal")
+    );
+    assert_eq!(diag.get("tags"), None);
+    assert_eq!(diag.get("relatedInformation"), None);
+}
+
+#[test]
+fn notes_become_related_information() {
+    let input = PaddedString::from_slice(b"aaa\nbbb");
+    let locator: LSPLocator = LSPLocator::new(input.view());
+    let mut reporter: LSPDiagReporter = new_reporter(&locator);
+    report(
+        &reporter,
+        DiagMultipleMessageTest {
+            a: span_of(&input, 0, 3),
+            b: span_of(&input, 4, 7),
+        },
+    );
+    let diagnostics: JSONValue = parse_json(&reporter.finish()).unwrap();
+    let related: &[JSONValue] = diagnostics.as_array().unwrap()[0]
+        .get("relatedInformation")
+        .and_then(JSONValue::as_array)
+        .unwrap();
+    assert_eq!(related.len(), 1);
+    assert_eq!(
+        json_string(&related[0]),
+        r#"{"location":{"uri":"file:///test.js","range":{"start":{"line":1,"character":0},"end":{"line":1,"character":3}}},"message":"second message here"}"#
+    );
+}
+
+#[test]
+fn tags_and_category_severity_are_reported() {
+    let input = PaddedString::from_slice(b"aaa");
+    let mut category_filter: DiagnosticCategoryFilter = DiagnosticCategoryFilter::new();
+    category_filter.set(DiagnosticCategory::Style, CategorySeverity::Warning);
+    let locator: LSPLocator = LSPLocator::new(input.view());
+    let mut reporter: LSPDiagReporter = LSPDiagReporter::new(
+        Translator::new_using_messages_from_source_code(),
+        category_filter,
+        &locator,
+        "file:///test.js",
+    );
+    report(
+        &reporter,
+        DiagOptionalNoteTest {
+            a: span_of(&input, 0, 3),
+            b: None,
+        },
+    );
+    let diagnostics: JSONValue = parse_json(&reporter.finish()).unwrap();
+    let diag: &JSONValue = &diagnostics.as_array().unwrap()[0];
+    assert_eq!(diag.get("severity").and_then(JSONValue::as_i64), Some(2));
+    assert_eq!(json_string(diag.get("tags").unwrap()), "[1,2]");
+}
+
+#[test]
+fn characters_follow_position_encoding() {
+    // U+1F430 is four UTF-8 code units and two UTF-16 code units.
+    let input = PaddedString::from_slice("\u{1f430} 'x".as_bytes());
+    for (encoding, expected_character) in [
+        (PositionEncoding::UTF8, 5),
+        (PositionEncoding::UTF16, 3),
+        (PositionEncoding::UTF32, 2),
+    ] {
+        let locator: LSPLocator = LSPLocator::new_with_encoding(input.view(), encoding);
+        let mut reporter: LSPDiagReporter = new_reporter(&locator);
+        report(
+            &reporter,
+            DiagUnclosedStringLiteral {
+                string_literal: span_of(&input, 5, 7),
+            },
+        );
+        let diagnostics: JSONValue = parse_json(&reporter.finish()).unwrap();
+        let start: &JSONValue = diagnostics.as_array().unwrap()[0]
+            .get("range")
+            .and_then(|range: &JSONValue| range.get("start"))
+            .unwrap();
+        assert_eq!(
+            start.get("character").and_then(JSONValue::as_i64),
+            Some(expected_character)
+        );
+    }
+}
+
+fn new_reporter<'code, 'locator>(
+    locator: &'locator LSPLocator<'code>,
+) -> LSPDiagReporter<'code, 'locator> {
+    LSPDiagReporter::new(
+        Translator::new_using_messages_from_source_code(),
+        DiagnosticCategoryFilter::new(),
+        locator,
+        "file:///test.js",
+    )
+}
+
+fn json_string(value: &JSONValue) -> String {
+    let mut out: Vec<u8> = vec![];
+    value.write(&mut out);
+    String::from_utf8(out).unwrap()
+}
@@ -0,0 +1,84 @@
+use cpp_vs_rust_lsp::lsp_message::*;
+
+#[test]
+fn read_messages_in_sequence() {
+    let mut input: &[u8] = b"Content-Length: 2\r\n\r\n{}Content-Length: 4\r\n\r\nnull";
+    assert_eq!(read_lsp_message(&mut input).unwrap(), Some(b"{}".to_vec()));
+    assert_eq!(
+        read_lsp_message(&mut input).unwrap(),
+        Some(b"null".to_vec())
+    );
+    assert_eq!(read_lsp_message(&mut input).unwrap(), None);
+}
+
+#[test]
+fn header_names_are_case_insensitive_and_other_headers_are_ignored() {
+    let mut input: &[u8] = b"content-length:  2\r\n\
+        Content-Type: application/vscode-jsonrpc; charset=utf-8\r\n\
+        \r\n\
+        []";
+    assert_eq!(read_lsp_message(&mut input).unwrap(), Some(b"[]".to_vec()));
+}
+
+#[test]
+fn missing_content_length_is_invalid() {
+    let mut input: &[u8] = b"Content-Type: x\r\n\r\n{}";
+    assert_eq!(
+        read_lsp_message(&mut input).unwrap_err().kind(),
+        std::io::ErrorKind::InvalidData
+    );
+}
+
+#[test]
+fn non_numeric_content_length_is_invalid() {
+    let mut input: &[u8] = b"Content-Length: two\r\n\r\n{}";
+    assert_eq!(
+        read_lsp_message(&mut input).unwrap_err().kind(),
+        std::io::ErrorKind::InvalidData
+    );
+}
+
+#[test]
+fn header_line_without_crlf_is_invalid() {
+    let mut input: &[u8] = b"Content-Length: 2\n\n{}";
+    assert_eq!(
+        read_lsp_message(&mut input).unwrap_err().kind(),
+        std::io::ErrorKind::InvalidData
+    );
+}
+
+#[test]
+fn huge_header_line_is_invalid() {
+    let mut input_data: Vec<u8> = b"X-Padding: ".to_vec();
+    input_data.resize(100_000, b'x');
+    input_data.extend_from_slice(b"\r\n\r\n");
+    let mut input: &[u8] = &input_data;
+    assert_eq!(
+        read_lsp_message(&mut input).unwrap_err().kind(),
+        std::io::ErrorKind::InvalidData
+    );
+}
+
+#[test]
+fn truncated_message_is_unexpected_eof() {
+    for input_data in [
+        &b"Content-Length: 10\r\n\r\n{}"[..],
+        &b"Content-Length: 10\r\n"[..],
+    ] {
+        let mut input: &[u8] = input_data;
+        assert_eq!(
+            read_lsp_message(&mut input).unwrap_err().kind(),
+            std::io::ErrorKind::UnexpectedEof
+        );
+    }
+}
+
+#[test]
+fn write_message_includes_content_length() {
+    let mut out: Vec<u8> = vec![];
+    write_lsp_message(&mut out, "{\"x\":\"\u{00e9}\"}".as_bytes()).unwrap();
+    assert_eq!(
+        out,
+        "Content-Length: 10\r\n\r\n{\"x\":\"\u{00e9}\"}".as_bytes()
+    );
+}
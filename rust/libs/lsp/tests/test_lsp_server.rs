@@ -0,0 +1,367 @@
+use cpp_vs_rust_lsp::lsp_message::*;
+use cpp_vs_rust_lsp::lsp_server::*;
+use cpp_vs_rust_util::json::*;
+
+const INITIALIZE: &str =
+    r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"capabilities":{}}}"#;
+const INITIALIZED: &str = r#"{"jsonrpc":"2.0","method":"initialized","params":{}}"#;
+const SHUTDOWN: &str = r#"{"jsonrpc":"2.0","id":"shutdown","method":"shutdown"}"#;
+const EXIT: &str = r#"{"jsonrpc":"2.0","method":"exit"}"#;
+
+#[test]
+fn initialize_reports_capabilities() {
+    let (_exit_code, responses) = run_transcript(&[INITIALIZE]);
+    assert_eq!(responses.len(), 1);
+    assert_eq!(responses[0].get("id").and_then(JSONValue::as_i64), Some(1));
+    let capabilities: &JSONValue = responses[0]
+        .get("result")
+        .and_then(|result: &JSONValue| result.get("capabilities"))
+        .unwrap();
+    assert_eq!(
+        capabilities
+            .get("positionEncoding")
+            .and_then(JSONValue::as_str),
+        Some("utf-16")
+    );
+    assert_eq!(
+        json_string(capabilities.get("textDocumentSync").unwrap()),
+        r#"{"openClose":true,"change":2}"#
+    );
+}
+
+#[test]
+fn initialize_uses_first_position_encoding_offered_by_client() {
+    let (_exit_code, responses) = run_transcript(&[
+        r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"capabilities":{"general":{"positionEncodings":["utf-7","utf-8","utf-16"]}}}}"#,
+        INITIALIZED,
+        r#"{"jsonrpc":"2.0","method":"textDocument/didOpen","params":{"textDocument":{"uri":"file:///a.js","languageId":"javascript","version":1,"text":"'é' 'x"}}}"#,
+    ]);
+    assert_eq!(
+        responses[0]
+            .get("result")
+            .and_then(|result: &JSONValue| result.get("capabilities"))
+            .and_then(|capabilities: &JSONValue| capabilities.get("positionEncoding"))
+            .and_then(JSONValue::as_str),
+        Some("utf-8")
+    );
+    // U+00E9 is two UTF-8 code units.
+    assert_eq!(
+        json_string(diagnostics_of(&responses[1])[0].get("range").unwrap()),
+        r#"{"start":{"line":0,"character":5},"end":{"line":0,"character":7}}"#
+    );
+}
+
+#[test]
+fn did_open_publishes_diagnostics() {
+    let (_exit_code, responses) = run_transcript(&[
+        INITIALIZE,
+        INITIALIZED,
+        r#"{"jsonrpc":"2.0","method":"textDocument/didOpen","params":{"textDocument":{"uri":"file:///a.js","languageId":"javascript","version":7,"text":"let x;\nlet y = 'unclosed"}}}"#,
+    ]);
+    assert_eq!(responses.len(), 2);
+    let notification: &JSONValue = &responses[1];
+    assert_eq!(
+        notification.get("method").and_then(JSONValue::as_str),
+        Some("textDocument/publishDiagnostics")
+    );
+    let params: &JSONValue = notification.get("params").unwrap();
+    assert_eq!(
+        params.get("uri").and_then(JSONValue::as_str),
+        Some("file:///a.js")
+    );
+    assert_eq!(params.get("version").and_then(JSONValue::as_i64), Some(7));
+    let diagnostics: &[JSONValue] = diagnostics_of(notification);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+        diagnostics[0].get("code").and_then(JSONValue::as_str),
+        Some("E0040")
+    );
+    assert_eq!(
+        json_string(diagnostics[0].get("range").unwrap()),
+        r#"{"start":{"line":1,"character":8},"end":{"line":1,"character":17}}"#
+    );
+}
+
+#[test]
+fn did_change_applies_incremental_edits() {
+    let (_exit_code, responses) = run_transcript(&[
+        INITIALIZE,
+        INITIALIZED,
+        r#"{"jsonrpc":"2.0","method":"textDocument/didOpen","params":{"textDocument":{"uri":"file:///a.js","languageId":"javascript","version":1,"text":"let x = 'a';\nlet y = 'b';"}}}"#,
+        // Delete the closing quote on the second line, then insert a line
+        // before it.
+        r#"{"jsonrpc":"2.0","method":"textDocument/didChange","params":{"textDocument":{"uri":"file:///a.js","version":2},"contentChanges":[{"range":{"start":{"line":1,"character":10},"end":{"line":1,"character":11}},"text":""},{"range":{"start":{"line":1,"character":0},"end":{"line":1,"character":0}},"text":"// inserted\n"}]}}"#,
+    ]);
+    assert_eq!(responses.len(), 3);
+    assert_eq!(diagnostics_of(&responses[1]).len(), 0);
+    let params: &JSONValue = responses[2].get("params").unwrap();
+    assert_eq!(params.get("version").and_then(JSONValue::as_i64), Some(2));
+    let diagnostics: &[JSONValue] = diagnostics_of(&responses[2]);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+        json_string(diagnostics[0].get("range").unwrap()),
+        r#"{"start":{"line":2,"character":8},"end":{"line":2,"character":11}}"#
+    );
+}
+
+#[test]
+fn did_change_without_range_replaces_whole_text() {
+    let (_exit_code, responses) = run_transcript(&[
+        INITIALIZE,
+        INITIALIZED,
+        r#"{"jsonrpc":"2.0","method":"textDocument/didOpen","params":{"textDocument":{"uri":"file:///a.js","languageId":"javascript","version":1,"text":"'unclosed"}}}"#,
+        r#"{"jsonrpc":"2.0","method":"textDocument/didChange","params":{"textDocument":{"uri":"file:///a.js","version":2},"contentChanges":[{"text":"'closed'"}]}}"#,
+    ]);
+    assert_eq!(diagnostics_of(&responses[1]).len(), 1);
+    assert_eq!(diagnostics_of(&responses[2]).len(), 0);
+}
+
+#[test]
+fn did_change_with_bad_range_ignores_incremental_edits_until_full_text() {
+    let (_exit_code, responses) = run_transcript(&[
+        INITIALIZE,
+        INITIALIZED,
+        r#"{"jsonrpc":"2.0","method":"textDocument/didOpen","params":{"textDocument":{"uri":"file:///a.js","languageId":"javascript","version":1,"text":"'unclosed"}}}"#,
+        // The range's end is before its start.
+        r#"{"jsonrpc":"2.0","method":"textDocument/didChange","params":{"textDocument":{"uri":"file:///a.js","version":2},"contentChanges":[{"range":{"start":{"line":0,"character":5},"end":{"line":0,"character":1}},"text":""}]}}"#,
+        // Ignored, so no diagnostics are published.
+        r#"{"jsonrpc":"2.0","method":"textDocument/didChange","params":{"textDocument":{"uri":"file:///a.js","version":3},"contentChanges":[{"range":{"start":{"line":0,"character":9},"end":{"line":0,"character":9}},"text":"'"}]}}"#,
+        r#"{"jsonrpc":"2.0","method":"textDocument/didChange","params":{"textDocument":{"uri":"file:///a.js","version":4},"contentChanges":[{"text":"'closed"},{"range":{"start":{"line":0,"character":7},"end":{"line":0,"character":7}},"text":"'"}]}}"#,
+    ]);
+    assert_eq!(responses.len(), 4);
+    assert_eq!(diagnostics_of(&responses[1]).len(), 1);
+    assert_eq!(
+        responses[2].get("method").and_then(JSONValue::as_str),
+        Some("window/logMessage")
+    );
+    let log_params: &JSONValue = responses[2].get("params").unwrap();
+    assert_eq!(log_params.get("type").and_then(JSONValue::as_i64), Some(1));
+    assert!(log_params
+        .get("message")
+        .and_then(JSONValue::as_str)
+        .unwrap()
+        .contains("file:///a.js"));
+
+    // After the full-text change, incremental changes apply again.
+    let params: &JSONValue = responses[3].get("params").unwrap();
+    assert_eq!(params.get("version").and_then(JSONValue::as_i64), Some(4));
+    assert_eq!(diagnostics_of(&responses[3]).len(), 0);
+}
+
+#[test]
+fn did_close_clears_diagnostics() {
+    let (_exit_code, responses) = run_transcript(&[
+        INITIALIZE,
+        INITIALIZED,
+        r#"{"jsonrpc":"2.0","method":"textDocument/didOpen","params":{"textDocument":{"uri":"file:///a.js","languageId":"javascript","version":1,"text":"'unclosed"}}}"#,
+        r#"{"jsonrpc":"2.0","method":"textDocument/didClose","params":{"textDocument":{"uri":"file:///a.js"}}}"#,
+        // Changes to closed documents are ignored.
+        r#"{"jsonrpc":"2.0","method":"textDocument/didChange","params":{"textDocument":{"uri":"file:///a.js","version":2},"contentChanges":[{"text":"'"}]}}"#,
+    ]);
+    assert_eq!(responses.len(), 3);
+    let params: &JSONValue = responses[2].get("params").unwrap();
+    assert_eq!(
+        params.get("uri").and_then(JSONValue::as_str),
+        Some("file:///a.js")
+    );
+    assert_eq!(diagnostics_of(&responses[2]).len(), 0);
+}
+
+#[test]
+fn documents_in_other_languages_are_ignored() {
+    let (_exit_code, responses) = run_transcript(&[
+        INITIALIZE,
+        INITIALIZED,
+        r#"{"jsonrpc":"2.0","method":"textDocument/didOpen","params":{"textDocument":{"uri":"file:///a.py","languageId":"python","version":1,"text":"'unclosed"}}}"#,
+    ]);
+    assert_eq!(responses.len(), 1);
+}
+
+#[test]
+fn binary_document_reports_single_diagnostic() {
+    let text: String = "\\u0000\\u0001\\u0002\\u0003 @".repeat(8);
+    let did_open: String = format!(
+        r#"{{"jsonrpc":"2.0","method":"textDocument/didOpen","params":{{"textDocument":{{"uri":"file:///a.js","languageId":"javascript","version":1,"text":"{text}"}}}}}}"#
+    );
+    let (_exit_code, responses) = run_transcript(&[INITIALIZE, INITIALIZED, &did_open]);
+    let diagnostics: &[JSONValue] = diagnostics_of(&responses[1]);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+        diagnostics[0].get("code").and_then(JSONValue::as_str),
+        Some("E0301")
+    );
+}
+
+#[test]
+fn configuration_changes_category_severity_and_relints() {
+    let (_exit_code, responses) = run_transcript(&[
+        INITIALIZE,
+        INITIALIZED,
+        r#"{"jsonrpc":"2.0","method":"textDocument/didOpen","params":{"textDocument":{"uri":"file:///b.js","languageId":"javascript","version":1,"text":"'unclosed"}}}"#,
+        r#"{"jsonrpc":"2.0","method":"textDocument/didOpen","params":{"textDocument":{"uri":"file:///a.ts","languageId":"typescript","version":1,"text":"'unclosed"}}}"#,
+        r#"{"jsonrpc":"2.0","method":"workspace/didChangeConfiguration","params":{"settings":{"quick-lint-js":{"categorySeverity":{"syntax":"warning"}}}}}"#,
+        r#"{"jsonrpc":"2.0","method":"workspace/didChangeConfiguration","params":{"settings":{"quick-lint-js":{"categorySeverity":{"syntax":"off"}}}}}"#,
+        r#"{"jsonrpc":"2.0","method":"workspace/didChangeConfiguration","params":{"settings":{}}}"#,
+    ]);
+    let uri_of = |message: &JSONValue| -> String {
+        message
+            .get("params")
+            .and_then(|params: &JSONValue| params.get("uri"))
+            .and_then(JSONValue::as_str)
+            .unwrap()
+            .to_string()
+    };
+    let severity_of = |message: &JSONValue| -> Option<i64> {
+        diagnostics_of(message)
+            .first()
+            .and_then(|diag: &JSONValue| diag.get("severity"))
+            .and_then(JSONValue::as_i64)
+    };
+    assert_eq!(responses.len(), 9);
+    // Open documents are relinted in URI order.
+    assert_eq!(uri_of(&responses[3]), "file:///a.ts");
+    assert_eq!(uri_of(&responses[4]), "file:///b.js");
+    assert_eq!(severity_of(&responses[3]), Some(2));
+    assert_eq!(severity_of(&responses[4]), Some(2));
+    assert_eq!(diagnostics_of(&responses[5]).len(), 0);
+    assert_eq!(diagnostics_of(&responses[6]).len(), 0);
+    // Settings without categorySeverity restore the defaults.
+    assert_eq!(severity_of(&responses[7]), Some(1));
+    assert_eq!(severity_of(&responses[8]), Some(1));
+}
+
+#[test]
+fn messages_are_translated_to_client_locale() {
+    let (_exit_code, responses) = run_transcript(&[
+        r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"locale":"pt-BR","capabilities":{}}}"#,
+        INITIALIZED,
+        r#"{"jsonrpc":"2.0","method":"textDocument/didOpen","params":{"textDocument":{"uri":"file:///a.js","languageId":"javascript","version":1,"text":"'unclosed"}}}"#,
+    ]);
+    assert_eq!(
+        diagnostics_of(&responses[1])[0]
+            .get("message")
+            .and_then(JSONValue::as_str),
+        Some("string n\u{00e3}o encerrada")
+    );
+}
+
+#[test]
+fn unknown_locale_uses_untranslated_messages() {
+    let (_exit_code, responses) = run_transcript(&[
+        r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"locale":"tlh","capabilities":{}}}"#,
+        INITIALIZED,
+        r#"{"jsonrpc":"2.0","method":"textDocument/didOpen","params":{"textDocument":{"uri":"file:///a.js","languageId":"javascript","version":1,"text":"'unclosed"}}}"#,
+    ]);
+    assert_eq!(
+        diagnostics_of(&responses[1])[0]
+            .get("message")
+            .and_then(JSONValue::as_str),
+        Some("unclosed string literal")
+    );
+}
+
+#[test]
+fn shutdown_then_exit_exits_successfully() {
+    let (exit_code, responses) = run_transcript(&[INITIALIZE, INITIALIZED, SHUTDOWN, EXIT]);
+    assert_eq!(exit_code, 0);
+    assert_eq!(responses.len(), 2);
+    assert_eq!(
+        json_string(&responses[1]),
+        r#"{"jsonrpc":"2.0","id":"shutdown","result":null}"#
+    );
+}
+
+#[test]
+fn exit_without_shutdown_fails() {
+    let (exit_code, _responses) = run_transcript(&[INITIALIZE, INITIALIZED, EXIT]);
+    assert_eq!(exit_code, 1);
+}
+
+#[test]
+fn messages_after_exit_are_not_read() {
+    let (_exit_code, responses) = run_transcript(&[EXIT, INITIALIZE]);
+    assert_eq!(responses.len(), 0);
+}
+
+#[test]
+fn request_before_initialize_is_rejected() {
+    let (_exit_code, responses) = run_transcript(&[SHUTDOWN]);
+    assert_eq!(error_code_of(&responses[0]), Some(-32002));
+    assert_eq!(
+        responses[0].get("id").and_then(JSONValue::as_str),
+        Some("shutdown")
+    );
+}
+
+#[test]
+fn second_initialize_is_rejected() {
+    let (_exit_code, responses) = run_transcript(&[
+        INITIALIZE,
+        r#"{"jsonrpc":"2.0","id":2,"method":"initialize","params":{"capabilities":{"general":{"positionEncodings":["utf-8"]}}}}"#,
+    ]);
+    assert_eq!(responses.len(), 2);
+    assert_eq!(error_code_of(&responses[1]), Some(-32600));
+    assert_eq!(responses[1].get("id").and_then(JSONValue::as_i64), Some(2));
+}
+
+#[test]
+fn request_after_shutdown_is_rejected() {
+    let (_exit_code, responses) = run_transcript(&[INITIALIZE, SHUTDOWN, SHUTDOWN]);
+    assert_eq!(error_code_of(&responses[2]), Some(-32600));
+}
+
+#[test]
+fn unknown_request_is_method_not_found() {
+    let (_exit_code, responses) = run_transcript(&[
+        INITIALIZE,
+        r#"{"jsonrpc":"2.0","id":2,"method":"textDocument/hover","params":{}}"#,
+    ]);
+    assert_eq!(error_code_of(&responses[1]), Some(-32601));
+    assert_eq!(responses[1].get("id").and_then(JSONValue::as_i64), Some(2));
+}
+
+#[test]
+fn malformed_json_is_parse_error() {
+    let (_exit_code, responses) = run_transcript(&[INITIALIZE, "{\"jsonrpc\":"]);
+    assert_eq!(error_code_of(&responses[1]), Some(-32700));
+    assert!(responses[1].get("id").unwrap().is_null());
+}
+
+// Sends each message to a new server, then returns the server's exit code and
+// the messages it sent.
+fn run_transcript(messages: &[&str]) -> (i32, Vec<JSONValue>) {
+    let mut input: Vec<u8> = vec![];
+    for message in messages {
+        write_lsp_message(&mut input, message.as_bytes()).unwrap();
+    }
+    let mut output: Vec<u8> = vec![];
+    let exit_code: i32 = run_lsp_server(&mut &input[..], &mut output).unwrap();
+
+    let mut output_reader: &[u8] = &output;
+    let mut responses: Vec<JSONValue> = vec![];
+    while let Some(body) = read_lsp_message(&mut output_reader).unwrap() {
+        responses.push(parse_json(&body).unwrap());
+    }
+    (exit_code, responses)
+}
+
+fn diagnostics_of(notification: &JSONValue) -> &[JSONValue] {
+    notification
+        .get("params")
+        .and_then(|params: &JSONValue| params.get("diagnostics"))
+        .and_then(JSONValue::as_array)
+        .unwrap()
+}
+
+fn error_code_of(response: &JSONValue) -> Option<i64> {
+    response
+        .get("error")
+        .and_then(|error: &JSONValue| error.get("code"))
+        .and_then(JSONValue::as_i64)
+}
+
+fn json_string(value: &JSONValue) -> String {
+    let mut out: Vec<u8> = vec![];
+    value.write(&mut out);
+    String::from_utf8(out).unwrap()
+}
@@ -59,3 +59,15 @@ pub const fn maximum_u8(xs: &[u8]) -> u8 {
     }
     max
 }
+
+pub const fn maximum_u16(xs: &[u16]) -> u16 {
+    let mut max: u16 = xs[0];
+    let mut i: usize = 1;
+    while i < xs.len() {
+        if xs[i] > max {
+            max = xs[i];
+        }
+        i += 1;
+    }
+    max
+}
@@ -8,34 +8,42 @@
 // Refactoring is easier if the shorthand syntax is avoided.
 #![allow(clippy::redundant_field_names)]
 
+mod message_format;
 mod token_stream_parser;
 mod token_writer;
 
+use message_format::*;
 use token_stream_parser::*;
 use token_writer::*;
 
-static mut REGISTERED_DIAG_STRUCTS: Vec<RegisteredDiag> = vec![];
-
-const DIAGNOSTIC_MAX_MESSAGE_COUNT: usize = 2;
-const DIAGNOSTIC_MAX_ARG_COUNT: usize = 3;
-
-// Uses of qljs_diagnostic should have the following signature:
+// Each diagnostic struct in qljs_diagnostic_types! should have the following
+// attribute:
 //
 // #[qljs_diagnostic(error_code, severity, message_0)]
 // struct DiagName { ... }
 //
 // or
 //
-// #[qljs_diagnostic(error_code, severity, message_0, message_1)]
+// #[qljs_diagnostic(error_code, severity, message_0, message_1, ...)]
+// struct DiagName { ... }
+//
+// or
+//
+// #[qljs_diagnostic(error_code, severity, category = ..., tags = [...], message_0, ...)]
 // struct DiagName { ... }
 //
 // * error_code: string literal (e.g. "E0001")
 // * severity: DiagnosticSeverity value (e.g. DiagnosticSeverity::Error)
-// * message_0, message_1: parenthesized format (see below)
+// * category (optional): DiagnosticCategory value (e.g.
+//   DiagnosticCategory::Syntax). Defaults to DiagnosticCategory::Correctness.
+// * tags (optional): bracketed list of DiagnosticTag values (e.g.
+//   [DiagnosticTag::Unnecessary]). Defaults to no tags.
+// * message_0: parenthesized format (see below) for the main message
+// * message_1, ...: parenthesized formats (see below) for notes, if any
 //
-// A format (*message_0* or *message_1*) should look like the following:
+// A format (*message_0*, *message_1*, etc.) should look like the following:
 //
-//    (qljs_translatable!("format string"), source_location)
+//    (qljs_translatable!("format string"), source_location, arg_1, ...)
 //
 // Within a format:
 //
@@ -43,25 +51,140 @@ const DIAGNOSTIC_MAX_ARG_COUNT: usize = 3;
 // * The tuple's second argument must be a field of the attributed struct
 //   (without "self.")
 // * The tuple's second argument must have type *Identifier* or *SourceCodeSpan*
+//   (or, for notes, *Option<SourceCodeSpan>*; a note whose location is None is
+//   not reported)
+// * The tuple's remaining arguments (if any) must be fields of the attributed
+//   struct, referred to by the format string as {1}, {2}, etc.
+//
+// Every field of the attributed struct must be an argument of at least one
+// format. (Diagnostics are serialized message by message; see
+// diag_serialization.rs in the fe crate.)
 //
-// Adding the qljs_diagnostic attribute will automatically derive Clone.
+// The format string's placeholders are checked at compile time. Each {N} or
+// {N:modifier} must refer to one of the tuple's fields, and the modifier (if
+// any) must be able to format that field's type.
+//
+// The error_code must look like E0001 and must be unique among all diagnostics.
+//
+// Each diagnostic struct automatically derives Clone and Debug.
 //
 // Example:
 //
-// #[qljs_diagnostic(
-//     "E0005",
-//     DiagnosticSeverity::Error,
-//     (qljs_translatable!("BigInt literal contains decimal point"), where_),
-// )]
-// struct DiagBigIntLiteralContainsDecimalPoint<'code> {
-//   where_: SourceCodeSpan<'code>,
+// qljs_diagnostic_types! {
+//     #[qljs_diagnostic(
+//         "E0005",
+//         DiagnosticSeverity::Error,
+//         (qljs_translatable!("BigInt literal contains decimal point"), where_),
+//     )]
+//     pub struct DiagBigIntLiteralContainsDecimalPoint<'code> {
+//         pub where_: SourceCodeSpan<'code>,
+//     }
+//
+//     /* ... */
 // }
-#[proc_macro_attribute]
-pub fn qljs_diagnostic(
-    attr: proc_macro::TokenStream,
-    item: proc_macro::TokenStream,
-) -> proc_macro::TokenStream {
-    let mut parser = TokenStreamParser::new(item.clone());
+//
+// In addition to the structs, qljs_diagnostic_types! writes the following
+// items describing all of the diagnostics:
+//
+// * DiagType
+// * AnyDiag
+// * impl HasDiagType for each diagnostic struct
+// * DIAG_TYPE_COUNT
+// * diag_type_from_code
+// * DIAG_SIZES
+// * ALL_DIAGNOSTIC_INFOS
+//
+// NOTE(strager): All diagnostics are declared in a single macro invocation so
+// that the generated items do not depend on the order in which the compiler
+// expands macros.
+#[proc_macro]
+pub fn qljs_diagnostic_types(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let mut out = TokenWriter::new();
+    let mut diag_structs: Vec<RegisteredDiag> = vec![];
+    // Key: error code. Value: name of the diagnostic struct using the code.
+    let mut diag_names_by_code: std::collections::HashMap<u16, String> =
+        std::collections::HashMap::new();
+
+    let mut parser = TokenStreamParser::new(item);
+    while !parser.is_eof() {
+        parser.skip_punct("#");
+        let attribute: proc_macro::TokenStream = parser
+            .try_parse_bracket()
+            .expect("expected #[qljs_diagnostic(...)] attribute");
+        let mut attribute_parser = TokenStreamParser::new(attribute);
+        attribute_parser.skip_keyword("qljs_diagnostic");
+        let attribute_args: proc_macro::TokenStream = attribute_parser
+            .try_parse_paren()
+            .expect("expected arguments for qljs_diagnostic");
+        attribute_parser.expect_eof();
+
+        let mut struct_tokens: Vec<proc_macro::TokenTree> = vec![];
+        loop {
+            let token: proc_macro::TokenTree = parser
+                .current
+                .clone()
+                .expect("expected struct after #[qljs_diagnostic(...)]");
+            parser.skip_any();
+            let is_struct_body: bool = matches!(
+                &token,
+                proc_macro::TokenTree::Group(group)
+                    if group.delimiter() == proc_macro::Delimiter::Brace
+            );
+            struct_tokens.push(token);
+            if is_struct_body {
+                break;
+            }
+        }
+        let struct_stream: proc_macro::TokenStream = struct_tokens.into_iter().collect();
+
+        let (name, fields): (String, Vec<QLJSDiagnosticField>) =
+            parse_diagnostic_struct(struct_stream.clone());
+        let (attribute, spans): (QLJSDiagnosticAttribute, QLJSDiagnosticAttributeSpans) =
+            parse_qljs_diagnostic_attribute(attribute_args);
+
+        out.derive_attribute(&[
+            "Clone", "Debug", // TODO(strager): Instead, only implement Debug on AnyDiag.
+        ]);
+        out.stream(struct_stream);
+
+        let diag_struct = RegisteredDiag {
+            name: name,
+            fields: fields,
+            attribute: attribute,
+        };
+        let Some(code) = check_qljs_diagnostic_code(&diag_struct, &spans, &mut out) else {
+            continue;
+        };
+        if let Some(other_name) = diag_names_by_code.get(&code) {
+            out.compile_error(
+                &format!(
+                    "error code {} is used by both {} and {}",
+                    diag_struct.attribute.code_string, other_name, diag_struct.name,
+                ),
+                spans.code,
+            );
+            continue;
+        }
+        if !check_qljs_diagnostic_messages(&diag_struct, &spans.messages, &mut out) {
+            continue;
+        }
+        diag_names_by_code.insert(code, diag_struct.name.clone());
+        diag_structs.push(diag_struct);
+    }
+
+    write_diag_type_enum(&mut out, &diag_structs);
+    write_any_diag_enum(&mut out, &diag_structs);
+    write_has_diag_type_impls(&mut out, &diag_structs);
+    write_diag_type_count(&mut out, &diag_structs);
+    write_diag_type_from_code_fn(&mut out, &diag_structs);
+    write_diag_sizes_array(&mut out, &diag_structs);
+    write_diag_type_infos(&mut out, &diag_structs);
+    out.to_token_stream()
+}
+
+// Returns the struct's name and fields.
+fn parse_diagnostic_struct(item: proc_macro::TokenStream) -> (String, Vec<QLJSDiagnosticField>) {
+    let mut parser = TokenStreamParser::new(item);
     parser.try_parse_keyword("pub");
     parser.skip_keyword("struct");
     let struct_name: proc_macro::Ident = parser.try_parse_ident().expect("expected struct name");
@@ -89,6 +212,7 @@ pub fn qljs_diagnostic(
         fields.push(QLJSDiagnosticField {
             name: field_name.to_string(),
             type_: arg_type,
+            span: field_name.span(),
         });
         if struct_body_parser
             .try_parse_punct_token(',', proc_macro::Spacing::Alone)
@@ -100,28 +224,148 @@ pub fn qljs_diagnostic(
     struct_body_parser.expect_eof();
     parser.expect_eof();
 
-    unsafe {
-        REGISTERED_DIAG_STRUCTS.push(RegisteredDiag {
-            name: struct_name.to_string(),
-            fields: fields,
-            attribute: parse_qljs_diagnostic_attribute(attr),
-        })
-    };
+    (struct_name.to_string(), fields)
+}
 
-    let mut derive = TokenWriter::new();
-    derive.derive_attribute(&[
-        "Clone", "Debug", // TODO(strager): Instead, only implement Debug on AnyDiag.
-    ]);
+// Check that diag_struct's error code looks like "E0001".
+//
+// Returns the numeric error code, or writes a compile_error!(...) call to
+// errors and returns None.
+fn check_qljs_diagnostic_code(
+    diag_struct: &RegisteredDiag,
+    spans: &QLJSDiagnosticAttributeSpans,
+    errors: &mut TokenWriter,
+) -> Option<u16> {
+    let code: Option<u16> = diag_struct.attribute.try_code();
+    if code.is_none() {
+        errors.compile_error(
+            &format!(
+                "malformed error code {:?} for {}; expected 'E' followed by four digits \
+                 (e.g. \"E0001\")",
+                diag_struct.attribute.code_string, diag_struct.name,
+            ),
+            spans.code,
+        );
+    }
+    code
+}
+
+// Check that each message's fields and format string placeholders agree with
+// diag_struct's fields.
+//
+// Returns false and writes compile_error!(...) calls to errors if there are any
+// problems.
+fn check_qljs_diagnostic_messages(
+    diag_struct: &RegisteredDiag,
+    message_spans: &[QLJSDiagnosticAttributeMessageSpans],
+    errors: &mut TokenWriter,
+) -> bool {
+    let mut ok: bool = true;
+    for (message_index, (message, spans)) in diag_struct
+        .attribute
+        .messages
+        .iter()
+        .zip(message_spans)
+        .enumerate()
+    {
+        let mut field_types: Vec<DiagnosticArgType> = vec![];
+        for (i, field_name) in message.fields.iter().enumerate() {
+            match diag_struct.field_type(field_name) {
+                Some(field_type) => field_types.push(field_type),
+                None => {
+                    errors.compile_error(
+                        &format!("{} has no field named '{}'", diag_struct.name, field_name),
+                        spans.fields[i],
+                    );
+                    ok = false;
+                }
+            }
+        }
+        if field_types.len() != message.fields.len() {
+            continue;
+        }
+
+        match field_types.first() {
+            Some(DiagnosticArgType::Identifier | DiagnosticArgType::SourceCodeSpan) => {}
+            // Notes (but not the main message) can be omitted at run time.
+            Some(DiagnosticArgType::OptionalSourceCodeSpan) if message_index != 0 => {}
+            Some(DiagnosticArgType::OptionalSourceCodeSpan) => {
+                errors.compile_error(
+                    "the main message's first field cannot be an Option; only notes can be omitted",
+                    spans.fields[0],
+                );
+                ok = false;
+            }
+            Some(_) => {
+                errors.compile_error(
+                    "a message's first field should be an Identifier or SourceCodeSpan",
+                    spans.fields[0],
+                );
+                ok = false;
+            }
+            None => {
+                errors.compile_error(
+                    "expected a field (Identifier or SourceCodeSpan) after the message format",
+                    spans.format,
+                );
+                ok = false;
+            }
+        }
+
+        let placeholders: Vec<Placeholder> = match parse_message_format(&message.format) {
+            Ok(placeholders) => placeholders,
+            Err(error) => {
+                errors.compile_error(&format!("invalid message format: {error}"), spans.format);
+                ok = false;
+                continue;
+            }
+        };
+        for placeholder in &placeholders {
+            let Some(arg_type) = field_types.get(placeholder.arg_index) else {
+                errors.compile_error(
+                    &format!(
+                        "invalid message format: placeholder {{{}}} refers to a missing field; \
+                         the message has {} field(s)",
+                        placeholder.arg_index,
+                        field_types.len(),
+                    ),
+                    spans.format,
+                );
+                ok = false;
+                continue;
+            };
+            if let Err(error) = check_placeholder_type(placeholder, *arg_type) {
+                errors.compile_error(&format!("invalid message format: {error}"), spans.format);
+                ok = false;
+            }
+        }
+    }
 
-    let mut tokens: proc_macro::TokenStream = derive.to_token_stream();
-    tokens.extend([item]);
-    tokens
+    for field in &diag_struct.fields {
+        let is_used: bool =
+            diag_struct.attribute.messages.iter().any(
+                |message: &QLJSDiagnosticAttributeMessage| message.fields.contains(&field.name),
+            );
+        if !is_used {
+            errors.compile_error(
+                &format!(
+                    "field '{}' of {} is not used by any message",
+                    field.name, diag_struct.name,
+                ),
+                field.span,
+            );
+            ok = false;
+        }
+    }
+    ok
 }
 
-fn parse_qljs_diagnostic_attribute(stream: proc_macro::TokenStream) -> QLJSDiagnosticAttribute {
+fn parse_qljs_diagnostic_attribute(
+    stream: proc_macro::TokenStream,
+) -> (QLJSDiagnosticAttribute, QLJSDiagnosticAttributeSpans) {
     let mut parser = TokenStreamParser::new(stream);
-    let code: String = parser
-        .try_parse_string()
+    let (code, code_span): (String, proc_macro::Span) = parser
+        .try_parse_string_with_span()
         .expect("expected error code string as first argument");
     parser.skip_comma();
     parser.skip_keyword("DiagnosticSeverity");
@@ -131,7 +375,44 @@ fn parse_qljs_diagnostic_attribute(stream: proc_macro::TokenStream) -> QLJSDiagn
         .expect("expected error severity as second argument");
     parser.skip_comma();
 
+    let mut category: String = "Correctness".to_string();
+    let mut tags: Vec<String> = vec![];
+    loop {
+        if parser.try_parse_keyword("category").is_some() {
+            parser.skip_punct("=");
+            parser.skip_keyword("DiagnosticCategory");
+            parser.skip_punct("::");
+            category = parser
+                .try_parse_ident()
+                .expect("expected category name")
+                .to_string();
+        } else if parser.try_parse_keyword("tags").is_some() {
+            parser.skip_punct("=");
+            let tags_stream: proc_macro::TokenStream =
+                parser.try_parse_bracket().expect("expected list of tags");
+            let mut tags_parser = TokenStreamParser::new(tags_stream);
+            while !tags_parser.is_eof() {
+                tags_parser.skip_keyword("DiagnosticTag");
+                tags_parser.skip_punct("::");
+                tags.push(
+                    tags_parser
+                        .try_parse_ident()
+                        .expect("expected tag name")
+                        .to_string(),
+                );
+                if tags_parser.try_parse_comma().is_none() {
+                    break;
+                }
+            }
+            tags_parser.expect_eof();
+        } else {
+            break;
+        }
+        parser.skip_comma();
+    }
+
     let mut messages = vec![];
+    let mut message_spans = vec![];
     loop {
         match parser.try_parse_paren() {
             Some(message_stream) => {
@@ -143,19 +424,19 @@ fn parse_qljs_diagnostic_attribute(stream: proc_macro::TokenStream) -> QLJSDiagn
                     .try_parse_paren()
                     .expect("expected argument for qljs_translatable");
                 let mut arguments_parser = TokenStreamParser::new(arguments);
-                let format: String = arguments_parser
-                    .try_parse_string()
+                let (format, format_span): (String, proc_macro::Span) = arguments_parser
+                    .try_parse_string_with_span()
                     .expect("expected string argument for qljs_translatable");
                 arguments_parser.expect_eof();
 
                 let mut fields: Vec<String> = vec![];
+                let mut field_spans: Vec<proc_macro::Span> = vec![];
                 while message_parser.try_parse_comma().is_some() {
-                    fields.push(
-                        message_parser
-                            .try_parse_ident()
-                            .expect("expected field name")
-                            .to_string(),
-                    );
+                    let field: proc_macro::Ident = message_parser
+                        .try_parse_ident()
+                        .expect("expected field name");
+                    fields.push(field.to_string());
+                    field_spans.push(field.span());
                 }
                 message_parser.expect_eof();
 
@@ -163,6 +444,10 @@ fn parse_qljs_diagnostic_attribute(stream: proc_macro::TokenStream) -> QLJSDiagn
                     format: format,
                     fields: fields,
                 });
+                message_spans.push(QLJSDiagnosticAttributeMessageSpans {
+                    format: format_span,
+                    fields: field_spans,
+                });
             }
             None => {
                 parser.expect_eof();
@@ -176,11 +461,19 @@ fn parse_qljs_diagnostic_attribute(stream: proc_macro::TokenStream) -> QLJSDiagn
     }
     parser.expect_eof();
 
-    QLJSDiagnosticAttribute {
-        code_string: code,
-        diagnostic_severity: ident.to_string(),
-        messages: messages,
-    }
+    (
+        QLJSDiagnosticAttribute {
+            code_string: code,
+            diagnostic_severity: ident.to_string(),
+            category: category,
+            tags: tags,
+            messages: messages,
+        },
+        QLJSDiagnosticAttributeSpans {
+            code: code_span,
+            messages: message_spans,
+        },
+    )
 }
 
 fn parse_arg_type(parser: &mut TokenStreamParser) -> DiagnosticArgType {
@@ -211,10 +504,36 @@ fn parse_arg_type(parser: &mut TokenStreamParser) -> DiagnosticArgType {
         return DiagnosticArgType::SourceCodeSpan;
     }
 
+    // Option<SourceCodeSpan<'code>>
+    if parser.try_parse_keyword("Option").is_some() {
+        parser.skip_punct_token('<', proc_macro::Spacing::Alone);
+        parser.skip_keyword("SourceCodeSpan");
+        parser.skip_punct_token('<', proc_macro::Spacing::Alone);
+        parser.skip_lifetime();
+        // HACK(strager): '>' is joined with the following '>', and the second
+        // '>' is often fused with the following ','.
+        parser.skip_punct_token('>', proc_macro::Spacing::Joint);
+        if parser
+            .try_parse_punct_token('>', proc_macro::Spacing::Alone)
+            .is_none()
+        {
+            parser.skip_punct_token('>', proc_macro::Spacing::Joint);
+        }
+        return DiagnosticArgType::OptionalSourceCodeSpan;
+    }
+
     if parser.try_parse_keyword("u8").is_some() {
         return DiagnosticArgType::Char8;
     }
 
+    if parser.try_parse_keyword("char").is_some() {
+        return DiagnosticArgType::CodePoint;
+    }
+
+    if parser.try_parse_keyword("usize").is_some() {
+        return DiagnosticArgType::Integer;
+    }
+
     if parser.try_parse_keyword("EnumKind").is_some() {
         return DiagnosticArgType::EnumKind;
     }
@@ -223,6 +542,10 @@ fn parse_arg_type(parser: &mut TokenStreamParser) -> DiagnosticArgType {
         return DiagnosticArgType::StatementKind;
     }
 
+    if parser.try_parse_keyword("TokenType").is_some() {
+        return DiagnosticArgType::TokenType;
+    }
+
     if parser.try_parse_keyword("VariableKind").is_some() {
         return DiagnosticArgType::VariableKind;
     }
@@ -249,20 +572,12 @@ fn parse_arg_type(parser: &mut TokenStreamParser) -> DiagnosticArgType {
 //
 // #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 // #[repr(u16)]
-// pub enum $name {
+// pub enum DiagType {
 //     Diag1,
 //     Diag2,
 //     /* ... */
 // }
-#[proc_macro]
-pub fn qljs_make_diag_type_enum(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let mut parser = TokenStreamParser::new(item);
-    let name: proc_macro::Ident = parser
-        .try_parse_ident()
-        .expect("expected parameter: enum name");
-    parser.expect_eof();
-
-    let mut enum_writer = TokenWriter::new();
+fn write_diag_type_enum(enum_writer: &mut TokenWriter, diag_structs: &[RegisteredDiag]) {
     enum_writer.derive_attribute(&["Clone", "Copy", "Debug", "Eq", "PartialEq"]);
     enum_writer.punct("#");
     enum_writer.build_bracket(|attribute: &mut TokenWriter| {
@@ -273,14 +588,13 @@ pub fn qljs_make_diag_type_enum(item: proc_macro::TokenStream) -> proc_macro::To
     });
     enum_writer.ident("pub");
     enum_writer.ident("enum");
-    enum_writer.token(proc_macro::TokenTree::Ident(name));
+    enum_writer.ident("DiagType");
     enum_writer.build_brace(|enum_members: &mut TokenWriter| {
-        for diag_struct in unsafe { &REGISTERED_DIAG_STRUCTS } {
+        for diag_struct in diag_structs {
             enum_members.ident(&diag_struct.name);
             enum_members.punct(",");
         }
     });
-    enum_writer.to_token_stream()
 }
 
 // Write:
@@ -301,10 +615,10 @@ pub fn qljs_make_diag_type_enum(item: proc_macro::TokenStream) -> proc_macro::To
 //         }
 //     }
 // }
-#[proc_macro]
-pub fn qljs_make_any_diag_enum(_args: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let mut writer = TokenWriter::new();
-
+//
+// NOTE(strager): AnyDiag is for use in tests only.
+// NOTE(port): This was called diag_collector::diag in the C++ code.
+fn write_any_diag_enum(writer: &mut TokenWriter, diag_structs: &[RegisteredDiag]) {
     writer.derive_attribute(&[
         "Clone",
         // TODO(strager): Instead, debug print without the enum name, to avoid duplicating the diag
@@ -318,7 +632,7 @@ pub fn qljs_make_any_diag_enum(_args: proc_macro::TokenStream) -> proc_macro::To
     writer.lifetime("code");
     writer.punct(">");
     writer.build_brace(|enum_members: &mut TokenWriter| {
-        for diag_struct in unsafe { &REGISTERED_DIAG_STRUCTS } {
+        for diag_struct in diag_structs {
             enum_members.ident(&diag_struct.name);
             enum_members.build_paren(|member: &mut TokenWriter| {
                 member.ident(&diag_struct.name);
@@ -360,7 +674,7 @@ pub fn qljs_make_any_diag_enum(_args: proc_macro::TokenStream) -> proc_macro::To
             fn_body.ident("match");
             fn_body.ident("type_");
             fn_body.build_brace(|match_body: &mut TokenWriter| {
-                for diag_struct in unsafe { &REGISTERED_DIAG_STRUCTS } {
+                for diag_struct in diag_structs {
                     match_body.ident("DiagType");
                     match_body.punct("::");
                     match_body.ident(&diag_struct.name);
@@ -389,8 +703,6 @@ pub fn qljs_make_any_diag_enum(_args: proc_macro::TokenStream) -> proc_macro::To
             });
         });
     });
-
-    writer.to_token_stream()
 }
 
 // For each registered diagnostic struct, write the following:
@@ -398,10 +710,8 @@ pub fn qljs_make_any_diag_enum(_args: proc_macro::TokenStream) -> proc_macro::To
 // impl<'code> HasDiagType for $diag<'code> {
 //     const TYPE_: DiagType = DiagType::$diag;
 // }
-#[proc_macro]
-pub fn qljs_make_has_diag_type_impls(_args: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let mut writer = TokenWriter::new();
-    for diag_struct in unsafe { &REGISTERED_DIAG_STRUCTS } {
+fn write_has_diag_type_impls(writer: &mut TokenWriter, diag_structs: &[RegisteredDiag]) {
+    for diag_struct in diag_structs {
         writer.ident("impl");
         writer.punct("<");
         writer.lifetime("code");
@@ -424,43 +734,94 @@ pub fn qljs_make_has_diag_type_impls(_args: proc_macro::TokenStream) -> proc_mac
             impl_members.punct(";");
         });
     }
-
-    writer.to_token_stream()
 }
 
-#[proc_macro]
-pub fn qljs_diag_type_count(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    TokenStreamParser::new(item).expect_eof();
+// Write:
+//
+// pub const fn diag_type_from_code(code: u16) -> Option<DiagType> {
+//     match code {
+//         $code1 => Some(DiagType::Diag1),
+//         $code2 => Some(DiagType::Diag2),
+//         /* ... */
+//         _ => None,
+//     }
+// }
+fn write_diag_type_from_code_fn(writer: &mut TokenWriter, diag_structs: &[RegisteredDiag]) {
+    writer.ident("pub");
+    writer.ident("const");
+    writer.ident("fn");
+    writer.ident("diag_type_from_code");
+    writer.build_paren(|parameters: &mut TokenWriter| {
+        parameters.ident("code");
+        parameters.punct(":");
+        parameters.ident("u16");
+    });
+    writer.punct("->");
+    writer.ident("Option");
+    writer.punct("<");
+    writer.ident("DiagType");
+    writer.punct(">");
+    writer.build_brace(|fn_body: &mut TokenWriter| {
+        fn_body.ident("match");
+        fn_body.ident("code");
+        fn_body.build_brace(|match_body: &mut TokenWriter| {
+            for diag_struct in diag_structs {
+                match_body.literal_u16(diag_struct.attribute.code());
+                match_body.punct("=>");
+                match_body.ident("Some");
+                match_body.build_paren(|some: &mut TokenWriter| {
+                    some.ident("DiagType");
+                    some.punct("::");
+                    some.ident(&diag_struct.name);
+                });
+                match_body.punct(",");
+            }
+            match_body.ident("_");
+            match_body.punct("=>");
+            match_body.ident("None");
+            match_body.punct(",");
+        });
+    });
+}
 
-    let mut size = TokenWriter::new();
-    size.literal_usize(unsafe { REGISTERED_DIAG_STRUCTS.len() });
-    size.to_token_stream()
+// Write:
+//
+// pub const DIAG_TYPE_COUNT: i32 = $diag_count;
+fn write_diag_type_count(writer: &mut TokenWriter, diag_structs: &[RegisteredDiag]) {
+    writer.ident("pub");
+    writer.ident("const");
+    writer.ident("DIAG_TYPE_COUNT");
+    writer.punct(":");
+    writer.ident("i32");
+    writer.punct("=");
+    writer.literal_usize(diag_structs.len());
+    writer.ident("as");
+    writer.ident("i32");
+    writer.punct(";");
 }
 
 // Write:
 //
-// pub const DIAG_SIZES: [u8; $diag_count] = [
-//     std::mem::size_of::<Diag1>() as u8,
-//     std::mem::size_of::<Diag2>() as u8,
+// pub const DIAG_SIZES: [u16; $diag_count] = [
+//     std::mem::size_of::<Diag1>() as u16,
+//     std::mem::size_of::<Diag2>() as u16,
 //     /* ... */
 // ];
-#[proc_macro]
-pub fn qljs_diag_sizes_array(args: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    TokenStreamParser::new(args).expect_eof();
-
-    let mut writer = TokenWriter::new();
+//
+// NOTE(port): This was called diag_sizes inside of diag_collector::report in the C++ code.
+fn write_diag_sizes_array(writer: &mut TokenWriter, diag_structs: &[RegisteredDiag]) {
     writer.ident("pub");
     writer.ident("const");
     writer.ident("DIAG_SIZES");
     writer.punct(":");
     writer.build_bracket(|array_type: &mut TokenWriter| {
-        array_type.ident("u8");
+        array_type.ident("u16");
         array_type.punct(";");
-        array_type.literal_usize(unsafe { REGISTERED_DIAG_STRUCTS.len() });
+        array_type.literal_usize(diag_structs.len());
     });
     writer.punct("=");
     writer.build_bracket(|array: &mut TokenWriter| {
-        for diag_struct in unsafe { &REGISTERED_DIAG_STRUCTS } {
+        for diag_struct in diag_structs {
             array.ident("std");
             array.punct("::");
             array.ident("mem");
@@ -472,70 +833,104 @@ pub fn qljs_diag_sizes_array(args: proc_macro::TokenStream) -> proc_macro::Token
             array.punct(">");
             array.empty_paren();
             array.ident("as");
-            array.ident("u8");
+            array.ident("u16");
             array.punct(",");
         }
     });
     writer.punct(";");
-    writer.to_token_stream()
 }
 
-#[proc_macro]
-pub fn qljs_make_diag_type_infos(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    TokenStreamParser::new(item).expect_eof();
-
+// Write:
+//
+// pub(crate) static ALL_DIAGNOSTIC_MESSAGES: [DiagnosticMessageInfo; $message_count] = [
+//     DiagnosticMessageInfo::new(
+//         qljs_translatable!($format),
+//         {
+//             const SLICE: &[DiagnosticMessageArgInfo] = &[
+//                 DiagnosticMessageArgInfo::new(/* ... */),
+//                 /* ... */
+//             ];
+//             SLICE
+//         },
+//     ),
+//     /* ... */
+// ];
+//
+// pub(crate) static ALL_DIAGNOSTIC_INFOS: [DiagnosticInfo; $diag_count] = [
+//     DiagnosticInfo::new(
+//         $code,
+//         DiagnosticSeverity::$severity,
+//         DiagnosticCategory::$category,
+//         DiagnosticTags::from_tags(&[/* ... */]),
+//         $first_message_index,
+//         $message_count,
+//     ),
+//     /* ... */
+// ];
+//
+// Each diagnostic's messages are consecutive in ALL_DIAGNOSTIC_MESSAGES.
+fn write_diag_type_infos(writer: &mut TokenWriter, diag_structs: &[RegisteredDiag]) {
+    let mut messages = TokenWriter::new();
     let mut infos = TokenWriter::new();
-    for diag_struct in unsafe { &REGISTERED_DIAG_STRUCTS } {
-        write_diagnostic_message_new(
+    let mut message_count: usize = 0;
+    for diag_struct in diag_structs {
+        let first_message_index: usize = message_count;
+        for message in &diag_struct.attribute.messages {
+            write_diagnostic_message_info_new(&mut messages, diag_struct, message);
+            messages.punct(",");
+            message_count += 1;
+        }
+        write_diagnostic_info_new(
             &mut infos,
-            diag_struct.attribute.code(),
-            &diag_struct.attribute.diagnostic_severity,
-            // message_formats
-            |formats: &mut TokenWriter| {
-                for message in &diag_struct.attribute.messages {
-                    write_qljs_translatable_call(formats, &message.format);
-                    formats.punct(",");
-                }
-                for _ in diag_struct.attribute.messages.len()..DIAGNOSTIC_MAX_MESSAGE_COUNT {
-                    write_translatable_message_unallocated(formats);
-                    formats.punct(",");
-                }
-            },
-            // message_args
-            |arg_infos: &mut TokenWriter| {
-                let write_filler_entries = |out: &mut TokenWriter, count: usize| {
-                    for _ in 0..count {
-                        write_diagnostic_message_arg_info_empty(out);
-                        out.punct(",");
-                    }
-                };
-                for message in &diag_struct.attribute.messages {
-                    arg_infos.build_bracket(|arg_info: &mut TokenWriter| {
-                        for field in &message.fields {
-                            write_diagnostic_message_arg_info_new(arg_info, diag_struct, field);
-                            arg_info.punct(",");
-                        }
-                        write_filler_entries(
-                            arg_info,
-                            DIAGNOSTIC_MAX_ARG_COUNT - message.fields.len(),
-                        );
-                    });
-                    arg_infos.punct(",");
-                }
-                for _ in diag_struct.attribute.messages.len()..DIAGNOSTIC_MAX_MESSAGE_COUNT {
-                    arg_infos.build_bracket(|arg_info: &mut TokenWriter| {
-                        write_filler_entries(arg_info, DIAGNOSTIC_MAX_ARG_COUNT);
-                    });
-                    arg_infos.punct(",");
-                }
-            },
+            &diag_struct.attribute,
+            u16::try_from(first_message_index).expect("too many diagnostic messages"),
+            u8::try_from(diag_struct.attribute.messages.len())
+                .expect("too many messages in one diagnostic"),
         );
         infos.punct(",");
     }
 
-    let mut infos_array = TokenWriter::new();
-    infos_array.bracket(infos);
-    infos_array.to_token_stream()
+    write_crate_static_array(
+        writer,
+        "ALL_DIAGNOSTIC_MESSAGES",
+        "DiagnosticMessageInfo",
+        message_count,
+        messages,
+    );
+    write_crate_static_array(
+        writer,
+        "ALL_DIAGNOSTIC_INFOS",
+        "DiagnosticInfo",
+        diag_structs.len(),
+        infos,
+    );
+}
+
+// Write:
+//
+// pub(crate) static $name: [$element_type; $len] = [$elements];
+fn write_crate_static_array(
+    writer: &mut TokenWriter,
+    name: &str,
+    element_type: &str,
+    len: usize,
+    elements: TokenWriter,
+) {
+    writer.ident("pub");
+    writer.build_paren(|visibility: &mut TokenWriter| {
+        visibility.ident("crate");
+    });
+    writer.ident("static");
+    writer.ident(name);
+    writer.punct(":");
+    writer.build_bracket(|array_type: &mut TokenWriter| {
+        array_type.ident(element_type);
+        array_type.punct(";");
+        array_type.literal_usize(len);
+    });
+    writer.punct("=");
+    writer.bracket(elements);
+    writer.punct(";");
 }
 
 struct RegisteredDiag {
@@ -547,51 +942,71 @@ struct RegisteredDiag {
 struct QLJSDiagnosticField {
     name: String,
     type_: DiagnosticArgType,
+    span: proc_macro::Span,
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
-enum DiagnosticArgType {
-    Char8,          // u8
-    EnumKind,       // EnumKind
-    Identifier,     // Identifier<'code>
-    SourceCodeSpan, // SourceCodeSpan<'code>
-    StatementKind,  // StatementKind
-    String8View,    // &'code [u8]
-    VariableKind,   // VariableKind
+pub(crate) enum DiagnosticArgType {
+    Char8,                  // u8
+    CodePoint,              // char
+    EnumKind,               // EnumKind
+    Identifier,             // Identifier<'code>
+    Integer,                // usize
+    OptionalSourceCodeSpan, // Option<SourceCodeSpan<'code>>
+    SourceCodeSpan,         // SourceCodeSpan<'code>
+    StatementKind,          // StatementKind
+    String8View,            // &'code [u8]
+    TokenType,              // TokenType
+    VariableKind,           // VariableKind
 }
 
 impl RegisteredDiag {
+    fn field_type(&self, field_name: &str) -> Option<DiagnosticArgType> {
+        self.fields
+            .iter()
+            .find(|field: &&QLJSDiagnosticField| field.name == field_name)
+            .map(|field: &QLJSDiagnosticField| field.type_)
+    }
+
     fn arg_type_string_for_field(&self, field_name: &str) -> &'static str {
-        for field in &self.fields {
-            if field.name == field_name {
-                return get_diagnostic_message_arg_type(field.type_);
-            }
+        match self.field_type(field_name) {
+            Some(field_type) => get_diagnostic_message_arg_type(field_type),
+            None => panic!("could not find field {}::{}", self.name, field_name),
         }
-        panic!("could not find field {}::{}", self.name, field_name);
     }
 }
 
 struct QLJSDiagnosticAttribute {
     code_string: String,
     diagnostic_severity: String, // "Error", "Warning", or "Note"
+    category: String,            // "Syntax", "Correctness", "Style", or "Compatibility"
+    tags: Vec<String>,           // "Unnecessary" or "Deprecated"
     messages: Vec<QLJSDiagnosticAttributeMessage>,
 }
 
 impl QLJSDiagnosticAttribute {
     // NOTE(port): This function was called parse_code_string in diagnostic.cpp.
     fn code(&self) -> u16 {
+        self.try_code()
+            .expect("error code should have been checked")
+    }
+
+    // Returns None if code_string is not of the form "E0001".
+    fn try_code(&self) -> Option<u16> {
         let code_string: &[u8] = self.code_string.as_bytes();
-        assert_eq!(code_string.len(), 5);
-        assert_eq!(code_string[0], b'E');
+        if code_string.len() != 5
+            || code_string[0] != b'E'
+            || !code_string[1..].iter().all(u8::is_ascii_digit)
+        {
+            return None;
+        }
         let zero: u8 = b'0';
-        assert!(zero <= code_string[1] && code_string[1] <= b'9');
-        assert!(zero <= code_string[2] && code_string[2] <= b'9');
-        assert!(zero <= code_string[3] && code_string[3] <= b'9');
-        assert!(zero <= code_string[4] && code_string[4] <= b'9');
-        ((code_string[1] - zero) as u16) * 1000
-            + ((code_string[2] - zero) as u16) * 100
-            + ((code_string[3] - zero) as u16) * 10
-            + ((code_string[4] - zero) as u16) * 1
+        Some(
+            ((code_string[1] - zero) as u16) * 1000
+                + ((code_string[2] - zero) as u16) * 100
+                + ((code_string[3] - zero) as u16) * 10
+                + ((code_string[4] - zero) as u16) * 1,
+        )
     }
 }
 
@@ -600,36 +1015,52 @@ struct QLJSDiagnosticAttributeMessage {
     fields: Vec<String>,
 }
 
+// Source locations for a QLJSDiagnosticAttribute, for error reporting.
+//
+// NOTE(strager): Spans are only valid during the macro invocation which
+// created them, so these are not part of RegisteredDiag.
+struct QLJSDiagnosticAttributeSpans {
+    code: proc_macro::Span,
+    messages: Vec<QLJSDiagnosticAttributeMessageSpans>,
+}
+
+// Source locations for a QLJSDiagnosticAttributeMessage, for error reporting.
+struct QLJSDiagnosticAttributeMessageSpans {
+    format: proc_macro::Span,
+    fields: Vec<proc_macro::Span>,
+}
+
 // NOTE(port): This was get_diagnostic_message_arg_type from diagnostic.h.
 fn get_diagnostic_message_arg_type(field_type: DiagnosticArgType) -> &'static str {
     match field_type {
         DiagnosticArgType::Char8 => "Char8",
+        DiagnosticArgType::CodePoint => "CodePoint",
         DiagnosticArgType::EnumKind => "EnumKind",
         DiagnosticArgType::Identifier => "Identifier",
+        DiagnosticArgType::Integer => "Integer",
+        DiagnosticArgType::OptionalSourceCodeSpan => "OptionalSourceCodeSpan",
         DiagnosticArgType::SourceCodeSpan => "SourceCodeSpan",
         DiagnosticArgType::StatementKind => "StatementKind",
         DiagnosticArgType::String8View => "String8View",
+        DiagnosticArgType::TokenType => "TokenType",
         DiagnosticArgType::VariableKind => "VariableKind",
     }
 }
 
 // Write:
 //
-// DiagnosticMessage::new(
+// DiagnosticInfo::new(
 //   $code,
-//   $severity,
-//   $message_formats,
-//   $message_args,
+//   DiagnosticSeverity::$severity,
+//   DiagnosticCategory::$category,
+//   DiagnosticTags::from_tags(&[DiagnosticTag::$tag, /* ... */]),
+//   /* static slice of $messages */,
 // )
-fn write_diagnostic_message_new<
-    FormatsBuilder: FnOnce(&mut TokenWriter),
-    ArgsBuilder: FnOnce(&mut TokenWriter),
->(
+fn write_diagnostic_info_new(
     out: &mut TokenWriter,
-    code: u16,
-    severity: &str,
-    message_formats: FormatsBuilder,
-    message_args: ArgsBuilder,
+    attribute: &QLJSDiagnosticAttribute,
+    first_message_index: u16,
+    message_count: u8,
 ) {
     out.ident("DiagnosticInfo");
     out.punct("::");
@@ -637,32 +1068,104 @@ fn write_diagnostic_message_new<
 
     out.build_paren(|args: &mut TokenWriter| {
         // code
-        args.literal_u16(code);
+        args.literal_u16(attribute.code());
         args.punct(",");
 
         // severity
         args.ident("DiagnosticSeverity");
         args.punct("::");
-        args.ident(severity);
+        args.ident(&attribute.diagnostic_severity);
         args.punct(",");
 
-        // message_formats
-        args.build_bracket(message_formats);
+        // category
+        args.ident("DiagnosticCategory");
+        args.punct("::");
+        args.ident(&attribute.category);
         args.punct(",");
 
-        // message_args
-        args.build_bracket(message_args);
+        // tags
+        args.ident("DiagnosticTags");
+        args.punct("::");
+        args.ident("from_tags");
+        args.build_paren(|from_tags_args: &mut TokenWriter| {
+            from_tags_args.punct("&");
+            from_tags_args.build_bracket(|tags: &mut TokenWriter| {
+                for tag in &attribute.tags {
+                    tags.ident("DiagnosticTag");
+                    tags.punct("::");
+                    tags.ident(tag);
+                    tags.punct(",");
+                }
+            });
+        });
+        args.punct(",");
+
+        // messages
+        args.literal_u16(first_message_index);
+        args.punct(",");
+        args.literal_u8(message_count);
     });
 }
 
 // Write:
 //
-// DiagnosticMessageArgInfo::empty()
-fn write_diagnostic_message_arg_info_empty(out: &mut TokenWriter) {
-    out.ident("DiagnosticMessageArgInfo");
+// DiagnosticMessageInfo::new(
+//   qljs_translatable!($format),
+//   /* static slice of $arg_infos */,
+// )
+fn write_diagnostic_message_info_new(
+    out: &mut TokenWriter,
+    diag_struct: &RegisteredDiag,
+    message: &QLJSDiagnosticAttributeMessage,
+) {
+    out.ident("DiagnosticMessageInfo");
     out.punct("::");
-    out.ident("empty");
-    out.empty_paren();
+    out.ident("new");
+    out.build_paren(|args: &mut TokenWriter| {
+        write_qljs_translatable_call(args, &message.format);
+        args.punct(",");
+
+        write_static_slice(
+            args,
+            "DiagnosticMessageArgInfo",
+            |arg_infos: &mut TokenWriter| {
+                for field in &message.fields {
+                    write_diagnostic_message_arg_info_new(arg_infos, diag_struct, field);
+                    arg_infos.punct(",");
+                }
+            },
+        );
+    });
+}
+
+// Write:
+//
+// {
+//   const SLICE: &[$element_type] = &[$elements];
+//   SLICE
+// }
+//
+// Function calls (including qljs_translatable!(...)) are not eligible for
+// static promotion, so '&[$elements]' alone would not have 'static lifetime.
+fn write_static_slice<ElementsBuilder: FnOnce(&mut TokenWriter)>(
+    out: &mut TokenWriter,
+    element_type: &str,
+    elements: ElementsBuilder,
+) {
+    out.build_brace(|block: &mut TokenWriter| {
+        block.ident("const");
+        block.ident("SLICE");
+        block.punct(":");
+        block.punct("&");
+        block.build_bracket(|slice_type: &mut TokenWriter| {
+            slice_type.ident(element_type);
+        });
+        block.punct("=");
+        block.punct("&");
+        block.build_bracket(elements);
+        block.punct(";");
+        block.ident("SLICE");
+    });
 }
 
 // Write:
@@ -709,13 +1212,3 @@ fn write_qljs_translatable_call(out: &mut TokenWriter, message: &str) {
         args.string(message);
     });
 }
-
-// Write:
-//
-// TranslatableMessage::unallocated()
-fn write_translatable_message_unallocated(out: &mut TokenWriter) {
-    out.ident("TranslatableMessage");
-    out.punct("::");
-    out.ident("unallocated");
-    out.empty_paren();
-}
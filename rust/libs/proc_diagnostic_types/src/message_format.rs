@@ -0,0 +1,72 @@
+use crate::DiagnosticArgType;
+use cpp_vs_rust_util::message_format::*;
+
+// A {N} or {N:modifier} placeholder in a message format string.
+pub struct Placeholder {
+    pub arg_index: usize,
+    pub modifier: Option<PlaceholderModifier>,
+}
+
+// Returns the placeholders in format, or a description of why format is
+// malformed.
+//
+// DiagnosticFormatter::format_message uses the same parser, so any format
+// accepted here can be expanded at run time.
+pub fn parse_message_format(format: &str) -> Result<Vec<Placeholder>, String> {
+    let mut placeholders: Vec<Placeholder> = vec![];
+    for part in MessageFormatParser::new(format) {
+        match part {
+            Ok(MessageFormatPart::Text(_)) => {}
+            Ok(MessageFormatPart::Placeholder {
+                arg_index,
+                modifier,
+            }) => placeholders.push(Placeholder {
+                arg_index: arg_index,
+                modifier: modifier,
+            }),
+            Err(error) => return Err(error.to_string()),
+        }
+    }
+    Ok(placeholders)
+}
+
+// Returns a description of why placeholder cannot format an argument of type
+// arg_type, if it can't.
+//
+// This should match the expand_argument* functions in diagnostic_formatter.rs.
+pub fn check_placeholder_type(
+    placeholder: &Placeholder,
+    arg_type: DiagnosticArgType,
+) -> Result<(), String> {
+    let ok: bool = match placeholder.modifier {
+        None => matches!(
+            arg_type,
+            DiagnosticArgType::Char8
+                | DiagnosticArgType::CodePoint
+                | DiagnosticArgType::Identifier
+                | DiagnosticArgType::Integer
+                | DiagnosticArgType::OptionalSourceCodeSpan
+                | DiagnosticArgType::SourceCodeSpan
+                | DiagnosticArgType::String8View
+                | DiagnosticArgType::TokenType
+        ),
+        Some(PlaceholderModifier::Headlinese) => matches!(
+            arg_type,
+            DiagnosticArgType::EnumKind | DiagnosticArgType::StatementKind
+        ),
+        Some(PlaceholderModifier::Singular) => matches!(arg_type, DiagnosticArgType::StatementKind),
+    };
+    if ok {
+        Ok(())
+    } else {
+        Err(format!(
+            "placeholder {{{}{}}} cannot format an argument of type {:?}",
+            placeholder.arg_index,
+            match placeholder.modifier {
+                Some(modifier) => format!(":{}", modifier.name()),
+                None => String::new(),
+            },
+            arg_type,
+        ))
+    }
+}
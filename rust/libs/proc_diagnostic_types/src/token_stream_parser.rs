@@ -124,13 +124,14 @@ impl TokenStreamParser {
         }
     }
 
-    pub fn try_parse_string(&mut self) -> Option<String> {
+    pub fn try_parse_string_with_span(&mut self) -> Option<(String, proc_macro::Span)> {
         match &self.current {
             Some(proc_macro::TokenTree::Literal(literal)) => {
                 let literal_code: String = literal.to_string();
                 if literal_code.starts_with('"') {
+                    let span: proc_macro::Span = literal.span();
                     self.skip();
-                    Some(decode_rust_string_literal(&literal_code))
+                    Some((decode_rust_string_literal(&literal_code), span))
                 } else {
                     None
                 }
@@ -149,6 +150,11 @@ impl TokenStreamParser {
         }
     }
 
+    // Skip the current token, whatever it is.
+    pub fn skip_any(&mut self) {
+        self.skip();
+    }
+
     fn skip(&mut self) {
         self.current = self.iterator.next();
     }
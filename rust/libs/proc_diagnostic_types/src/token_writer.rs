@@ -22,6 +22,10 @@ impl TokenWriter {
         self.0.push(token);
     }
 
+    pub fn stream(&mut self, tokens: proc_macro::TokenStream) {
+        self.0.extend(tokens);
+    }
+
     pub fn ident(&mut self, name: &str) {
         self.token(proc_macro::TokenTree::Ident(proc_macro::Ident::new(
             name,
@@ -60,6 +64,12 @@ impl TokenWriter {
             )));
     }
 
+    pub fn literal_u8(&mut self, value: u8) {
+        self.token(proc_macro::TokenTree::Literal(
+            proc_macro::Literal::u8_suffixed(value),
+        ));
+    }
+
     pub fn literal_u16(&mut self, value: u16) {
         self.token(proc_macro::TokenTree::Literal(
             proc_macro::Literal::u16_suffixed(value),
@@ -113,6 +123,34 @@ impl TokenWriter {
             )));
     }
 
+    // Write:
+    //
+    // compile_error!($message);
+    //
+    // The error is reported at span.
+    pub fn compile_error(&mut self, message: &str, span: proc_macro::Span) {
+        let mut message_literal = proc_macro::Literal::string(message);
+        message_literal.set_span(span);
+        let mut args = proc_macro::Group::new(
+            proc_macro::Delimiter::Parenthesis,
+            [proc_macro::TokenTree::Literal(message_literal)]
+                .into_iter()
+                .collect(),
+        );
+        args.set_span(span);
+
+        let mut tokens: Vec<proc_macro::TokenTree> = vec![
+            proc_macro::TokenTree::Ident(proc_macro::Ident::new("compile_error", span)),
+            proc_macro::TokenTree::Punct(proc_macro::Punct::new('!', proc_macro::Spacing::Alone)),
+            proc_macro::TokenTree::Group(args),
+            proc_macro::TokenTree::Punct(proc_macro::Punct::new(';', proc_macro::Spacing::Alone)),
+        ];
+        for token in &mut tokens {
+            token.set_span(span);
+        }
+        self.0.extend(tokens);
+    }
+
     pub fn to_token_stream(self) -> proc_macro::TokenStream {
         self.0.into_iter().collect()
     }
@@ -0,0 +1,27 @@
+// Each diagnostic needs its own error code.
+
+cpp_vs_rust_proc_diagnostic_types::qljs_diagnostic_types! {
+    #[qljs_diagnostic(
+        "E0001", DiagnosticSeverity::Error,
+        (qljs_translatable!("first"), where_),
+    )]
+    pub struct DiagFirst<'code> {
+        pub where_: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0002", DiagnosticSeverity::Error,
+        (qljs_translatable!("second"), where_),
+    )]
+    pub struct DiagSecond<'code> {
+        pub where_: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0001", DiagnosticSeverity::Error, //~ ERROR error code E0001 is used by both DiagFirst and DiagThird
+        (qljs_translatable!("third"), where_),
+    )]
+    pub struct DiagThird<'code> {
+        pub where_: SourceCodeSpan<'code>,
+    }
+}
@@ -0,0 +1,12 @@
+// {N:headlinese} only works with EnumKind and StatementKind fields.
+
+cpp_vs_rust_proc_diagnostic_types::qljs_diagnostic_types! {
+    #[qljs_diagnostic(
+        "E0001", DiagnosticSeverity::Error,
+        (qljs_translatable!("missing body for {1:headlinese}"), where_, kind), //~ ERROR invalid message format: placeholder {1:headlinese} cannot format an argument of type VariableKind
+    )]
+    pub struct DiagTest<'code> {
+        pub where_: SourceCodeSpan<'code>,
+        pub kind: VariableKind,
+    }
+}
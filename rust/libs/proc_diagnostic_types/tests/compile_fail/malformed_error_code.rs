@@ -0,0 +1,27 @@
+// Error codes look like "E0001".
+
+cpp_vs_rust_proc_diagnostic_types::qljs_diagnostic_types! {
+    #[qljs_diagnostic(
+        "E001", DiagnosticSeverity::Error, //~ ERROR malformed error code "E001" for DiagTooShort; expected 'E' followed by four digits (e.g. "E0001")
+        (qljs_translatable!("too short"), where_),
+    )]
+    pub struct DiagTooShort<'code> {
+        pub where_: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "e0002", DiagnosticSeverity::Error, //~ ERROR malformed error code "e0002" for DiagLowercase; expected 'E' followed by four digits (e.g. "E0001")
+        (qljs_translatable!("lowercase"), where_),
+    )]
+    pub struct DiagLowercase<'code> {
+        pub where_: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E00x3", DiagnosticSeverity::Error, //~ ERROR malformed error code "E00x3" for DiagNotADigit; expected 'E' followed by four digits (e.g. "E0001")
+        (qljs_translatable!("not a digit"), where_),
+    )]
+    pub struct DiagNotADigit<'code> {
+        pub where_: SourceCodeSpan<'code>,
+    }
+}
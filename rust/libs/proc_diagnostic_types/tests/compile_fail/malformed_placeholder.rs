@@ -0,0 +1,19 @@
+// Placeholders must look like {N} or {N:modifier}.
+
+cpp_vs_rust_proc_diagnostic_types::qljs_diagnostic_types! {
+    #[qljs_diagnostic(
+        "E0001", DiagnosticSeverity::Error,
+        (qljs_translatable!("unexpected {name}"), where_), //~ ERROR invalid message format: placeholder {name} should start with an argument index
+    )]
+    pub struct DiagUnexpectedName<'code> {
+        pub where_: SourceCodeSpan<'code>,
+    }
+
+    #[qljs_diagnostic(
+        "E0002", DiagnosticSeverity::Error,
+        (qljs_translatable!("unclosed {0"), where_), //~ ERROR invalid message format: '{' has no matching '}'
+    )]
+    pub struct DiagUnclosedPlaceholder<'code> {
+        pub where_: SourceCodeSpan<'code>,
+    }
+}
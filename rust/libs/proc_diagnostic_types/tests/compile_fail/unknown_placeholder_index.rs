@@ -0,0 +1,12 @@
+// A placeholder must refer to one of its message's fields.
+
+cpp_vs_rust_proc_diagnostic_types::qljs_diagnostic_types! {
+    #[qljs_diagnostic(
+        "E0001", DiagnosticSeverity::Error,
+        (qljs_translatable!("'{1}' is not '{2}'"), where_, name), //~ ERROR invalid message format: placeholder {2} refers to a missing field; the message has 2 field(s)
+    )]
+    pub struct DiagTest<'code> {
+        pub where_: SourceCodeSpan<'code>,
+        pub name: SourceCodeSpan<'code>,
+    }
+}
@@ -0,0 +1,12 @@
+// The only placeholder modifiers are headlinese and singular.
+
+cpp_vs_rust_proc_diagnostic_types::qljs_diagnostic_types! {
+    #[qljs_diagnostic(
+        "E0001", DiagnosticSeverity::Error,
+        (qljs_translatable!("missing body for {1:plural}"), where_, kind), //~ ERROR invalid message format: placeholder {1:plural} has an unknown modifier; expected 'headlinese' or 'singular'
+    )]
+    pub struct DiagTest<'code> {
+        pub where_: SourceCodeSpan<'code>,
+        pub kind: StatementKind,
+    }
+}
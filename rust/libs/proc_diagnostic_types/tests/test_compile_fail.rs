@@ -0,0 +1,140 @@
+// Each file in tests/compile_fail/ uses qljs_diagnostic_types! incorrectly.
+// Every line which should have an error ends with a comment like the
+// following:
+//
+//   //~ ERROR message text
+//
+// The test compiles each file with rustc and checks that rustc reports each
+// expected error on its line. Other errors (such as unresolved names used by
+// the generated code) are ignored.
+
+#[test]
+fn unknown_placeholder_index() {
+    check_compile_fail("unknown_placeholder_index.rs");
+}
+
+#[test]
+fn headlinese_on_unsupported_field_type() {
+    check_compile_fail("headlinese_on_unsupported_field_type.rs");
+}
+
+#[test]
+fn malformed_placeholder() {
+    check_compile_fail("malformed_placeholder.rs");
+}
+
+#[test]
+fn unknown_placeholder_modifier() {
+    check_compile_fail("unknown_placeholder_modifier.rs");
+}
+
+#[test]
+fn duplicate_error_code() {
+    check_compile_fail("duplicate_error_code.rs");
+}
+
+#[test]
+fn malformed_error_code() {
+    check_compile_fail("malformed_error_code.rs");
+}
+
+fn check_compile_fail(file_name: &str) {
+    let source_path: std::path::PathBuf = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("compile_fail")
+        .join(file_name);
+    let source: String = std::fs::read_to_string(&source_path).unwrap();
+    let expected_errors: Vec<(usize, &str)> = source
+        .lines()
+        .enumerate()
+        .filter_map(|(line_index, line): (usize, &str)| {
+            line.split_once("//~ ERROR ")
+                .map(|(_code, message): (&str, &str)| (line_index + 1, message.trim()))
+        })
+        .collect();
+    assert!(
+        !expected_errors.is_empty(),
+        "{file_name} should have at least one //~ ERROR comment"
+    );
+
+    let out_dir: std::path::PathBuf = std::env::temp_dir().join(format!(
+        "cpp_vs_rust_test_{}_compile_fail_{}",
+        std::process::id(),
+        file_name.trim_end_matches(".rs"),
+    ));
+    std::fs::create_dir_all(&out_dir).unwrap();
+    let (deps_dir, proc_macro_path): (std::path::PathBuf, std::path::PathBuf) =
+        find_proc_macro_library();
+    let output: std::process::Output =
+        std::process::Command::new(std::env::var_os("RUSTC").unwrap_or("rustc".into()))
+            .arg("--edition=2021")
+            .arg("--crate-type=lib")
+            .arg("--crate-name=compile_fail")
+            .arg("--emit=metadata")
+            .arg("--error-format=short")
+            .arg("--out-dir")
+            .arg(&out_dir)
+            .arg("-L")
+            .arg(format!("dependency={}", deps_dir.display()))
+            .arg("--extern")
+            .arg(format!(
+                "cpp_vs_rust_proc_diagnostic_types={}",
+                proc_macro_path.display()
+            ))
+            .arg(&source_path)
+            .output()
+            .unwrap();
+    let _ = std::fs::remove_dir_all(&out_dir);
+    assert!(!output.status.success(), "{file_name} should not compile");
+
+    // With --error-format=short, each error looks like the following:
+    //
+    //   path/to/file.rs:12:34: error: message text
+    let stderr: String = String::from_utf8_lossy(&output.stderr).into_owned();
+    let actual_errors: Vec<(usize, &str)> = stderr
+        .lines()
+        .filter_map(|line: &str| {
+            let (location, message): (&str, &str) = line.split_once(": error: ")?;
+            let mut location_parts = location.rsplitn(3, ':');
+            let _column: &str = location_parts.next()?;
+            let line_number: usize = location_parts.next()?.parse::<usize>().ok()?;
+            Some((line_number, message))
+        })
+        .collect();
+    for (expected_line, expected_message) in &expected_errors {
+        assert!(
+            actual_errors.contains(&(*expected_line, *expected_message)),
+            "{file_name}:{expected_line}: expected error: {expected_message}\nrustc's errors:\n{stderr}"
+        );
+    }
+}
+
+// Returns the directory containing this test's dependencies, and the path of
+// the proc macro library within that directory.
+fn find_proc_macro_library() -> (std::path::PathBuf, std::path::PathBuf) {
+    // The test executable is in the same directory as its dependencies.
+    let deps_dir: std::path::PathBuf = std::env::current_exe()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .to_path_buf();
+    let file_name_prefix: String = format!(
+        "{}cpp_vs_rust_proc_diagnostic_types-",
+        std::env::consts::DLL_PREFIX
+    );
+    // If the library was built more than once (e.g. with different flags),
+    // use the newest build.
+    let proc_macro_path: std::path::PathBuf = std::fs::read_dir(&deps_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path: &std::path::PathBuf| {
+            let file_name: &str = path.file_name().unwrap().to_str().unwrap_or("");
+            file_name.starts_with(&file_name_prefix)
+                && file_name.ends_with(std::env::consts::DLL_SUFFIX)
+        })
+        .max_by_key(|path: &std::path::PathBuf| {
+            std::fs::metadata(path).unwrap().modified().unwrap()
+        })
+        .expect("could not find the cpp_vs_rust_proc_diagnostic_types library");
+    (deps_dir, proc_macro_path)
+}
@@ -4,3 +4,5 @@ pub mod diag_collector;
 pub mod diag_matcher;
 pub mod parse_support;
 pub mod scoped_trace;
+pub mod span;
+pub mod temp_directory;
@@ -0,0 +1,7 @@
+use cpp_vs_rust_fe::source_code_span::*;
+use cpp_vs_rust_util::padded_string::*;
+
+// Returns the span of code[begin..end].
+pub fn span_of(code: &PaddedString, begin: usize, end: usize) -> SourceCodeSpan<'_> {
+    SourceCodeSpan::from_slice(&code.as_slice()[begin..end])
+}
@@ -0,0 +1,43 @@
+// A directory for a test's files. The directory and its contents are deleted
+// when the TempDirectory is dropped.
+pub struct TempDirectory {
+    path: std::path::PathBuf,
+}
+
+impl TempDirectory {
+    // name should be unique among tests, such as the test's function name.
+    pub fn new(name: &str) -> TempDirectory {
+        let path: std::path::PathBuf =
+            std::env::temp_dir().join(format!("cpp_vs_rust_test_{}_{}", std::process::id(), name));
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+        TempDirectory { path: path }
+    }
+
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+
+    // Write a file (and its parent directories, if necessary) at the path
+    // relative to this directory. Returns the file's full path.
+    pub fn write_file(&self, relative_path: &str, content: &[u8]) -> String {
+        let path: std::path::PathBuf = self.path.join(relative_path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, content).unwrap();
+        path.to_str().unwrap().to_string()
+    }
+
+    // Create a directory (and its parent directories, if necessary) at the
+    // path relative to this directory. Returns the directory's full path.
+    pub fn create_directory(&self, relative_path: &str) -> String {
+        let path: std::path::PathBuf = self.path.join(relative_path);
+        std::fs::create_dir_all(&path).unwrap();
+        path.to_str().unwrap().to_string()
+    }
+}
+
+impl Drop for TempDirectory {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
@@ -0,0 +1,55 @@
+// FNV-1a, a fast non-cryptographic hash function.
+//
+// Unlike std::collections::hash_map::DefaultHasher, FNV-1a's output is the
+// same on every run and in every build, so it is suitable for keys which are
+// stored on disk.
+//
+// See: http://www.isthe.com/chongo/tech/comp/fnv/
+#[derive(Clone, Copy, Debug)]
+pub struct FNV1a64 {
+    state: u64,
+}
+
+const FNV_64_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_64_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+impl FNV1a64 {
+    pub const fn new() -> FNV1a64 {
+        FNV1a64 {
+            state: FNV_64_OFFSET_BASIS,
+        }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        for byte in data {
+            self.state ^= *byte as u64;
+            self.state = self.state.wrapping_mul(FNV_64_PRIME);
+        }
+    }
+
+    pub const fn finish(&self) -> u64 {
+        self.state
+    }
+}
+
+impl Default for FNV1a64 {
+    fn default() -> FNV1a64 {
+        FNV1a64::new()
+    }
+}
+
+impl std::hash::Hasher for FNV1a64 {
+    fn write(&mut self, bytes: &[u8]) {
+        self.update(bytes);
+    }
+
+    fn finish(&self) -> u64 {
+        FNV1a64::finish(self)
+    }
+}
+
+pub fn fnv_1a_64(data: &[u8]) -> u64 {
+    let mut hasher: FNV1a64 = FNV1a64::new();
+    hasher.update(data);
+    hasher.finish()
+}
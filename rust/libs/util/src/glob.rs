@@ -0,0 +1,173 @@
+// Matches '/'-separated paths against glob patterns, with the syntax used by
+// .gitignore files:
+//
+// * '*' matches any sequence of characters except '/'.
+// * '?' matches any one character except '/'.
+// * '[abc]', '[a-z]', '[!a-z]', and '[^a-z]' match one byte (except '/') in
+//   (or not in) the set.
+// * '**' as a whole path component matches zero or more path components:
+//   '**/x' matches 'x' and 'a/b/x', 'a/**/x' matches 'a/x' and 'a/b/c/x', and
+//   'a/**' matches everything inside 'a'. Elsewhere, '**' is like '*'.
+// * '\x' matches 'x' literally.
+//
+// https://git-scm.com/docs/gitignore#_pattern_format
+pub fn glob_matches(pattern: &[u8], path: &[u8]) -> bool {
+    glob_matches_from(pattern, 0, path, 0)
+}
+
+fn glob_matches_from(
+    pattern: &[u8],
+    mut pattern_index: usize,
+    path: &[u8],
+    mut path_index: usize,
+) -> bool {
+    while pattern_index < pattern.len() {
+        match pattern[pattern_index] {
+            b'*' => {
+                let at_component_begin: bool =
+                    pattern_index == 0 || pattern[pattern_index - 1] == b'/';
+                let mut rest_index: usize = pattern_index + 1;
+                while rest_index < pattern.len() && pattern[rest_index] == b'*' {
+                    rest_index += 1;
+                }
+                let is_double_star: bool = rest_index - pattern_index >= 2;
+                if is_double_star && at_component_begin {
+                    if rest_index == pattern.len() {
+                        // 'a/**': Match everything.
+                        return true;
+                    }
+                    if pattern[rest_index] == b'/' {
+                        // '**/': Match zero or more whole path components.
+                        let after_slash_index: usize = rest_index + 1;
+                        if glob_matches_from(pattern, after_slash_index, path, path_index) {
+                            return true;
+                        }
+                        return (path_index..path.len()).any(|i: usize| {
+                            path[i] == b'/'
+                                && glob_matches_from(pattern, after_slash_index, path, i + 1)
+                        });
+                    }
+                }
+                // '*': Match within the current path component.
+                let mut i: usize = path_index;
+                loop {
+                    if glob_matches_from(pattern, rest_index, path, i) {
+                        return true;
+                    }
+                    if i == path.len() || path[i] == b'/' {
+                        return false;
+                    }
+                    i += 1;
+                }
+            }
+            b'?' => {
+                if path_index == path.len() || path[path_index] == b'/' {
+                    return false;
+                }
+                path_index += 1;
+                // Match a whole UTF-8 character.
+                while path_index < path.len() && is_utf_8_continuation_byte(path[path_index]) {
+                    path_index += 1;
+                }
+                pattern_index += 1;
+            }
+            b'[' => match parse_character_class(pattern, pattern_index) {
+                Some(class) => {
+                    if path_index == path.len()
+                        || path[path_index] == b'/'
+                        || !class.matches(path[path_index])
+                    {
+                        return false;
+                    }
+                    path_index += 1;
+                    pattern_index = class.end_index;
+                }
+                // An unterminated '[' is an ordinary character.
+                None => {
+                    if path.get(path_index) != Some(&b'[') {
+                        return false;
+                    }
+                    path_index += 1;
+                    pattern_index += 1;
+                }
+            },
+            c => {
+                let literal: u8 = if c == b'\\' && pattern_index + 1 < pattern.len() {
+                    pattern_index += 1;
+                    pattern[pattern_index]
+                } else {
+                    c
+                };
+                if path.get(path_index) != Some(&literal) {
+                    return false;
+                }
+                path_index += 1;
+                pattern_index += 1;
+            }
+        }
+    }
+    path_index == path.len()
+}
+
+struct CharacterClass<'pattern> {
+    // The bytes between '[' (and '!' or '^', if any) and ']'.
+    items: &'pattern [u8],
+    negated: bool,
+    // The index after the closing ']'.
+    end_index: usize,
+}
+
+impl<'pattern> CharacterClass<'pattern> {
+    fn matches(&self, c: u8) -> bool {
+        let mut found: bool = false;
+        let mut i: usize = 0;
+        while i < self.items.len() {
+            let mut low: u8 = self.items[i];
+            if low == b'\\' && i + 1 < self.items.len() {
+                i += 1;
+                low = self.items[i];
+            }
+            if i + 2 < self.items.len() && self.items[i + 1] == b'-' {
+                let high: u8 = self.items[i + 2];
+                found |= low <= c && c <= high;
+                i += 3;
+            } else {
+                found |= low == c;
+                i += 1;
+            }
+        }
+        found != self.negated
+    }
+}
+
+// Returns None if the '[' at pattern[open_index] has no matching ']'.
+fn parse_character_class(pattern: &[u8], open_index: usize) -> Option<CharacterClass<'_>> {
+    let mut items_begin: usize = open_index + 1;
+    let negated: bool = matches!(pattern.get(items_begin), Some(b'!' | b'^'));
+    if negated {
+        items_begin += 1;
+    }
+    // A ']' immediately after '[' (or '[!') is part of the set.
+    let mut i: usize = items_begin;
+    if pattern.get(i) == Some(&b']') {
+        i += 1;
+    }
+    while i < pattern.len() {
+        match pattern[i] {
+            b']' => {
+                return Some(CharacterClass {
+                    items: &pattern[items_begin..i],
+                    negated: negated,
+                    end_index: i + 1,
+                });
+            }
+            b'\\' => i += 2,
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+fn is_utf_8_continuation_byte(c: u8) -> bool {
+    (c & 0xc0) == 0x80
+}
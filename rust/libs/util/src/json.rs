@@ -0,0 +1,554 @@
+// Writes s as a JSON string literal, including the surrounding quotes.
+//
+// Invalid UTF-8 sequences are replaced with U+FFFD REPLACEMENT CHARACTER.
+pub fn write_json_escaped_string(out: &mut Vec<u8>, s: &[u8]) {
+    out.push(b'"');
+    for c in String::from_utf8_lossy(s).chars() {
+        match c {
+            '"' => out.extend_from_slice(b"\\\""),
+            '\\' => out.extend_from_slice(b"\\\\"),
+            '\n' => out.extend_from_slice(b"\\n"),
+            '\r' => out.extend_from_slice(b"\\r"),
+            '\t' => out.extend_from_slice(b"\\t"),
+            c if (c as u32) < 0x20 || c == '\u{2028}' || c == '\u{2029}' => {
+                out.extend_from_slice(format!("\\u{:04x}", c as u32).as_bytes());
+            }
+            c => {
+                let mut buffer: [u8; 4] = [0; 4];
+                out.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+            }
+        }
+    }
+    out.push(b'"');
+}
+
+// A parsed JSON value.
+//
+// Object members are kept in document order. If an object has duplicate keys,
+// JSONValue::get returns the last member with the key, like JSON.parse.
+#[derive(Clone, Debug, PartialEq)]
+pub enum JSONValue {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<JSONValue>),
+    Object(Vec<(String, JSONValue)>),
+}
+
+impl JSONValue {
+    // Returns the value of the object member named key, or None if self is not
+    // an object or has no such member.
+    pub fn get(&self, key: &str) -> Option<&JSONValue> {
+        match self {
+            JSONValue::Object(members) => members
+                .iter()
+                .rev()
+                .find(|(member_key, _)| member_key == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            JSONValue::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            JSONValue::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    // Returns None if self is not a number or is not an integer which fits in
+    // an i64.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            JSONValue::Number(n)
+                if n.fract() == 0.0 && *n >= i64::MIN as f64 && *n < i64::MAX as f64 =>
+            {
+                Some(*n as i64)
+            }
+            _ => None,
+        }
+    }
+
+    // Returns None if self is not a number or is not an integer which fits in
+    // a u64.
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            JSONValue::Number(n) if n.fract() == 0.0 && *n >= 0.0 && *n < u64::MAX as f64 => {
+                Some(*n as u64)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            JSONValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[JSONValue]> {
+        match self {
+            JSONValue::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&[(String, JSONValue)]> {
+        match self {
+            JSONValue::Object(members) => Some(members),
+            _ => None,
+        }
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, JSONValue::Null)
+    }
+
+    // Writes self as compact JSON.
+    pub fn write(&self, out: &mut Vec<u8>) {
+        match self {
+            JSONValue::Null => out.extend_from_slice(b"null"),
+            JSONValue::Bool(true) => out.extend_from_slice(b"true"),
+            JSONValue::Bool(false) => out.extend_from_slice(b"false"),
+            JSONValue::Number(n) => match self.as_i64() {
+                Some(i) => out.extend_from_slice(i.to_string().as_bytes()),
+                None => out.extend_from_slice(n.to_string().as_bytes()),
+            },
+            JSONValue::String(s) => write_json_escaped_string(out, s.as_bytes()),
+            JSONValue::Array(items) => {
+                out.push(b'[');
+                for (i, item) in items.iter().enumerate() {
+                    if i != 0 {
+                        out.push(b',');
+                    }
+                    item.write(out);
+                }
+                out.push(b']');
+            }
+            JSONValue::Object(members) => {
+                out.push(b'{');
+                for (i, (key, value)) in members.iter().enumerate() {
+                    if i != 0 {
+                        out.push(b',');
+                    }
+                    write_json_escaped_string(out, key.as_bytes());
+                    out.push(b':');
+                    value.write(out);
+                }
+                out.push(b'}');
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum JSONParseErrorKind {
+    UnexpectedEndOfInput,
+    UnexpectedCharacter,
+    InvalidNumber,
+    InvalidEscapeSequence,
+    InvalidUTF8,
+    TooDeeplyNested,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct JSONParseError {
+    pub kind: JSONParseErrorKind,
+    // Byte offset into the input where the problem was found.
+    pub offset: usize,
+}
+
+// Arrays and objects nested deeper than this are rejected so malicious input
+// cannot overflow the stack.
+pub const JSON_MAX_NESTING_DEPTH: usize = 512;
+
+// Parses a JSON document (RFC 8259). Whitespace may surround the value, but
+// nothing else may follow it.
+pub fn parse_json(input: &[u8]) -> Result<JSONValue, JSONParseError> {
+    let mut parser = JSONParser {
+        input: input,
+        offset: 0,
+        depth: 0,
+        spans: None,
+    };
+    parser.parse_document()
+}
+
+// Like parse_json, but also returns where each value is in input.
+pub fn parse_json_with_spans(input: &[u8]) -> Result<(JSONValue, JSONSpans), JSONParseError> {
+    let mut parser = JSONParser {
+        input: input,
+        offset: 0,
+        depth: 0,
+        spans: Some(JSONSpans {
+            spans: std::collections::HashMap::new(),
+            path: vec![],
+        }),
+    };
+    let value: JSONValue = parser.parse_document()?;
+    Ok((value, parser.spans.unwrap()))
+}
+
+// One step from a JSON value to one of its children.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum JSONPathComponent {
+    // An object member's key.
+    Key(String),
+    // An array item's index.
+    Index(usize),
+}
+
+// Byte offsets of a value in the input given to parse_json_with_spans. end is
+// exclusive.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct JSONSpan {
+    pub begin: usize,
+    pub end: usize,
+}
+
+// The location of every value parsed by parse_json_with_spans, looked up by
+// the path from the root value.
+pub struct JSONSpans {
+    spans: std::collections::HashMap<Vec<JSONPathComponent>, JSONSpan>,
+    // The path of the value being parsed.
+    path: Vec<JSONPathComponent>,
+}
+
+impl JSONSpans {
+    // Returns the span of the value at path, or None if there is no such
+    // value. The root value's path is empty.
+    //
+    // If an object has duplicate keys, the last member's span is returned,
+    // like JSONValue::get.
+    pub fn get(&self, path: &[JSONPathComponent]) -> Option<JSONSpan> {
+        self.spans.get(path).copied()
+    }
+}
+
+struct JSONParser<'input> {
+    input: &'input [u8],
+    offset: usize,
+    depth: usize,
+    // If Some, record the span of each value.
+    spans: Option<JSONSpans>,
+}
+
+impl<'input> JSONParser<'input> {
+    fn parse_document(&mut self) -> Result<JSONValue, JSONParseError> {
+        self.skip_whitespace();
+        let value: JSONValue = self.parse_value()?;
+        self.skip_whitespace();
+        if self.offset != self.input.len() {
+            return Err(self.error(JSONParseErrorKind::UnexpectedCharacter));
+        }
+        Ok(value)
+    }
+
+    fn parse_value(&mut self) -> Result<JSONValue, JSONParseError> {
+        let begin: usize = self.offset;
+        let value: JSONValue = self.parse_value_without_span()?;
+        if let Some(spans) = &mut self.spans {
+            spans.spans.insert(
+                spans.path.clone(),
+                JSONSpan {
+                    begin: begin,
+                    end: self.offset,
+                },
+            );
+        }
+        Ok(value)
+    }
+
+    // Parses an array item or object member value. make_component is only
+    // called if spans are being recorded.
+    fn parse_child_value<MakeComponent: FnOnce() -> JSONPathComponent>(
+        &mut self,
+        make_component: MakeComponent,
+    ) -> Result<JSONValue, JSONParseError> {
+        if let Some(spans) = &mut self.spans {
+            spans.path.push(make_component());
+        }
+        let value: Result<JSONValue, JSONParseError> = self.parse_value();
+        if let Some(spans) = &mut self.spans {
+            spans.path.pop();
+        }
+        value
+    }
+
+    fn parse_value_without_span(&mut self) -> Result<JSONValue, JSONParseError> {
+        match self.peek() {
+            None => Err(self.error(JSONParseErrorKind::UnexpectedEndOfInput)),
+            Some(b'n') => self.parse_keyword(b"null", JSONValue::Null),
+            Some(b't') => self.parse_keyword(b"true", JSONValue::Bool(true)),
+            Some(b'f') => self.parse_keyword(b"false", JSONValue::Bool(false)),
+            Some(b'"') => Ok(JSONValue::String(self.parse_string()?)),
+            Some(b'-' | b'0'..=b'9') => self.parse_number(),
+            Some(b'[') => self.parse_array(),
+            Some(b'{') => self.parse_object(),
+            Some(_) => Err(self.error(JSONParseErrorKind::UnexpectedCharacter)),
+        }
+    }
+
+    fn parse_keyword(
+        &mut self,
+        keyword: &[u8],
+        value: JSONValue,
+    ) -> Result<JSONValue, JSONParseError> {
+        for expected in keyword {
+            match self.peek() {
+                Some(c) if c == *expected => self.offset += 1,
+                Some(_) => return Err(self.error(JSONParseErrorKind::UnexpectedCharacter)),
+                None => return Err(self.error(JSONParseErrorKind::UnexpectedEndOfInput)),
+            }
+        }
+        Ok(value)
+    }
+
+    fn parse_number(&mut self) -> Result<JSONValue, JSONParseError> {
+        let begin: usize = self.offset;
+        if self.peek() == Some(b'-') {
+            self.offset += 1;
+        }
+        match self.peek() {
+            Some(b'0') => self.offset += 1,
+            Some(b'1'..=b'9') => self.skip_digits(),
+            _ => return Err(self.error(JSONParseErrorKind::InvalidNumber)),
+        }
+        if self.peek() == Some(b'.') {
+            self.offset += 1;
+            if !matches!(self.peek(), Some(b'0'..=b'9')) {
+                return Err(self.error(JSONParseErrorKind::InvalidNumber));
+            }
+            self.skip_digits();
+        }
+        if matches!(self.peek(), Some(b'e' | b'E')) {
+            self.offset += 1;
+            if matches!(self.peek(), Some(b'+' | b'-')) {
+                self.offset += 1;
+            }
+            if !matches!(self.peek(), Some(b'0'..=b'9')) {
+                return Err(self.error(JSONParseErrorKind::InvalidNumber));
+            }
+            self.skip_digits();
+        }
+        // The grammar above only accepts ASCII, so this cannot fail.
+        let text: &str = std::str::from_utf8(&self.input[begin..self.offset]).unwrap();
+        match text.parse::<f64>() {
+            Ok(n) => Ok(JSONValue::Number(n)),
+            Err(_) => Err(JSONParseError {
+                kind: JSONParseErrorKind::InvalidNumber,
+                offset: begin,
+            }),
+        }
+    }
+
+    fn skip_digits(&mut self) {
+        while matches!(self.peek(), Some(b'0'..=b'9')) {
+            self.offset += 1;
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, JSONParseError> {
+        // Skip '"'.
+        self.offset += 1;
+        let mut bytes: Vec<u8> = vec![];
+        loop {
+            match self.peek() {
+                None => return Err(self.error(JSONParseErrorKind::UnexpectedEndOfInput)),
+                Some(b'"') => {
+                    self.offset += 1;
+                    break;
+                }
+                Some(b'\\') => {
+                    self.offset += 1;
+                    self.parse_escape_sequence(&mut bytes)?;
+                }
+                Some(c) if c < 0x20 => {
+                    return Err(self.error(JSONParseErrorKind::UnexpectedCharacter))
+                }
+                Some(c) => {
+                    bytes.push(c);
+                    self.offset += 1;
+                }
+            }
+        }
+        match String::from_utf8(bytes) {
+            Ok(s) => Ok(s),
+            Err(_) => Err(self.error(JSONParseErrorKind::InvalidUTF8)),
+        }
+    }
+
+    // Parses the part of an escape sequence after '\'.
+    fn parse_escape_sequence(&mut self, out: &mut Vec<u8>) -> Result<(), JSONParseError> {
+        let c: u8 = match self.peek() {
+            None => return Err(self.error(JSONParseErrorKind::UnexpectedEndOfInput)),
+            Some(c) => c,
+        };
+        self.offset += 1;
+        let unescaped: u8 = match c {
+            b'"' => b'"',
+            b'\\' => b'\\',
+            b'/' => b'/',
+            b'b' => 0x08,
+            b'f' => 0x0c,
+            b'n' => b'\n',
+            b'r' => b'\r',
+            b't' => b'\t',
+            b'u' => {
+                let code_unit: u32 = self.parse_hex_code_unit()?;
+                let code_point: char = if (0xd800..0xdc00).contains(&code_unit)
+                    && self.input[self.offset..].starts_with(b"\\u")
+                {
+                    let low_offset: usize = self.offset;
+                    self.offset += 2;
+                    let low: u32 = self.parse_hex_code_unit()?;
+                    if (0xdc00..0xe000).contains(&low) {
+                        char::from_u32(0x10000 + ((code_unit - 0xd800) << 10) + (low - 0xdc00))
+                            .unwrap()
+                    } else {
+                        // Not a surrogate pair. Parse the second escape
+                        // sequence on its own.
+                        self.offset = low_offset;
+                        char::REPLACEMENT_CHARACTER
+                    }
+                } else {
+                    // Unpaired surrogates become U+FFFD.
+                    char::from_u32(code_unit).unwrap_or(char::REPLACEMENT_CHARACTER)
+                };
+                let mut buffer: [u8; 4] = [0; 4];
+                out.extend_from_slice(code_point.encode_utf8(&mut buffer).as_bytes());
+                return Ok(());
+            }
+            _ => {
+                self.offset -= 1;
+                return Err(self.error(JSONParseErrorKind::InvalidEscapeSequence));
+            }
+        };
+        out.push(unescaped);
+        Ok(())
+    }
+
+    // Parses the four hexadecimal digits after '\u'.
+    fn parse_hex_code_unit(&mut self) -> Result<u32, JSONParseError> {
+        let mut code_unit: u32 = 0;
+        for _ in 0..4 {
+            let digit: u32 = match self.peek() {
+                None => return Err(self.error(JSONParseErrorKind::UnexpectedEndOfInput)),
+                Some(c) => match (c as char).to_digit(16) {
+                    Some(digit) => digit,
+                    None => return Err(self.error(JSONParseErrorKind::InvalidEscapeSequence)),
+                },
+            };
+            code_unit = (code_unit << 4) | digit;
+            self.offset += 1;
+        }
+        Ok(code_unit)
+    }
+
+    fn parse_array(&mut self) -> Result<JSONValue, JSONParseError> {
+        self.enter_nesting()?;
+        // Skip '['.
+        self.offset += 1;
+        let mut items: Vec<JSONValue> = vec![];
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.offset += 1;
+            self.depth -= 1;
+            return Ok(JSONValue::Array(items));
+        }
+        loop {
+            self.skip_whitespace();
+            let index: usize = items.len();
+            items.push(self.parse_child_value(|| JSONPathComponent::Index(index))?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.offset += 1,
+                Some(b']') => {
+                    self.offset += 1;
+                    break;
+                }
+                Some(_) => return Err(self.error(JSONParseErrorKind::UnexpectedCharacter)),
+                None => return Err(self.error(JSONParseErrorKind::UnexpectedEndOfInput)),
+            }
+        }
+        self.depth -= 1;
+        Ok(JSONValue::Array(items))
+    }
+
+    fn parse_object(&mut self) -> Result<JSONValue, JSONParseError> {
+        self.enter_nesting()?;
+        // Skip '{'.
+        self.offset += 1;
+        let mut members: Vec<(String, JSONValue)> = vec![];
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.offset += 1;
+            self.depth -= 1;
+            return Ok(JSONValue::Object(members));
+        }
+        loop {
+            self.skip_whitespace();
+            let key: String = match self.peek() {
+                Some(b'"') => self.parse_string()?,
+                Some(_) => return Err(self.error(JSONParseErrorKind::UnexpectedCharacter)),
+                None => return Err(self.error(JSONParseErrorKind::UnexpectedEndOfInput)),
+            };
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b':') => self.offset += 1,
+                Some(_) => return Err(self.error(JSONParseErrorKind::UnexpectedCharacter)),
+                None => return Err(self.error(JSONParseErrorKind::UnexpectedEndOfInput)),
+            }
+            self.skip_whitespace();
+            let value: JSONValue =
+                self.parse_child_value(|| JSONPathComponent::Key(key.clone()))?;
+            members.push((key, value));
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.offset += 1,
+                Some(b'}') => {
+                    self.offset += 1;
+                    break;
+                }
+                Some(_) => return Err(self.error(JSONParseErrorKind::UnexpectedCharacter)),
+                None => return Err(self.error(JSONParseErrorKind::UnexpectedEndOfInput)),
+            }
+        }
+        self.depth -= 1;
+        Ok(JSONValue::Object(members))
+    }
+
+    fn enter_nesting(&mut self) -> Result<(), JSONParseError> {
+        if self.depth >= JSON_MAX_NESTING_DEPTH {
+            return Err(self.error(JSONParseErrorKind::TooDeeplyNested));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.offset += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.offset).copied()
+    }
+
+    fn error(&self, kind: JSONParseErrorKind) -> JSONParseError {
+        JSONParseError {
+            kind: kind,
+            offset: self.offset,
+        }
+    }
+}
@@ -3,9 +3,15 @@ pub mod array;
 pub mod assert;
 pub mod c_string;
 pub mod const_assert;
+pub mod fnv;
+pub mod glob;
+pub mod json;
+pub mod message_format;
 pub mod narrow_cast;
 pub mod padded_string;
+pub mod sha256;
 pub mod permutations;
 pub mod simd;
 pub mod simd_neon_arm;
+pub mod try_from_u8;
 pub mod utf_8;
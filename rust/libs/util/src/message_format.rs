@@ -0,0 +1,161 @@
+// Parser for diagnostic message formats, such as "{0} is not {1:headlinese}".
+//
+// This parser is shared by the qljs_diagnostic proc macro (which checks
+// messages at compile time) and DiagnosticFormatter (which expands messages at
+// run time), so both agree on what a valid message is.
+
+// A modifier in a {N:modifier} placeholder.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PlaceholderModifier {
+    Headlinese,
+    Singular,
+}
+
+impl PlaceholderModifier {
+    pub fn from_name(name: &str) -> Option<PlaceholderModifier> {
+        match name {
+            "headlinese" => Some(PlaceholderModifier::Headlinese),
+            "singular" => Some(PlaceholderModifier::Singular),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            PlaceholderModifier::Headlinese => "headlinese",
+            PlaceholderModifier::Singular => "singular",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MessageFormatPart<'format> {
+    // Text to write as-is. An escaped "{{" is returned as a Text ending in "{".
+    Text(&'format str),
+    // A {N} or {N:modifier} placeholder.
+    Placeholder {
+        arg_index: usize,
+        modifier: Option<PlaceholderModifier>,
+    },
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MessageFormatErrorKind {
+    // "{" without a matching "}".
+    UnmatchedLeftCurly,
+    // A placeholder such as "{}" or "{x}" which doesn't start with a number.
+    MissingArgIndex,
+    // A placeholder such as "{99999999999999999999}".
+    ArgIndexTooLarge,
+    // A placeholder such as "{0:shouting}".
+    UnknownModifier,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct MessageFormatError<'format> {
+    pub kind: MessageFormatErrorKind,
+    // The rest of the format, starting with the offending "{".
+    pub rest: &'format str,
+}
+
+impl<'format> std::fmt::Display for MessageFormatError<'format> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let placeholder: &str = match self.rest.find('}') {
+            Some(right_curly_index) => &self.rest[..=right_curly_index],
+            None => self.rest,
+        };
+        match self.kind {
+            MessageFormatErrorKind::UnmatchedLeftCurly => write!(f, "'{{' has no matching '}}'"),
+            MessageFormatErrorKind::MissingArgIndex => write!(
+                f,
+                "placeholder {placeholder} should start with an argument index"
+            ),
+            MessageFormatErrorKind::ArgIndexTooLarge => {
+                write!(f, "placeholder {placeholder} has too large an index")
+            }
+            MessageFormatErrorKind::UnknownModifier => write!(
+                f,
+                "placeholder {placeholder} has an unknown modifier; expected 'headlinese' or 'singular'"
+            ),
+        }
+    }
+}
+
+// Splits a message format into text and placeholders.
+//
+// After yielding an error, the parser yields nothing more.
+pub struct MessageFormatParser<'format> {
+    remaining: &'format str,
+}
+
+impl<'format> MessageFormatParser<'format> {
+    pub fn new(format: &'format str) -> MessageFormatParser<'format> {
+        MessageFormatParser { remaining: format }
+    }
+
+    fn fail(
+        &mut self,
+        kind: MessageFormatErrorKind,
+        rest: &'format str,
+    ) -> Option<Result<MessageFormatPart<'format>, MessageFormatError<'format>>> {
+        self.remaining = "";
+        Some(Err(MessageFormatError {
+            kind: kind,
+            rest: rest,
+        }))
+    }
+}
+
+impl<'format> Iterator for MessageFormatParser<'format> {
+    type Item = Result<MessageFormatPart<'format>, MessageFormatError<'format>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining.is_empty() {
+            return None;
+        }
+        let Some(left_curly_index) = self.remaining.find('{') else {
+            let text: &str = self.remaining;
+            self.remaining = "";
+            return Some(Ok(MessageFormatPart::Text(text)));
+        };
+        if left_curly_index != 0 {
+            let text: &str = &self.remaining[..left_curly_index];
+            self.remaining = &self.remaining[left_curly_index..];
+            return Some(Ok(MessageFormatPart::Text(text)));
+        }
+
+        let rest: &str = self.remaining;
+        let after_left_curly: &str = &rest[1..];
+        if let Some(after_escaped_curly) = after_left_curly.strip_prefix('{') {
+            // "{{"; the '{' is escaped.
+            self.remaining = after_escaped_curly;
+            return Some(Ok(MessageFormatPart::Text(&rest[..1])));
+        }
+        let Some((curly_content, after_right_curly)) = after_left_curly.split_once('}') else {
+            return self.fail(MessageFormatErrorKind::UnmatchedLeftCurly, rest);
+        };
+        let (arg_index_string, modifier_name): (&str, Option<&str>) =
+            match curly_content.split_once(':') {
+                Some((arg_index_string, modifier_name)) => (arg_index_string, Some(modifier_name)),
+                None => (curly_content, None),
+            };
+        if arg_index_string.is_empty() || !arg_index_string.bytes().all(|c| c.is_ascii_digit()) {
+            return self.fail(MessageFormatErrorKind::MissingArgIndex, rest);
+        }
+        let Ok(arg_index) = arg_index_string.parse::<usize>() else {
+            return self.fail(MessageFormatErrorKind::ArgIndexTooLarge, rest);
+        };
+        let modifier: Option<PlaceholderModifier> = match modifier_name {
+            Some(modifier_name) => match PlaceholderModifier::from_name(modifier_name) {
+                Some(modifier) => Some(modifier),
+                None => return self.fail(MessageFormatErrorKind::UnknownModifier, rest),
+            },
+            None => None,
+        };
+        self.remaining = after_right_curly;
+        Some(Ok(MessageFormatPart::Placeholder {
+            arg_index: arg_index,
+            modifier: modifier,
+        }))
+    }
+}
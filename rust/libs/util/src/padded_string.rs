@@ -26,6 +26,14 @@ pub struct PaddedString {
     capacity: PaddedStringSizeType,
 }
 
+// SAFETY: data is a heap allocation owned by this PaddedString and freed only by
+// Drop, so moving the PaddedString to another thread moves sole ownership of
+// the bytes.
+unsafe impl Send for PaddedString {}
+// SAFETY: The bytes are only written through &mut self (data_ptr, resize,
+// as_mut_slice), so concurrent &PaddedString accesses only read.
+unsafe impl Sync for PaddedString {}
+
 impl PaddedString {
     pub fn new() -> PaddedString {
         PaddedString {
@@ -183,6 +191,14 @@ pub struct PaddedStringView<'a> {
     phantom: std::marker::PhantomData<&'a u8>,
 }
 
+// SAFETY: data points to length bytes plus padding borrowed for 'a from a
+// PaddedString or a caller-provided slice. PaddedStringView never writes
+// through data, and the borrow keeps the bytes alive and unmodified for 'a.
+unsafe impl<'a> Send for PaddedStringView<'a> {}
+// SAFETY: PaddedStringView has no interior mutability; every method only reads
+// the borrowed bytes.
+unsafe impl<'a> Sync for PaddedStringView<'a> {}
+
 impl<'a> PaddedStringView<'a> {
     pub fn from(s: &'a PaddedString) -> PaddedStringView<'a> {
         let result = PaddedStringView {
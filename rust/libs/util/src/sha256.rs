@@ -0,0 +1,140 @@
+// SHA-256, a cryptographic hash function.
+//
+// Use SHA-256 instead of FNV-1a when different inputs must not be confused,
+// such as when checking that a cache entry belongs to the expected input.
+//
+// See: FIPS 180-4, Secure Hash Standard
+#[derive(Clone, Debug)]
+pub struct SHA256 {
+    state: [u32; 8],
+    // Bytes which have not been compressed yet. Only the first
+    // pending_len bytes are used.
+    pending: [u8; SHA256_BLOCK_SIZE],
+    pending_len: usize,
+    total_len: u64,
+}
+
+pub const SHA256_DIGEST_SIZE: usize = 32;
+const SHA256_BLOCK_SIZE: usize = 64;
+
+const SHA256_INITIAL_STATE: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+const SHA256_ROUND_CONSTANTS: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+impl SHA256 {
+    pub const fn new() -> SHA256 {
+        SHA256 {
+            state: SHA256_INITIAL_STATE,
+            pending: [0; SHA256_BLOCK_SIZE],
+            pending_len: 0,
+            total_len: 0,
+        }
+    }
+
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.total_len = self.total_len.wrapping_add(data.len() as u64);
+        if self.pending_len > 0 {
+            let fill_len: usize = std::cmp::min(SHA256_BLOCK_SIZE - self.pending_len, data.len());
+            self.pending[self.pending_len..self.pending_len + fill_len]
+                .copy_from_slice(&data[..fill_len]);
+            self.pending_len += fill_len;
+            data = &data[fill_len..];
+            if self.pending_len < SHA256_BLOCK_SIZE {
+                return;
+            }
+            let block: [u8; SHA256_BLOCK_SIZE] = self.pending;
+            self.compress(&block);
+            self.pending_len = 0;
+        }
+        let mut blocks = data.chunks_exact(SHA256_BLOCK_SIZE);
+        for block in &mut blocks {
+            self.compress(block.try_into().unwrap());
+        }
+        let rest: &[u8] = blocks.remainder();
+        self.pending[..rest.len()].copy_from_slice(rest);
+        self.pending_len = rest.len();
+    }
+
+    pub fn finish(mut self) -> [u8; SHA256_DIGEST_SIZE] {
+        let bit_len: u64 = self.total_len.wrapping_mul(8);
+        // Pad with 0x80, then zeros, then the message length in bits, such that
+        // the padded message is a whole number of blocks.
+        let zero_len: usize =
+            (SHA256_BLOCK_SIZE * 2 - 1 - 8 - self.pending_len) % SHA256_BLOCK_SIZE;
+        let mut padding: [u8; SHA256_BLOCK_SIZE * 2] = [0; SHA256_BLOCK_SIZE * 2];
+        padding[0] = 0x80;
+        padding[1 + zero_len..1 + zero_len + 8].copy_from_slice(&bit_len.to_be_bytes());
+        let total_len: u64 = self.total_len;
+        self.update(&padding[..1 + zero_len + 8]);
+        self.total_len = total_len;
+
+        let mut digest: [u8; SHA256_DIGEST_SIZE] = [0; SHA256_DIGEST_SIZE];
+        for (word, out) in self.state.iter().zip(digest.chunks_exact_mut(4)) {
+            out.copy_from_slice(&word.to_be_bytes());
+        }
+        digest
+    }
+
+    fn compress(&mut self, block: &[u8; SHA256_BLOCK_SIZE]) {
+        let mut w: [u32; 64] = [0; 64];
+        for (i, word) in block.chunks_exact(4).enumerate() {
+            w[i] = u32::from_be_bytes(word.try_into().unwrap());
+        }
+        for i in 16..64 {
+            let s0: u32 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1: u32 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = self.state;
+        for i in 0..64 {
+            let s1: u32 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch: u32 = (e & f) ^ (!e & g);
+            let temp1: u32 = h
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(SHA256_ROUND_CONSTANTS[i])
+                .wrapping_add(w[i]);
+            let s0: u32 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj: u32 = (a & b) ^ (a & c) ^ (b & c);
+            let temp2: u32 = s0.wrapping_add(maj);
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(temp1);
+            d = c;
+            c = b;
+            b = a;
+            a = temp1.wrapping_add(temp2);
+        }
+        for (state, value) in self.state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *state = state.wrapping_add(value);
+        }
+    }
+}
+
+impl Default for SHA256 {
+    fn default() -> SHA256 {
+        SHA256::new()
+    }
+}
+
+pub fn sha_256(data: &[u8]) -> [u8; SHA256_DIGEST_SIZE] {
+    let mut hasher: SHA256 = SHA256::new();
+    hasher.update(data);
+    hasher.finish()
+}
@@ -0,0 +1,34 @@
+// Implement TryFrom<u8> for a fieldless enum. The conversion fails (returning
+// the u8) if the u8 is not the discriminant of any of the listed variants.
+//
+// Every variant of the enum must be listed. If a variant is missing, the
+// generated code fails to compile with a non-exhaustive match error.
+//
+// Example:
+//
+//   qljs_impl_try_from_u8!(Fruit { Apple, Banana, Cherry });
+#[macro_export]
+macro_rules! qljs_impl_try_from_u8 {
+    ($enum:ident { $($variant:ident),+ $(,)? }) => {
+        impl TryFrom<u8> for $enum {
+            type Error = u8;
+
+            fn try_from(value: u8) -> Result<$enum, u8> {
+                // Fail to compile if a variant is not listed.
+                #[allow(dead_code)]
+                fn check_every_variant_is_listed(value: $enum) {
+                    match value {
+                        $($enum::$variant)|+ => {}
+                    }
+                }
+
+                $(
+                    if value == $enum::$variant as u8 {
+                        return Ok($enum::$variant);
+                    }
+                )+
+                Err(value)
+            }
+        }
+    };
+}
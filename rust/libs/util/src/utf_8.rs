@@ -1,3 +1,4 @@
+use crate::narrow_cast::*;
 use crate::padded_string::*;
 use crate::qljs_assert;
 use crate::qljs_const_assert;
@@ -183,6 +184,39 @@ pub fn count_lsp_characters_in_utf_8(utf_8: PaddedStringView, offset: i32) -> is
     count
 }
 
+// The inverse of count_lsp_characters_in_utf_8: returns the byte offset after
+// character_count UTF-16 code units.
+//
+// If character_count is past the end of utf_8, returns utf_8.len(). If
+// character_count is in the middle of a surrogate pair, returns the offset of
+// the beginning of the pair's UTF-8 sequence.
+pub fn advance_lsp_characters_in_utf_8(
+    utf_8: PaddedStringView,
+    character_count: isize,
+) -> PaddedStringSizeType {
+    let mut c: PaddedStringSizeType = 0;
+    let mut count: isize = 0;
+    while count < character_count && c < utf_8.len() {
+        let result: DecodeUTF8Result = decode_utf_8(utf_8.substr(c));
+        if result.ok {
+            let character_len: isize = if (result.code_point as u32) >= 0x10000 {
+                2
+            } else {
+                1
+            };
+            if count + character_len > character_count {
+                break;
+            }
+            c += result.size;
+            count += character_len;
+        } else {
+            c += 1;
+            count += 1;
+        }
+    }
+    c
+}
+
 pub fn count_utf_8_characters(utf_8: PaddedStringView, offset: usize) -> usize {
     let mut c: usize = 0;
     let stop: usize = offset;
@@ -205,3 +239,95 @@ pub fn count_utf_8_characters(utf_8: PaddedStringView, offset: usize) -> usize {
 
     count
 }
+
+// The inverse of count_utf_8_characters: returns the byte offset after
+// character_count code points.
+//
+// If character_count is past the end of utf_8, returns utf_8.len().
+pub fn advance_utf_8_characters(
+    utf_8: PaddedStringView,
+    character_count: usize,
+) -> PaddedStringSizeType {
+    let mut c: PaddedStringSizeType = 0;
+    let mut count: usize = 0;
+    while count < character_count && c < utf_8.len() {
+        let result: DecodeUTF8Result = decode_utf_8(utf_8.substr(c));
+        c += if result.ok { result.size } else { 1 };
+        count += 1;
+    }
+    c
+}
+
+// How positions within a line or document count characters.
+//
+// Corresponds to LSP's PositionEncodingKind.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PositionEncoding {
+    // Count bytes.
+    UTF8,
+    // Count UTF-16 code units. This is LSP's default.
+    UTF16,
+    // Count code points.
+    UTF32,
+}
+
+impl PositionEncoding {
+    // Parse an LSP PositionEncodingKind, such as "utf-16".
+    pub fn from_lsp_name(name: &[u8]) -> Option<PositionEncoding> {
+        match name {
+            b"utf-8" => Some(PositionEncoding::UTF8),
+            b"utf-16" => Some(PositionEncoding::UTF16),
+            b"utf-32" => Some(PositionEncoding::UTF32),
+            _ => None,
+        }
+    }
+
+    pub fn lsp_name(self) -> &'static str {
+        match self {
+            PositionEncoding::UTF8 => "utf-8",
+            PositionEncoding::UTF16 => "utf-16",
+            PositionEncoding::UTF32 => "utf-32",
+        }
+    }
+}
+
+// Returns the number of characters (according to encoding) in the first
+// byte_offset bytes of utf_8.
+//
+// A character which straddles byte_offset is not counted. Bytes which are not
+// part of a valid UTF-8 sequence count as one character each.
+pub fn count_characters_in_utf_8(
+    utf_8: PaddedStringView,
+    byte_offset: usize,
+    encoding: PositionEncoding,
+) -> usize {
+    match encoding {
+        PositionEncoding::UTF8 => byte_offset,
+        PositionEncoding::UTF16 => narrow_cast::<usize, _>(count_lsp_characters_in_utf_8(
+            utf_8,
+            narrow_cast::<i32, _>(byte_offset),
+        )),
+        PositionEncoding::UTF32 => count_utf_8_characters(utf_8, byte_offset),
+    }
+}
+
+// The inverse of count_characters_in_utf_8: returns the byte offset after
+// character_count characters (according to encoding).
+//
+// If character_count is past the end of utf_8, returns utf_8.len().
+pub fn advance_characters_in_utf_8(
+    utf_8: PaddedStringView,
+    character_count: usize,
+    encoding: PositionEncoding,
+) -> PaddedStringSizeType {
+    match encoding {
+        PositionEncoding::UTF8 => narrow_cast::<PaddedStringSizeType, _>(std::cmp::min(
+            character_count,
+            narrow_cast::<usize, _>(utf_8.len()),
+        )),
+        PositionEncoding::UTF16 => {
+            advance_lsp_characters_in_utf_8(utf_8, narrow_cast::<isize, _>(character_count))
+        }
+        PositionEncoding::UTF32 => advance_utf_8_characters(utf_8, character_count),
+    }
+}
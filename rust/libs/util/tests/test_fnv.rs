@@ -0,0 +1,22 @@
+use cpp_vs_rust_util::fnv::*;
+
+#[test]
+fn empty_input_hashes_to_offset_basis() {
+    assert_eq!(fnv_1a_64(b""), 0xcbf29ce484222325);
+}
+
+#[test]
+fn known_hashes() {
+    // Test vectors from http://www.isthe.com/chongo/src/fnv/test_fnv.c
+    assert_eq!(fnv_1a_64(b"a"), 0xaf63dc4c8601ec8c);
+    assert_eq!(fnv_1a_64(b"foobar"), 0x85944171f73967e8);
+}
+
+#[test]
+fn incremental_updates_match_one_update() {
+    let mut hasher: FNV1a64 = FNV1a64::new();
+    hasher.update(b"foo");
+    hasher.update(b"");
+    hasher.update(b"bar");
+    assert_eq!(hasher.finish(), fnv_1a_64(b"foobar"));
+}
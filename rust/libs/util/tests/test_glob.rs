@@ -0,0 +1,97 @@
+use cpp_vs_rust_util::glob::*;
+
+fn matches(pattern: &str, path: &str) -> bool {
+    glob_matches(pattern.as_bytes(), path.as_bytes())
+}
+
+#[test]
+fn literal_pattern_matches_only_itself() {
+    assert!(matches("hello.js", "hello.js"));
+    assert!(!matches("hello.js", "hello.jsx"));
+    assert!(!matches("hello.js", "hello.j"));
+    assert!(matches("", ""));
+    assert!(!matches("", "x"));
+}
+
+#[test]
+fn star_matches_within_one_path_component() {
+    assert!(matches("*.js", "hello.js"));
+    assert!(matches("*.js", ".js"));
+    assert!(matches("a*b*c", "aXXbYYc"));
+    assert!(matches("a*b*c", "abc"));
+    assert!(!matches("a*b*c", "aXXbYY"));
+    assert!(!matches("*.js", "dir/hello.js"));
+    assert!(matches("dir/*", "dir/hello.js"));
+    assert!(!matches("dir/*", "dir/sub/hello.js"));
+}
+
+#[test]
+fn question_mark_matches_one_character() {
+    assert!(matches("?.js", "a.js"));
+    assert!(!matches("?.js", ".js"));
+    assert!(!matches("?.js", "ab.js"));
+    assert!(!matches("a?b", "a/b"));
+    assert!(matches("?.js", "\u{00e9}.js"));
+    assert!(matches("?.js", "\u{1f600}.js"));
+}
+
+#[test]
+fn character_classes() {
+    assert!(matches("[abc].js", "b.js"));
+    assert!(!matches("[abc].js", "d.js"));
+    assert!(matches("[a-c].js", "b.js"));
+    assert!(!matches("[a-c].js", "-.js"));
+    assert!(matches("[!a-c].js", "d.js"));
+    assert!(matches("[^a-c].js", "d.js"));
+    assert!(!matches("[!a-c].js", "a.js"));
+    assert!(matches("[]x].js", "].js"));
+    assert!(matches("[a-].js", "-.js"));
+    assert!(!matches("a[/]b", "a/b"));
+}
+
+#[test]
+fn unterminated_bracket_is_literal() {
+    assert!(matches("[abc", "[abc"));
+    assert!(!matches("[abc", "a"));
+}
+
+#[test]
+fn backslash_escapes_special_characters() {
+    assert!(matches("\\*.js", "*.js"));
+    assert!(!matches("\\*.js", "x.js"));
+    assert!(matches("\\#x", "#x"));
+    assert!(matches("[\\]].js", "].js"));
+}
+
+#[test]
+fn leading_double_star_matches_in_any_directory() {
+    assert!(matches("**/foo", "foo"));
+    assert!(matches("**/foo", "a/foo"));
+    assert!(matches("**/foo", "a/b/foo"));
+    assert!(!matches("**/foo", "a/xfoo"));
+    assert!(matches("**/foo/bar", "a/foo/bar"));
+}
+
+#[test]
+fn trailing_double_star_matches_everything_inside() {
+    assert!(matches("abc/**", "abc/x"));
+    assert!(matches("abc/**", "abc/x/y.js"));
+    assert!(!matches("abc/**", "abc"));
+    assert!(!matches("abc/**", "abcd/x"));
+}
+
+#[test]
+fn middle_double_star_matches_zero_or_more_directories() {
+    assert!(matches("a/**/b", "a/b"));
+    assert!(matches("a/**/b", "a/x/b"));
+    assert!(matches("a/**/b", "a/x/y/b"));
+    assert!(!matches("a/**/b", "a/x/y/c"));
+}
+
+#[test]
+fn double_star_inside_component_is_like_star() {
+    assert!(matches("a**b", "aXXb"));
+    assert!(!matches("a**b", "a/b"));
+    assert!(matches("**.js", "x.js"));
+    assert!(!matches("**.js", "dir/x.js"));
+}
@@ -0,0 +1,245 @@
+use cpp_vs_rust_util::json::*;
+
+fn escaped(s: &[u8]) -> String {
+    let mut out: Vec<u8> = vec![];
+    write_json_escaped_string(&mut out, s);
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn plain_string_is_quoted() {
+    assert_eq!(escaped(b""), r#""""#);
+    assert_eq!(escaped(b"hello world"), r#""hello world""#);
+}
+
+#[test]
+fn quotes_and_backslashes_are_escaped() {
+    assert_eq!(escaped(br#"say "hi""#), r#""say \"hi\"""#);
+    assert_eq!(escaped(br"C:\dir"), r#""C:\\dir""#);
+}
+
+#[test]
+fn control_characters_are_escaped() {
+    assert_eq!(escaped(b"a\nb\rc\td"), r#""a\nb\rc\td""#);
+    assert_eq!(escaped(b"\x00\x1b\x7f"), "\"\\u0000\\u001b\x7f\"");
+}
+
+#[test]
+fn line_and_paragraph_separators_are_escaped() {
+    assert_eq!(escaped("\u{2028}\u{2029}".as_bytes()), r#""\u2028\u2029""#);
+}
+
+#[test]
+fn non_ascii_characters_are_written_as_utf_8() {
+    assert_eq!(
+        escaped("\u{00e9}\u{01f496}".as_bytes()),
+        "\"\u{00e9}\u{01f496}\""
+    );
+}
+
+#[test]
+fn invalid_utf_8_is_replaced() {
+    assert_eq!(escaped(b"a\xffb"), "\"a\u{fffd}b\"");
+}
+
+#[test]
+fn parse_keywords_and_numbers() {
+    assert_eq!(parse_json(b"null"), Ok(JSONValue::Null));
+    assert_eq!(parse_json(b" true "), Ok(JSONValue::Bool(true)));
+    assert_eq!(parse_json(b"false"), Ok(JSONValue::Bool(false)));
+    assert_eq!(parse_json(b"0"), Ok(JSONValue::Number(0.0)));
+    assert_eq!(parse_json(b"-12"), Ok(JSONValue::Number(-12.0)));
+    assert_eq!(parse_json(b"1.5e2"), Ok(JSONValue::Number(150.0)));
+    assert_eq!(parse_json(b"2E-1"), Ok(JSONValue::Number(0.2)));
+}
+
+#[test]
+fn parse_strings_with_escapes() {
+    assert_eq!(
+        parse_json(br#""a\"b\\c\/d\n\t""#),
+        Ok(JSONValue::String("a\"b\\c/d\n\t".to_string()))
+    );
+    assert_eq!(
+        parse_json(br#""\u00e9\u2603""#),
+        Ok(JSONValue::String("\u{00e9}\u{2603}".to_string()))
+    );
+    assert_eq!(
+        parse_json("\"\u{1f430}\"".as_bytes()),
+        Ok(JSONValue::String("\u{1f430}".to_string()))
+    );
+}
+
+#[test]
+fn parse_surrogate_pair_escapes() {
+    assert_eq!(
+        parse_json(br#""\ud83d\udc30""#),
+        Ok(JSONValue::String("\u{1f430}".to_string()))
+    );
+    // Unpaired surrogates become U+FFFD.
+    assert_eq!(
+        parse_json(br#""\ud83dx""#),
+        Ok(JSONValue::String("\u{fffd}x".to_string()))
+    );
+    assert_eq!(
+        parse_json(br#""\udc30""#),
+        Ok(JSONValue::String("\u{fffd}".to_string()))
+    );
+    assert_eq!(
+        parse_json(br#""\ud83dA""#),
+        Ok(JSONValue::String("\u{fffd}A".to_string()))
+    );
+}
+
+#[test]
+fn parse_arrays_and_objects() {
+    let value: JSONValue =
+        parse_json(br#" { "a" : [1, "two", null], "b": {}, "c": [] } "#).unwrap();
+    assert_eq!(
+        value.get("a"),
+        Some(&JSONValue::Array(vec![
+            JSONValue::Number(1.0),
+            JSONValue::String("two".to_string()),
+            JSONValue::Null,
+        ]))
+    );
+    assert_eq!(value.get("b"), Some(&JSONValue::Object(vec![])));
+    assert_eq!(value.get("c").and_then(JSONValue::as_array), Some(&[][..]));
+    assert_eq!(value.get("missing"), None);
+}
+
+#[test]
+fn duplicate_object_key_uses_last_member() {
+    let value: JSONValue = parse_json(br#"{"a": 1, "a": 2}"#).unwrap();
+    assert_eq!(value.get("a").and_then(JSONValue::as_i64), Some(2));
+}
+
+#[test]
+fn accessors_reject_other_types() {
+    let value: JSONValue = parse_json(br#"{"n": 1.5, "s": "x", "b": true}"#).unwrap();
+    assert_eq!(value.get("n").and_then(JSONValue::as_i64), None);
+    assert_eq!(value.get("n").and_then(JSONValue::as_f64), Some(1.5));
+    assert_eq!(value.get("s").and_then(JSONValue::as_str), Some("x"));
+    assert_eq!(value.get("s").and_then(JSONValue::as_bool), None);
+    assert_eq!(value.get("b").and_then(JSONValue::as_bool), Some(true));
+    assert_eq!(parse_json(b"-1").unwrap().as_u64(), None);
+    assert_eq!(parse_json(b"[]").unwrap().get("x"), None);
+}
+
+#[test]
+fn spans_locate_values_by_path() {
+    let input: &[u8] = br#"{"a": [1, "two"], "b": {"c": null}, "a": true}"#;
+    let (value, spans) = parse_json_with_spans(input).unwrap();
+    assert_eq!(value, parse_json(input).unwrap());
+    let text = |path: &[JSONPathComponent]| -> &str {
+        let span: JSONSpan = spans.get(path).unwrap();
+        std::str::from_utf8(&input[span.begin..span.end]).unwrap()
+    };
+    let key = |k: &str| JSONPathComponent::Key(k.to_string());
+    assert_eq!(text(&[]), std::str::from_utf8(input).unwrap());
+    // Like JSONValue::get, the last duplicate key wins.
+    assert_eq!(text(&[key("a")]), "true");
+    assert_eq!(text(&[key("b")]), r#"{"c": null}"#);
+    assert_eq!(text(&[key("b"), key("c")]), "null");
+    assert_eq!(spans.get(&[key("b"), key("d")]), None);
+
+    let (_, spans) = parse_json_with_spans(b" [1, [\"x\"]] ").unwrap();
+    assert_eq!(
+        spans.get(&[JSONPathComponent::Index(1), JSONPathComponent::Index(0)]),
+        Some(JSONSpan { begin: 6, end: 9 })
+    );
+    assert_eq!(spans.get(&[]), Some(JSONSpan { begin: 1, end: 11 }));
+}
+
+#[test]
+fn parse_errors_report_kind_and_offset() {
+    let error = |input: &[u8]| parse_json(input).unwrap_err();
+    assert_eq!(
+        error(b""),
+        JSONParseError {
+            kind: JSONParseErrorKind::UnexpectedEndOfInput,
+            offset: 0
+        }
+    );
+    assert_eq!(
+        error(b"[1, 2"),
+        JSONParseError {
+            kind: JSONParseErrorKind::UnexpectedEndOfInput,
+            offset: 5
+        }
+    );
+    assert_eq!(
+        error(b"[1 2]"),
+        JSONParseError {
+            kind: JSONParseErrorKind::UnexpectedCharacter,
+            offset: 3
+        }
+    );
+    assert_eq!(
+        error(b"{\"a\" 1}"),
+        JSONParseError {
+            kind: JSONParseErrorKind::UnexpectedCharacter,
+            offset: 5
+        }
+    );
+    assert_eq!(
+        error(b"01"),
+        JSONParseError {
+            kind: JSONParseErrorKind::UnexpectedCharacter,
+            offset: 1
+        }
+    );
+    assert_eq!(
+        error(b"1."),
+        JSONParseError {
+            kind: JSONParseErrorKind::InvalidNumber,
+            offset: 2
+        }
+    );
+    assert_eq!(
+        error(br#""\x""#),
+        JSONParseError {
+            kind: JSONParseErrorKind::InvalidEscapeSequence,
+            offset: 2
+        }
+    );
+    assert_eq!(error(b"\"\xff\"").kind, JSONParseErrorKind::InvalidUTF8);
+    assert_eq!(
+        error(b"\"a\nb\"").kind,
+        JSONParseErrorKind::UnexpectedCharacter
+    );
+    assert_eq!(error(b"nul").kind, JSONParseErrorKind::UnexpectedEndOfInput);
+    assert_eq!(error(b"[1,]").kind, JSONParseErrorKind::UnexpectedCharacter);
+    assert_eq!(
+        error(b"{\"a\":1,}").kind,
+        JSONParseErrorKind::UnexpectedCharacter
+    );
+}
+
+#[test]
+fn deeply_nested_input_is_rejected() {
+    let ok_input: Vec<u8> = [
+        vec![b'['; JSON_MAX_NESTING_DEPTH],
+        vec![b']'; JSON_MAX_NESTING_DEPTH],
+    ]
+    .concat();
+    assert!(parse_json(&ok_input).is_ok());
+
+    let deep_input: Vec<u8> = vec![b'['; 100_000];
+    assert_eq!(
+        parse_json(&deep_input).unwrap_err().kind,
+        JSONParseErrorKind::TooDeeplyNested
+    );
+}
+
+#[test]
+fn write_round_trips_parsed_values() {
+    for input in [
+        r#"null"#,
+        r#"[true,false,-3,0.5,"x\ny"]"#,
+        r#"{"id":12,"method":"initialize","params":{"a":[],"b":{}}}"#,
+    ] {
+        let mut out: Vec<u8> = vec![];
+        parse_json(input.as_bytes()).unwrap().write(&mut out);
+        assert_eq!(String::from_utf8(out).unwrap(), input);
+    }
+}
@@ -0,0 +1,105 @@
+use cpp_vs_rust_util::message_format::*;
+
+fn parse(format: &str) -> Vec<Result<MessageFormatPart<'_>, MessageFormatError<'_>>> {
+    MessageFormatParser::new(format).collect()
+}
+
+#[test]
+fn text_without_placeholders() {
+    assert_eq!(parse(""), []);
+    assert_eq!(
+        parse("hello world"),
+        [Ok(MessageFormatPart::Text("hello world"))]
+    );
+}
+
+#[test]
+fn placeholders_between_text() {
+    assert_eq!(
+        parse("{0} is not {1:headlinese}"),
+        [
+            Ok(MessageFormatPart::Placeholder {
+                arg_index: 0,
+                modifier: None,
+            }),
+            Ok(MessageFormatPart::Text(" is not ")),
+            Ok(MessageFormatPart::Placeholder {
+                arg_index: 1,
+                modifier: Some(PlaceholderModifier::Headlinese),
+            }),
+        ]
+    );
+    assert_eq!(
+        parse("a {12:singular}."),
+        [
+            Ok(MessageFormatPart::Text("a ")),
+            Ok(MessageFormatPart::Placeholder {
+                arg_index: 12,
+                modifier: Some(PlaceholderModifier::Singular),
+            }),
+            Ok(MessageFormatPart::Text(".")),
+        ]
+    );
+}
+
+#[test]
+fn escaped_left_curly_is_text() {
+    assert_eq!(
+        parse("a {{0}"),
+        [
+            Ok(MessageFormatPart::Text("a ")),
+            Ok(MessageFormatPart::Text("{")),
+            Ok(MessageFormatPart::Text("0}")),
+        ]
+    );
+}
+
+#[test]
+fn malformed_placeholders_are_errors() {
+    for (format, expected_kind, expected_rest) in [
+        ("oops {0", MessageFormatErrorKind::UnmatchedLeftCurly, "{0"),
+        ("oops {", MessageFormatErrorKind::UnmatchedLeftCurly, "{"),
+        ("{} x", MessageFormatErrorKind::MissingArgIndex, "{} x"),
+        ("{x} x", MessageFormatErrorKind::MissingArgIndex, "{x} x"),
+        ("{-1}", MessageFormatErrorKind::MissingArgIndex, "{-1}"),
+        (
+            "{:headlinese}",
+            MessageFormatErrorKind::MissingArgIndex,
+            "{:headlinese}",
+        ),
+        (
+            "{99999999999999999999999}",
+            MessageFormatErrorKind::ArgIndexTooLarge,
+            "{99999999999999999999999}",
+        ),
+        (
+            "{0:shouting} x",
+            MessageFormatErrorKind::UnknownModifier,
+            "{0:shouting} x",
+        ),
+    ] {
+        let parts = parse(format);
+        let error: &MessageFormatError = parts.last().unwrap().as_ref().unwrap_err();
+        assert_eq!(error.kind, expected_kind, "{format:?}");
+        assert_eq!(error.rest, expected_rest, "{format:?}");
+    }
+}
+
+#[test]
+fn parser_stops_after_error() {
+    let mut parser = MessageFormatParser::new("a {x} {0}");
+    assert_eq!(parser.next(), Some(Ok(MessageFormatPart::Text("a "))));
+    assert!(matches!(parser.next(), Some(Err(_))));
+    assert_eq!(parser.next(), None);
+}
+
+#[test]
+fn error_message_names_placeholder() {
+    let error: MessageFormatError = MessageFormatParser::new("a {0:shouting} b")
+        .find_map(|part| part.err())
+        .unwrap();
+    assert_eq!(
+        error.to_string(),
+        "placeholder {0:shouting} has an unknown modifier; expected 'headlinese' or 'singular'"
+    );
+}
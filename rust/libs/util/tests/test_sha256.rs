@@ -0,0 +1,64 @@
+use cpp_vs_rust_util::sha256::*;
+
+#[test]
+fn known_hashes() {
+    // Test vectors from FIPS 180-4 examples and NIST's SHA-256 test vectors.
+    assert_eq!(
+        hex(&sha_256(b"")),
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+    );
+    assert_eq!(
+        hex(&sha_256(b"abc")),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+    assert_eq!(
+        hex(&sha_256(
+            b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"
+        )),
+        "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+    );
+    assert_eq!(
+        hex(&sha_256(&[b'a'; 1_000_000])),
+        "cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0"
+    );
+}
+
+#[test]
+fn incremental_updates_match_one_update() {
+    let data: Vec<u8> = (0..=255).cycle().take(1000).collect();
+    for split_size in [1, 3, 55, 56, 63, 64, 65, 128, 999] {
+        let mut hasher: SHA256 = SHA256::new();
+        for chunk in data.chunks(split_size) {
+            hasher.update(chunk);
+            hasher.update(b"");
+        }
+        assert_eq!(hasher.finish(), sha_256(&data), "split_size={split_size}");
+    }
+}
+
+#[test]
+fn padding_boundaries() {
+    // Lengths around 55 and 56 bytes need one or two padding blocks.
+    for len in 54..=66 {
+        let data: Vec<u8> = vec![b'x'; len];
+        let mut hasher: SHA256 = SHA256::new();
+        hasher.update(&data[..len / 2]);
+        hasher.update(&data[len / 2..]);
+        assert_eq!(hasher.finish(), sha_256(&data), "len={len}");
+    }
+    assert_eq!(
+        hex(&sha_256(&[b'a'; 55])),
+        "9f4390f8d30c2dd92ec9f095b65e2b9ae9b0a925a5258e241c9f1e910f734318"
+    );
+    assert_eq!(
+        hex(&sha_256(&[b'a'; 56])),
+        "b35439a4ac6f0948b6d6f9e3c6af0f5f590ce20f1bde7090ef7970686ec6738a"
+    );
+}
+
+fn hex(digest: &[u8]) -> String {
+    digest
+        .iter()
+        .map(|byte: &u8| format!("{byte:02x}"))
+        .collect()
+}
@@ -0,0 +1,28 @@
+use cpp_vs_rust_util::qljs_impl_try_from_u8;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Fruit {
+    Apple,
+    Banana,
+    Cherry = 10,
+}
+
+qljs_impl_try_from_u8!(Fruit {
+    Apple,
+    Banana,
+    Cherry,
+});
+
+#[test]
+fn discriminants_convert_to_variants() {
+    assert_eq!(Fruit::try_from(0), Ok(Fruit::Apple));
+    assert_eq!(Fruit::try_from(1), Ok(Fruit::Banana));
+    assert_eq!(Fruit::try_from(10), Ok(Fruit::Cherry));
+}
+
+#[test]
+fn other_values_are_rejected() {
+    assert_eq!(Fruit::try_from(2), Err(2));
+    assert_eq!(Fruit::try_from(9), Err(9));
+    assert_eq!(Fruit::try_from(255), Err(255));
+}
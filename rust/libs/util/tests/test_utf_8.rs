@@ -587,3 +587,140 @@ fn count_utf_8_characters_mixed_ascii_with_invalid() {
     let n: usize = count_utf_8_characters_full(PaddedString::from_slice(b"a\xe2\x80").view());
     assert_eq!(n, 3);
 }
+
+#[test]
+fn advance_lsp_characters_ascii() {
+    let input = PaddedString::from_slice(b"hello");
+    assert_eq!(advance_lsp_characters_in_utf_8(input.view(), 0), 0);
+    assert_eq!(advance_lsp_characters_in_utf_8(input.view(), 3), 3);
+    assert_eq!(advance_lsp_characters_in_utf_8(input.view(), 5), 5);
+}
+
+#[test]
+fn advance_lsp_characters_past_end_stops_at_end() {
+    let input = PaddedString::from_slice(b"hi");
+    assert_eq!(advance_lsp_characters_in_utf_8(input.view(), 10), 2);
+}
+
+#[test]
+fn advance_lsp_characters_multi_byte() {
+    // U+0100 has two UTF-8 code units. U+2306 has three.
+    let input = PaddedString::from_slice("\u{0100}\u{2306}x".as_bytes());
+    assert_eq!(advance_lsp_characters_in_utf_8(input.view(), 1), 2);
+    assert_eq!(advance_lsp_characters_in_utf_8(input.view(), 2), 5);
+    assert_eq!(advance_lsp_characters_in_utf_8(input.view(), 3), 6);
+}
+
+#[test]
+fn advance_lsp_characters_middle_of_surrogate_pair_stops_before_pair() {
+    // U+1F430 has four UTF-8 code units and two UTF-16 code units.
+    let input = PaddedString::from_slice("x\u{01F430}y".as_bytes());
+    assert_eq!(advance_lsp_characters_in_utf_8(input.view(), 2), 1);
+    assert_eq!(advance_lsp_characters_in_utf_8(input.view(), 3), 5);
+}
+
+#[test]
+fn advance_lsp_characters_invalid_bytes_count_as_one_each() {
+    let input = PaddedString::from_slice(b"\xff\xfex");
+    assert_eq!(advance_lsp_characters_in_utf_8(input.view(), 2), 2);
+    assert_eq!(advance_lsp_characters_in_utf_8(input.view(), 3), 3);
+}
+
+#[test]
+fn advance_lsp_characters_inverts_count_lsp_characters() {
+    let input = PaddedString::from_slice("a\u{0100}b\u{01F430}c\u{2306}".as_bytes());
+    for offset in 0..=input.len() {
+        let characters: isize = count_lsp_characters_in_utf_8(input.view(), offset);
+        let advanced: PaddedStringSizeType =
+            advance_lsp_characters_in_utf_8(input.view(), characters);
+        scoped_trace!(offset);
+        assert!(advanced <= offset);
+        assert_eq!(
+            count_lsp_characters_in_utf_8(input.view(), advanced),
+            characters
+        );
+    }
+}
+
+#[test]
+fn advance_utf_8_characters_counts_code_points() {
+    // U+0100 has two UTF-8 code units. U+1F430 has four.
+    let input = PaddedString::from_slice("\u{0100}\u{01F430}x".as_bytes());
+    assert_eq!(advance_utf_8_characters(input.view(), 0), 0);
+    assert_eq!(advance_utf_8_characters(input.view(), 1), 2);
+    assert_eq!(advance_utf_8_characters(input.view(), 2), 6);
+    assert_eq!(advance_utf_8_characters(input.view(), 3), 7);
+    assert_eq!(advance_utf_8_characters(input.view(), 100), 7);
+}
+
+#[test]
+fn count_characters_in_each_encoding() {
+    // U+0100 has two UTF-8 code units. U+1F430 has four UTF-8 code units and
+    // two UTF-16 code units.
+    let input = PaddedString::from_slice("a\u{0100}\u{01F430}b".as_bytes());
+    let offset_of_b: usize = 7;
+    assert_eq!(
+        count_characters_in_utf_8(input.view(), offset_of_b, PositionEncoding::UTF8),
+        7
+    );
+    assert_eq!(
+        count_characters_in_utf_8(input.view(), offset_of_b, PositionEncoding::UTF16),
+        4
+    );
+    assert_eq!(
+        count_characters_in_utf_8(input.view(), offset_of_b, PositionEncoding::UTF32),
+        3
+    );
+}
+
+#[test]
+fn advance_characters_inverts_count_characters_in_each_encoding() {
+    let input = PaddedString::from_slice("a\u{0100}b\u{01F430}c\u{2306}".as_bytes());
+    for encoding in [
+        PositionEncoding::UTF8,
+        PositionEncoding::UTF16,
+        PositionEncoding::UTF32,
+    ] {
+        scoped_trace!(encoding);
+        for offset in 0..=(input.len() as usize) {
+            scoped_trace!(offset);
+            let characters: usize = count_characters_in_utf_8(input.view(), offset, encoding);
+            let advanced: PaddedStringSizeType =
+                advance_characters_in_utf_8(input.view(), characters, encoding);
+            assert!(advanced as usize <= offset);
+            assert_eq!(
+                count_characters_in_utf_8(input.view(), advanced as usize, encoding),
+                characters
+            );
+        }
+    }
+}
+
+#[test]
+fn advance_utf_8_byte_characters_stops_at_end() {
+    let input = PaddedString::from_slice(b"abc");
+    assert_eq!(
+        advance_characters_in_utf_8(input.view(), 100, PositionEncoding::UTF8),
+        3
+    );
+}
+
+#[test]
+fn position_encoding_lsp_names() {
+    for encoding in [
+        PositionEncoding::UTF8,
+        PositionEncoding::UTF16,
+        PositionEncoding::UTF32,
+    ] {
+        assert_eq!(
+            PositionEncoding::from_lsp_name(encoding.lsp_name().as_bytes()),
+            Some(encoding)
+        );
+    }
+    assert_eq!(
+        PositionEncoding::from_lsp_name(b"utf-16"),
+        Some(PositionEncoding::UTF16)
+    );
+    assert_eq!(PositionEncoding::from_lsp_name(b"UTF-16"), None);
+    assert_eq!(PositionEncoding::from_lsp_name(b"utf-7"), None);
+}